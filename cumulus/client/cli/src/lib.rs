@@ -316,6 +316,14 @@ pub struct RunCmd {
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	#[arg(long)]
 	pub experimental_max_pov_percentage: Option<u32>,
+
+	/// EXPERIMENTAL: Warp sync to the parachain head proven by the warp proofs of other parachain
+	/// nodes, instead of waiting for the relay chain to announce the head to sync to.
+	///
+	/// Only has an effect together with `--sync warp` and a relay chain node that can verify
+	/// relay chain warp proofs.
+	#[arg(long)]
+	pub experimental_warp_sync_with_proofs: bool,
 }
 
 impl RunCmd {
@@ -338,7 +346,10 @@ impl RunCmd {
 				_ => RelayChainMode::Embedded,
 			};
 
-		CollatorOptions { relay_chain_mode }
+		CollatorOptions {
+			relay_chain_mode,
+			warp_sync_with_proofs: self.experimental_warp_sync_with_proofs,
+		}
 	}
 }
 
@@ -358,6 +369,8 @@ pub enum RelayChainMode {
 pub struct CollatorOptions {
 	/// How this collator retrieves relay chain information
 	pub relay_chain_mode: RelayChainMode,
+	/// Whether to warp sync with the warp proofs of other parachain nodes
+	pub warp_sync_with_proofs: bool,
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
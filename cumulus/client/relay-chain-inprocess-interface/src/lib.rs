@@ -42,6 +42,8 @@ use sc_client_api::{
 	blockchain::BlockStatus, Backend, BlockchainEvents, HeaderBackend, ImportNotifications,
 	StorageProof,
 };
+use sc_consensus_grandpa::GenesisAuthoritySetProvider;
+use sc_network_sync::strategy::warp::WarpSyncProvider;
 use sc_telemetry::TelemetryWorkerHandle;
 use sp_api::{CallApiAt, CallApiAtParams, CallContext, ProvideRuntimeApi};
 use sp_consensus::SyncOracle;
@@ -59,6 +61,8 @@ pub struct RelayChainInProcessInterface {
 	backend: Arc<FullBackend>,
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 	overseer_handle: Handle,
+	warp_sync_provider: Option<Arc<dyn WarpSyncProvider<PBlock>>>,
+	warp_proof_verifier: Option<Arc<dyn WarpSyncProvider<PBlock>>>,
 }
 
 impl RelayChainInProcessInterface {
@@ -69,7 +73,32 @@ impl RelayChainInProcessInterface {
 		sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 		overseer_handle: Handle,
 	) -> Self {
-		Self { full_client, backend, sync_oracle, overseer_handle }
+		Self {
+			full_client,
+			backend,
+			sync_oracle,
+			overseer_handle,
+			warp_sync_provider: None,
+			warp_proof_verifier: None,
+		}
+	}
+
+	/// Use the given GRANDPA warp sync provider of the relay chain node.
+	pub fn with_warp_sync_provider(
+		mut self,
+		warp_sync_provider: Arc<dyn WarpSyncProvider<PBlock>>,
+	) -> Self {
+		self.warp_sync_provider = Some(warp_sync_provider);
+		self
+	}
+
+	/// Use the given verifier of GRANDPA warp proofs of the relay chain.
+	pub fn with_warp_proof_verifier(
+		mut self,
+		warp_proof_verifier: Arc<dyn WarpSyncProvider<PBlock>>,
+	) -> Self {
+		self.warp_proof_verifier = Some(warp_proof_verifier);
+		self
 	}
 }
 
@@ -321,6 +350,14 @@ impl RelayChainInterface for RelayChainInProcessInterface {
 	async fn scheduling_lookahead(&self, hash: PHash) -> RelayChainResult<u32> {
 		Ok(self.full_client.runtime_api().scheduling_lookahead(hash)?)
 	}
+
+	fn warp_sync_provider(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		self.warp_sync_provider.clone()
+	}
+
+	fn warp_proof_verifier(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		self.warp_proof_verifier.clone()
+	}
 }
 
 pub enum BlockCheckStatus {
@@ -385,7 +422,9 @@ fn build_polkadot_full_node(
 			prepare_workers_hard_max_num: None,
 			prepare_workers_soft_max_num: None,
 			enable_approval_voting_parallel: false,
+			enable_experimental_collator_protocol: false,
 			keep_finalized_for: None,
+			persist_pvf_artifacts: false,
 		},
 	)?;
 
@@ -405,6 +444,7 @@ pub fn build_inprocess_relay_chain(
 	polkadot_config.impl_version = polkadot_cli::Cli::impl_version();
 	polkadot_config.impl_name = polkadot_cli::Cli::impl_name();
 
+	let chain_spec = polkadot_config.chain_spec.cloned_box();
 	let (full_node, collator_key) = build_polkadot_full_node(
 		polkadot_config,
 		parachain_config,
@@ -413,14 +453,24 @@ pub fn build_inprocess_relay_chain(
 	)
 	.map_err(|e| RelayChainError::Application(Box::new(e) as Box<_>))?;
 
-	let relay_chain_interface = Arc::new(RelayChainInProcessInterface::new(
-		full_node.client,
-		full_node.backend,
-		full_node.sync_service,
-		full_node.overseer_handle.clone().ok_or(RelayChainError::GenericError(
-			"Overseer not running in full node.".to_string(),
-		))?,
-	));
+	// The genesis state is gone once pruned, then the chain spec is used instead.
+	let genesis_authorities = GenesisAuthoritySetProvider::<PBlock>::get(&full_node.client).ok();
+	let warp_proof_verifier =
+		polkadot_service::grandpa_warp_proof_verifier(&chain_spec, genesis_authorities)
+			.map_err(|e| RelayChainError::Application(Box::new(e) as Box<_>))?;
+
+	let relay_chain_interface = Arc::new(
+		RelayChainInProcessInterface::new(
+			full_node.client,
+			full_node.backend,
+			full_node.sync_service,
+			full_node.overseer_handle.clone().ok_or(RelayChainError::GenericError(
+				"Overseer not running in full node.".to_string(),
+			))?,
+		)
+		.with_warp_sync_provider(full_node.warp_sync_provider)
+		.with_warp_proof_verifier(Arc::new(warp_proof_verifier)),
+	);
 
 	task_manager.add_child(full_node.task_manager);
 
@@ -24,6 +24,7 @@ use std::{
 use futures::Stream;
 use polkadot_overseer::prometheus::PrometheusError;
 use sc_client_api::StorageProof;
+use sc_network_sync::strategy::warp::WarpSyncProvider;
 use sp_version::RuntimeVersion;
 
 use async_trait::async_trait;
@@ -31,7 +32,7 @@ use codec::{Decode, Encode, Error as CodecError};
 use jsonrpsee_core::ClientError as JsonRpcError;
 use sp_api::ApiError;
 
-use cumulus_primitives_core::relay_chain::{BlockId, Hash as RelayHash};
+use cumulus_primitives_core::relay_chain::{Block as PBlock, BlockId, Hash as RelayHash};
 pub use cumulus_primitives_core::{
 	relay_chain::{
 		vstaging::{CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CoreState},
@@ -248,6 +249,22 @@ pub trait RelayChainInterface: Send + Sync {
 
 	/// Fetch the scheduling lookahead value.
 	async fn scheduling_lookahead(&self, relay_parent: PHash) -> RelayChainResult<u32>;
+
+	/// Returns the GRANDPA warp sync provider of the relay chain node.
+	///
+	/// Only available if the relay chain node has the justifications of the relay chain, which is
+	/// required to generate warp proofs.
+	fn warp_sync_provider(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		None
+	}
+
+	/// Returns a verifier of GRANDPA warp proofs of the relay chain.
+	///
+	/// Proofs are verified starting at the GRANDPA authorities of the relay chain genesis, so the
+	/// verifier doesn't depend on the state of the relay chain node.
+	fn warp_proof_verifier(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		None
+	}
 }
 
 #[async_trait]
@@ -406,6 +423,14 @@ where
 	async fn scheduling_lookahead(&self, relay_parent: PHash) -> RelayChainResult<u32> {
 		(**self).scheduling_lookahead(relay_parent).await
 	}
+
+	fn warp_sync_provider(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		(**self).warp_sync_provider()
+	}
+
+	fn warp_proof_verifier(&self) -> Option<Arc<dyn WarpSyncProvider<PBlock>>> {
+		(**self).warp_proof_verifier()
+	}
 }
 
 /// Helper function to call an arbitrary runtime API using a `RelayChainInterface` client.
@@ -98,6 +98,24 @@ async fn build_interface(
 ) -> RelayChainResult<(Arc<(dyn RelayChainInterface + 'static)>, Option<CollatorPair>)> {
 	let collator_pair = CollatorPair::generate().0;
 	let blockchain_rpc_client = Arc::new(BlockChainRpcClient::new(client.clone()));
+	// The RPC node only has the genesis state if it doesn't prune it, otherwise the chain spec is
+	// used instead.
+	let genesis_authorities = match client.chain_get_block_hash(Some(0)).await {
+		Ok(Some(genesis_hash)) => client
+			.call_remote_runtime_function(
+				"GrandpaApi_grandpa_authorities",
+				genesis_hash,
+				None::<()>,
+			)
+			.await
+			.ok(),
+		_ => None,
+	};
+	let warp_proof_verifier = polkadot_service::grandpa_warp_proof_verifier(
+		&polkadot_config.chain_spec,
+		genesis_authorities,
+	)
+	.map_err(|e| RelayChainError::Application(Box::new(e) as Box<_>))?;
 
 	// If the network backend is unspecified, use the default for the given chain.
 	let default_backend = polkadot_config.chain_spec.network_backend();
@@ -121,7 +139,10 @@ async fn build_interface(
 	};
 	task_manager.add_child(collator_node.task_manager);
 	Ok((
-		Arc::new(RelayChainRpcInterface::new(client, collator_node.overseer_handle)),
+		Arc::new(
+			RelayChainRpcInterface::new(client, collator_node.overseer_handle)
+				.with_warp_proof_verifier(Arc::new(warp_proof_verifier)),
+		),
 		Some(collator_pair),
 	))
 }
@@ -19,9 +19,9 @@ use async_trait::async_trait;
 use core::time::Duration;
 use cumulus_primitives_core::{
 	relay_chain::{
-		vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, Hash as RelayHash,
-		Header as RelayHeader, InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex,
-		ValidationCodeHash, ValidatorId,
+		vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, Block as RelayBlock,
+		Hash as RelayHash, Header as RelayHeader, InboundHrmpMessage, OccupiedCoreAssumption,
+		SessionIndex, ValidationCodeHash, ValidatorId,
 	},
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
@@ -32,10 +32,11 @@ use futures::{FutureExt, Stream, StreamExt};
 use polkadot_overseer::Handle;
 
 use sc_client_api::StorageProof;
+use sc_network_sync::strategy::warp::WarpSyncProvider;
 use sp_state_machine::StorageValue;
 use sp_storage::StorageKey;
 use sp_version::RuntimeVersion;
-use std::{collections::btree_map::BTreeMap, pin::Pin};
+use std::{collections::btree_map::BTreeMap, pin::Pin, sync::Arc};
 
 use cumulus_primitives_core::relay_chain::BlockId;
 pub use url::Url;
@@ -59,11 +60,21 @@ const TIMEOUT_IN_SECONDS: u64 = 6;
 pub struct RelayChainRpcInterface {
 	rpc_client: RelayChainRpcClient,
 	overseer_handle: Handle,
+	warp_proof_verifier: Option<Arc<dyn WarpSyncProvider<RelayBlock>>>,
 }
 
 impl RelayChainRpcInterface {
 	pub fn new(rpc_client: RelayChainRpcClient, overseer_handle: Handle) -> Self {
-		Self { rpc_client, overseer_handle }
+		Self { rpc_client, overseer_handle, warp_proof_verifier: None }
+	}
+
+	/// Use the given verifier of GRANDPA warp proofs of the relay chain.
+	pub fn with_warp_proof_verifier(
+		mut self,
+		warp_proof_verifier: Arc<dyn WarpSyncProvider<RelayBlock>>,
+	) -> Self {
+		self.warp_proof_verifier = Some(warp_proof_verifier);
+		self
 	}
 }
 
@@ -287,4 +298,8 @@ impl RelayChainInterface for RelayChainRpcInterface {
 	async fn scheduling_lookahead(&self, relay_parent: RelayHash) -> RelayChainResult<u32> {
 		self.rpc_client.parachain_host_scheduling_lookahead(relay_parent).await
 	}
+
+	fn warp_proof_verifier(&self) -> Option<Arc<dyn WarpSyncProvider<RelayBlock>>> {
+		self.warp_proof_verifier.clone()
+	}
 }
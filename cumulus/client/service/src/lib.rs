@@ -23,7 +23,10 @@ use cumulus_client_cli::CollatorOptions;
 use cumulus_client_consensus_common::ParachainConsensus;
 use cumulus_client_network::{AssumeSybilResistance, RequireSecondedInBlockAnnounce};
 use cumulus_client_pov_recovery::{PoVRecovery, RecoveryDelayRange, RecoveryHandle};
-use cumulus_primitives_core::{CollectCollationInfo, ParaId};
+use cumulus_primitives_core::{
+	relay_chain::{Block as RelayBlock, BlockId},
+	CollectCollationInfo, ParaId,
+};
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
 use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
 use cumulus_relay_chain_minimal_node::{
@@ -39,7 +42,7 @@ use sc_consensus::{
 	BlockImport,
 };
 use sc_network::{config::SyncMode, service::traits::NetworkService, NetworkBackend};
-use sc_network_sync::SyncingService;
+use sc_network_sync::{strategy::warp::WarpSyncProvider, SyncingService};
 use sc_network_transactions::TransactionsHandlerController;
 use sc_service::{Configuration, SpawnTaskHandle, TaskManager, WarpSyncConfig};
 use sc_telemetry::{log, TelemetryWorkerHandle};
@@ -51,6 +54,9 @@ use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header};
 use std::{sync::Arc, time::Duration};
 
 pub use cumulus_primitives_proof_size_hostfunction::storage_proof_size;
+pub use warp_sync::{collect_para_head_proofs, ParaHeadProofs, ParachainWarpSyncProvider};
+
+mod warp_sync;
 
 /// Host functions that should be used in parachain nodes.
 ///
@@ -425,6 +431,9 @@ pub struct BuildNetworkParams<
 	pub relay_chain_interface: RCInterface,
 	pub spawn_handle: SpawnTaskHandle,
 	pub import_queue: IQ,
+	/// Warp sync with the warp proofs of other parachain nodes instead of waiting for the relay
+	/// chain to announce the target block.
+	pub warp_sync_with_proofs: bool,
 	pub sybil_resistance_level: CollatorSybilResistance,
 }
 
@@ -439,6 +448,7 @@ pub async fn build_network<'a, Block, Client, RCInterface, IQ, Network>(
 		spawn_handle,
 		relay_chain_interface,
 		import_queue,
+		warp_sync_with_proofs,
 		sybil_resistance_level,
 	}: BuildNetworkParams<'a, Block, Client, Network, RCInterface, IQ>,
 ) -> sc_service::error::Result<(
@@ -468,8 +478,35 @@ where
 	IQ: ImportQueue<Block> + 'static,
 	Network: NetworkBackend<Block, <Block as BlockT>::Hash>,
 {
-	let warp_sync_config = match parachain_config.network.sync_mode {
-		SyncMode::Warp => {
+	let sync_mode = parachain_config.network.sync_mode;
+	// A node warp syncing to the announced target block doesn't serve warp proofs either.
+	let warp_sync_provider = match relay_chain_interface.warp_proof_verifier() {
+		Some(relay_verifier) if warp_sync_with_proofs || !matches!(sync_mode, SyncMode::Warp) =>
+			Some(
+				build_warp_sync_provider::<Block>(
+					para_id,
+					client.info().genesis_hash,
+					relay_verifier,
+					Arc::new(relay_chain_interface.clone()),
+					&spawn_handle,
+				)
+				.await?,
+			),
+		_ => None,
+	};
+
+	let warp_sync_config = match (sync_mode, warp_sync_provider) {
+		// Warp syncs with the proofs of other nodes only if asked to.
+		(SyncMode::Warp, Some(warp_sync_provider)) if warp_sync_with_proofs =>
+			Some(WarpSyncConfig::WithProvider(warp_sync_provider)),
+		(SyncMode::Warp, _) => {
+			if warp_sync_with_proofs {
+				log::warn!(
+					target: LOG_TARGET_SYNC,
+					"Relay chain interface can't verify warp proofs, waiting for announce block instead",
+				);
+			}
+
 			log::debug!(target: LOG_TARGET_SYNC, "waiting for announce block...");
 
 			let target_block =
@@ -484,7 +521,9 @@ where
 					})?;
 			Some(WarpSyncConfig::WithTarget(target_block))
 		},
-		_ => None,
+		// Only serves warp proofs to other nodes.
+		(_, Some(warp_sync_provider)) => Some(WarpSyncConfig::WithProvider(warp_sync_provider)),
+		(_, None) => None,
 	};
 
 	let block_announce_validator = match sybil_resistance_level {
@@ -516,6 +555,45 @@ where
 	})
 }
 
+/// Builds the warp sync provider of the parachain on top of the relay chain warp proof verifier.
+///
+/// If the relay chain node is also able to generate relay chain warp proofs, the provider serves
+/// warp proofs to other nodes and the parachain head proofs they require are collected on a
+/// background task.
+async fn build_warp_sync_provider<Block: BlockT>(
+	para_id: ParaId,
+	genesis_hash: Block::Hash,
+	relay_verifier: Arc<dyn WarpSyncProvider<RelayBlock>>,
+	relay_chain_interface: Arc<dyn RelayChainInterface>,
+	spawn_handle: &SpawnTaskHandle,
+) -> sc_service::error::Result<Arc<dyn WarpSyncProvider<Block>>> {
+	let relay_genesis_hash = relay_chain_interface
+		.header(BlockId::Number(0))
+		.await
+		.map_err(|e| format!("Unable to fetch relay chain genesis header: {e}"))?
+		.ok_or("Relay chain genesis header not found")?
+		.hash();
+
+	let mut provider = ParachainWarpSyncProvider::<Block>::new(
+		para_id,
+		genesis_hash,
+		relay_genesis_hash,
+		relay_verifier,
+	);
+
+	if let Some(relay_generator) = relay_chain_interface.warp_sync_provider() {
+		let para_head_proofs = ParaHeadProofs::default();
+		spawn_handle.spawn(
+			"cumulus-warp-sync-para-head-proofs",
+			None,
+			collect_para_head_proofs(para_id, relay_chain_interface, para_head_proofs.clone()),
+		);
+		provider = provider.with_generator(relay_generator, para_head_proofs);
+	}
+
+	Ok(Arc::new(provider))
+}
+
 /// Waits for the relay chain to have finished syncing and then gets the parachain header that
 /// corresponds to the last finalized relay chain block.
 async fn wait_for_finalized_para_head<B, RCInterface>(
@@ -0,0 +1,628 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Warp sync for parachain nodes.
+//!
+//! The parachain doesn't have a finality gadget of its own, its blocks are final once they are
+//! included in a finalized relay chain block. [`ParachainWarpSyncProvider`] therefore proves the
+//! latest finalized parachain head with a GRANDPA warp proof of the relay chain, followed by a
+//! relay chain state proof of the `paras::Heads` entry of the parachain at the last relay chain
+//! block the warp proof finalizes, or at one of its ancestors together with the headers linking
+//! the two.
+//!
+//! Relay chain warp proofs are verified starting at the GRANDPA authorities of the relay chain
+//! genesis, so any parachain node can warp sync, independently of the state of its relay chain
+//! node. Generating proofs requires a relay chain node with the justifications of the relay chain,
+//! i.e. an in-process relay chain node.
+
+use codec::{Decode, Encode};
+use cumulus_primitives_core::{
+	relay_chain::{well_known_keys, Block as RelayBlock, BlockId, HeadData, Header as RelayHeader},
+	ParaId,
+};
+use cumulus_relay_chain_interface::{PHash, RelayChainInterface};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use sc_client_api::StorageProof;
+use sc_consensus_grandpa::warp_proof::WarpSyncFragment;
+use sc_network_sync::strategy::warp::{
+	AuthorityList, EncodedProof, SetId, VerificationResult, WarpSyncProvider,
+};
+use sc_telemetry::log;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header as HeaderT};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+};
+
+const LOG_TARGET: &str = "sync::cumulus";
+
+/// The number of finalized relay chain blocks for which the parachain head proof is kept.
+const PARA_HEAD_PROOFS_TO_KEEP: usize = 32;
+
+/// The maximum number of relay chain blocks finalized at once, for which the headers are fetched to
+/// link them to the previously finalized blocks. The collected proofs are dropped on larger gaps.
+const MAX_FINALITY_GAP: usize = 256;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Warp proof of a parachain.
+#[derive(Encode, Decode)]
+struct ParachainWarpProof {
+	/// The SCALE-encoded relay chain GRANDPA warp proof.
+	relay_proof: Vec<u8>,
+	/// Proof of the parachain head in the state of the last relay chain block finalized by
+	/// `relay_proof`, or one of its ancestors.
+	///
+	/// Only present if the relay chain warp proof is complete.
+	para_head_proof: Option<ParaHeadProof>,
+}
+
+/// Proof of the parachain head at a finalized relay chain block.
+#[derive(Encode, Decode)]
+struct ParaHeadProof {
+	/// The headers from the parent of the finalized relay chain block down to the relay chain
+	/// block the head is proven at.
+	///
+	/// Empty if the head is proven at the finalized block itself.
+	ancestry: Vec<RelayHeader>,
+	/// Proof of the `paras::Heads` entry of the parachain.
+	proof: StorageProof,
+}
+
+/// The most recently finalized relay chain blocks.
+#[derive(Default)]
+struct FinalizedRelayBlocks {
+	/// Headers of the finalized blocks starting at the oldest proven one, without gaps.
+	headers: VecDeque<RelayHeader>,
+	/// The proven blocks, oldest first.
+	proven: VecDeque<PHash>,
+	/// Proofs of the parachain head at the proven blocks.
+	proofs: HashMap<PHash, StorageProof>,
+}
+
+/// Proofs of the parachain head at the most recently finalized relay chain blocks.
+///
+/// Warp proofs are generated synchronously, so the parachain head proofs are collected in advance
+/// by [`collect_para_head_proofs`].
+#[derive(Clone, Default)]
+pub struct ParaHeadProofs(Arc<Mutex<FinalizedRelayBlocks>>);
+
+impl ParaHeadProofs {
+	/// Adds the relay chain blocks finalized together, oldest first, with the proof of the
+	/// parachain head at the last of them.
+	fn insert(&self, finalized: Vec<RelayHeader>, proof: StorageProof) {
+		let Some(relay_hash) = finalized.last().map(|header| header.hash()) else { return };
+
+		let mut blocks = self.0.lock();
+		if finalized.first().map(|header| header.parent_hash) != blocks.last_finalized() {
+			// Not linked to the previously finalized blocks.
+			*blocks = Default::default();
+		}
+		blocks.headers.extend(finalized);
+		blocks.proven.push_back(relay_hash);
+		blocks.proofs.insert(relay_hash, proof);
+
+		if blocks.proven.len() > PARA_HEAD_PROOFS_TO_KEEP {
+			if let Some(pruned) = blocks.proven.pop_front() {
+				blocks.proofs.remove(&pruned);
+			}
+			let oldest = blocks.proven.front().copied();
+			while blocks.headers.front().is_some_and(|header| Some(header.hash()) != oldest) {
+				blocks.headers.pop_front();
+			}
+		}
+	}
+
+	fn last_finalized(&self) -> Option<PHash> {
+		self.0.lock().last_finalized()
+	}
+
+	/// Returns the proof of the parachain head at the given relay chain block, or else at its
+	/// nearest proven ancestor.
+	fn get(&self, relay_header: &RelayHeader) -> Option<ParaHeadProof> {
+		let blocks = self.0.lock();
+		let relay_hash = relay_header.hash();
+		let position = blocks.headers.iter().rposition(|header| header.hash() == relay_hash)?;
+
+		let mut ancestry = Vec::new();
+		for (index, header) in blocks.headers.range(..=position).enumerate().rev() {
+			if index != position {
+				ancestry.push(header.clone());
+			}
+			if let Some(proof) = blocks.proofs.get(&header.hash()) {
+				return Some(ParaHeadProof { ancestry, proof: proof.clone() })
+			}
+		}
+		None
+	}
+}
+
+impl FinalizedRelayBlocks {
+	fn last_finalized(&self) -> Option<PHash> {
+		self.headers.back().map(|header| header.hash())
+	}
+}
+
+/// Collects the proofs of the parachain head at every finalized relay chain block.
+pub async fn collect_para_head_proofs(
+	para_id: ParaId,
+	relay_chain_interface: Arc<dyn RelayChainInterface>,
+	para_head_proofs: ParaHeadProofs,
+) {
+	let mut finality_notifications =
+		match relay_chain_interface.finality_notification_stream().await {
+			Ok(finality_notifications) => finality_notifications,
+			Err(e) => {
+				log::error!(target: LOG_TARGET, "Unable to follow relay chain finality: {e}");
+				return
+			},
+		};
+
+	let key = well_known_keys::para_head(para_id);
+	while let Some(header) = finality_notifications.next().await {
+		let relay_hash = header.hash();
+		let proof = match relay_chain_interface.prove_read(relay_hash, &vec![key.clone()]).await {
+			Ok(proof) => proof,
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Unable to prove parachain head at relay chain block {relay_hash:?}: {e}"
+				);
+				continue
+			},
+		};
+
+		let finalized =
+			finalized_since(&*relay_chain_interface, header, para_head_proofs.last_finalized())
+				.await;
+		para_head_proofs.insert(finalized, proof);
+	}
+}
+
+/// Returns the relay chain blocks finalized together with `header`, oldest first.
+///
+/// The headers of the blocks finalized implicitly are fetched up to the previously finalized block
+/// `last_finalized`, or up to [`MAX_FINALITY_GAP`] blocks.
+async fn finalized_since(
+	relay_chain_interface: &dyn RelayChainInterface,
+	header: RelayHeader,
+	last_finalized: Option<PHash>,
+) -> Vec<RelayHeader> {
+	let mut finalized = vec![header];
+	let Some(last_finalized) = last_finalized else { return finalized };
+
+	while let Some(oldest) = finalized.last() {
+		if oldest.parent_hash == last_finalized || finalized.len() > MAX_FINALITY_GAP {
+			break
+		}
+		match relay_chain_interface.header(BlockId::Hash(oldest.parent_hash)).await {
+			Ok(Some(parent)) => finalized.push(parent),
+			Ok(None) => break,
+			Err(e) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Unable to fetch relay chain header {:?}: {e}",
+					oldest.parent_hash
+				);
+				break
+			},
+		}
+	}
+	finalized.reverse();
+	finalized
+}
+
+/// Warp sync provider of a parachain.
+///
+/// Without a relay chain warp proof generator, see [`Self::with_generator`], the provider is only
+/// able to verify warp proofs.
+pub struct ParachainWarpSyncProvider<Block: BlockT> {
+	para_id: ParaId,
+	genesis_hash: Block::Hash,
+	relay_genesis_hash: PHash,
+	relay_verifier: Arc<dyn WarpSyncProvider<RelayBlock>>,
+	generator: Option<(Arc<dyn WarpSyncProvider<RelayBlock>>, ParaHeadProofs)>,
+}
+
+impl<Block: BlockT> ParachainWarpSyncProvider<Block> {
+	/// Create a new instance.
+	///
+	/// `genesis_hash` is the hash of the parachain genesis block. Warp proofs of the relay chain
+	/// are verified by `relay_verifier`, starting at the relay chain genesis block with hash
+	/// `relay_genesis_hash`.
+	pub fn new(
+		para_id: ParaId,
+		genesis_hash: Block::Hash,
+		relay_genesis_hash: PHash,
+		relay_verifier: Arc<dyn WarpSyncProvider<RelayBlock>>,
+	) -> Self {
+		Self { para_id, genesis_hash, relay_genesis_hash, relay_verifier, generator: None }
+	}
+
+	/// Generate warp proofs with the given relay chain warp sync provider.
+	///
+	/// The parachain head proofs are taken from `para_head_proofs`, so proofs can only be served
+	/// up to relay chain blocks for which [`collect_para_head_proofs`] already proved the head.
+	pub fn with_generator(
+		mut self,
+		relay_generator: Arc<dyn WarpSyncProvider<RelayBlock>>,
+		para_head_proofs: ParaHeadProofs,
+	) -> Self {
+		self.generator = Some((relay_generator, para_head_proofs));
+		self
+	}
+
+	/// Warp sync requests of the parachain start at the parachain genesis and then continue from
+	/// the relay chain blocks returned by [`VerificationResult::Partial`].
+	fn relay_start(&self, start: Block::Hash) -> Result<PHash, BoxError> {
+		if start == self.genesis_hash {
+			return Ok(self.relay_genesis_hash)
+		}
+		convert_hash(&start)
+	}
+
+	fn para_head_proof(
+		&self,
+		relay_proof: &[u8],
+		para_head_proofs: &ParaHeadProofs,
+	) -> Result<Option<ParaHeadProof>, BoxError> {
+		let (fragments, is_finished) =
+			<(Vec<WarpSyncFragment<RelayBlock>>, bool)>::decode(&mut &relay_proof[..])?;
+		let Some(last) = fragments.last().filter(|_| is_finished) else { return Ok(None) };
+
+		let proof = para_head_proofs.get(&last.header).ok_or(
+			"Parachain head proof at or before the last finalized relay chain block not available",
+		)?;
+		Ok(Some(proof))
+	}
+
+	fn verify_para_head(
+		&self,
+		relay_header: &RelayHeader,
+		proof: Option<ParaHeadProof>,
+	) -> Result<Block::Header, BoxError> {
+		let ParaHeadProof { ancestry, proof } = proof.ok_or("Missing parachain head proof")?;
+
+		let mut proven_at = relay_header;
+		for header in &ancestry {
+			if header.hash() != proven_at.parent_hash {
+				return Err(
+					"Parachain head proof not linked to the finalized relay chain block".into()
+				)
+			}
+			proven_at = header;
+		}
+
+		let key = well_known_keys::para_head(self.para_id);
+		let head = sp_state_machine::read_proof_check::<BlakeTwo256, _>(
+			proven_at.state_root,
+			proof,
+			[&key],
+		)
+		.map_err(|e| format!("Invalid parachain head proof: {e:?}"))?
+		.remove(&key)
+		.flatten()
+		.ok_or("Parachain head not found in relay chain state")?;
+
+		let head = HeadData::decode(&mut &head[..])?;
+		Ok(Block::Header::decode(&mut &head.0[..])?)
+	}
+}
+
+impl<Block: BlockT> WarpSyncProvider<Block> for ParachainWarpSyncProvider<Block> {
+	fn generate(&self, start: Block::Hash) -> Result<EncodedProof, BoxError> {
+		let (relay_generator, para_head_proofs) =
+			self.generator.as_ref().ok_or("Generating warp proofs is not supported")?;
+
+		let EncodedProof(relay_proof) = relay_generator.generate(self.relay_start(start)?)?;
+		let para_head_proof = self.para_head_proof(&relay_proof, para_head_proofs)?;
+
+		Ok(EncodedProof(ParachainWarpProof { relay_proof, para_head_proof }.encode()))
+	}
+
+	fn verify(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<VerificationResult<Block>, BoxError> {
+		let ParachainWarpProof { relay_proof, para_head_proof } =
+			ParachainWarpProof::decode(&mut &proof.0[..])
+				.map_err(|e| format!("Proof decoding error: {e:?}"))?;
+
+		match self.relay_verifier.verify(&EncodedProof(relay_proof), set_id, authorities)? {
+			VerificationResult::Partial(set_id, authorities, relay_hash) =>
+				Ok(VerificationResult::Partial(set_id, authorities, convert_hash(&relay_hash)?)),
+			VerificationResult::Complete(set_id, authorities, relay_header) => {
+				let header = self.verify_para_head(&relay_header, para_head_proof)?;
+				Ok(VerificationResult::Complete(set_id, authorities, header))
+			},
+		}
+	}
+
+	fn current_authorities(&self) -> AuthorityList {
+		self.relay_verifier.current_authorities()
+	}
+}
+
+/// Converts between parachain and relay chain block hashes.
+///
+/// Relay chain block hashes are passed through the warp sync strategy of the parachain as
+/// parachain block hashes.
+fn convert_hash<A: AsRef<[u8]>, B: Decode>(hash: &A) -> Result<B, BoxError> {
+	B::decode(&mut hash.as_ref()).map_err(|_| "Incompatible relay chain block hash".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cumulus_primitives_core::relay_chain::Hash;
+	use sp_core::storage::StateVersion;
+
+	type Block = RelayBlock;
+
+	const PARA_ID: ParaId = ParaId::new(100);
+	const GENESIS: Hash = Hash::repeat_byte(0xaa);
+	const RELAY_GENESIS: Hash = Hash::repeat_byte(0xbb);
+
+	/// Relay chain provider returning a fixed proof, which accepts any proof without checking the
+	/// justifications.
+	struct TestRelayProvider {
+		proof: Vec<u8>,
+		requested: Mutex<Vec<PHash>>,
+	}
+
+	impl TestRelayProvider {
+		fn new(proof: Vec<u8>) -> Arc<Self> {
+			Arc::new(Self { proof, requested: Default::default() })
+		}
+	}
+
+	impl WarpSyncProvider<RelayBlock> for TestRelayProvider {
+		fn generate(&self, start: PHash) -> Result<EncodedProof, BoxError> {
+			self.requested.lock().push(start);
+			Ok(EncodedProof(self.proof.clone()))
+		}
+
+		fn verify(
+			&self,
+			proof: &EncodedProof,
+			set_id: SetId,
+			authorities: AuthorityList,
+		) -> Result<VerificationResult<RelayBlock>, BoxError> {
+			let (fragments, is_finished) =
+				<(Vec<WarpSyncFragment<RelayBlock>>, bool)>::decode(&mut &proof.0[..])?;
+			let header = fragments.last().ok_or("Empty proof")?.header.clone();
+			if is_finished {
+				Ok(VerificationResult::Complete(set_id + 1, authorities, header))
+			} else {
+				Ok(VerificationResult::Partial(set_id + 1, authorities, header.hash()))
+			}
+		}
+
+		fn current_authorities(&self) -> AuthorityList {
+			Vec::new()
+		}
+	}
+
+	fn relay_proof(header: RelayHeader, is_finished: bool) -> Vec<u8> {
+		let justification = sp_consensus_grandpa::GrandpaJustification::<RelayHeader> {
+			round: 1,
+			commit: sp_consensus_grandpa::Commit::<RelayHeader> {
+				target_hash: header.hash(),
+				target_number: header.number,
+				precommits: Vec::new(),
+			},
+			votes_ancestries: Vec::new(),
+		};
+		let fragment =
+			WarpSyncFragment::<RelayBlock> { header, justification: justification.into() };
+		(vec![fragment], is_finished).encode()
+	}
+
+	fn relay_header(state_root: Hash) -> RelayHeader {
+		RelayHeader::new(10, Default::default(), state_root, Default::default(), Default::default())
+	}
+
+	fn child_header(parent: &RelayHeader) -> RelayHeader {
+		RelayHeader::new(
+			parent.number + 1,
+			Default::default(),
+			Hash::repeat_byte(parent.number as u8 + 1),
+			parent.hash(),
+			Default::default(),
+		)
+	}
+
+	fn para_header() -> RelayHeader {
+		RelayHeader::new(5, Default::default(), Hash::repeat_byte(1), GENESIS, Default::default())
+	}
+
+	/// Returns a relay chain header with the given parachain head in its state, together with the
+	/// proof of the head.
+	fn relay_block_with_para_head(para_head: &RelayHeader) -> (RelayHeader, StorageProof) {
+		let key = well_known_keys::para_head(PARA_ID);
+		let value = HeadData(para_head.encode()).encode();
+		let backend = sp_state_machine::InMemoryBackend::<BlakeTwo256>::from((
+			vec![(None, vec![(key.clone(), Some(value))])],
+			StateVersion::V1,
+		));
+		let header = relay_header(*backend.root());
+		let proof = sp_state_machine::prove_read(backend, [&key]).unwrap();
+		(header, proof)
+	}
+
+	fn provider(
+		relay_provider: Arc<TestRelayProvider>,
+		para_head_proofs: ParaHeadProofs,
+	) -> ParachainWarpSyncProvider<Block> {
+		ParachainWarpSyncProvider::new(PARA_ID, GENESIS, RELAY_GENESIS, relay_provider.clone())
+			.with_generator(relay_provider, para_head_proofs)
+	}
+
+	#[test]
+	fn complete_proof_generate_verify() {
+		let para_head = para_header();
+		let (relay_header, head_proof) = relay_block_with_para_head(&para_head);
+		let para_head_proofs = ParaHeadProofs::default();
+		para_head_proofs.insert(vec![relay_header.clone()], head_proof);
+
+		let relay_provider = TestRelayProvider::new(relay_proof(relay_header, true));
+		let provider = provider(relay_provider.clone(), para_head_proofs);
+
+		let proof = provider.generate(GENESIS).unwrap();
+		assert_eq!(*relay_provider.requested.lock(), vec![RELAY_GENESIS]);
+
+		match provider.verify(&proof, 0, Vec::new()).unwrap() {
+			VerificationResult::Complete(set_id, _, header) => {
+				assert_eq!(set_id, 1);
+				assert_eq!(header, para_head);
+			},
+			VerificationResult::Partial(..) => panic!("The proof is complete"),
+		}
+	}
+
+	#[test]
+	fn partial_proof_continues_at_relay_chain_block() {
+		let relay_header = relay_header(Hash::repeat_byte(2));
+		let relay_hash = relay_header.hash();
+
+		let relay_provider = TestRelayProvider::new(relay_proof(relay_header, false));
+		let provider = provider(relay_provider.clone(), Default::default());
+
+		// no parachain head proof is required for partial proofs
+		let proof = provider.generate(GENESIS).unwrap();
+		let next = match provider.verify(&proof, 0, Vec::new()).unwrap() {
+			VerificationResult::Partial(set_id, _, hash) => {
+				assert_eq!(set_id, 1);
+				hash
+			},
+			VerificationResult::Complete(..) => panic!("The proof is partial"),
+		};
+		assert_eq!(next, relay_hash);
+
+		provider.generate(next).unwrap();
+		assert_eq!(*relay_provider.requested.lock(), vec![RELAY_GENESIS, relay_hash]);
+	}
+
+	#[test]
+	fn generate_fails_without_para_head_proof() {
+		let (relay_header, _) = relay_block_with_para_head(&para_header());
+
+		let relay_provider = TestRelayProvider::new(relay_proof(relay_header, true));
+		let provider = provider(relay_provider, Default::default());
+
+		assert!(provider.generate(GENESIS).is_err());
+	}
+
+	#[test]
+	fn generate_fails_without_generator() {
+		let relay_provider = TestRelayProvider::new(relay_proof(relay_header(GENESIS), true));
+		let provider = ParachainWarpSyncProvider::<Block>::new(
+			PARA_ID,
+			GENESIS,
+			RELAY_GENESIS,
+			relay_provider,
+		);
+
+		assert!(provider.generate(GENESIS).is_err());
+	}
+
+	#[test]
+	fn verify_rejects_para_head_proof_of_other_state() {
+		let (_, head_proof) = relay_block_with_para_head(&para_header());
+		let relay_header = relay_header(Hash::repeat_byte(2));
+		let para_head_proofs = ParaHeadProofs::default();
+		para_head_proofs.insert(vec![relay_header.clone()], head_proof);
+
+		let relay_provider = TestRelayProvider::new(relay_proof(relay_header, true));
+		let provider = provider(relay_provider, para_head_proofs);
+
+		let proof = provider.generate(GENESIS).unwrap();
+		assert!(provider.verify(&proof, 0, Vec::new()).is_err());
+	}
+
+	#[test]
+	fn falls_back_to_para_head_proof_at_ancestor() {
+		let para_head = para_header();
+		let (proven_header, head_proof) = relay_block_with_para_head(&para_head);
+		let finalized_header = child_header(&proven_header);
+		let para_head_proofs = ParaHeadProofs::default();
+		para_head_proofs.insert(vec![proven_header], head_proof);
+		// The head couldn't be proven at `finalized_header`, which was finalized together with its
+		// child.
+		para_head_proofs.insert(
+			vec![finalized_header.clone(), child_header(&finalized_header)],
+			StorageProof::empty(),
+		);
+
+		let relay_provider = TestRelayProvider::new(relay_proof(finalized_header, true));
+		let provider = provider(relay_provider, para_head_proofs);
+
+		let proof = provider.generate(GENESIS).unwrap();
+		match provider.verify(&proof, 0, Vec::new()).unwrap() {
+			VerificationResult::Complete(_, _, header) => assert_eq!(header, para_head),
+			VerificationResult::Partial(..) => panic!("The proof is complete"),
+		}
+	}
+
+	#[test]
+	fn verify_rejects_para_head_proof_at_unrelated_block() {
+		let (proven_header, head_proof) = relay_block_with_para_head(&para_header());
+		let finalized_header = relay_header(Hash::repeat_byte(2));
+		let proof = ParachainWarpProof {
+			relay_proof: relay_proof(finalized_header, true),
+			para_head_proof: Some(ParaHeadProof {
+				ancestry: vec![proven_header],
+				proof: head_proof,
+			}),
+		};
+
+		let relay_provider = TestRelayProvider::new(Vec::new());
+		let provider = provider(relay_provider, Default::default());
+		assert!(provider.verify(&EncodedProof(proof.encode()), 0, Vec::new()).is_err());
+	}
+
+	#[test]
+	fn only_recent_para_head_proofs_are_kept() {
+		let para_head_proofs = ParaHeadProofs::default();
+		let mut headers = vec![relay_header(Hash::repeat_byte(0))];
+		for _ in 0..PARA_HEAD_PROOFS_TO_KEEP {
+			headers.push(child_header(headers.last().unwrap()));
+		}
+		for header in &headers {
+			para_head_proofs.insert(vec![header.clone()], StorageProof::empty());
+		}
+
+		assert!(para_head_proofs.get(&headers[0]).is_none());
+		assert!(para_head_proofs.get(&headers[1]).is_some());
+		assert!(para_head_proofs.get(&headers[PARA_HEAD_PROOFS_TO_KEEP]).is_some());
+	}
+
+	#[test]
+	fn unlinked_blocks_drop_para_head_proofs() {
+		let para_head_proofs = ParaHeadProofs::default();
+		let first = relay_header(Hash::repeat_byte(1));
+		let unlinked = relay_header(Hash::repeat_byte(2));
+		para_head_proofs.insert(vec![first.clone()], StorageProof::empty());
+		para_head_proofs.insert(vec![unlinked.clone()], StorageProof::empty());
+
+		assert!(para_head_proofs.get(&first).is_none());
+		assert!(para_head_proofs.get(&unlinked).is_some());
+	}
+}
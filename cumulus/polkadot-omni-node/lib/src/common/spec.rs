@@ -303,6 +303,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 					spawn_handle: task_manager.spawn_handle(),
 					relay_chain_interface: relay_chain_interface.clone(),
 					import_queue: params.import_queue,
+					warp_sync_with_proofs: collator_options.warp_sync_with_proofs,
 					sybil_resistance_level: Self::SYBIL_RESISTANCE,
 				})
 				.await?;
@@ -384,6 +384,7 @@ where
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			warp_sync_with_proofs: false,
 			sybil_resistance_level: CollatorSybilResistance::Resistant, /* Either Aura that is
 			                                                             * resistant or null that
 			                                                             * is not producing any
@@ -755,7 +756,10 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options = CollatorOptions { relay_chain_mode: self.relay_chain_mode };
+		let collator_options = CollatorOptions {
+			relay_chain_mode: self.relay_chain_mode,
+			warp_sync_with_proofs: false,
+		};
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);
@@ -56,6 +56,128 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Replay the validation of a candidate from the local databases.
+	ReplayCandidate(ReplayCandidateCmd),
+
+	/// Export the persisted PVF artifacts into a bundle.
+	ExportPvfArtifacts(PvfArtifactsBundleCmd),
+
+	/// Import PVF artifacts from a bundle, to be used on the next start with
+	/// `--persist-pvf-artifacts`.
+	ImportPvfArtifacts(PvfArtifactsBundleCmd),
+
+	/// Inspect the approval state of the unfinalized blocks of a running validator.
+	InspectApprovals(InspectApprovalsCmd),
+
+	/// Export the recent disputes known to a running validator.
+	ExportDisputes(ExportDisputesCmd),
+}
+
+/// The `replay-candidate` command.
+///
+/// Rebuilds the available data of a candidate from the local availability store and runs it
+/// through the PVF host with the executor parameters of the session it was validated in.
+#[derive(Debug, Parser)]
+pub struct ReplayCandidateCmd {
+	/// Hash of the candidate to replay.
+	#[arg(long, value_name = "HASH")]
+	pub candidate: sp_core::H256,
+
+	/// Hash of the relay chain block the candidate was backed or included in.
+	#[arg(long, value_name = "HASH")]
+	pub relay_block: sp_core::H256,
+
+	/// Directory to write the validation code, the PoV, the persisted validation data, the
+	/// candidate receipt and the prepared artifact to.
+	///
+	/// Defaults to `candidate-<HASH>` in the current directory.
+	#[arg(long, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	/// Path to the directory where auxiliary worker binaries reside.
+	///
+	/// If not specified, the main binary's directory is searched first, then
+	/// `/usr/lib/polkadot` is searched.
+	#[arg(long, value_name = "PATH")]
+	pub workers_path: Option<PathBuf>,
+
+	/// TESTING ONLY: disable the version check between nodes and workers.
+	#[arg(long, hide = true)]
+	pub disable_worker_version_check: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub import_params: sc_cli::ImportParams,
+}
+
+impl sc_cli::CliConfiguration for ReplayCandidateCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&sc_cli::ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// The `export-pvf-artifacts` and `import-pvf-artifacts` commands.
+///
+/// Bundles allow new validators to start with the artifacts prepared by another node, instead of
+/// preparing every PVF on their first start. Bundles can only be imported by nodes of the same
+/// version as the exporting node. Artifacts are native code, so only import bundles from trusted
+/// sources.
+#[derive(Debug, Parser)]
+pub struct PvfArtifactsBundleCmd {
+	/// Path of the bundle file.
+	#[arg(value_name = "FILE")]
+	pub bundle: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+}
+
+impl sc_cli::CliConfiguration for PvfArtifactsBundleCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+}
+
+/// The `inspect-approvals` command.
+///
+/// Queries the `parachain_inspectApprovals` RPC of a running validator and lists, for each
+/// unfinalized block, the candidates lacking approvals together with their current tranche and
+/// the validators which no-showed. The node has to expose unsafe RPC methods.
+#[derive(Debug, Parser)]
+pub struct InspectApprovalsCmd {
+	/// WebSocket URL of the node's RPC server.
+	#[arg(long, value_name = "URL", default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Print the full inspection as JSON, including approved candidates.
+	#[arg(long)]
+	pub json: bool,
+}
+
+/// The `export-disputes` command.
+///
+/// Queries the `parachain_exportDisputes` RPC of a running validator and lists the recent
+/// disputes with their votes and the outcome of the node's own participation. The node has to
+/// expose unsafe RPC methods.
+#[derive(Debug, Parser)]
+pub struct ExportDisputesCmd {
+	/// WebSocket URL of the node's RPC server.
+	#[arg(long, value_name = "URL", default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Print the full export as JSON, including every statement.
+	#[arg(long)]
+	pub json: bool,
 }
 
 #[allow(missing_docs)]
@@ -153,11 +275,26 @@ pub struct RunCmd {
 	#[arg(long, default_value = "true", action=ArgAction::Set)]
 	pub enable_approval_voting_parallel: bool,
 
+	/// Use the experimental, reputation based, validator side of the collator protocol.
+	///
+	/// Requires the node to be compiled with the `experimental-collator-protocol` feature,
+	/// should not be used unless explicitly advised to.
+	#[arg(long, hide = true)]
+	pub experimental_collator_protocol: bool,
+
 	/// How long finalized data should be kept in the availability store (in hours).
 	/// Only used for testnets. If not specified, set to 1 hour. Always set to 25 hours for live
 	/// networks.
 	#[arg(long)]
 	pub keep_finalized_for: Option<u32>,
+
+	/// Keep prepared PVF artifacts across restarts.
+	///
+	/// Artifacts are checked for integrity on startup and are reused as long as the wasmtime
+	/// version of the node doesn't change, so that PVFs don't all have to be prepared again after
+	/// a restart or an upgrade.
+	#[arg(long)]
+	pub persist_pvf_artifacts: bool,
 }
 
 #[allow(missing_docs)]
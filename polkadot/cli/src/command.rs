@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand, NODE_VERSION};
+use crate::cli::{Cli, ExportDisputesCmd, InspectApprovalsCmd, Subcommand, NODE_VERSION};
 use frame_benchmarking_cli::{
 	BenchmarkCmd, ExtrinsicFactory, SubstrateRemarkBuilder, SUBSTRATE_REFERENCE_HARDWARE,
 };
@@ -157,6 +157,132 @@ fn set_default_ss58_version(spec: &Box<dyn polkadot_service::ChainSpec>) {
 	sp_core::crypto::set_default_ss58_version(ss58_version);
 }
 
+/// Query the approval state of a running node and print the candidates lacking approvals.
+fn inspect_approvals(cmd: &InspectApprovalsCmd) -> Result<()> {
+	use jsonrpsee::ws_client::WsClientBuilder;
+	use polkadot_node_primitives::approval::inspection::TrancheRequirement;
+	use polkadot_rpc::approvals::ApprovalsApiClient;
+
+	let runtime = sc_cli::build_runtime().map_err(sc_cli::Error::from)?;
+	let blocks = runtime.block_on(async {
+		let client = WsClientBuilder::default().max_response_size(u32::MAX).build(&cmd.url).await?;
+		let mut blocks = Vec::new();
+		let mut from = None;
+		loop {
+			let page = client.inspect_approvals(from).await?;
+			blocks.extend(page.blocks);
+			match page.next {
+				Some(next) => from = Some(next),
+				None => break,
+			}
+		}
+		Ok::<_, jsonrpsee::core::client::Error>(blocks)
+	})?;
+
+	if cmd.json {
+		println!("{}", serde_json::to_string_pretty(&blocks)?);
+		return Ok(())
+	}
+
+	if blocks.is_empty() {
+		println!("No unfinalized blocks are tracked by approval voting");
+	}
+
+	for block in &blocks {
+		let unapproved: Vec<_> = block.unapproved_candidates().collect();
+		println!(
+			"#{} {:?} (session {}): {} of {} candidates unapproved",
+			block.block_number,
+			block.block_hash,
+			block.session,
+			unapproved.len(),
+			block.candidates.len(),
+		);
+
+		for candidate in unapproved {
+			let required = match candidate.required_tranches {
+				Some(TrancheRequirement::All) => "all validators".to_string(),
+				Some(TrancheRequirement::Pending { considered }) =>
+					format!("pending, considered up to tranche {considered}"),
+				Some(TrancheRequirement::Exact { needed, tolerated_missing }) =>
+					format!("up to tranche {needed}, {tolerated_missing} missing tolerated"),
+				None => "unknown".to_string(),
+			};
+			let no_shows: Vec<_> = candidate.no_shows.iter().map(|validator| validator.0).collect();
+			println!(
+				"  candidate {:?} (para {}, core {}): tranche {}, required {}, {} approvals of \
+				 {} assignments, no-shows {:?}",
+				candidate.candidate_hash,
+				candidate.para_id,
+				candidate.core_index,
+				candidate.tranche_now,
+				required,
+				candidate.approvals,
+				candidate.assignments,
+				no_shows,
+			);
+		}
+	}
+
+	Ok(())
+}
+
+/// Query the recent disputes of a running node and print them.
+fn export_disputes(cmd: &ExportDisputesCmd) -> Result<()> {
+	use jsonrpsee::ws_client::WsClientBuilder;
+	use polkadot_rpc::disputes::DisputesApiClient;
+
+	let runtime = sc_cli::build_runtime().map_err(sc_cli::Error::from)?;
+	let disputes = runtime.block_on(async {
+		let client = WsClientBuilder::default().max_response_size(u32::MAX).build(&cmd.url).await?;
+		let mut disputes = Vec::new();
+		let mut from = None;
+		loop {
+			let page = client.export_disputes(from).await?;
+			disputes.extend(page.disputes);
+			match page.next {
+				Some(next) => from = Some(next),
+				None => break,
+			}
+		}
+		Ok::<_, jsonrpsee::core::client::Error>(disputes)
+	})?;
+
+	if cmd.json {
+		println!("{}", serde_json::to_string_pretty(&disputes)?);
+		return Ok(())
+	}
+
+	if disputes.is_empty() {
+		println!("No recent disputes are known to the dispute coordinator");
+	}
+
+	for dispute in &disputes {
+		let valid = dispute.statements.iter().filter(|statement| statement.valid).count();
+		let participation = match &dispute.participation {
+			Some(record) => match &record.error {
+				Some(error) => format!("{:?} ({error})", record.result),
+				None => format!("{:?}", record.result),
+			},
+			None => "none".to_string(),
+		};
+		println!(
+			"session {} candidate {:?} (para {}): {:?}, {} valid and {} invalid votes, \
+			 {} unconfirmed spam slots, participation {}",
+			dispute.session,
+			dispute.candidate_hash,
+			dispute.para_id,
+			dispute.status,
+			valid,
+			dispute.statements.len() - valid,
+			dispute.spam_slots.len(),
+			participation,
+		);
+	}
+
+	Ok(())
+}
+
 /// Launch a node, accepting arguments just like a regular node,
 /// accepts an alternative overseer generator, to adjust behavior
 /// for integration tests as needed.
@@ -235,7 +361,9 @@ where
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
+				enable_experimental_collator_protocol: cli.run.experimental_collator_protocol,
 				keep_finalized_for: cli.run.keep_finalized_for,
+				persist_pvf_artifacts: cli.run.persist_pvf_artifacts,
 			},
 		)
 		.map(|full| full.task_manager)?;
@@ -463,6 +591,62 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<polkadot_service::Block>(&config))?)
 		},
+		Some(Subcommand::ReplayCandidate(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			let chain_spec = &runner.config().chain_spec;
+
+			set_default_ss58_version(chain_spec);
+
+			let params = polkadot_service::candidate_replay::CandidateReplayParams {
+				candidate_hash: polkadot_service::CandidateHash(cmd.candidate),
+				relay_block: cmd.relay_block,
+				output_dir: cmd
+					.output
+					.clone()
+					.unwrap_or_else(|| format!("candidate-{:?}", cmd.candidate).into()),
+				workers_path: cmd.workers_path.clone(),
+				node_version: if cmd.disable_worker_version_check {
+					None
+				} else {
+					Some(NODE_VERSION.to_string())
+				},
+			};
+
+			Ok(runner.async_run(|mut config| {
+				let (client, _, _, task_manager) =
+					polkadot_service::new_chain_ops(&mut config).map_err(Error::PolkadotService)?;
+				Ok((
+					async move {
+						let report = polkadot_service::candidate_replay::replay_candidate(
+							client, &config, params,
+						)
+						.await
+						.map_err(polkadot_service::Error::from)?;
+						println!("{report}");
+						Ok::<_, Error>(())
+					},
+					task_manager,
+				))
+			})?)
+		},
+		Some(Subcommand::ExportPvfArtifacts(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				let exported = polkadot_service::export_pvf_artifacts(&config, &cmd.bundle)?;
+				println!("Exported {exported} PVF artifacts to {}", cmd.bundle.display());
+				Ok::<_, Error>(())
+			})?)
+		},
+		Some(Subcommand::ImportPvfArtifacts(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				let imported = polkadot_service::import_pvf_artifacts(&config, &cmd.bundle)?;
+				println!("Imported {imported} PVF artifacts from {}", cmd.bundle.display());
+				Ok::<_, Error>(())
+			})?)
+		},
+		Some(Subcommand::InspectApprovals(cmd)) => inspect_approvals(cmd),
+		Some(Subcommand::ExportDisputes(cmd)) => export_disputes(cmd),
 	}?;
 
 	#[cfg(feature = "pyroscope")]
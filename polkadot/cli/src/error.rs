@@ -48,6 +48,12 @@ pub enum Error {
 	#[error(transparent)]
 	Storage(#[from] sc_storage_monitor::Error),
 
+	#[error(transparent)]
+	Rpc(#[from] jsonrpsee::core::client::Error),
+
+	#[error(transparent)]
+	Json(#[from] serde_json::Error),
+
 	#[error("Other: {0}")]
 	Other(String),
 
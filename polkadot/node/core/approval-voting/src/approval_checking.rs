@@ -24,7 +24,7 @@ use crate::{
 	persisted_entries::{ApprovalEntry, CandidateEntry, TrancheEntry},
 	MAX_RECORDED_NO_SHOW_VALIDATORS_PER_CANDIDATE,
 };
-use polkadot_node_primitives::approval::time::Tick;
+use polkadot_node_primitives::approval::{inspection::TrancheRequirement, time::Tick};
 
 /// Result of counting the necessary tranches needed for approving a block.
 #[derive(Debug, PartialEq, Clone)]
@@ -74,6 +74,18 @@ pub enum RequiredTranches {
 	},
 }
 
+impl From<&RequiredTranches> for TrancheRequirement {
+	fn from(required: &RequiredTranches) -> Self {
+		match *required {
+			RequiredTranches::All => TrancheRequirement::All,
+			RequiredTranches::Pending { considered, .. } =>
+				TrancheRequirement::Pending { considered },
+			RequiredTranches::Exact { needed, tolerated_missing, .. } =>
+				TrancheRequirement::Exact { needed, tolerated_missing: tolerated_missing as _ },
+		}
+	}
+}
+
 /// The result of a check.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Check {
@@ -24,6 +24,10 @@
 use futures_timer::Delay;
 use polkadot_node_primitives::{
 	approval::{
+		inspection::{
+			ApprovalsInspectionPage, BlockApprovalInspection, CandidateApprovalInspection,
+			TrancheRequirement,
+		},
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{
 			AssignmentCertKindV2, BitfieldError, CandidateBitfield, CoreBitfield,
@@ -126,6 +130,11 @@ const APPROVAL_CACHE_SIZE: u32 = 1024;
 /// The maximum number of times we retry to approve a block if is still needed.
 const MAX_APPROVAL_RETRIES: u32 = 16;
 
+/// The number of blocks after which an approvals inspection stops at the next block number.
+///
+/// Inspections are answered from the main loop, so each request has to stay cheap.
+const MAX_INSPECTED_BLOCKS_PER_PAGE: usize = 16;
+
 const APPROVAL_DELAY: Tick = 2;
 pub(crate) const LOG_TARGET: &str = "parachain::approval-voting";
 
@@ -2163,12 +2172,122 @@ async fn handle_from_overseer<
 				.await?;
 				Vec::new()
 			},
+			ApprovalVotingMessage::InspectApprovals(from, tx) => {
+				let inspection =
+					inspect_approvals(sender, state, db, session_info_provider, from).await?;
+				let _ = tx.send(inspection);
+				Vec::new()
+			},
 		},
 	};
 
 	Ok(actions)
 }
 
+/// Inspect the approval state of the unfinalized blocks from block number `from` on, ordered by
+/// block number.
+///
+/// Stops after the first block number at which [`MAX_INSPECTED_BLOCKS_PER_PAGE`] blocks have been
+/// inspected.
+async fn inspect_approvals<Sender: SubsystemSender<RuntimeApiMessage>>(
+	sender: &mut Sender,
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	session_info_provider: &mut RuntimeInfo,
+	from: BlockNumber,
+) -> SubsystemResult<ApprovalsInspectionPage> {
+	let mut inspections = Vec::new();
+	let mut next = None;
+	let range = db.load_stored_blocks()?.map_or(0..0, |range| range.0.max(from)..range.1);
+	for height in range {
+		if inspections.len() >= MAX_INSPECTED_BLOCKS_PER_PAGE {
+			next = Some(height);
+			break
+		}
+
+		let mut block_hashes = db.load_blocks_at_height(&height)?;
+		block_hashes.sort();
+		for block_hash in block_hashes {
+			let block_entry = match db.load_block_entry(&block_hash)? {
+				Some(block_entry) => block_entry,
+				None => continue,
+			};
+			inspections.push(
+				inspect_block_approvals(
+					sender,
+					state,
+					db,
+					session_info_provider,
+					block_hash,
+					block_entry,
+				)
+				.await?,
+			);
+		}
+	}
+
+	Ok(ApprovalsInspectionPage { blocks: inspections, next })
+}
+
+/// Inspect the approval state of the candidates included by a single block.
+async fn inspect_block_approvals<Sender: SubsystemSender<RuntimeApiMessage>>(
+	sender: &mut Sender,
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	session_info_provider: &mut RuntimeInfo,
+	block_hash: Hash,
+	block_entry: BlockEntry,
+) -> SubsystemResult<BlockApprovalInspection> {
+	let tranche_now = state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+
+	let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+	for (core_index, candidate_hash) in block_entry.candidates() {
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			Some(candidate_entry) => candidate_entry,
+			None => continue,
+		};
+
+		// Without session info only the stored assignments can be reported.
+		let (assignments, required_tranches, no_shows) = match state
+			.approval_status(sender, session_info_provider, &block_entry, &candidate_entry)
+			.await
+		{
+			Some((approval_entry, status)) => (
+				approval_entry.n_assignments(),
+				Some(TrancheRequirement::from(&status.required_tranches)),
+				status.no_show_validators,
+			),
+			None => (
+				candidate_entry
+					.approval_entry(&block_hash)
+					.map_or(0, |approval_entry| approval_entry.n_assignments()),
+				None,
+				Vec::new(),
+			),
+		};
+
+		candidates.push(CandidateApprovalInspection {
+			candidate_hash: candidate_hash.0,
+			para_id: candidate_entry.candidate_receipt().descriptor.para_id(),
+			core_index: core_index.0,
+			approved: block_entry.is_candidate_approved(candidate_hash),
+			tranche_now,
+			required_tranches,
+			assignments: assignments as _,
+			approvals: candidate_entry.approvals().count_ones() as _,
+			no_shows,
+		});
+	}
+
+	Ok(BlockApprovalInspection {
+		block_hash,
+		block_number: block_entry.block_number(),
+		session: block_entry.session(),
+		approved: block_entry.is_fully_approved(),
+		candidates,
+	})
+}
+
 /// Retrieve approval signatures.
 ///
 /// This involves an unbounded message send to approval-distribution, the caller has to ensure that
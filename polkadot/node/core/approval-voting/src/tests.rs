@@ -1632,6 +1632,107 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+#[test]
+fn subsystem_inspects_approvals_of_unfinalized_blocks() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_hash = {
+			let mut candidate_receipt =
+				dummy_candidate_receipt_v2_bad_sig(block_hash, Some(Default::default()));
+			candidate_receipt.descriptor.set_para_id(ParaId::from(0_u32));
+			candidate_receipt.descriptor.set_relay_parent(block_hash);
+			candidate_receipt.hash()
+		};
+
+		let candidate_index = 0;
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(1),
+					candidates: None,
+					session_info: Some(session_info(&[Sr25519Keyring::Alice, Sr25519Keyring::Bob])),
+					end_syncing: false,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let rx =
+			import_assignment(&mut virtual_overseer, block_hash, candidate_index, validator, 0)
+				.await;
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		let rx = import_approval(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+			candidate_hash,
+			session_index,
+			true,
+			None,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOrchestra::Communication { msg: ApprovalVotingMessage::InspectApprovals(0, tx) },
+		)
+		.await;
+
+		// A single approval does not satisfy the two needed approvals.
+		let inspection = rx.await.unwrap();
+		assert_eq!(inspection.next, None);
+		assert_eq!(inspection.blocks.len(), 1);
+		let block = &inspection.blocks[0];
+		assert_eq!(block.block_hash, block_hash);
+		assert_eq!(block.block_number, 1);
+		assert_eq!(block.session, session_index);
+		assert!(!block.approved);
+		assert_eq!(block.unapproved_candidates().count(), 1);
+
+		let candidate = &block.candidates[0];
+		assert_eq!(candidate.candidate_hash, candidate_hash.0);
+		assert_eq!(candidate.para_id, ParaId::from(0_u32));
+		assert!(!candidate.approved);
+		assert!(candidate.required_tranches.is_some());
+		assert_eq!(candidate.assignments, 1);
+		assert_eq!(candidate.approvals, 1);
+		assert!(candidate.no_shows.is_empty());
+
+		// Inspecting from a later block number skips the block.
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOrchestra::Communication { msg: ApprovalVotingMessage::InspectApprovals(2, tx) },
+		)
+		.await;
+		let inspection = rx.await.unwrap();
+		assert!(inspection.blocks.is_empty());
+		assert_eq!(inspection.next, None);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -502,6 +502,44 @@ impl AvailabilityStoreSubsystem {
 	}
 }
 
+/// Load the [`AvailableData`] of a candidate directly from the availability store database,
+/// without running the subsystem.
+///
+/// If the full data is not stored, it is reconstructed from the locally stored erasure chunks.
+/// `n_validators` must be the size of the validator set of the session the candidate was backed
+/// in. Returns `None` if neither the data nor enough chunks to recover it are stored.
+pub fn load_available_data_offline(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: &CandidateHash,
+	n_validators: usize,
+) -> Result<Option<AvailableData>, Error> {
+	if let Some(available_data) = load_available_data(db, config, candidate_hash)? {
+		return Ok(Some(available_data))
+	}
+
+	let Some(meta) = load_meta(db, config, candidate_hash)? else { return Ok(None) };
+
+	let mut chunks = Vec::new();
+	for validator_index in meta.chunks_stored.iter_ones() {
+		let validator_index = ValidatorIndex(validator_index as _);
+		if let Some(chunk) = load_chunk(db, config, candidate_hash, validator_index)? {
+			chunks.push(chunk);
+		}
+	}
+
+	if chunks.len() < polkadot_erasure_coding::recovery_threshold(n_validators)? {
+		return Ok(None)
+	}
+
+	let available_data = polkadot_erasure_coding::reconstruct_v1(
+		n_validators,
+		chunks.iter().map(|chunk| (&chunk.chunk[..], chunk.index.0 as usize)),
+	)?;
+
+	Ok(Some(available_data))
+}
+
 /// We keep the hashes and numbers of all unfinalized
 /// processed blocks in memory.
 #[derive(Default, Debug)]
@@ -1384,3 +1384,68 @@ fn query_chunk_size_works() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn load_available_data_offline_reconstructs_from_chunks() {
+	let store = test_store();
+	let test_state = TestState::default();
+	let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+	let n_validators = 10;
+
+	let available_data = AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+		validation_data: test_state.persisted_validation_data,
+	};
+
+	assert!(load_available_data_offline(&store, &TEST_CONFIG, &candidate_hash, n_validators)
+		.unwrap()
+		.is_none());
+
+	let chunks =
+		polkadot_erasure_coding::obtain_chunks_v1(n_validators as _, &available_data).unwrap();
+	let branches: Vec<_> = polkadot_erasure_coding::branches(chunks.as_ref()).collect();
+	let threshold = polkadot_erasure_coding::recovery_threshold(n_validators).unwrap();
+
+	let store_chunks = |stored: usize| {
+		let mut chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+		with_tx(&store, |tx| {
+			for (index, (proof, chunk)) in branches.iter().enumerate().take(stored) {
+				let chunk = ErasureChunk {
+					chunk: chunk.to_vec(),
+					index: ChunkIndex(index as _),
+					proof: Proof::try_from(proof.clone()).unwrap(),
+				};
+				chunks_stored.set(index, true);
+				super::write_chunk(
+					tx,
+					&TEST_CONFIG,
+					&candidate_hash,
+					ValidatorIndex(index as _),
+					&chunk,
+				);
+			}
+			super::write_meta(
+				tx,
+				&TEST_CONFIG,
+				&candidate_hash,
+				&CandidateMeta {
+					data_available: false,
+					chunks_stored,
+					state: State::Unavailable(BETimestamp(0)),
+				},
+			);
+		});
+	};
+
+	// One chunk less than needed for recovery.
+	store_chunks(threshold - 1);
+	assert!(load_available_data_offline(&store, &TEST_CONFIG, &candidate_hash, n_validators)
+		.unwrap()
+		.is_none());
+
+	store_chunks(threshold);
+	assert_eq!(
+		load_available_data_offline(&store, &TEST_CONFIG, &candidate_hash, n_validators).unwrap(),
+		Some(available_data),
+	);
+}
@@ -99,6 +99,8 @@ const TASK_LIMIT: usize = 30;
 pub struct Config {
 	/// The path where candidate validation can store compiled artifacts for PVFs.
 	pub artifacts_cache_path: PathBuf,
+	/// Whether compiled artifacts are kept across restarts.
+	pub persist_artifacts: bool,
 	/// The version of the node. `None` can be passed to skip the version check (only for tests).
 	pub node_version: Option<String>,
 	/// Whether the node is attempting to run as a secure validator.
@@ -310,6 +312,7 @@ async fn run<Context>(
 	pvf_metrics: polkadot_node_core_pvf::Metrics,
 	Config {
 		artifacts_cache_path,
+		persist_artifacts,
 		node_version,
 		secure_validator_mode,
 		prep_worker_path,
@@ -319,20 +322,19 @@ async fn run<Context>(
 		pvf_prepare_workers_hard_max_num,
 	}: Config,
 ) -> SubsystemResult<()> {
-	let (mut validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(
-			artifacts_cache_path,
-			node_version,
-			secure_validator_mode,
-			prep_worker_path,
-			exec_worker_path,
-			pvf_execute_workers_max_num,
-			pvf_prepare_workers_soft_max_num,
-			pvf_prepare_workers_hard_max_num,
-		),
-		pvf_metrics,
-	)
-	.await?;
+	let mut pvf_config = polkadot_node_core_pvf::Config::new(
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num,
+		pvf_prepare_workers_soft_max_num,
+		pvf_prepare_workers_hard_max_num,
+	);
+	pvf_config.persist_artifacts = persist_artifacts;
+	let (mut validation_host, task) =
+		polkadot_node_core_pvf::start(pvf_config, pvf_metrics).await?;
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
 	let mut tasks = FuturesUnordered::new();
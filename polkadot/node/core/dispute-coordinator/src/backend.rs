@@ -21,6 +21,7 @@
 //! [`Backend`], maintaining consistency between queries and temporary writes,
 //! before any commit to the underlying storage is made.
 
+use polkadot_node_primitives::disputes::ParticipationRecord;
 use polkadot_primitives::{CandidateHash, SessionIndex};
 
 use std::collections::HashMap;
@@ -32,6 +33,7 @@ use crate::error::FatalResult;
 pub enum BackendWriteOp {
 	WriteEarliestSession(SessionIndex),
 	WriteRecentDisputes(RecentDisputes),
+	WriteParticipationRecord(SessionIndex, CandidateHash, ParticipationRecord),
 	WriteCandidateVotes(SessionIndex, CandidateHash, CandidateVotes),
 	DeleteCandidateVotes(SessionIndex, CandidateHash),
 }
@@ -44,6 +46,13 @@ pub trait Backend {
 	/// Load the recent disputes, if any.
 	fn load_recent_disputes(&self) -> FatalResult<Option<RecentDisputes>>;
 
+	/// Load the record of our own participation for the specific session-candidate pair, if any.
+	fn load_participation_record(
+		&self,
+		session: SessionIndex,
+		candidate_hash: &CandidateHash,
+	) -> FatalResult<Option<ParticipationRecord>>;
+
 	/// Load the candidate votes for the specific session-candidate pair, if any.
 	fn load_candidate_votes(
 		&self,
@@ -70,6 +79,8 @@ pub struct OverlayedBackend<'a, B: 'a> {
 	earliest_session: Option<SessionIndex>,
 	// `None` means unchanged.
 	recent_disputes: Option<RecentDisputes>,
+	// Missing means query inner.
+	participation_records: HashMap<(SessionIndex, CandidateHash), ParticipationRecord>,
 	// `None` means deleted, missing means query inner.
 	candidate_votes: HashMap<(SessionIndex, CandidateHash), Option<CandidateVotes>>,
 }
@@ -80,6 +91,7 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 			inner: backend,
 			earliest_session: None,
 			recent_disputes: None,
+			participation_records: HashMap::new(),
 			candidate_votes: HashMap::new(),
 		}
 	}
@@ -88,6 +100,7 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 	pub fn is_empty(&self) -> bool {
 		self.earliest_session.is_none() &&
 			self.recent_disputes.is_none() &&
+			self.participation_records.is_empty() &&
 			self.candidate_votes.is_empty()
 	}
 
@@ -109,6 +122,19 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.inner.load_recent_disputes()
 	}
 
+	/// Load the record of our own participation for the specific session-candidate pair, if any.
+	pub fn load_participation_record(
+		&self,
+		session: SessionIndex,
+		candidate_hash: &CandidateHash,
+	) -> FatalResult<Option<ParticipationRecord>> {
+		if let Some(val) = self.participation_records.get(&(session, *candidate_hash)) {
+			return Ok(Some(val.clone()))
+		}
+
+		self.inner.load_participation_record(session, candidate_hash)
+	}
+
 	/// Load the candidate votes for the specific session-candidate pair, if any.
 	pub fn load_candidate_votes(
 		&self,
@@ -136,6 +162,18 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.recent_disputes = Some(recent_disputes)
 	}
 
+	/// Prepare a write of the record of our own participation under the indicated candidate.
+	///
+	/// Later calls to this function for the same candidate will override earlier ones.
+	pub fn write_participation_record(
+		&mut self,
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		record: ParticipationRecord,
+	) {
+		self.participation_records.insert((session, candidate_hash), record);
+	}
+
 	/// Prepare a write of the candidate votes under the indicated candidate.
 	///
 	/// Later calls to this function for the same candidate will override earlier ones.
@@ -158,6 +196,11 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		let recent_dispute_ops =
 			self.recent_disputes.map(|d| BackendWriteOp::WriteRecentDisputes(d)).into_iter();
 
+		let participation_record_ops =
+			self.participation_records.into_iter().map(|((session, candidate), record)| {
+				BackendWriteOp::WriteParticipationRecord(session, candidate, record)
+			});
+
 		let candidate_vote_ops =
 			self.candidate_votes
 				.into_iter()
@@ -166,6 +209,9 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 					None => BackendWriteOp::DeleteCandidateVotes(session, candidate),
 				});
 
-		earliest_session_ops.chain(recent_dispute_ops).chain(candidate_vote_ops)
+		earliest_session_ops
+			.chain(recent_dispute_ops)
+			.chain(participation_record_ops)
+			.chain(candidate_vote_ops)
 	}
 }
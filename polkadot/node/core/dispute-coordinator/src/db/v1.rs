@@ -22,7 +22,7 @@
 //! the dispute data in the database. Any breaking changes here will still
 //! require a db migration (check `node/service/src/parachains_db/upgrade.rs`).
 
-use polkadot_node_primitives::DisputeStatus;
+use polkadot_node_primitives::{disputes::ParticipationRecord, DisputeStatus};
 use polkadot_node_subsystem_util::database::{DBTransaction, Database};
 use polkadot_primitives::{
 	vstaging::CandidateReceiptV2 as CandidateReceipt, CandidateHash, Hash,
@@ -44,6 +44,7 @@ use crate::{
 const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
 const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
 const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
+const PARTICIPATION_RECORD_SUBKEY: &[u8; 20] = b"participation-record";
 /// Until what session have votes been cleaned up already?
 const CLEANED_VOTES_WATERMARK_KEY: &[u8; 23] = b"cleaned-votes-watermark";
 
@@ -106,6 +107,10 @@ impl DbBackend {
 			"Cleaning votes for session index"
 			);
 			tx.delete_prefix(self.config.col_dispute_data, &candidate_votes_session_prefix(index));
+			tx.delete_prefix(
+				self.config.col_dispute_data,
+				&participation_record_session_prefix(index),
+			);
 		}
 		// New watermark:
 		tx.put_vec(self.config.col_dispute_data, CLEANED_VOTES_WATERMARK_KEY, clean_until.encode());
@@ -124,6 +129,15 @@ impl Backend for DbBackend {
 		load_recent_disputes(&*self.inner, &self.config)
 	}
 
+	/// Load the record of our own participation for the specific session-candidate pair, if any.
+	fn load_participation_record(
+		&self,
+		session: SessionIndex,
+		candidate_hash: &CandidateHash,
+	) -> FatalResult<Option<ParticipationRecord>> {
+		load_participation_record(&*self.inner, &self.config, session, candidate_hash)
+	}
+
 	/// Load the candidate votes for the specific session-candidate pair, if any.
 	fn load_candidate_votes(
 		&self,
@@ -167,6 +181,13 @@ impl Backend for DbBackend {
 						recent_disputes.encode(),
 					);
 				},
+				BackendWriteOp::WriteParticipationRecord(session, candidate_hash, record) => {
+					tx.put_vec(
+						self.config.col_dispute_data,
+						&participation_record_key(session, &candidate_hash),
+						record.encode(),
+					);
+				},
 				BackendWriteOp::WriteCandidateVotes(session, candidate_hash, votes) => {
 					gum::trace!(target: LOG_TARGET, ?session, "Writing candidate votes");
 					tx.put_vec(
@@ -208,6 +229,29 @@ fn candidate_votes_session_prefix(session: SessionIndex) -> [u8; 15 + 4] {
 	buf
 }
 
+fn participation_record_key(
+	session: SessionIndex,
+	candidate_hash: &CandidateHash,
+) -> [u8; 20 + 4 + 32] {
+	let mut buf = [0u8; 20 + 4 + 32];
+	buf[..20].copy_from_slice(PARTICIPATION_RECORD_SUBKEY);
+
+	// big-endian encoding is used to ensure lexicographic ordering.
+	buf[20..][..4].copy_from_slice(&session.to_be_bytes());
+	candidate_hash.using_encoded(|s| buf[(20 + 4)..].copy_from_slice(s));
+
+	buf
+}
+
+fn participation_record_session_prefix(session: SessionIndex) -> [u8; 20 + 4] {
+	let mut buf = [0u8; 20 + 4];
+	buf[..20].copy_from_slice(PARTICIPATION_RECORD_SUBKEY);
+
+	// big-endian encoding is used to ensure lexicographic ordering.
+	buf[20..][..4].copy_from_slice(&session.to_be_bytes());
+	buf
+}
+
 /// Column configuration information for the DB.
 #[derive(Debug, Clone)]
 pub struct ColumnConfiguration {
@@ -314,6 +358,17 @@ pub(crate) fn load_recent_disputes(
 		.map_err(|e| FatalError::DbReadFailed(e))
 }
 
+/// Load the record of our own participation for the specific session-candidate pair, if any.
+pub(crate) fn load_participation_record(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+	session: SessionIndex,
+	candidate_hash: &CandidateHash,
+) -> FatalResult<Option<ParticipationRecord>> {
+	load_decode(db, config.col_dispute_data, &participation_record_key(session, candidate_hash))
+		.map_err(|e| FatalError::DbReadFailed(e))
+}
+
 /// Maybe prune data in the DB based on the provided session index.
 ///
 /// This is intended to be called on every block, and as such will be used to populate the DB on
@@ -347,8 +402,8 @@ pub(crate) fn note_earliest_session(
 
 				if pruned_disputes.len() != 0 {
 					overlay_db.write_recent_disputes(new_recent_disputes);
-					// Note: Deleting old candidate votes is handled in `write` based on the
-					// earliest session.
+					// Note: Deleting old candidate votes and participation records is handled in
+					// `write` based on the earliest session.
 				}
 			}
 		},
@@ -687,4 +742,43 @@ mod tests {
 			.is_some());
 		assert!(overlay_db.load_candidate_votes(very_recent, &hash_d).unwrap().is_some());
 	}
+
+	#[test]
+	fn participation_records_are_persisted_and_pruned() {
+		use polkadot_node_primitives::disputes::ParticipationResult;
+
+		let mut backend = make_db();
+
+		let hash_a = CandidateHash(Hash::repeat_byte(0x0a));
+		let hash_b = CandidateHash(Hash::repeat_byte(0x0b));
+
+		let record = |result| ParticipationRecord {
+			result,
+			error: None,
+			recovery_millis: Some(10),
+			validation_millis: Some(20),
+			concluded_at: 1,
+		};
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_earliest_session(0);
+		overlay_db.write_participation_record(3, hash_a, record(ParticipationResult::Invalid));
+		overlay_db.write_participation_record(5, hash_b, record(ParticipationResult::Valid));
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert!(backend.load_participation_record(3, &hash_a).unwrap().is_some());
+		assert!(backend.load_participation_record(5, &hash_b).unwrap().is_some());
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		note_earliest_session(&mut overlay_db, 5).unwrap();
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(backend.load_participation_record(3, &hash_a).unwrap(), None);
+		assert_eq!(
+			backend.load_participation_record(5, &hash_b).unwrap(),
+			Some(record(ParticipationResult::Valid)),
+		);
+	}
 }
@@ -29,7 +29,8 @@ use futures::{
 use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
-	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
+	disputes::{DisputeStatementRecord, ParticipationRecord, ValidCandidateVotes},
+	CandidateVotes, DisputeForensics, DisputeForensicsPage, DisputeStatus, SignedDisputeStatement,
 	Timestamp, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
@@ -80,6 +81,12 @@ use super::{
 /// updates (and especially on startup) so the dispute coordinator won't be considered stalling.
 const CHAIN_IMPORT_MAX_BATCH_SIZE: usize = 8;
 
+/// How many disputes are exported per `ExportDisputes` request.
+///
+/// Exports are answered from the main loop and load the votes of every exported dispute, so each
+/// request has to stay cheap.
+const MAX_EXPORTED_DISPUTES_PER_PAGE: usize = 32;
+
 // Initial data for `dispute-coordinator`. It is provided only at first start.
 pub struct InitialData {
 	pub participations: Vec<(ParticipationPriority, ParticipationRequest)>,
@@ -242,7 +249,23 @@ impl Initialized {
 							candidate_hash,
 							candidate_receipt,
 							outcome,
+							details,
 						} = self.participation.get_participation_result(ctx, msg).await?;
+						overlay_db.write_participation_record(
+							session,
+							candidate_hash,
+							ParticipationRecord {
+								result: outcome.into(),
+								error: details.error,
+								recovery_millis: details
+									.recovery_time
+									.map(|time| time.as_millis() as u64),
+								validation_millis: details
+									.validation_time
+									.map(|time| time.as_millis() as u64),
+								concluded_at: clock.now(),
+							},
+						);
 						if let Some(valid) = outcome.validity() {
 							gum::trace!(
 								target: LOG_TARGET,
@@ -876,6 +899,44 @@ impl Initialized {
 						.collect(),
 				);
 			},
+			DisputeCoordinatorMessage::ExportDisputes(from, tx) => {
+				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::ExportDisputes");
+				let recent_disputes = overlay_db.load_recent_disputes()?.unwrap_or_default();
+
+				let mut page = DisputeForensicsPage { disputes: Vec::new(), next: None };
+				for (&(session, candidate_hash), &status) in recent_disputes.range(from..) {
+					if page.disputes.len() >= MAX_EXPORTED_DISPUTES_PER_PAGE {
+						page.next = Some((session, candidate_hash.0));
+						break
+					}
+
+					let Some(votes) = overlay_db.load_candidate_votes(session, &candidate_hash)?
+					else {
+						gum::debug!(
+							target: LOG_TARGET,
+							session,
+							?candidate_hash,
+							"No votes found for recent dispute",
+						);
+						continue
+					};
+					let votes = CandidateVotes::from(votes);
+					let key = (session, candidate_hash);
+
+					page.disputes.push(DisputeForensics {
+						session,
+						candidate_hash: candidate_hash.0,
+						para_id: votes.candidate_receipt.descriptor.para_id(),
+						relay_parent: votes.candidate_receipt.descriptor.relay_parent(),
+						status,
+						statements: DisputeStatementRecord::from_votes(&votes),
+						spam_slots: self.spam_slots.unconfirmed_validators(&key).collect(),
+						participation: overlay_db
+							.load_participation_record(session, &candidate_hash)?,
+					});
+				}
+				let _ = tx.send(page);
+			},
 			DisputeCoordinatorMessage::QueryCandidateVotes(query, tx) => {
 				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::QueryCandidateVotes");
 				let mut query_output = Vec::new();
@@ -14,9 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashSet;
-#[cfg(test)]
-use std::time::Duration;
+use std::{
+	collections::HashSet,
+	time::{Duration, Instant},
+};
 
 use futures::{
 	channel::{mpsc, oneshot},
@@ -25,7 +26,7 @@ use futures::{
 #[cfg(test)]
 use futures_timer::Delay;
 
-use polkadot_node_primitives::ValidationResult;
+use polkadot_node_primitives::{disputes::ParticipationResult, ValidationResult};
 use polkadot_node_subsystem::{
 	messages::{AvailabilityRecoveryMessage, CandidateValidationMessage, PvfExecKind},
 	overseer, ActiveLeavesUpdate, RecoveryError,
@@ -100,6 +101,8 @@ pub struct ParticipationStatement {
 	pub candidate_receipt: CandidateReceipt,
 	/// Actual result.
 	pub outcome: ParticipationOutcome,
+	/// Details about the participation, kept for forensics.
+	pub details: ParticipationDetails,
 }
 
 /// Outcome of the validation process.
@@ -126,11 +129,43 @@ impl ParticipationOutcome {
 	}
 }
 
+impl From<ParticipationOutcome> for ParticipationResult {
+	fn from(outcome: ParticipationOutcome) -> Self {
+		match outcome {
+			ParticipationOutcome::Valid => Self::Valid,
+			ParticipationOutcome::Invalid => Self::Invalid,
+			ParticipationOutcome::Unavailable => Self::Unavailable,
+			ParticipationOutcome::Error => Self::Error,
+		}
+	}
+}
+
+/// Details about a participation, besides its outcome.
+#[derive(Debug, Clone, Default)]
+pub struct ParticipationDetails {
+	/// Why the candidate was not found valid, if it wasn't.
+	pub error: Option<String>,
+	/// Time spent recovering the available data.
+	pub recovery_time: Option<Duration>,
+	/// Time spent validating the candidate.
+	pub validation_time: Option<Duration>,
+}
+
 impl WorkerMessage {
-	fn from_request(req: ParticipationRequest, outcome: ParticipationOutcome) -> Self {
+	fn from_request(
+		req: ParticipationRequest,
+		outcome: ParticipationOutcome,
+		details: ParticipationDetails,
+	) -> Self {
 		let session = req.session();
 		let (candidate_hash, candidate_receipt) = req.into_candidate_info();
-		Self(ParticipationStatement { session, candidate_hash, candidate_receipt, outcome })
+		Self(ParticipationStatement {
+			session,
+			candidate_hash,
+			candidate_receipt,
+			outcome,
+			details,
+		})
 	}
 }
 
@@ -298,8 +333,11 @@ async fn participate(
 	#[cfg(test)]
 	// Hack for tests, so we get recovery messages not too early.
 	Delay::new(Duration::from_millis(100)).await;
+	let mut details = ParticipationDetails::default();
+
 	// in order to validate a candidate we need to start by recovering the
 	// available data
+	let recovery_started = Instant::now();
 	let (recover_available_data_tx, recover_available_data_rx) = oneshot::channel();
 	sender
 		.send_message(AvailabilityRecoveryMessage::RecoverAvailableData(
@@ -311,14 +349,17 @@ async fn participate(
 		))
 		.await;
 
-	let available_data = match recover_available_data_rx.await {
+	let recovered = recover_available_data_rx.await;
+	details.recovery_time = Some(recovery_started.elapsed());
+	let available_data = match recovered {
 		Err(oneshot::Canceled) => {
 			gum::warn!(
 				target: LOG_TARGET,
 				"`Oneshot` got cancelled when recovering available data {:?}",
 				req.candidate_hash(),
 			);
-			send_result(&mut result_sender, req, ParticipationOutcome::Error).await;
+			details.error = Some("availability recovery canceled".into());
+			send_result(&mut result_sender, req, ParticipationOutcome::Error, details).await;
 			return
 		},
 		Ok(Ok(data)) => data,
@@ -331,7 +372,8 @@ async fn participate(
 			);
 			// the available data was recovered but it is invalid, therefore we'll
 			// vote negatively for the candidate dispute
-			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
+			details.error = Some("invalid available data".into());
+			send_result(&mut result_sender, req, ParticipationOutcome::Invalid, details).await;
 			return
 		},
 		Ok(Err(RecoveryError::Unavailable)) | Ok(Err(RecoveryError::ChannelClosed)) => {
@@ -341,7 +383,8 @@ async fn participate(
 				session = req.session(),
 				"Can't fetch availability data in participation"
 			);
-			send_result(&mut result_sender, req, ParticipationOutcome::Unavailable).await;
+			details.error = Some("available data unavailable".into());
+			send_result(&mut result_sender, req, ParticipationOutcome::Unavailable, details).await;
 			return
 		},
 	};
@@ -364,12 +407,14 @@ async fn participate(
 				block_hash,
 			);
 
-			send_result(&mut result_sender, req, ParticipationOutcome::Error).await;
+			details.error = Some("validation code unavailable".into());
+			send_result(&mut result_sender, req, ParticipationOutcome::Error, details).await;
 			return
 		},
 		Err(err) => {
 			gum::warn!(target: LOG_TARGET, ?err, "Error when fetching validation code.");
-			send_result(&mut result_sender, req, ParticipationOutcome::Error).await;
+			details.error = Some(format!("fetching validation code failed: {err}"));
+			send_result(&mut result_sender, req, ParticipationOutcome::Error, details).await;
 			return
 		},
 	};
@@ -380,6 +425,7 @@ async fn participate(
 	// We use the approval execution timeout because this is intended to
 	// be run outside of backing and therefore should be subject to the
 	// same level of leeway.
+	let validation_started = Instant::now();
 	let (validation_tx, validation_rx) = oneshot::channel();
 	sender
 		.send_message(CandidateValidationMessage::ValidateFromExhaustive {
@@ -395,14 +441,17 @@ async fn participate(
 
 	// we cast votes (either positive or negative) depending on the outcome of
 	// the validation and if valid, whether the commitments hash matches
-	match validation_rx.await {
+	let validated = validation_rx.await;
+	details.validation_time = Some(validation_started.elapsed());
+	match validated {
 		Err(oneshot::Canceled) => {
 			gum::warn!(
 				target: LOG_TARGET,
 				"`Oneshot` got cancelled when validating candidate {:?}",
 				req.candidate_hash(),
 			);
-			send_result(&mut result_sender, req, ParticipationOutcome::Error).await;
+			details.error = Some("candidate validation canceled".into());
+			send_result(&mut result_sender, req, ParticipationOutcome::Error, details).await;
 			return
 		},
 		Ok(Err(err)) => {
@@ -413,7 +462,8 @@ async fn participate(
 				err,
 			);
 
-			send_result(&mut result_sender, req, ParticipationOutcome::Error).await;
+			details.error = Some(err.to_string());
+			send_result(&mut result_sender, req, ParticipationOutcome::Error, details).await;
 		},
 
 		Ok(Ok(ValidationResult::Invalid(invalid))) => {
@@ -424,10 +474,11 @@ async fn participate(
 				invalid,
 			);
 
-			send_result(&mut result_sender, req, ParticipationOutcome::Invalid).await;
+			details.error = Some(format!("{invalid:?}"));
+			send_result(&mut result_sender, req, ParticipationOutcome::Invalid, details).await;
 		},
 		Ok(Ok(ValidationResult::Valid(_, _))) => {
-			send_result(&mut result_sender, req, ParticipationOutcome::Valid).await;
+			send_result(&mut result_sender, req, ParticipationOutcome::Valid, details).await;
 		},
 	}
 }
@@ -437,8 +488,9 @@ async fn send_result(
 	sender: &mut WorkerMessageSender,
 	req: ParticipationRequest,
 	outcome: ParticipationOutcome,
+	details: ParticipationDetails,
 ) {
-	if let Err(err) = sender.feed(WorkerMessage::from_request(req, outcome)).await {
+	if let Err(err) = sender.feed(WorkerMessage::from_request(req, outcome, details)).await {
 		gum::error!(
 			target: LOG_TARGET,
 			?err,
@@ -127,6 +127,14 @@ impl SpamSlots {
 			}
 		}
 	}
+	/// Validators whose spam slots are taken by the given unconfirmed dispute, ascending.
+	pub fn unconfirmed_validators(
+		&self,
+		key: &(SessionIndex, CandidateHash),
+	) -> impl Iterator<Item = ValidatorIndex> + '_ {
+		self.unconfirmed.get(key).into_iter().flatten().copied()
+	}
+
 	/// Prune all spam slots for sessions older than the given index.
 	pub fn prune_old(&mut self, oldest_index: SessionIndex) {
 		self.unconfirmed.retain(|(session, _), _| *session >= oldest_index);
@@ -32,8 +32,8 @@ use futures::{
 use polkadot_node_subsystem_util::database::Database;
 
 use polkadot_node_primitives::{
-	DisputeMessage, DisputeStatus, SignedDisputeStatement, SignedFullStatement, Statement,
-	DISPUTE_WINDOW,
+	disputes::ParticipationResult, DisputeMessage, DisputeStatus, SignedDisputeStatement,
+	SignedFullStatement, Statement, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -1486,6 +1486,114 @@ fn conflicting_votes_lead_to_dispute_participation() {
 	});
 }
 
+#[test]
+fn export_disputes_includes_statements_and_participation() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1,
+					vec![make_candidate_backed_event(candidate_receipt.clone())],
+				)
+				.await;
+
+			let (valid_vote, invalid_vote) = generate_opposing_votes_pair(
+				&test_state,
+				ValidatorIndex(3),
+				ValidatorIndex(1),
+				candidate_hash,
+				session,
+				VoteType::Explicit,
+			)
+			.await;
+
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(3)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			handle_disabled_validators_queries(&mut virtual_overseer, Vec::new()).await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				candidate_receipt.commitments_hash,
+			)
+			.await;
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ExportDisputes(
+						(0, CandidateHash(Hash::zero())),
+						tx,
+					),
+				})
+				.await;
+
+			let page = rx.await.unwrap();
+			assert_eq!(page.next, None);
+			assert_eq!(page.disputes.len(), 1);
+			let forensics = &page.disputes[0];
+			assert_eq!(forensics.session, session);
+			assert_eq!(forensics.candidate_hash, candidate_hash.0);
+			assert_eq!(forensics.status, DisputeStatus::Active);
+
+			// The two imported votes and our own one.
+			assert_eq!(forensics.statements.len(), 3);
+			assert!(forensics
+				.statements
+				.iter()
+				.any(|s| s.validator_index == ValidatorIndex(1) && !s.valid));
+			assert!(forensics
+				.statements
+				.iter()
+				.any(|s| s.validator_index == ValidatorIndex(3) && s.valid));
+
+			let participation = forensics.participation.as_ref().unwrap();
+			assert_eq!(participation.result, ParticipationResult::Valid);
+			assert_eq!(participation.error, None);
+			assert!(participation.validation_millis.is_some());
+
+			// Exports starting after the dispute are empty.
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ExportDisputes(
+						(session + 1, CandidateHash(Hash::zero())),
+						tx,
+					),
+				})
+				.await;
+			assert!(rx.await.unwrap().disputes.is_empty());
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn positive_votes_dont_trigger_participation() {
 	test_harness(|mut test_state, mut virtual_overseer| {
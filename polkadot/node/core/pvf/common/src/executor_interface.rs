@@ -29,7 +29,23 @@ use sc_executor_common::{
 use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics, WasmtimeRuntime};
 use sp_core::storage::{ChildInfo, TrackedStorageKey};
 use sp_externalities::MultiRemovalResults;
-use std::any::{Any, TypeId};
+use std::{
+	any::{Any, TypeId},
+	sync::OnceLock,
+};
+
+/// Returns the key identifying the wasmtime build and configuration used to compile the artifacts.
+///
+/// Artifacts can only be loaded by the wasmtime build that compiled them, which is why the key is
+/// part of the key of artifacts persisted across restarts.
+pub fn wasmtime_compatibility_key() -> &'static str {
+	static KEY: OnceLock<String> = OnceLock::new();
+	KEY.get_or_init(|| {
+		let hash = sc_executor_wasmtime::artifact_compatibility_hash(&DEFAULT_CONFIG.semantics)
+			.expect("the default configuration is valid; qed");
+		format!("{hash:016x}")
+	})
+}
 
 // Memory configuration
 //
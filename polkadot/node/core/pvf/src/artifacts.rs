@@ -18,7 +18,9 @@
 //!
 //! # Lifecycle of an artifact
 //!
-//! 1. During node start-up, we prune all the cached artifacts, if any.
+//! 1. During node start-up, we prune all the cached artifacts, if any. If artifact persistence is
+//!    enabled, the intact persisted artifacts are then loaded as [`ArtifactState::Prepared`] (see
+//!    [`crate::persisted`]).
 //!
 //! 2. In order to be executed, a PVF should be prepared first. This means that artifacts should
 //!    have an [`ArtifactState::Prepared`] entry for that artifact in the table. If not, the
@@ -35,7 +37,9 @@
 //! 3. The pool gets an available worker and instructs it to work on the given PVF. The worker
 //!    starts compilation. When the worker finishes successfully, it writes the serialized artifact
 //!    into a temporary file and notifies the host that it's done. The host atomically moves
-//!    (renames) the temporary file to the destination filename of the artifact.
+//!    (renames) the temporary file to the destination filename of the artifact. With artifact
+//!    persistence enabled, the artifact is then moved once more into the persisted artifacts
+//!    directory.
 //!
 //! 4. If the worker concluded successfully or returned an error, then the pool notifies the queue.
 //!    In both cases, the queue reports to the host that the result is ready.
@@ -56,6 +60,7 @@
 
 use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX};
 use always_assert::always;
+use codec::{Decode, Encode};
 use polkadot_node_core_pvf_common::{error::PrepareError, pvf::PvfPrepData};
 use polkadot_parachain_primitives::primitives::ValidationCodeHash;
 use polkadot_primitives::ExecutorParamsPrepHash;
@@ -67,7 +72,7 @@ use std::{
 };
 
 /// The extension to use for cached artifacts.
-const ARTIFACT_EXTENSION: &str = "pvf";
+pub(crate) const ARTIFACT_EXTENSION: &str = "pvf";
 
 /// The prefix that artifacts used to start with under the old naming scheme.
 const ARTIFACT_OLD_PREFIX: &str = "wasmtime_";
@@ -87,7 +92,7 @@ pub fn generate_artifact_path(cache_path: &Path) -> PathBuf {
 
 /// Identifier of an artifact. Encodes a code hash of the PVF and a hash of preparation-related
 ///  executor parameter set.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
 pub struct ArtifactId {
 	pub(crate) code_hash: ValidationCodeHash,
 	pub(crate) executor_params_prep_hash: ExecutorParamsPrepHash,
@@ -260,7 +265,6 @@ impl Artifacts {
 	///
 	/// This function should only be used to build the artifact table at startup with valid
 	/// artifact caches.
-	#[cfg(test)]
 	pub(crate) fn insert_prepared(
 		&mut self,
 		artifact_id: ArtifactId,
@@ -24,7 +24,7 @@ use crate::{
 	artifacts::{ArtifactId, ArtifactPathId, ArtifactState, Artifacts, ArtifactsCleanupConfig},
 	execute::{self, PendingExecutionRequest},
 	metrics::Metrics,
-	prepare, Priority, SecurityStatus, ValidationError, LOG_TARGET,
+	persisted, prepare, Priority, SecurityStatus, ValidationError, LOG_TARGET,
 };
 use always_assert::never;
 use futures::{
@@ -44,7 +44,7 @@ use polkadot_parachain_primitives::primitives::ValidationResult;
 use polkadot_primitives::{Hash, PersistedValidationData};
 use std::{
 	collections::HashMap,
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::Arc,
 	time::{Duration, SystemTime},
 };
@@ -204,6 +204,9 @@ pub struct Config {
 	pub execute_worker_spawn_timeout: Duration,
 	/// The maximum number of execute workers that can run at the same time.
 	pub execute_workers_max_num: usize,
+
+	/// Whether prepared artifacts are kept across restarts, instead of being pruned on startup.
+	pub persist_artifacts: bool,
 }
 
 impl Config {
@@ -231,6 +234,8 @@ impl Config {
 			execute_worker_program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
 			execute_workers_max_num,
+
+			persist_artifacts: false,
 		}
 	}
 }
@@ -250,7 +255,13 @@ pub async fn start(
 	gum::debug!(target: LOG_TARGET, ?config, "starting PVF validation host");
 
 	// Make sure the cache is initialized before doing anything else.
-	let artifacts = Artifacts::new(&config.cache_path).await;
+	let mut artifacts = Artifacts::new(&config.cache_path).await;
+	let persisted_artifacts_path = if config.persist_artifacts {
+		persisted::load(&config.cache_path, &mut artifacts);
+		Some(persisted::persisted_path(&config.cache_path))
+	} else {
+		None
+	};
 
 	// Run checks for supported security features once per host startup. If some checks fail, warn
 	// if Secure Validator Mode is disabled and return an error otherwise.
@@ -321,6 +332,7 @@ pub async fn start(
 			cleanup_pulse_interval: Duration::from_secs(3600),
 			cleanup_config: ArtifactsCleanupConfig::default(),
 			artifacts,
+			persisted_artifacts_path,
 			to_host_rx,
 			to_prepare_queue_tx,
 			from_prepare_queue_rx,
@@ -365,6 +377,8 @@ struct Inner {
 	cleanup_pulse_interval: Duration,
 	cleanup_config: ArtifactsCleanupConfig,
 	artifacts: Artifacts,
+	/// The directory successfully prepared artifacts are moved to, if they should be persisted.
+	persisted_artifacts_path: Option<PathBuf>,
 
 	to_host_rx: mpsc::Receiver<ToHost>,
 
@@ -387,6 +401,7 @@ async fn run(
 		cleanup_pulse_interval,
 		cleanup_config,
 		mut artifacts,
+		persisted_artifacts_path,
 		to_host_rx,
 		from_prepare_queue_rx,
 		mut to_prepare_queue_tx,
@@ -479,6 +494,7 @@ async fn run(
 				// worker but we don't for the sake of simplicity.
 				break_if_fatal!(handle_prepare_done(
 					&mut artifacts,
+					persisted_artifacts_path.as_deref(),
 					&mut to_execute_queue_tx,
 					&mut awaiting_prepare,
 					from_queue,
@@ -767,6 +783,7 @@ async fn handle_heads_up(
 
 async fn handle_prepare_done(
 	artifacts: &mut Artifacts,
+	persisted_artifacts_path: Option<&Path>,
 	execute_queue: &mut mpsc::Sender<execute::ToQueue>,
 	awaiting_prepare: &mut AwaitingPrepare,
 	from_queue: prepare::FromQueue,
@@ -815,6 +832,27 @@ async fn handle_prepare_done(
 		return Ok(())
 	};
 
+	// Persist the artifact before its path is handed out to the execution queue.
+	let result = match (result, persisted_artifacts_path) {
+		(Ok(PrepareSuccess { path, size, stats }), Some(persisted_artifacts_path)) => {
+			let path = match persisted::persist(persisted_artifacts_path, &artifact_id, &path).await
+			{
+				Ok(persisted_path) => persisted_path,
+				Err(err) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?artifact_id,
+						"failed to persist the prepared artifact: {}",
+						err,
+					);
+					path
+				},
+			};
+			Ok(PrepareSuccess { path, size, stats })
+		},
+		(result, _) => result,
+	};
+
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
@@ -975,6 +1013,8 @@ async fn sweeper_task(mut sweeper_rx: mpsc::Receiver<PathBuf>) {
 			None => break,
 			Some(condemned) => {
 				let result = tokio::fs::remove_file(&condemned).await;
+				// Persisted artifacts come with a metadata file, which has to go as well.
+				let _ = tokio::fs::remove_file(persisted::metadata_path(&condemned)).await;
 				gum::trace!(
 					target: LOG_TARGET,
 					?result,
@@ -1087,6 +1127,7 @@ pub(crate) mod tests {
 				cleanup_pulse_interval,
 				cleanup_config,
 				artifacts,
+				persisted_artifacts_path: None,
 				to_host_rx,
 				to_prepare_queue_tx,
 				from_prepare_queue_rx,
@@ -84,6 +84,10 @@
 //! A pruning task will run at a fixed interval of time. This task will remove all artifacts that
 //! weren't used or received a heads up signal for a while.
 //!
+//! Artifacts are pruned on startup, unless artifact persistence is enabled. In that case they are
+//! kept across restarts as long as the node and wasmtime versions don't change, and can be exported
+//! into and imported from a bundle with [`export_bundle`] and [`import_bundle`].
+//!
 //! ## Execution
 //!
 //! The execute workers will be fed by the requests from the execution queue, which is basically a
@@ -95,6 +99,7 @@ mod error;
 mod execute;
 mod host;
 mod metrics;
+mod persisted;
 mod prepare;
 mod priority;
 #[cfg(target_os = "linux")]
@@ -110,6 +115,7 @@ pub use host::{
 	PREPARE_BINARY_NAME,
 };
 pub use metrics::Metrics;
+pub use persisted::{export_bundle, import_bundle, BundleError};
 pub use priority::Priority;
 pub use worker_interface::{framed_recv, framed_send, JOB_TIMEOUT_WALL_CLOCK_FACTOR};
 
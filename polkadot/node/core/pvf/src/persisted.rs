@@ -0,0 +1,451 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prepared artifacts kept across restarts.
+//!
+//! By default, all artifacts are pruned on startup (see [`crate::artifacts`]). With artifact
+//! persistence enabled, every successfully prepared artifact is moved into a directory keyed by the
+//! node version and the [`wasmtime_compatibility_key`], next to a small metadata file recording its
+//! [`ArtifactId`] and checksum. On the next startup, the artifacts whose checksum still matches are
+//! put back into the artifact table as prepared, while anything else in that directory, as well as
+//! the directories of other versions, is removed.
+//!
+//! The wasmtime key alone is not enough: the node decides how the PVFs are compiled, e.g.
+//! through the wasmtime configuration and the host functions, and may change that in any release.
+//! Keying by the node version as well means an upgraded node starts from an empty cache instead of
+//! executing code compiled with different semantics.
+//!
+//! The persisted artifacts can also be exported into a single bundle file and imported by another
+//! node of the same version, so that it doesn't have to prepare every PVF on its first start.
+//!
+//! Note that artifacts are native code run by the execute workers. The checksums only protect
+//! against corruption, so bundles must only be imported from trusted sources.
+
+use crate::{
+	artifacts::{generate_artifact_path, ArtifactId, Artifacts, ARTIFACT_EXTENSION},
+	LOG_TARGET,
+};
+use codec::{Decode, Encode};
+use polkadot_node_core_pvf_common::executor_interface::wasmtime_compatibility_key;
+use polkadot_node_primitives::NODE_VERSION;
+use polkadot_parachain_primitives::primitives::ValidationCodeHash;
+use std::{
+	fs,
+	io::{self, BufReader, BufWriter, Write},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+/// The directory within the cache path holding the persisted artifacts.
+const PERSISTED_DIR: &str = "persisted";
+
+/// The extension of the metadata file stored next to every persisted artifact.
+const METADATA_EXTENSION: &str = "meta";
+
+/// The magic bytes every artifact bundle starts with.
+const BUNDLE_MAGIC: [u8; 8] = *b"pvfbndl1";
+
+/// The metadata stored next to every persisted artifact.
+#[derive(Encode, Decode)]
+struct Metadata {
+	id: ArtifactId,
+	checksum: [u8; 32],
+}
+
+/// An artifact within a bundle.
+///
+/// A bundle consists of the [`BUNDLE_MAGIC`], the node version and wasmtime key the artifacts were
+/// compiled with and a sequence of `Some(BundledArtifact)`, terminated by `None`.
+#[derive(Encode, Decode)]
+struct BundledArtifact {
+	id: ArtifactId,
+	checksum: [u8; 32],
+	artifact: Vec<u8>,
+}
+
+/// An error exporting or importing an artifact bundle.
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+	#[error("I/O error: {0}")]
+	Io(#[from] io::Error),
+	#[error("malformed artifact bundle: {0}")]
+	Codec(#[from] codec::Error),
+	#[error("the file is not a PVF artifact bundle")]
+	NotABundle,
+	#[error("the bundle was built by node version {found}, this node is version {expected}")]
+	NodeVersionMismatch { expected: String, found: String },
+	#[error("the bundle was built with wasmtime {found}, this node requires wasmtime {expected}")]
+	WasmtimeMismatch { expected: String, found: String },
+	#[error("checksum mismatch for the artifact of validation code {0:?}")]
+	ChecksumMismatch(ValidationCodeHash),
+}
+
+/// Returns the directory of the persisted artifacts usable by this node.
+pub(crate) fn persisted_path(cache_path: &Path) -> PathBuf {
+	cache_path
+		.join(PERSISTED_DIR)
+		.join(format!("node-{NODE_VERSION}-wasmtime-{}", wasmtime_compatibility_key()))
+}
+
+/// Returns the path of the metadata file belonging to the given artifact.
+///
+/// For artifacts that aren't persisted, the returned file simply doesn't exist.
+pub(crate) fn metadata_path(artifact_path: &Path) -> PathBuf {
+	artifact_path.with_extension(METADATA_EXTENSION)
+}
+
+fn checksum(artifact: &[u8]) -> [u8; 32] {
+	*blake3::hash(artifact).as_bytes()
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Reads the persisted artifact at the given path together with its metadata, and checks its
+/// integrity.
+fn read_verified(path: &Path) -> io::Result<(Metadata, Vec<u8>)> {
+	let metadata =
+		Metadata::decode(&mut &fs::read(metadata_path(path))?[..]).map_err(invalid_data)?;
+	let artifact = fs::read(path)?;
+	if checksum(&artifact) != metadata.checksum {
+		return Err(invalid_data("checksum mismatch"))
+	}
+	Ok((metadata, artifact))
+}
+
+/// Returns the paths of all persisted artifacts in the given directory.
+fn artifact_paths(dir: &Path) -> impl Iterator<Item = PathBuf> {
+	fs::read_dir(dir)
+		.into_iter()
+		.flatten()
+		.flatten()
+		.map(|entry| entry.path())
+		.filter(|path| path.extension().map_or(false, |ext| ext == ARTIFACT_EXTENSION))
+}
+
+/// Puts the intact persisted artifacts back into the artifact table and removes everything else.
+pub(crate) fn load(cache_path: &Path, artifacts: &mut Artifacts) {
+	let dir = persisted_path(cache_path);
+
+	// Artifacts compiled by another node version or wasmtime build are never used again.
+	for entry in fs::read_dir(cache_path.join(PERSISTED_DIR)).into_iter().flatten().flatten() {
+		if entry.path() != dir {
+			let _ = fs::remove_dir_all(entry.path());
+		}
+	}
+	if let Err(err) = fs::create_dir_all(&dir) {
+		gum::warn!(
+			target: LOG_TARGET,
+			"failed to create the persisted artifacts directory {}: {}",
+			dir.display(),
+			err,
+		);
+		return
+	}
+
+	let now = SystemTime::now();
+	let mut loaded = 0;
+	for path in artifact_paths(&dir) {
+		match read_verified(&path) {
+			Ok((Metadata { id, .. }, artifact)) if artifacts.artifact_state_mut(&id).is_none() => {
+				artifacts.insert_prepared(id, path, now, artifact.len() as u64);
+				loaded += 1;
+			},
+			Ok((Metadata { id, .. }, _)) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					validation_code_hash = ?id.code_hash,
+					"removing duplicate persisted artifact {}",
+					path.display(),
+				);
+				let _ = fs::remove_file(metadata_path(&path));
+				let _ = fs::remove_file(&path);
+			},
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"removing persisted artifact {} which failed the integrity check: {}",
+					path.display(),
+					err,
+				);
+				let _ = fs::remove_file(metadata_path(&path));
+				let _ = fs::remove_file(&path);
+			},
+		}
+	}
+
+	// Remove the metadata of artifacts that were never fully persisted.
+	for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+		let path = entry.path();
+		if path.extension().map_or(false, |ext| ext == METADATA_EXTENSION) &&
+			!path.with_extension(ARTIFACT_EXTENSION).exists()
+		{
+			let _ = fs::remove_file(path);
+		}
+	}
+
+	gum::info!(target: LOG_TARGET, "loaded {} persisted PVF artifacts", loaded);
+}
+
+/// Moves a freshly prepared artifact into the persisted artifacts directory and records its
+/// metadata. Returns the new path of the artifact.
+pub(crate) async fn persist(
+	dir: &Path,
+	artifact_id: &ArtifactId,
+	path: &Path,
+) -> io::Result<PathBuf> {
+	let artifact = tokio::fs::read(path).await?;
+	let persisted_path = generate_artifact_path(dir);
+
+	// Written before the artifact is moved, so that a persisted artifact always has metadata.
+	let metadata = Metadata { id: artifact_id.clone(), checksum: checksum(&artifact) };
+	tokio::fs::write(metadata_path(&persisted_path), metadata.encode()).await?;
+	tokio::fs::rename(path, &persisted_path).await?;
+
+	Ok(persisted_path)
+}
+
+/// Writes all intact persisted artifacts found under the given cache path into a bundle.
+///
+/// Returns the number of exported artifacts.
+pub fn export_bundle(cache_path: &Path, bundle_path: &Path) -> Result<usize, BundleError> {
+	let dir = persisted_path(cache_path);
+	let mut output = BufWriter::new(fs::File::create(bundle_path)?);
+
+	BUNDLE_MAGIC.encode_to(&mut output);
+	NODE_VERSION.encode_to(&mut output);
+	wasmtime_compatibility_key().encode_to(&mut output);
+
+	let mut exported = 0;
+	for path in artifact_paths(&dir) {
+		match read_verified(&path) {
+			Ok((Metadata { id, checksum }, artifact)) => {
+				Some(BundledArtifact { id, checksum, artifact }).encode_to(&mut output);
+				exported += 1;
+			},
+			Err(err) => gum::warn!(
+				target: LOG_TARGET,
+				"skipping persisted artifact {} which failed the integrity check: {}",
+				path.display(),
+				err,
+			),
+		}
+	}
+	None::<BundledArtifact>.encode_to(&mut output);
+	output.flush()?;
+
+	Ok(exported)
+}
+
+/// Imports the artifacts of a bundle into the persisted artifacts under the given cache path.
+///
+/// The artifacts are picked up on the next start of a validation host with artifact persistence
+/// enabled, so this should be done while the node is stopped. Either all artifacts of the bundle
+/// are imported or none. Returns the number of imported artifacts.
+pub fn import_bundle(cache_path: &Path, bundle_path: &Path) -> Result<usize, BundleError> {
+	let mut input = codec::IoReader(BufReader::new(fs::File::open(bundle_path)?));
+
+	if <[u8; 8]>::decode(&mut input).ok() != Some(BUNDLE_MAGIC) {
+		return Err(BundleError::NotABundle)
+	}
+	let node_version = String::decode(&mut input)?;
+	if node_version != NODE_VERSION {
+		return Err(BundleError::NodeVersionMismatch {
+			expected: NODE_VERSION.into(),
+			found: node_version,
+		})
+	}
+	let wasmtime_key = String::decode(&mut input)?;
+	if wasmtime_key != wasmtime_compatibility_key() {
+		return Err(BundleError::WasmtimeMismatch {
+			expected: wasmtime_compatibility_key().into(),
+			found: wasmtime_key,
+		})
+	}
+
+	let dir = persisted_path(cache_path);
+	fs::create_dir_all(&dir)?;
+
+	let mut imported = Vec::new();
+	let result = (|| {
+		while let Some(BundledArtifact { id, checksum: expected, artifact }) =
+			Option::<BundledArtifact>::decode(&mut input)?
+		{
+			if checksum(&artifact) != expected {
+				return Err(BundleError::ChecksumMismatch(id.code_hash))
+			}
+
+			let path = generate_artifact_path(&dir);
+			imported.push(path.clone());
+			fs::write(metadata_path(&path), Metadata { id, checksum: expected }.encode())?;
+			fs::write(&path, &artifact)?;
+		}
+		Ok(())
+	})();
+
+	// Don't leave a partial import behind, e.g. of a truncated bundle.
+	if let Err(err) = result {
+		for path in imported {
+			let _ = fs::remove_file(metadata_path(&path));
+			let _ = fs::remove_file(&path);
+		}
+		return Err(err)
+	}
+
+	Ok(imported.len())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{artifacts::ArtifactState, testing::artifact_id};
+
+	fn persisted_artifacts(cache_path: &Path) -> Vec<PathBuf> {
+		artifact_paths(&persisted_path(cache_path)).collect()
+	}
+
+	#[tokio::test]
+	async fn persisted_artifacts_are_loaded_on_startup() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+		let dir = persisted_path(cache_path);
+		fs::create_dir_all(&dir).unwrap();
+
+		let prepared = generate_artifact_path(cache_path);
+		fs::write(&prepared, b"artifact").unwrap();
+		let persisted = persist(&dir, &artifact_id(1), &prepared).await.unwrap();
+		assert!(!prepared.exists());
+
+		// A corrupted artifact is removed together with its metadata.
+		let prepared = generate_artifact_path(cache_path);
+		fs::write(&prepared, b"artifact").unwrap();
+		let corrupted = persist(&dir, &artifact_id(2), &prepared).await.unwrap();
+		fs::write(&corrupted, b"tampered").unwrap();
+
+		// So are artifacts of other wasmtime builds.
+		let stale_dir = cache_path.join(PERSISTED_DIR).join("wasmtime-0.0.0");
+		fs::create_dir_all(&stale_dir).unwrap();
+		// And of other node versions.
+		let stale_node_dir = cache_path
+			.join(PERSISTED_DIR)
+			.join(format!("node-0.0.0-wasmtime-{}", wasmtime_compatibility_key()));
+		fs::create_dir_all(&stale_node_dir).unwrap();
+
+		let mut artifacts = Artifacts::new(cache_path).await;
+		load(cache_path, &mut artifacts);
+
+		assert_matches::assert_matches!(
+			artifacts.artifact_state_mut(&artifact_id(1)),
+			Some(ArtifactState::Prepared { path, size: 8, .. }) if *path == persisted
+		);
+		assert!(artifacts.artifact_state_mut(&artifact_id(2)).is_none());
+		assert!(!corrupted.exists());
+		assert!(!metadata_path(&corrupted).exists());
+		assert!(!stale_dir.exists());
+		assert!(!stale_node_dir.exists());
+		assert_eq!(persisted_artifacts(cache_path), vec![persisted]);
+	}
+
+	#[tokio::test]
+	async fn bundle_roundtrip() {
+		let source = tempfile::tempdir().unwrap();
+		let dir = persisted_path(source.path());
+		fs::create_dir_all(&dir).unwrap();
+		for n in 1..=2 {
+			let prepared = generate_artifact_path(source.path());
+			fs::write(&prepared, vec![n as u8; 16]).unwrap();
+			persist(&dir, &artifact_id(n), &prepared).await.unwrap();
+		}
+
+		let bundle = source.path().join("artifacts.bundle");
+		assert_eq!(export_bundle(source.path(), &bundle).unwrap(), 2);
+
+		let target = tempfile::tempdir().unwrap();
+		assert_eq!(import_bundle(target.path(), &bundle).unwrap(), 2);
+
+		let mut artifacts = Artifacts::new(target.path()).await;
+		load(target.path(), &mut artifacts);
+		assert!(artifacts.artifact_state_mut(&artifact_id(1)).is_some());
+		assert!(artifacts.artifact_state_mut(&artifact_id(2)).is_some());
+
+		// Anything else is rejected.
+		assert_matches::assert_matches!(
+			import_bundle(target.path(), &persisted_artifacts(target.path())[0]),
+			Err(BundleError::NotABundle)
+		);
+	}
+
+	/// Writes a bundle with the given header and no artifacts.
+	fn write_empty_bundle(path: &Path, node_version: &str, wasmtime_version: &str) {
+		let mut bundle = BUNDLE_MAGIC.encode();
+		node_version.encode_to(&mut bundle);
+		wasmtime_version.encode_to(&mut bundle);
+		None::<BundledArtifact>.encode_to(&mut bundle);
+		fs::write(path, bundle).unwrap();
+	}
+
+	#[test]
+	fn bundles_of_other_versions_are_rejected() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let bundle = tempdir.path().join("artifacts.bundle");
+
+		write_empty_bundle(&bundle, NODE_VERSION, wasmtime_compatibility_key());
+		assert_eq!(import_bundle(tempdir.path(), &bundle).unwrap(), 0);
+
+		write_empty_bundle(&bundle, NODE_VERSION, "0.0.0");
+		assert_matches::assert_matches!(
+			import_bundle(tempdir.path(), &bundle),
+			Err(BundleError::WasmtimeMismatch { expected, found })
+				if expected == wasmtime_compatibility_key() && found == "0.0.0"
+		);
+
+		write_empty_bundle(&bundle, "0.0.0", wasmtime_compatibility_key());
+		assert_matches::assert_matches!(
+			import_bundle(tempdir.path(), &bundle),
+			Err(BundleError::NodeVersionMismatch { expected, found })
+				if expected == NODE_VERSION && found == "0.0.0"
+		);
+	}
+
+	#[tokio::test]
+	async fn truncated_bundles_are_not_imported() {
+		let source = tempfile::tempdir().unwrap();
+		let dir = persisted_path(source.path());
+		fs::create_dir_all(&dir).unwrap();
+		for n in 1..=2 {
+			let prepared = generate_artifact_path(source.path());
+			fs::write(&prepared, vec![n as u8; 16]).unwrap();
+			persist(&dir, &artifact_id(n), &prepared).await.unwrap();
+		}
+
+		let bundle = source.path().join("artifacts.bundle");
+		assert_eq!(export_bundle(source.path(), &bundle).unwrap(), 2);
+
+		// Cut off the terminator and part of the second artifact.
+		let mut bytes = fs::read(&bundle).unwrap();
+		bytes.truncate(bytes.len() - 8);
+		fs::write(&bundle, bytes).unwrap();
+
+		let target = tempfile::tempdir().unwrap();
+		assert_matches::assert_matches!(
+			import_bundle(target.path(), &bundle),
+			Err(BundleError::Codec(_))
+		);
+		assert!(persisted_artifacts(target.path()).is_empty());
+		assert_eq!(fs::read_dir(persisted_path(target.path())).unwrap().count(), 0);
+	}
+}
@@ -42,6 +42,12 @@ enum NemesisVariant {
 	DisputeFinalizedCandidates(DisputeFinalizedCandidatesOptions),
 	/// Spam many request statements instead of sending a single one.
 	SpamStatementRequests(SpamStatementRequestsOptions),
+	/// Refuse to serve availability chunks and available data.
+	WithholdAvailability(WithholdAvailabilityOptions),
+	/// Delay or drop the approvals, and optionally assignments, issued by this node.
+	DelayApprovals(DelayApprovalsOptions),
+	/// Accept collation advertisements but never fetch the collations.
+	StallCollationFetches(StallCollationFetchesOptions),
 }
 
 #[derive(Debug, Parser)]
@@ -105,6 +111,32 @@ impl MalusCli {
 
 				polkadot_cli::run_node(cli, SpamStatementRequests { spam_factor }, finality_delay)?
 			},
+			NemesisVariant::WithholdAvailability(opts) => {
+				let WithholdAvailabilityOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, WithholdAvailability { percentage }, finality_delay)?
+			},
+			NemesisVariant::DelayApprovals(opts) => {
+				let DelayApprovalsOptions {
+					delay_millis,
+					drop,
+					include_assignments,
+					percentage,
+					cli,
+				} = opts;
+				let delay = (!drop).then(|| std::time::Duration::from_millis(delay_millis));
+
+				polkadot_cli::run_node(
+					cli,
+					DelayApprovals { delay, include_assignments, percentage },
+					finality_delay,
+				)?
+			},
+			NemesisVariant::StallCollationFetches(opts) => {
+				let StallCollationFetchesOptions { cli } = opts;
+
+				polkadot_cli::run_node(cli, StallCollationFetches, finality_delay)?
+			},
 		}
 		Ok(())
 	}
@@ -244,4 +276,76 @@ mod tests {
 			assert!(opts.cli.run.base.bob);
 		});
 	}
+
+	#[test]
+	fn withhold_availability_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"withhold-availability",
+			"--percentage",
+			"50",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::WithholdAvailability(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 50);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn delay_approvals_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"delay-approvals",
+			"--delay-millis",
+			"30000",
+			"--include-assignments",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::DelayApprovals(opts),
+			..
+		} => {
+			assert_eq!(opts.delay_millis, 30000);
+			assert!(!opts.drop);
+			assert!(opts.include_assignments);
+			assert_eq!(opts.percentage, 100);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	#[should_panic]
+	fn delay_approvals_rejects_drop_with_delay() {
+		MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"delay-approvals",
+			"--delay-millis",
+			"30000",
+			"--drop",
+			"--bob",
+		]))
+		.unwrap();
+	}
+
+	#[test]
+	fn stall_collation_fetches_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"stall-collation-fetches",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::StallCollationFetches(run),
+			..
+		} => {
+			assert!(run.cli.run.base.bob);
+		});
+	}
 }
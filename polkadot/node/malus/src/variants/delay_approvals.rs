@@ -0,0 +1,205 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that delays or drops its own approvals.
+//!
+//! This malus variant behaves honestly in backing and does all the approval checking work, but the
+//! resulting approval votes, and optionally the assignments, are held back for a configurable time
+//! before being handed to approval distribution, or not sent at all. To the rest of the network the
+//! node looks like an approval checker that does not show up, which allows testing the no-show
+//! handling of approval voting.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use futures_timer::Delay;
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Handle, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use sp_core::traits::SpawnNamed;
+
+use rand::distributions::{Bernoulli, Distribution};
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use std::{sync::Arc, time::Duration};
+
+/// Wraps around approval voting and holds back the approvals it wants to distribute.
+#[derive(Clone)]
+struct ApprovalDelayer<Spawner> {
+	spawner: Spawner,
+	/// Used to hand delayed messages to approval distribution, bypassing this interceptor.
+	overseer_handle: Handle,
+	/// How long to hold back the messages. `None` means they are dropped.
+	delay: Option<Duration>,
+	/// Whether assignments are held back as well.
+	include_assignments: bool,
+	distribution: Bernoulli,
+}
+
+impl<Spawner> ApprovalDelayer<Spawner>
+where
+	Spawner: overseer::gen::Spawner + Clone + 'static,
+{
+	fn is_targeted(&self, msg: &overseer::ApprovalVotingOutgoingMessages) -> bool {
+		match msg {
+			overseer::ApprovalVotingOutgoingMessages::ApprovalDistributionMessage(
+				ApprovalDistributionMessage::DistributeApproval(_),
+			) => true,
+			overseer::ApprovalVotingOutgoingMessages::ApprovalDistributionMessage(
+				ApprovalDistributionMessage::DistributeAssignment(_, _),
+			) => self.include_assignments,
+			_ => false,
+		}
+	}
+
+	fn delay_message(&self, msg: ApprovalDistributionMessage, delay: Duration) {
+		let mut overseer_handle = self.overseer_handle.clone();
+		self.spawner.spawn(
+			"malus-delay-approval",
+			Some("malus"),
+			Box::pin(async move {
+				Delay::new(delay).await;
+				overseer_handle.send_msg(msg, MALUS).await;
+			}),
+		);
+	}
+}
+
+impl<Sender, Spawner> MessageInterceptor<Sender> for ApprovalDelayer<Spawner>
+where
+	Sender: overseer::ApprovalVotingSenderTrait + Clone + Send + 'static,
+	Spawner: overseer::gen::Spawner + Clone + 'static,
+{
+	type Message = ApprovalVotingMessage;
+
+	fn need_intercept_outgoing(&self, msg: &overseer::ApprovalVotingOutgoingMessages) -> bool {
+		self.is_targeted(msg) && self.distribution.sample(&mut rand::thread_rng())
+	}
+
+	/// Drop the outgoing approval or assignment and, unless configured to drop it for good,
+	/// send it to approval distribution after the configured delay.
+	fn intercept_outgoing(
+		&self,
+		msg: &overseer::ApprovalVotingOutgoingMessages,
+	) -> Option<overseer::ApprovalVotingOutgoingMessages> {
+		let msg = match msg {
+			overseer::ApprovalVotingOutgoingMessages::ApprovalDistributionMessage(
+				ApprovalDistributionMessage::DistributeApproval(vote),
+			) => ApprovalDistributionMessage::DistributeApproval(vote.clone()),
+			overseer::ApprovalVotingOutgoingMessages::ApprovalDistributionMessage(
+				ApprovalDistributionMessage::DistributeAssignment(cert, claimed_candidates),
+			) => ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone(),
+				claimed_candidates.clone(),
+			),
+			_ => return None,
+		};
+
+		match self.delay {
+			Some(delay) => {
+				gum::info!(target: MALUS, ?delay, "😈 Delaying approval message.");
+				self.delay_message(msg, delay);
+			},
+			None => gum::info!(target: MALUS, "😈 Dropping approval message."),
+		}
+
+		None
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct DelayApprovalsOptions {
+	/// For how many milliseconds approvals are held back before being distributed.
+	#[clap(long, ignore_case = true, default_value_t = 24000)]
+	pub delay_millis: u64,
+
+	/// Drop the approvals instead of delaying them.
+	#[clap(long, conflicts_with = "delay_millis")]
+	pub drop: bool,
+
+	/// Hold back the assignments as well, not only the approvals.
+	#[clap(long)]
+	pub include_assignments: bool,
+
+	/// Determines the percentage of approvals that are held back.
+	/// Defaults to 100% of approvals being held back.
+	#[clap(short, long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// DelayApprovals implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct DelayApprovals {
+	/// How long approvals are held back. `None` means they are dropped.
+	pub delay: Option<Duration>,
+	/// Whether assignments are held back as well.
+	pub include_assignments: bool,
+	/// The probability of holding back an approval.
+	pub percentage: u8,
+}
+
+impl OverseerGen for DelayApprovals {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that holds back {}% of its approvals, delay = {:?} (none means dropped), including assignments = {}.",
+			&self.percentage,
+			&self.delay,
+			&self.include_assignments,
+		);
+
+		let approval_delayer = ApprovalDelayer {
+			spawner: SpawnGlue(args.spawner.clone()),
+			overseer_handle: Handle::new(connector.handle()),
+			delay: self.delay,
+			include_assignments: self.include_assignments,
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+		};
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_approval_voting(move |cb| InterceptedSubsystem::new(cb, approval_delayer))
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -18,18 +18,24 @@
 
 mod back_garbage_candidate;
 mod common;
+mod delay_approvals;
 mod dispute_finalized_candidates;
 mod dispute_valid_candidates;
 mod spam_statement_requests;
+mod stall_collation_fetches;
 mod suggest_garbage_candidate;
 mod support_disabled;
+mod withhold_availability;
 
 pub(crate) use self::{
 	back_garbage_candidate::{BackGarbageCandidateOptions, BackGarbageCandidates},
+	delay_approvals::{DelayApprovals, DelayApprovalsOptions},
 	dispute_finalized_candidates::{DisputeFinalizedCandidates, DisputeFinalizedCandidatesOptions},
 	dispute_valid_candidates::{DisputeAncestorOptions, DisputeValidCandidates},
 	spam_statement_requests::{SpamStatementRequests, SpamStatementRequestsOptions},
+	stall_collation_fetches::{StallCollationFetches, StallCollationFetchesOptions},
 	suggest_garbage_candidate::{SuggestGarbageCandidateOptions, SuggestGarbageCandidates},
 	support_disabled::{SupportDisabled, SupportDisabledOptions},
+	withhold_availability::{WithholdAvailability, WithholdAvailabilityOptions},
 };
 pub(crate) use common::*;
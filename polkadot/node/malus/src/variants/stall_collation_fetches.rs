@@ -0,0 +1,151 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that never fetches advertised collations.
+//!
+//! This malus variant behaves honestly towards collators up to the point of fetching: it stays
+//! connected, accepts their declarations and advertisements, and lets the collator protocol issue
+//! collation fetch requests. Those requests are intercepted at the network bridge and never sent.
+//! Their response channels are kept open until the next active leaves update, so the collator
+//! protocol waits for the fetch to time out as it would with an unresponsive validator.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_network_protocol::request_response::{outgoing::Requests, ResponseSender};
+use polkadot_node_subsystem::{messages::NetworkBridgeTxMessage, SpawnGlue};
+use polkadot_node_subsystem_types::{ChainApiBackend, OverseerSignal, RuntimeApiSubsystemClient};
+use sp_core::traits::SpawnNamed;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use std::sync::{Arc, Mutex};
+
+/// Wraps around network bridge and swallows collation fetch requests.
+#[derive(Clone, Default)]
+struct CollationFetchStaller {
+	/// Response channels of the swallowed requests, kept open so that the requests look pending.
+	stalled: Arc<Mutex<Vec<ResponseSender>>>,
+}
+
+impl CollationFetchStaller {
+	fn stall(&self, pending_response: ResponseSender) {
+		self.stalled.lock().expect("poisoned lock").push(pending_response);
+	}
+}
+
+impl<Sender> MessageInterceptor<Sender> for CollationFetchStaller
+where
+	Sender: overseer::NetworkBridgeTxSenderTrait + Clone + Send + 'static,
+{
+	type Message = NetworkBridgeTxMessage;
+
+	/// Intercept NetworkBridgeTxMessage::SendRequests and remove any collation fetch requests.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendRequests(requests, if_disconnected),
+			} => {
+				let mut new_requests = Vec::with_capacity(requests.len());
+
+				for request in requests {
+					match request {
+						Requests::CollationFetchingV1(req) => {
+							gum::info!(
+								target: MALUS,
+								peer = ?req.peer,
+								"😈 Not fetching advertised collation.",
+							);
+							self.stall(req.pending_response);
+						},
+						Requests::CollationFetchingV2(req) => {
+							gum::info!(
+								target: MALUS,
+								peer = ?req.peer,
+								candidate_hash = ?req.payload.candidate_hash,
+								"😈 Not fetching advertised collation.",
+							);
+							self.stall(req.pending_response);
+						},
+						_ => new_requests.push(request),
+					}
+				}
+
+				Some(FromOrchestra::Communication {
+					msg: NetworkBridgeTxMessage::SendRequests(new_requests, if_disconnected),
+				})
+			},
+			FromOrchestra::Communication { msg } => Some(FromOrchestra::Communication { msg }),
+			FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
+				// The collator protocol has long given up on requests made for older leaves.
+				self.stalled.lock().expect("poisoned lock").clear();
+				Some(FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)))
+			},
+			FromOrchestra::Signal(signal) => Some(FromOrchestra::Signal(signal)),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct StallCollationFetchesOptions {
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// StallCollationFetches implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct StallCollationFetches;
+
+impl OverseerGen for StallCollationFetches {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(target: MALUS, "😈 Started Malus node that never fetches advertised collations.");
+
+		let fetch_staller = CollationFetchStaller::default();
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_network_bridge_tx(move |cb| InterceptedSubsystem::new(cb, fetch_staller))
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
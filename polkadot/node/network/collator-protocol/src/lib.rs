@@ -41,9 +41,12 @@ use polkadot_node_subsystem::{errors::SubsystemError, overseer, DummySubsystem,
 
 mod collator_side;
 mod validator_side;
-#[cfg(feature = "experimental-collator-protocol")]
+#[cfg(any(test, feature = "experimental-collator-protocol"))]
 mod validator_side_experimental;
 
+#[cfg(any(test, feature = "experimental-collator-protocol"))]
+pub use validator_side_experimental::ReputationConfig;
+
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
 /// A collator eviction policy - how fast to evict collators which are inactive.
@@ -76,10 +79,14 @@ pub enum ProtocolSide {
 		metrics: validator_side::Metrics,
 	},
 	/// Experimental variant of the validator side. Do not use in production.
-	#[cfg(feature = "experimental-collator-protocol")]
+	#[cfg(any(test, feature = "experimental-collator-protocol"))]
 	ValidatorExperimental {
 		/// The keystore holding validator keys.
 		keystore: KeystorePtr,
+		/// The database used to persist collator reputations.
+		db: std::sync::Arc<dyn polkadot_node_subsystem_util::database::Database>,
+		/// Configuration of the collator reputation storage.
+		reputation_config: ReputationConfig,
 		/// Prometheus metrics for validators.
 		metrics: validator_side_experimental::Metrics,
 	},
@@ -122,9 +129,9 @@ impl<Context> CollatorProtocolSubsystem {
 				validator_side::run(ctx, keystore, eviction_policy, metrics)
 					.map_err(|e| SubsystemError::with_origin("collator-protocol", e))
 					.boxed(),
-			#[cfg(feature = "experimental-collator-protocol")]
-			ProtocolSide::ValidatorExperimental { keystore, metrics } =>
-				validator_side_experimental::run(ctx, keystore, metrics)
+			#[cfg(any(test, feature = "experimental-collator-protocol"))]
+			ProtocolSide::ValidatorExperimental { keystore, db, reputation_config, metrics } =>
+				validator_side_experimental::run(ctx, keystore, db, reputation_config, metrics)
 					.map_err(|e| SubsystemError::with_origin("collator-protocol", e))
 					.boxed(),
 			ProtocolSide::Collator { peer_id, collator_pair, request_receiver_v2, metrics } =>
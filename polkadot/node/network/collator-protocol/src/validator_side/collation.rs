@@ -323,7 +323,7 @@ impl Collations {
 
 // Any error that can occur when awaiting a collation fetch response.
 #[derive(Debug, thiserror::Error)]
-pub(super) enum CollationFetchError {
+pub(crate) enum CollationFetchError {
 	#[error("Future was cancelled.")]
 	Cancelled,
 	#[error("{0}")]
@@ -332,7 +332,7 @@ pub(super) enum CollationFetchError {
 
 /// Future that concludes when the collator has responded to our collation fetch request
 /// or the request was cancelled by the validator.
-pub(super) struct CollationFetchRequest {
+pub(crate) struct CollationFetchRequest {
 	/// Info about the requested collation.
 	pub pending_collation: PendingCollation,
 	/// Collator id.
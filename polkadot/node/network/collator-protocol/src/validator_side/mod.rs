@@ -60,9 +60,9 @@ use polkadot_primitives::{
 
 use super::{modify_reputation, tick_stream, LOG_TARGET};
 
-mod claim_queue_state;
-mod collation;
-mod error;
+pub(crate) mod claim_queue_state;
+pub(crate) mod collation;
+pub(crate) mod error;
 mod metrics;
 
 use claim_queue_state::ClaimQueueState;
@@ -568,7 +568,10 @@ fn collator_peer_id(
 		.find_map(|(peer, data)| data.collator_id().filter(|c| c == &collator_id).map(|_| *peer))
 }
 
-async fn disconnect_peer(sender: &mut impl overseer::CollatorProtocolSenderTrait, peer_id: PeerId) {
+pub(crate) async fn disconnect_peer(
+	sender: &mut impl overseer::CollatorProtocolSenderTrait,
+	peer_id: PeerId,
+) {
 	sender
 		.send_message(NetworkBridgeTxMessage::DisconnectPeer(peer_id, PeerSet::Collation))
 		.await
@@ -627,7 +630,7 @@ async fn note_good_collation(
 }
 
 /// Notify a collator that its collation got seconded.
-async fn notify_collation_seconded(
+pub(crate) async fn notify_collation_seconded(
 	sender: &mut impl overseer::CollatorProtocolSenderTrait,
 	peer_id: PeerId,
 	version: CollationVersion,
@@ -959,7 +962,7 @@ impl AdvertisementError {
 }
 
 // Requests backing to sanity check the advertisement.
-async fn can_second<Sender>(
+pub(crate) async fn can_second<Sender>(
 	sender: &mut Sender,
 	candidate_para_id: ParaId,
 	candidate_relay_parent: Hash,
@@ -1798,7 +1801,7 @@ where
 	.map_err(SecondingError::RuntimeApi)
 }
 
-async fn request_prospective_validation_data<Sender>(
+pub(crate) async fn request_prospective_validation_data<Sender>(
 	sender: &mut Sender,
 	candidate_relay_parent: Hash,
 	parent_head_data_hash: Hash,
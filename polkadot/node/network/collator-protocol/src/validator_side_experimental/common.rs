@@ -0,0 +1,121 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Constants and types shared by the different parts of the experimental validator side.
+
+use std::time::Duration;
+
+/// Maximum reputation score a collator can reach for a para.
+pub const MAX_SCORE: u16 = 10_000;
+
+/// Reputation bump for a collator whose collation got seconded.
+pub const VALID_COLLATION_BUMP: u16 = 100;
+
+/// Reputation slash for a collator which provided an invalid or malformed collation.
+pub const INVALID_COLLATION_SLASH: u16 = 1_000;
+
+/// Reputation slash for a collator from which we failed to fetch an advertised collation.
+pub const FAILED_FETCH_SLASH: u16 = 20;
+
+/// Reputation decay applied to all collators on every finalized block.
+///
+/// This makes sure collators which stopped producing useful collations eventually lose the
+/// priority they built up in the past.
+pub const INACTIVITY_DECAY: u16 = 1;
+
+/// Collators with at least this score have their collations fetched as soon as they are
+/// advertised. Advertisements from collators with a lower score are delayed for up to
+/// [`MAX_FETCH_DELAY`], giving collators with a better reputation the chance to advertise.
+pub const INSTANT_FETCH_REP_THRESHOLD: Score = Score(1_000);
+
+/// Maximum time an advertisement from a collator without a good reputation waits before we
+/// fetch it.
+pub const MAX_FETCH_DELAY: Duration = Duration::from_millis(300);
+
+/// Time after starting a collation download from a collator we will start another one from the
+/// next collator even if the upload was not finished yet.
+///
+/// See the documentation of the constant with the same name in the legacy validator side.
+pub const MAX_UNSHARED_DOWNLOAD_TIME: Duration = Duration::from_millis(400);
+
+/// How many collators can be connected for a single para. Once the limit is reached, a newly
+/// declared collator is only accepted if it has a better reputation than the worst connected
+/// one, which gets disconnected in exchange.
+pub const CONNECTED_PEERS_PARA_LIMIT: usize = 100;
+
+/// How many reputation entries we persist per para. Entries with the lowest score are dropped
+/// first.
+pub const MAX_STORED_SCORES_PER_PARA: usize = 150;
+
+/// How long a connected peer has to declare itself as a collator before being disconnected.
+pub const UNDECLARED_PEER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often we check for advertisements which can be fetched.
+#[cfg(not(test))]
+pub const FETCH_POLL: Duration = Duration::from_millis(50);
+
+/// How often we check for peers which didn't declare themselves as collators.
+#[cfg(not(test))]
+pub const ACTIVITY_POLL: Duration = Duration::from_secs(1);
+
+#[cfg(test)]
+pub const FETCH_POLL: Duration = Duration::from_millis(10);
+
+#[cfg(test)]
+pub const ACTIVITY_POLL: Duration = Duration::from_millis(10);
+
+/// Reputation of a collator for a specific para. Always in the `0..=MAX_SCORE` range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(u16);
+
+impl Score {
+	/// Create a new score. Returns `None` if the value is higher than [`MAX_SCORE`].
+	pub const fn new(value: u16) -> Option<Self> {
+		if value > MAX_SCORE {
+			None
+		} else {
+			Some(Self(value))
+		}
+	}
+
+	/// Increase the score, capping it at [`MAX_SCORE`].
+	pub fn saturating_add(self, value: u16) -> Self {
+		Self(self.0.saturating_add(value).min(MAX_SCORE))
+	}
+
+	/// Decrease the score, stopping at zero.
+	pub fn saturating_sub(self, value: u16) -> Self {
+		Self(self.0.saturating_sub(value))
+	}
+
+	/// Whether the score is zero.
+	pub fn is_zero(&self) -> bool {
+		self.0 == 0
+	}
+}
+
+impl From<Score> for u16 {
+	fn from(score: Score) -> Self {
+		score.0
+	}
+}
+
+/// Database configuration for the collator reputation storage.
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationConfig {
+	/// The column in the parachains database used for storing collator reputations.
+	pub col_reputation_data: u32,
+}
@@ -17,12 +17,131 @@
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 
 #[derive(Clone, Default)]
-pub struct Metrics;
+pub struct Metrics(Option<MetricsInner>);
+
+impl Metrics {
+	pub fn on_request(&self, succeeded: std::result::Result<(), ()>) {
+		if let Some(metrics) = &self.0 {
+			match succeeded {
+				Ok(()) => metrics.collation_requests.with_label_values(&["succeeded"]).inc(),
+				Err(()) => metrics.collation_requests.with_label_values(&["failed"]).inc(),
+			}
+		}
+	}
+
+	/// Provide a timer for `process_msg` which observes on drop.
+	pub fn time_process_msg(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.process_msg.start_timer())
+	}
+
+	/// Note the current number of collator peers.
+	pub fn note_collator_peer_count(&self, collator_peers: usize) {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.collator_peer_count.set(collator_peers as u64));
+	}
+
+	/// Provide a timer for `CollationFetchRequest` structure which observes on drop.
+	pub fn time_collation_request_duration(
+		&self,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.collation_request_duration.start_timer())
+	}
+
+	/// Note how long an advertisement waited before being fetched.
+	pub fn on_fetch_started(&self, delay: std::time::Duration) {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.advertisement_fetch_delay.observe(delay.as_secs_f64()));
+	}
+
+	/// Note a collator being disconnected to make room for a collator with a better reputation.
+	pub fn on_collator_evicted(&self) {
+		self.0.as_ref().map(|metrics| metrics.collator_evictions.inc());
+	}
+
+	/// Note the number of collators we keep a reputation for.
+	pub fn note_reputation_count(&self, count: usize) {
+		self.0.as_ref().map(|metrics| metrics.reputation_count.set(count as u64));
+	}
+}
+
+#[derive(Clone)]
+struct MetricsInner {
+	collation_requests: prometheus::CounterVec<prometheus::U64>,
+	process_msg: prometheus::Histogram,
+	collator_peer_count: prometheus::Gauge<prometheus::U64>,
+	collation_request_duration: prometheus::Histogram,
+	advertisement_fetch_delay: prometheus::Histogram,
+	collator_evictions: prometheus::Counter<prometheus::U64>,
+	reputation_count: prometheus::Gauge<prometheus::U64>,
+}
 
 impl metrics::Metrics for Metrics {
 	fn try_register(
-		_registry: &prometheus::Registry,
+		registry: &prometheus::Registry,
 	) -> std::result::Result<Self, prometheus::PrometheusError> {
-		Ok(Metrics)
+		let metrics = MetricsInner {
+			collation_requests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collation_requests_total",
+						"Number of collations requested from Collators.",
+					),
+					&["success"],
+				)?,
+				registry,
+			)?,
+			process_msg: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_process_msg",
+						"Time spent within `collator_protocol_validator::process_msg`",
+					)
+				)?,
+				registry,
+			)?,
+			collator_peer_count: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_collator_peer_count",
+					"Amount of collator peers connected",
+				)?,
+				registry,
+			)?,
+			collation_request_duration: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_collation_request_duration",
+						"Lifetime of the `CollationFetchRequest` structure",
+					).buckets(vec![0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.75, 0.9, 1.0, 1.2, 1.5, 1.75]),
+				)?,
+				registry,
+			)?,
+			advertisement_fetch_delay: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_advertisement_fetch_delay",
+						"Time between receiving an advertisement and starting to fetch the collation",
+					).buckets(vec![0.01, 0.025, 0.05, 0.1, 0.15, 0.2, 0.3, 0.4, 0.6, 0.8, 1.0, 2.0]),
+				)?,
+				registry,
+			)?,
+			collator_evictions: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_collator_protocol_validator_collator_evictions_total",
+					"Number of collators disconnected in favour of collators with a better reputation",
+				)?,
+				registry,
+			)?,
+			reputation_count: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_collator_protocol_validator_reputation_count",
+					"Number of collators we keep a reputation for",
+				)?,
+				registry,
+			)?,
+		};
+
+		Ok(Metrics(Some(metrics)))
 	}
 }
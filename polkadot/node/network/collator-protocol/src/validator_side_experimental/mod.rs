@@ -14,19 +14,1251 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-mod metrics;
+//! Experimental validator side of the collator protocol.
+//!
+//! In contrast to the legacy validator side, collators are selected based on a reputation which
+//! is persisted in the parachains database across restarts:
+//!   - A collator earns reputation when one of its collations gets seconded and loses it when it
+//!     provides an invalid collation or fails to deliver an advertised one. All reputations slowly
+//!     decay on every finalized block.
+//!   - The number of collators connected per para is limited. When the limit is reached, a newly
+//!     declared collator replaces the connected collator with the lowest reputation, provided its
+//!     own reputation is higher.
+//!   - Advertisements are fetched in claim queue order, one per unfulfilled claim queue entry, so
+//!     that paras sharing a core are treated fairly. For every entry the collation of the collator
+//!     with the best reputation is fetched. Collators without a good reputation have to wait up to
+//!     [`common::MAX_FETCH_DELAY`] before their collation gets fetched.
+//!
+//! Only collators using version 2 of the collator protocol are supported.
+
+use futures::{select, stream::FuturesUnordered, FutureExt, StreamExt};
+use std::{
+	collections::hash_map::Entry,
+	future::Future,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
-use polkadot_node_subsystem::overseer;
 use sp_keystore::KeystorePtr;
 
+use polkadot_node_network_protocol::{
+	self as net_protocol,
+	peer_set::CollationVersion,
+	request_response::{
+		outgoing::{Recipient, RequestError},
+		v1 as request_v1, v2 as request_v2, OutgoingRequest, Requests,
+	},
+	v1 as protocol_v1, v2 as protocol_v2, CollationProtocols, OurView, PeerId,
+	UnifiedReputationChange as Rep,
+};
+use polkadot_node_primitives::Statement;
+use polkadot_node_subsystem::{
+	messages::{
+		CandidateBackingMessage, CollatorProtocolMessage, IfDisconnected, NetworkBridgeEvent,
+		NetworkBridgeTxMessage,
+	},
+	overseer, CollatorProtocolSenderTrait, FromOrchestra, OverseerSignal,
+};
+use polkadot_node_subsystem_util::{
+	database::Database,
+	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
+	request_claim_queue, request_node_features, request_session_index_for_child,
+};
+use polkadot_primitives::{
+	node_features,
+	vstaging::{CandidateDescriptorV2, CandidateDescriptorVersion},
+	CandidateHash, Hash, HeadData, Id as ParaId, SessionIndex,
+};
+
+use super::{modify_reputation, tick_stream, LOG_TARGET};
+use crate::validator_side::{
+	can_second,
+	collation::{
+		fetched_collation_sanity_check, BlockedCollationId, CollationEvent, CollationFetchError,
+		CollationFetchRequest, FetchedCollation, PendingCollation, PendingCollationFetch,
+		ProspectiveCandidate,
+	},
+	disconnect_peer,
+	error::SecondingError,
+	notify_collation_seconded, request_prospective_validation_data,
+};
+
+mod common;
+mod error;
+mod metrics;
+mod peer_manager;
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+use common::{ACTIVITY_POLL, FETCH_POLL, UNDECLARED_PEER_TIMEOUT};
+use error::{Error, Result};
+use peer_manager::{DeclarationOutcome, PeerManager, ReputationDb};
+use state::{Advertisement, AdvertisementError, CollationManager, PerRelayParent};
+
+pub use common::ReputationConfig;
 pub use metrics::Metrics;
 
-/// The main run loop
+const COST_UNEXPECTED_MESSAGE: Rep = Rep::CostMinor("An unexpected message");
+/// Message could not be decoded properly.
+const COST_CORRUPTED_MESSAGE: Rep = Rep::CostMinor("Message was corrupt");
+/// Network errors that originated at the remote host should have same cost as timeout.
+const COST_NETWORK_ERROR: Rep = Rep::CostMinor("Some network error");
+const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message signature");
+const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
+const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
+const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const BENEFIT_NOTIFY_GOOD: Rep =
+	Rep::BenefitMinor("A collator was noted good by another subsystem");
+
+/// All state relevant for the validator side of the protocol lives here.
+struct State {
+	/// Connected collators and their reputations.
+	peer_manager: PeerManager,
+
+	/// Advertisements and collations per relay parent.
+	collations: CollationManager,
+
+	/// The collations we have requested from collators.
+	collation_requests: FuturesUnordered<CollationFetchRequest>,
+
+	/// Aggregated reputation change.
+	reputation: ReputationAggregator,
+
+	/// Metrics.
+	metrics: Metrics,
+}
+
+/// The main run loop.
 #[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
 pub(crate) async fn run<Context>(
-	_ctx: Context,
-	_keystore: KeystorePtr,
-	_metrics: Metrics,
-) -> std::result::Result<(), std::convert::Infallible> {
+	ctx: Context,
+	keystore: KeystorePtr,
+	db: Arc<dyn Database>,
+	reputation_config: ReputationConfig,
+	metrics: Metrics,
+) -> Result<()> {
+	let reputations = ReputationDb::load(db, reputation_config)?;
+
+	run_inner(
+		ctx,
+		keystore,
+		reputations,
+		metrics,
+		ReputationAggregator::default(),
+		REPUTATION_CHANGE_INTERVAL,
+	)
+	.await
+}
+
+#[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
+async fn run_inner<Context>(
+	mut ctx: Context,
+	keystore: KeystorePtr,
+	reputations: ReputationDb,
+	metrics: Metrics,
+	reputation: ReputationAggregator,
+	reputation_interval: Duration,
+) -> Result<()> {
+	let new_reputation_delay = || futures_timer::Delay::new(reputation_interval).fuse();
+	let mut reputation_delay = new_reputation_delay();
+
+	metrics.note_reputation_count(reputations.collator_count());
+	let mut state = State {
+		peer_manager: PeerManager::new(reputations),
+		collations: CollationManager::default(),
+		collation_requests: FuturesUnordered::new(),
+		reputation,
+		metrics,
+	};
+
+	let next_fetch_stream = tick_stream(FETCH_POLL);
+	futures::pin_mut!(next_fetch_stream);
+	let next_inactivity_stream = tick_stream(ACTIVITY_POLL);
+	futures::pin_mut!(next_inactivity_stream);
+
+	let mut network_error_freq = gum::Freq::new();
+	let mut canceled_freq = gum::Freq::new();
+
+	loop {
+		select! {
+			_ = reputation_delay => {
+				state.reputation.send(ctx.sender()).await;
+				reputation_delay = new_reputation_delay();
+			},
+			res = ctx.recv().fuse() => {
+				match res {
+					Ok(FromOrchestra::Communication { msg }) => {
+						gum::trace!(target: LOG_TARGET, msg = ?msg, "received a message");
+						process_msg(&mut ctx, &keystore, msg, &mut state).await;
+					},
+					Ok(FromOrchestra::Signal(OverseerSignal::BlockFinalized(..))) => {
+						if let Err(err) = state.peer_manager.on_finalized_block() {
+							gum::warn!(
+								target: LOG_TARGET,
+								?err,
+								"Failed to persist collator reputations",
+							);
+						}
+						state.metrics.note_reputation_count(state.peer_manager.reputation_count());
+					},
+					Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => break,
+					Ok(FromOrchestra::Signal(_)) => continue,
+				}
+			},
+			_ = next_fetch_stream.next() => {
+				fetch_collations(ctx.sender(), &mut state).await;
+			},
+			_ = next_inactivity_stream.next() => {
+				for peer_id in
+					state.peer_manager.undeclared_peers(Instant::now(), UNDECLARED_PEER_TIMEOUT)
+				{
+					gum::trace!(target: LOG_TARGET, ?peer_id, "Disconnecting undeclared peer");
+					disconnect_peer(ctx.sender(), peer_id).await;
+				}
+			},
+			resp = state.collation_requests.select_next_some() => {
+				let res = match handle_collation_fetch_response(
+					&mut state,
+					resp,
+					&mut network_error_freq,
+					&mut canceled_freq,
+				) {
+					Err(Some((peer_id, rep))) => {
+						modify_reputation(&mut state.reputation, ctx.sender(), peer_id, rep).await;
+						fetch_collations(ctx.sender(), &mut state).await;
+						continue
+					},
+					Err(None) => {
+						fetch_collations(ctx.sender(), &mut state).await;
+						continue
+					},
+					Ok(res) => res,
+				};
+
+				let collator_id = res.collation_event.collator_id.clone();
+				let pending_collation = res.collation_event.pending_collation;
+				if let Err(err) = kick_off_seconding(&mut ctx, &mut state, res).await {
+					gum::warn!(
+						target: LOG_TARGET,
+						relay_parent = ?pending_collation.relay_parent,
+						para_id = ?pending_collation.para_id,
+						peer_id = ?pending_collation.peer_id,
+						error = %err,
+						"Seconding aborted due to an error",
+					);
+
+					if err.is_malicious() {
+						// Report malicious peer.
+						modify_reputation(
+							&mut state.reputation,
+							ctx.sender(),
+							pending_collation.peer_id,
+							COST_REPORT_BAD,
+						)
+						.await;
+						state
+							.peer_manager
+							.on_invalid_collation(pending_collation.para_id, &collator_id);
+					}
+				}
+
+				// The claim of the fetched collation is either held by the collation waiting on
+				// validation or it's free again.
+				fetch_collations(ctx.sender(), &mut state).await;
+			},
+		}
+	}
+
+	state.peer_manager.flush()
+}
+
+/// The main message receiver switch.
+#[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
+async fn process_msg<Context>(
+	ctx: &mut Context,
+	keystore: &KeystorePtr,
+	msg: CollatorProtocolMessage,
+	state: &mut State,
+) {
+	use CollatorProtocolMessage::*;
+
+	let _timer = state.metrics.time_process_msg();
+
+	match msg {
+		CollateOn(id) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				para_id = %id,
+				"CollateOn message is not expected on the validator side of the protocol",
+			);
+		},
+		DistributeCollation { .. } => {
+			gum::warn!(
+				target: LOG_TARGET,
+				"DistributeCollation message is not expected on the validator side of the protocol",
+			);
+		},
+		NetworkBridgeUpdate(event) => {
+			if let Err(e) = handle_network_msg(ctx, state, keystore, event).await {
+				gum::warn!(
+					target: LOG_TARGET,
+					err = ?e,
+					"Failed to handle incoming network message",
+				);
+			}
+		},
+		Seconded(parent, stmt) => {
+			let receipt = match stmt.payload() {
+				Statement::Seconded(receipt) => receipt,
+				Statement::Valid(_) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?stmt,
+						relay_parent = %parent,
+						"Seconded message received with a `Valid` statement",
+					);
+					return
+				},
+			};
+			let output_head_data = receipt.commitments.head_data.clone();
+			let output_head_data_hash = receipt.descriptor.para_head();
+			let fetched_collation = FetchedCollation::from(&receipt.to_plain());
+			let Some(CollationEvent { collator_id, pending_collation, .. }) =
+				state.collations.fetched_candidates.remove(&fetched_collation)
+			else {
+				gum::debug!(
+					target: LOG_TARGET,
+					relay_parent = ?parent,
+					"Collation has been seconded, but the relay parent is deactivated",
+				);
+				return
+			};
+
+			let PendingCollation { relay_parent, peer_id, para_id, .. } = pending_collation;
+			state.peer_manager.on_seconded(para_id, collator_id);
+			state.collations.note_seconded(&relay_parent, para_id);
+
+			if state.peer_manager.is_connected(&peer_id) {
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					peer_id,
+					BENEFIT_NOTIFY_GOOD,
+				)
+				.await;
+				notify_collation_seconded(
+					ctx.sender(),
+					peer_id,
+					CollationVersion::V2,
+					relay_parent,
+					stmt,
+				)
+				.await;
+			}
+
+			// See if we've unblocked other collations for seconding.
+			second_unblocked_collations(
+				ctx,
+				state,
+				para_id,
+				output_head_data,
+				output_head_data_hash,
+			)
+			.await;
+
+			fetch_collations(ctx.sender(), state).await;
+		},
+		Invalid(parent, candidate_receipt) => {
+			// Remove collations which were blocked from seconding and had this candidate as parent.
+			state.collations.blocked_from_seconding.remove(&BlockedCollationId {
+				para_id: candidate_receipt.descriptor.para_id(),
+				parent_head_data_hash: candidate_receipt.descriptor.para_head(),
+			});
+
+			let fetched_collation = FetchedCollation::from(&candidate_receipt);
+			let collation_event = match state.collations.fetched_candidates.entry(fetched_collation)
+			{
+				Entry::Occupied(entry)
+					if entry.get().pending_collation.commitments_hash ==
+						Some(candidate_receipt.commitments_hash) =>
+					entry.remove(),
+				Entry::Occupied(_) => {
+					gum::error!(
+						target: LOG_TARGET,
+						relay_parent = ?parent,
+						candidate = ?candidate_receipt.hash(),
+						"Reported invalid candidate for unknown `pending_candidate`!",
+					);
+					return
+				},
+				Entry::Vacant(_) => return,
+			};
+
+			let CollationEvent {
+				collator_id,
+				pending_collation: PendingCollation { peer_id, para_id, .. },
+				..
+			} = collation_event;
+			state.peer_manager.on_invalid_collation(para_id, &collator_id);
+			if state.peer_manager.is_connected(&peer_id) {
+				modify_reputation(&mut state.reputation, ctx.sender(), peer_id, COST_REPORT_BAD)
+					.await;
+			}
+
+			fetch_collations(ctx.sender(), state).await;
+		},
+	}
+}
+
+/// Bridge event switch.
+#[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
+async fn handle_network_msg<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	keystore: &KeystorePtr,
+	bridge_message: NetworkBridgeEvent<net_protocol::CollatorProtocolMessage>,
+) -> Result<()> {
+	use NetworkBridgeEvent::*;
+
+	match bridge_message {
+		PeerConnected(peer_id, observed_role, protocol_version, _) => {
+			let version: CollationVersion = match protocol_version.try_into() {
+				Ok(version) => version,
+				Err(err) => {
+					// Network bridge is expected to handle this.
+					gum::error!(
+						target: LOG_TARGET,
+						?peer_id,
+						?observed_role,
+						?err,
+						"Unsupported protocol version"
+					);
+					return Ok(())
+				},
+			};
+
+			if version == CollationVersion::V1 {
+				gum::debug!(
+					target: LOG_TARGET,
+					?peer_id,
+					"Disconnecting peer using the legacy collator protocol version",
+				);
+				disconnect_peer(ctx.sender(), peer_id).await;
+				return Ok(())
+			}
+
+			state.peer_manager.on_connected(peer_id, Instant::now());
+			state.metrics.note_collator_peer_count(state.peer_manager.peer_count());
+		},
+		PeerDisconnected(peer_id) => {
+			state.peer_manager.on_disconnected(&peer_id);
+			state.collations.remove_peer(&peer_id);
+			state.metrics.note_collator_peer_count(state.peer_manager.peer_count());
+		},
+		NewGossipTopology { .. } => {
+			// impossible!
+		},
+		PeerViewChange(..) => {
+			// Advertisements are checked against our own view, the view of the peers is not
+			// relevant.
+		},
+		OurViewChange(view) => {
+			handle_our_view_change(ctx.sender(), state, keystore, view).await?;
+		},
+		PeerMessage(remote, msg) => {
+			process_incoming_peer_message(ctx, state, remote, msg).await;
+		},
+		UpdatedAuthorityIds { .. } => {
+			// The validator side doesn't deal with `AuthorityDiscoveryId`s.
+		},
+	}
+
 	Ok(())
 }
+
+/// Networking message has been received.
+#[overseer::contextbounds(CollatorProtocol, prefix = overseer)]
+async fn process_incoming_peer_message<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	origin: PeerId,
+	msg: CollationProtocols<
+		protocol_v1::CollatorProtocolMessage,
+		protocol_v2::CollatorProtocolMessage,
+	>,
+) {
+	use protocol_v2::CollatorProtocolMessage as V2;
+	use sp_runtime::traits::AppVerify;
+
+	match msg {
+		CollationProtocols::V2(V2::Declare(collator_id, para_id, signature)) => {
+			if state.peer_manager.is_declared(&collator_id) {
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					origin,
+					COST_UNEXPECTED_MESSAGE,
+				)
+				.await;
+				return
+			}
+
+			if !state.peer_manager.is_connected(&origin) {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?para_id,
+					"Unknown peer",
+				);
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					origin,
+					COST_UNEXPECTED_MESSAGE,
+				)
+				.await;
+				return
+			}
+
+			if state.peer_manager.collator(&origin).is_some() {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?para_id,
+					"Peer is already in the collating state",
+				);
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					origin,
+					COST_UNEXPECTED_MESSAGE,
+				)
+				.await;
+				return
+			}
+
+			if !signature.verify(&*protocol_v1::declare_signature_payload(&origin), &collator_id) {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?para_id,
+					"Signature verification failure",
+				);
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					origin,
+					COST_INVALID_SIGNATURE,
+				)
+				.await;
+				return
+			}
+
+			if !state.collations.current_assignments.contains_key(&para_id) {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared as collator for unneeded para. Current assignments: {:?}",
+					&state.collations.current_assignments
+				);
+
+				modify_reputation(
+					&mut state.reputation,
+					ctx.sender(),
+					origin,
+					COST_UNNEEDED_COLLATOR,
+				)
+				.await;
+				gum::trace!(target: LOG_TARGET, "Disconnecting unneeded collator");
+				disconnect_peer(ctx.sender(), origin).await;
+				return
+			}
+
+			match state.peer_manager.declare(origin, collator_id.clone(), para_id) {
+				DeclarationOutcome::Accepted { evicted } => {
+					gum::debug!(
+						target: LOG_TARGET,
+						peer_id = ?origin,
+						?collator_id,
+						?para_id,
+						"Declared as collator for current para",
+					);
+
+					if let Some(evicted) = evicted {
+						gum::debug!(
+							target: LOG_TARGET,
+							peer_id = ?evicted,
+							?para_id,
+							"Disconnecting collator in favour of a collator with a better reputation",
+						);
+						state.metrics.on_collator_evicted();
+						state.collations.remove_peer(&evicted);
+						state.collations.cancel_fetches_from(&evicted);
+						disconnect_peer(ctx.sender(), evicted).await;
+					}
+				},
+				DeclarationOutcome::Rejected => {
+					gum::debug!(
+						target: LOG_TARGET,
+						peer_id = ?origin,
+						?collator_id,
+						?para_id,
+						"Para has enough connected collators with a better reputation",
+					);
+					disconnect_peer(ctx.sender(), origin).await;
+				},
+			}
+		},
+		CollationProtocols::V2(V2::AdvertiseCollation {
+			relay_parent,
+			candidate_hash,
+			parent_head_data_hash,
+		}) => {
+			if let Err(err) = handle_advertisement(
+				ctx.sender(),
+				state,
+				relay_parent,
+				origin,
+				candidate_hash,
+				parent_head_data_hash,
+			)
+			.await
+			{
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?relay_parent,
+					?candidate_hash,
+					error = ?err,
+					"Rejected v2 advertisement",
+				);
+
+				if let Some(rep) = err.reputation_changes() {
+					modify_reputation(&mut state.reputation, ctx.sender(), origin, rep).await;
+				}
+			}
+		},
+		CollationProtocols::V2(V2::CollationSeconded(..)) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				"Unexpected `CollationSeconded` message, decreasing reputation",
+			);
+
+			modify_reputation(&mut state.reputation, ctx.sender(), origin, COST_UNEXPECTED_MESSAGE)
+				.await;
+		},
+		CollationProtocols::V1(_) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				"Unexpected message using the legacy collator protocol version",
+			);
+
+			modify_reputation(&mut state.reputation, ctx.sender(), origin, COST_UNEXPECTED_MESSAGE)
+				.await;
+		},
+	}
+}
+
+async fn handle_advertisement<Sender>(
+	sender: &mut Sender,
+	state: &mut State,
+	relay_parent: Hash,
+	peer_id: PeerId,
+	candidate_hash: CandidateHash,
+	parent_head_data_hash: Hash,
+) -> std::result::Result<(), AdvertisementError>
+where
+	Sender: CollatorProtocolSenderTrait,
+{
+	let (collator_id, para_id) = match state.peer_manager.collator(&peer_id) {
+		Some((collator_id, para_id)) => (collator_id.clone(), para_id),
+		None if state.peer_manager.is_connected(&peer_id) =>
+			return Err(AdvertisementError::UndeclaredCollator),
+		None => return Err(AdvertisementError::UnknownPeer),
+	};
+
+	let advertisement = Advertisement {
+		peer_id,
+		collator_id,
+		para_id,
+		relay_parent,
+		prospective_candidate: ProspectiveCandidate { candidate_hash, parent_head_data_hash },
+		received_at: Instant::now(),
+	};
+
+	// Do the cheap checks before asking the backing subsystem.
+	state
+		.collations
+		.check_advertisement(&advertisement, advertisement.received_at)?;
+
+	if !can_second(sender, para_id, relay_parent, candidate_hash, parent_head_data_hash).await {
+		return Err(AdvertisementError::BlockedByBacking)
+	}
+
+	gum::debug!(
+		target: LOG_TARGET,
+		?peer_id,
+		%para_id,
+		?relay_parent,
+		?candidate_hash,
+		score = u16::from(state.peer_manager.score(&para_id, &advertisement.collator_id)),
+		"Received advertise collation",
+	);
+
+	state.collations.insert_advertisement(advertisement, Instant::now())?;
+	fetch_collations(sender, state).await;
+
+	Ok(())
+}
+
+/// Fetch the collations of the best collators for all unfulfilled claim queue entries.
+async fn fetch_collations<Sender>(sender: &mut Sender, state: &mut State)
+where
+	Sender: CollatorProtocolSenderTrait,
+{
+	let now = Instant::now();
+	let peer_manager = &state.peer_manager;
+	let to_fetch = state.collations.pick_advertisements_to_fetch(now, |para_id, collator_id| {
+		peer_manager.score(para_id, collator_id)
+	});
+
+	for (advertisement, cancellation_token) in to_fetch {
+		state
+			.metrics
+			.on_fetch_started(now.saturating_duration_since(advertisement.received_at));
+		request_collation(sender, state, advertisement, cancellation_token).await;
+	}
+}
+
+/// Request a collation from the network.
+async fn request_collation(
+	sender: &mut impl overseer::CollatorProtocolSenderTrait,
+	state: &mut State,
+	advertisement: Advertisement,
+	cancellation_token: tokio_util::sync::CancellationToken,
+) {
+	let pending_collation = advertisement.pending_collation();
+	let Advertisement {
+		peer_id, collator_id, para_id, relay_parent, prospective_candidate, ..
+	} = advertisement;
+
+	let (req, response_recv) = OutgoingRequest::new(
+		Recipient::Peer(peer_id),
+		request_v2::CollationFetchingRequest {
+			relay_parent,
+			para_id,
+			candidate_hash: prospective_candidate.candidate_hash,
+		},
+	);
+
+	state.collation_requests.push(CollationFetchRequest {
+		pending_collation,
+		collator_id,
+		collator_protocol_version: CollationVersion::V2,
+		from_collator: response_recv.boxed(),
+		cancellation_token,
+		_lifetime_timer: state.metrics.time_collation_request_duration(),
+	});
+
+	gum::debug!(
+		target: LOG_TARGET,
+		peer_id = %peer_id,
+		%para_id,
+		?relay_parent,
+		"Requesting collation",
+	);
+
+	sender
+		.send_message(NetworkBridgeTxMessage::SendRequests(
+			vec![Requests::CollationFetchingV2(req)],
+			IfDisconnected::ImmediateError,
+		))
+		.await;
+}
+
+/// Handle a collation fetch response.
+fn handle_collation_fetch_response(
+	state: &mut State,
+	response: <CollationFetchRequest as Future>::Output,
+	network_error_freq: &mut gum::Freq,
+	canceled_freq: &mut gum::Freq,
+) -> std::result::Result<PendingCollationFetch, Option<(PeerId, Rep)>> {
+	let (CollationEvent { collator_id, collator_protocol_version, pending_collation }, response) =
+		response;
+
+	// Frees up the claim queue entry held by the fetch.
+	if !state.collations.note_fetch_concluded(&pending_collation) {
+		gum::debug!(
+			target: LOG_TARGET,
+			hash = ?pending_collation.relay_parent,
+			para_id = ?pending_collation.para_id,
+			peer_id = ?pending_collation.peer_id,
+			"Fetched collation is not relevant anymore"
+		);
+		return Err(None)
+	}
+
+	let response = match response {
+		Err(CollationFetchError::Cancelled) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				hash = ?pending_collation.relay_parent,
+				para_id = ?pending_collation.para_id,
+				peer_id = ?pending_collation.peer_id,
+				"Request was cancelled from the validator side"
+			);
+			return Err(None)
+		},
+		Err(CollationFetchError::Request(req_error)) => Err(req_error),
+		Ok(resp) => Ok(resp),
+	};
+
+	let PendingCollation { para_id, peer_id, .. } = pending_collation;
+	let mut metrics_result = Err(());
+
+	let result = match response {
+		Err(RequestError::InvalidResponse(err)) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				hash = ?pending_collation.relay_parent,
+				?para_id,
+				?peer_id,
+				err = ?err,
+				"Collator provided response that could not be decoded"
+			);
+			state.peer_manager.on_failed_fetch(para_id, &collator_id);
+			Err(Some((peer_id, COST_CORRUPTED_MESSAGE)))
+		},
+		Err(err) if err.is_timed_out() => {
+			gum::debug!(
+				target: LOG_TARGET,
+				hash = ?pending_collation.relay_parent,
+				?para_id,
+				?peer_id,
+				"Request timed out"
+			);
+			// A collator failing to deliver in time loses priority for future fetches, but we
+			// don't change its network reputation.
+			state.peer_manager.on_failed_fetch(para_id, &collator_id);
+			Err(None)
+		},
+		Err(RequestError::NetworkError(err)) => {
+			gum::warn_if_frequent!(
+				freq: network_error_freq,
+				max_rate: gum::Times::PerHour(100),
+				target: LOG_TARGET,
+				hash = ?pending_collation.relay_parent,
+				?para_id,
+				?peer_id,
+				err = ?err,
+				"Fetching collation failed due to network error"
+			);
+			state.peer_manager.on_failed_fetch(para_id, &collator_id);
+			Err(Some((peer_id, COST_NETWORK_ERROR)))
+		},
+		Err(RequestError::Canceled(err)) => {
+			gum::warn_if_frequent!(
+				freq: canceled_freq,
+				max_rate: gum::Times::PerHour(100),
+				target: LOG_TARGET,
+				hash = ?pending_collation.relay_parent,
+				?para_id,
+				?peer_id,
+				err = ?err,
+				"Canceled should be handled by `is_timed_out` above - this is a bug!"
+			);
+			Err(None)
+		},
+		Ok(
+			request_v1::CollationFetchingResponse::Collation(receipt, _) |
+			request_v1::CollationFetchingResponse::CollationWithParentHeadData { receipt, .. },
+		) if receipt.descriptor().para_id() != para_id => {
+			gum::debug!(
+				target: LOG_TARGET,
+				expected_para_id = ?para_id,
+				got_para_id = ?receipt.descriptor().para_id(),
+				?peer_id,
+				"Got wrong para ID for requested collation."
+			);
+			state.peer_manager.on_invalid_collation(para_id, &collator_id);
+			Err(Some((peer_id, COST_WRONG_PARA)))
+		},
+		Ok(request_v1::CollationFetchingResponse::Collation(candidate_receipt, pov)) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				%para_id,
+				hash = ?pending_collation.relay_parent,
+				candidate_hash = ?candidate_receipt.hash(),
+				"Received collation",
+			);
+
+			metrics_result = Ok(());
+			Ok(PendingCollationFetch {
+				collation_event: CollationEvent {
+					collator_id,
+					pending_collation,
+					collator_protocol_version,
+				},
+				candidate_receipt,
+				pov,
+				maybe_parent_head_data: None,
+			})
+		},
+		Ok(request_v1::CollationFetchingResponse::CollationWithParentHeadData {
+			receipt,
+			pov,
+			parent_head_data,
+		}) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				%para_id,
+				hash = ?pending_collation.relay_parent,
+				candidate_hash = ?receipt.hash(),
+				"Received collation (v3)",
+			);
+
+			metrics_result = Ok(());
+			Ok(PendingCollationFetch {
+				collation_event: CollationEvent {
+					collator_id,
+					pending_collation,
+					collator_protocol_version,
+				},
+				candidate_receipt: receipt,
+				pov,
+				maybe_parent_head_data: Some(parent_head_data),
+			})
+		},
+	};
+	state.metrics.on_request(metrics_result);
+	result
+}
+
+/// Handle a fetched collation result.
+/// Returns whether or not seconding has begun.
+#[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
+async fn kick_off_seconding<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	PendingCollationFetch { mut collation_event, candidate_receipt, pov, maybe_parent_head_data }: PendingCollationFetch,
+) -> std::result::Result<bool, SecondingError> {
+	let pending_collation = collation_event.pending_collation;
+	let relay_parent = pending_collation.relay_parent;
+
+	let Some(per_relay_parent) = state.collations.per_relay_parent.get(&relay_parent) else {
+		// Relay parent went out of view, not an error.
+		gum::trace!(
+			target: LOG_TARGET,
+			relay_parent = ?relay_parent,
+			"Fetched collation for a parent out of view",
+		);
+		return Ok(false)
+	};
+
+	// Sanity check of the candidate receipt version.
+	descriptor_version_sanity_check(candidate_receipt.descriptor(), per_relay_parent)?;
+
+	let Some(ProspectiveCandidate { parent_head_data_hash, .. }) =
+		pending_collation.prospective_candidate
+	else {
+		// We only request collations advertised using the v2 protocol.
+		return Ok(false)
+	};
+
+	let fetched_collation = FetchedCollation::from(&candidate_receipt);
+	if state.collations.fetched_candidates.contains_key(&fetched_collation) {
+		return Err(SecondingError::Duplicate)
+	}
+	collation_event.pending_collation.commitments_hash = Some(candidate_receipt.commitments_hash);
+
+	let maybe_pvd = request_prospective_validation_data(
+		ctx.sender(),
+		relay_parent,
+		parent_head_data_hash,
+		pending_collation.para_id,
+		maybe_parent_head_data.clone(),
+	)
+	.await?;
+
+	let pvd = match (maybe_pvd, maybe_parent_head_data.clone()) {
+		(Some(pvd), _) => pvd,
+		(None, None) => {
+			// In this case, the collator did not supply the head data and neither could
+			// prospective-parachains. We add this to the blocked_from_seconding collection
+			// until we second its parent.
+			let blocked_collation = PendingCollationFetch {
+				collation_event,
+				candidate_receipt,
+				pov,
+				maybe_parent_head_data: None,
+			};
+			gum::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?blocked_collation.candidate_receipt.hash(),
+				relay_parent = ?blocked_collation.candidate_receipt.descriptor.relay_parent(),
+				"Collation having parent head data hash {} is blocked from seconding. Waiting on its parent to be validated.",
+				parent_head_data_hash
+			);
+			state
+				.collations
+				.blocked_from_seconding
+				.entry(BlockedCollationId {
+					para_id: blocked_collation.candidate_receipt.descriptor.para_id(),
+					parent_head_data_hash,
+				})
+				.or_insert_with(Vec::new)
+				.push(blocked_collation);
+
+			return Ok(false)
+		},
+		(None, Some(_)) => {
+			// Even though we already have the parent head data, the pvd fetching failed. We
+			// don't need to wait for seconding another collation outputting this head data.
+			return Err(SecondingError::PersistedValidationDataNotFound)
+		},
+	};
+
+	fetched_collation_sanity_check(
+		&collation_event.pending_collation,
+		&candidate_receipt,
+		&pvd,
+		maybe_parent_head_data.map(|head| (head, parent_head_data_hash)),
+	)?;
+
+	ctx.send_message(CandidateBackingMessage::Second(relay_parent, candidate_receipt, pvd, pov))
+		.await;
+	state.collations.fetched_candidates.insert(fetched_collation, collation_event);
+
+	Ok(true)
+}
+
+// Try seconding any collations which were waiting on the validation of their parent
+#[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
+async fn second_unblocked_collations<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	para_id: ParaId,
+	head_data: HeadData,
+	head_data_hash: Hash,
+) {
+	let Some(unblocked_collations) = state
+		.collations
+		.blocked_from_seconding
+		.remove(&BlockedCollationId { para_id, parent_head_data_hash: head_data_hash })
+	else {
+		return
+	};
+
+	if !unblocked_collations.is_empty() {
+		gum::debug!(
+			target: LOG_TARGET,
+			"Candidate outputting head data with hash {} unblocked {} collations for seconding.",
+			head_data_hash,
+			unblocked_collations.len()
+		);
+	}
+
+	for mut unblocked_collation in unblocked_collations {
+		unblocked_collation.maybe_parent_head_data = Some(head_data.clone());
+		let peer_id = unblocked_collation.collation_event.pending_collation.peer_id;
+		let collator_id = unblocked_collation.collation_event.collator_id.clone();
+		let relay_parent = unblocked_collation.candidate_receipt.descriptor.relay_parent();
+
+		if let Err(err) = kick_off_seconding(ctx, state, unblocked_collation).await {
+			gum::warn!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?para_id,
+				?peer_id,
+				error = %err,
+				"Seconding aborted due to an error",
+			);
+
+			if err.is_malicious() {
+				// Report malicious peer.
+				modify_reputation(&mut state.reputation, ctx.sender(), peer_id, COST_REPORT_BAD)
+					.await;
+				state.peer_manager.on_invalid_collation(para_id, &collator_id);
+			}
+		}
+	}
+}
+
+/// Our view has changed.
+async fn handle_our_view_change<Sender>(
+	sender: &mut Sender,
+	state: &mut State,
+	keystore: &KeystorePtr,
+	view: OurView,
+) -> Result<()>
+where
+	Sender: CollatorProtocolSenderTrait,
+{
+	let collations = &mut state.collations;
+	let current_leaves = collations.active_leaves.clone();
+
+	let removed = current_leaves.iter().filter(|h| !view.contains(h));
+	let added = view.iter().filter(|h| !current_leaves.contains(h));
+
+	for leaf in added {
+		let session_index = request_session_index_for_child(*leaf, sender)
+			.await
+			.await
+			.map_err(Error::CancelledSessionIndex)??;
+
+		let v2_receipts = request_node_features(*leaf, session_index, sender)
+			.await
+			.await
+			.map_err(Error::CancelledNodeFeatures)??
+			.get(node_features::FeatureIndex::CandidateReceiptV2 as usize)
+			.map(|b| *b)
+			.unwrap_or(false);
+
+		let Some(per_relay_parent) =
+			construct_per_relay_parent(sender, keystore, *leaf, v2_receipts, session_index).await?
+		else {
+			continue
+		};
+
+		collations.active_leaves.insert(*leaf);
+		collations.insert_relay_parent(*leaf, per_relay_parent);
+
+		collations
+			.implicit_view
+			.activate_leaf(sender, *leaf)
+			.await
+			.map_err(Error::ImplicitViewFetchError)?;
+
+		// Order is always descending.
+		let allowed_ancestry = collations
+			.implicit_view
+			.known_allowed_relay_parents_under(leaf, None)
+			.unwrap_or_default()
+			.to_vec();
+		for block_hash in allowed_ancestry {
+			if collations.per_relay_parent.contains_key(&block_hash) {
+				continue
+			}
+
+			// Safe to use the same v2 receipts config for the allowed relay parents as well
+			// as the same session index since they must be in the same session.
+			if let Some(per_relay_parent) =
+				construct_per_relay_parent(sender, keystore, block_hash, v2_receipts, session_index)
+					.await?
+			{
+				collations.insert_relay_parent(block_hash, per_relay_parent);
+			}
+		}
+	}
+
+	for removed in removed {
+		gum::trace!(
+			target: LOG_TARGET,
+			?view,
+			?removed,
+			"handle_our_view_change - removed",
+		);
+
+		collations.active_leaves.remove(removed);
+		// If the leaf is deactivated it still may stay in the view as a part
+		// of implicit ancestry. Only update the state after the hash is actually
+		// pruned from the block info storage.
+		for removed in collations.implicit_view.deactivate_leaf(*removed) {
+			collations.remove_relay_parent(&removed);
+		}
+	}
+
+	// Disconnect collators of paras which are not assigned to us anymore.
+	//
+	// If the peer hasn't declared yet, they will be disconnected if they do not
+	// declare.
+	for peer_id in state.peer_manager.unneeded_collators(&state.collations.current_assignments) {
+		gum::trace!(
+			target: LOG_TARGET,
+			?peer_id,
+			"Disconnecting peer on view change (not current parachain id)"
+		);
+		disconnect_peer(sender, peer_id).await;
+	}
+
+	Ok(())
+}
+
+async fn construct_per_relay_parent<Sender>(
+	sender: &mut Sender,
+	keystore: &KeystorePtr,
+	relay_parent: Hash,
+	v2_receipts: bool,
+	session_index: SessionIndex,
+) -> Result<Option<PerRelayParent>>
+where
+	Sender: CollatorProtocolSenderTrait,
+{
+	let validators = polkadot_node_subsystem_util::request_validators(relay_parent, sender)
+		.await
+		.await
+		.map_err(Error::CancelledActiveValidators)??;
+
+	let (groups, rotation_info) =
+		polkadot_node_subsystem_util::request_validator_groups(relay_parent, sender)
+			.await
+			.await
+			.map_err(Error::CancelledValidatorGroups)??;
+
+	let core_now = if let Some(group) =
+		polkadot_node_subsystem_util::signing_key_and_index(&validators, keystore).and_then(
+			|(_, index)| polkadot_node_subsystem_util::find_validator_group(&groups, index),
+		) {
+		rotation_info.core_for_group(group, groups.len())
+	} else {
+		gum::trace!(target: LOG_TARGET, ?relay_parent, "Not a validator");
+		return Ok(None)
+	};
+
+	let mut claim_queue = request_claim_queue(relay_parent, sender)
+		.await
+		.await
+		.map_err(Error::CancelledClaimQueue)??;
+
+	let assignment = claim_queue.remove(&core_now).unwrap_or_default().into_iter().collect();
+
+	Ok(Some(PerRelayParent::new(assignment, v2_receipts, core_now, session_index)))
+}
+
+// Sanity check the candidate descriptor version.
+fn descriptor_version_sanity_check(
+	descriptor: &CandidateDescriptorV2,
+	per_relay_parent: &PerRelayParent,
+) -> std::result::Result<(), SecondingError> {
+	match descriptor.version() {
+		CandidateDescriptorVersion::V1 => Ok(()),
+		CandidateDescriptorVersion::V2 if per_relay_parent.v2_receipts => {
+			if let Some(core_index) = descriptor.core_index() {
+				if core_index != per_relay_parent.current_core {
+					return Err(SecondingError::InvalidCoreIndex(
+						core_index.0,
+						per_relay_parent.current_core.0,
+					))
+				}
+			}
+
+			if let Some(session_index) = descriptor.session_index() {
+				if session_index != per_relay_parent.session_index {
+					return Err(SecondingError::InvalidSessionIndex(
+						session_index,
+						per_relay_parent.session_index,
+					))
+				}
+			}
+
+			Ok(())
+		},
+		descriptor_version => Err(SecondingError::InvalidReceiptVersion(descriptor_version)),
+	}
+}
@@ -0,0 +1,275 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent storage of collator reputations.
+//!
+//! Reputations are cached in memory and written back to the parachains database whenever
+//! [`ReputationDb::flush`] is called. Every para has its own entry, keyed by the SCALE-encoded
+//! `ParaId`, holding the scores of at most [`MAX_STORED_SCORES_PER_PARA`] collators.
+//!
+//! Scores belong to the [`CollatorId`] a collator declares itself with rather than to its network
+//! identity, so a collator can't shed a bad reputation by reconnecting with a new `PeerId`.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+use codec::{Decode, Encode};
+
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::{CollatorId, Id as ParaId};
+
+use super::super::{
+	common::{ReputationConfig, Score, MAX_STORED_SCORES_PER_PARA},
+	error::Result,
+};
+use crate::LOG_TARGET;
+
+const REPUTATION_PREFIX: &[u8; 20] = b"CollatorReputations_";
+
+/// The format in which the scores of a para are persisted: collator ids along with their score.
+#[derive(Encode, Decode)]
+struct StoredScores(Vec<(CollatorId, u16)>);
+
+fn reputation_key(para_id: ParaId) -> Vec<u8> {
+	(REPUTATION_PREFIX, para_id).encode()
+}
+
+fn decode_reputation_key(key: &[u8]) -> Option<ParaId> {
+	key.strip_prefix(&REPUTATION_PREFIX[..])
+		.and_then(|mut para_id| ParaId::decode(&mut para_id).ok())
+}
+
+/// Collator reputations, backed by the parachains database.
+pub struct ReputationDb {
+	db: Arc<dyn Database>,
+	config: ReputationConfig,
+	scores: HashMap<ParaId, HashMap<CollatorId, Score>>,
+	/// Paras which were modified since the last flush.
+	dirty: HashSet<ParaId>,
+}
+
+impl ReputationDb {
+	/// Load all the stored reputations from the database.
+	///
+	/// Entries which can't be decoded are skipped.
+	pub fn load(db: Arc<dyn Database>, config: ReputationConfig) -> Result<Self> {
+		let mut scores = HashMap::new();
+
+		for item in db.iter_with_prefix(config.col_reputation_data, &REPUTATION_PREFIX[..]) {
+			let (key, value) = item?;
+
+			let Some(para_id) = decode_reputation_key(&key) else {
+				gum::warn!(target: LOG_TARGET, ?key, "Invalid collator reputation key");
+				continue
+			};
+
+			let stored = match StoredScores::decode(&mut &value[..]) {
+				Ok(stored) => stored,
+				Err(err) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?para_id,
+						?err,
+						"Failed to decode stored collator reputations",
+					);
+					continue
+				},
+			};
+
+			let para_scores = stored
+				.0
+				.into_iter()
+				.filter_map(|(collator_id, score)| Some((collator_id, Score::new(score)?)))
+				.filter(|(_, score)| !score.is_zero())
+				.collect::<HashMap<_, _>>();
+
+			if !para_scores.is_empty() {
+				scores.insert(para_id, para_scores);
+			}
+		}
+
+		gum::debug!(
+			target: LOG_TARGET,
+			paras = scores.len(),
+			"Loaded collator reputations from the database",
+		);
+
+		Ok(Self { db, config, scores, dirty: HashSet::new() })
+	}
+
+	/// The reputation of `collator_id` as a collator of `para_id`.
+	pub fn score(&self, para_id: &ParaId, collator_id: &CollatorId) -> Score {
+		self.scores
+			.get(para_id)
+			.and_then(|para_scores| para_scores.get(collator_id))
+			.copied()
+			.unwrap_or_default()
+	}
+
+	/// Increase the reputation of `collator_id` for `para_id` by `value`.
+	pub fn bump(&mut self, para_id: ParaId, collator_id: CollatorId, value: u16) {
+		let score = self.scores.entry(para_id).or_default().entry(collator_id).or_default();
+		*score = score.saturating_add(value);
+		self.dirty.insert(para_id);
+	}
+
+	/// Decrease the reputation of `collator_id` for `para_id` by `value`.
+	pub fn slash(&mut self, para_id: ParaId, collator_id: &CollatorId, value: u16) {
+		let Some(para_scores) = self.scores.get_mut(&para_id) else { return };
+		let Some(score) = para_scores.get_mut(collator_id) else { return };
+
+		*score = score.saturating_sub(value);
+		if score.is_zero() {
+			para_scores.remove(collator_id);
+		}
+		self.dirty.insert(para_id);
+	}
+
+	/// Decrease the reputation of all known collators by `value`.
+	pub fn decay(&mut self, value: u16) {
+		for (para_id, para_scores) in self.scores.iter_mut() {
+			for score in para_scores.values_mut() {
+				*score = score.saturating_sub(value);
+			}
+			para_scores.retain(|_, score| !score.is_zero());
+			self.dirty.insert(*para_id);
+		}
+	}
+
+	/// The number of collators we keep a reputation for.
+	pub fn collator_count(&self) -> usize {
+		self.scores.values().map(HashMap::len).sum()
+	}
+
+	/// Write all the reputations modified since the last flush to the database.
+	///
+	/// Only the [`MAX_STORED_SCORES_PER_PARA`] best collators of every para are retained.
+	pub fn flush(&mut self) -> Result<()> {
+		if self.dirty.is_empty() {
+			return Ok(())
+		}
+
+		let mut tx = DBTransaction::new();
+		for para_id in self.dirty.drain() {
+			let key = reputation_key(para_id);
+			let para_scores = match self.scores.get_mut(&para_id) {
+				Some(para_scores) if !para_scores.is_empty() => para_scores,
+				_ => {
+					self.scores.remove(&para_id);
+					tx.delete(self.config.col_reputation_data, &key);
+					continue
+				},
+			};
+
+			let mut sorted = para_scores.iter().map(|(c, s)| (c.clone(), *s)).collect::<Vec<_>>();
+			sorted.sort_by(|a, b| b.1.cmp(&a.1));
+			if sorted.len() > MAX_STORED_SCORES_PER_PARA {
+				for (collator_id, _) in sorted.drain(MAX_STORED_SCORES_PER_PARA..) {
+					para_scores.remove(&collator_id);
+				}
+			}
+
+			let stored = StoredScores(sorted.into_iter().map(|(c, s)| (c, u16::from(s))).collect());
+			tx.put_vec(self.config.col_reputation_data, &key, stored.encode());
+		}
+
+		self.db.write(tx)?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter;
+
+	const CONFIG: ReputationConfig = ReputationConfig { col_reputation_data: 0 };
+
+	fn make_db() -> Arc<dyn Database> {
+		Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[CONFIG.col_reputation_data]))
+	}
+
+	fn collator_id(seed: u8) -> CollatorId {
+		sp_core::sr25519::Public::from_raw([seed; 32]).into()
+	}
+
+	#[test]
+	fn reputations_are_persisted() {
+		let db = make_db();
+		let (collator_a, collator_b) = (collator_id(1), collator_id(2));
+		let para_id = ParaId::from(100);
+
+		let mut reputations = ReputationDb::load(db.clone(), CONFIG).unwrap();
+		reputations.bump(para_id, collator_a.clone(), 100);
+		reputations.bump(para_id, collator_b.clone(), 50);
+		reputations.slash(para_id, &collator_b, 20);
+		reputations.flush().unwrap();
+
+		let reputations = ReputationDb::load(db, CONFIG).unwrap();
+		assert_eq!(u16::from(reputations.score(&para_id, &collator_a)), 100);
+		assert_eq!(u16::from(reputations.score(&para_id, &collator_b)), 30);
+		assert!(reputations.score(&ParaId::from(200), &collator_a).is_zero());
+	}
+
+	#[test]
+	fn decayed_reputations_are_removed() {
+		let db = make_db();
+		let para_id = ParaId::from(100);
+
+		let mut reputations = ReputationDb::load(db.clone(), CONFIG).unwrap();
+		reputations.bump(para_id, collator_id(1), 2);
+		reputations.flush().unwrap();
+		assert_eq!(reputations.collator_count(), 1);
+
+		reputations.decay(1);
+		reputations.flush().unwrap();
+		assert_eq!(ReputationDb::load(db.clone(), CONFIG).unwrap().collator_count(), 1);
+
+		reputations.decay(1);
+		reputations.flush().unwrap();
+		assert_eq!(reputations.collator_count(), 0);
+		assert_eq!(ReputationDb::load(db.clone(), CONFIG).unwrap().collator_count(), 0);
+		assert!(db.get(CONFIG.col_reputation_data, &reputation_key(para_id)).unwrap().is_none());
+	}
+
+	#[test]
+	fn only_best_collators_are_stored() {
+		let db = make_db();
+		let para_id = ParaId::from(100);
+
+		let mut reputations = ReputationDb::load(db.clone(), CONFIG).unwrap();
+		let collators = (0..MAX_STORED_SCORES_PER_PARA + 10)
+			.map(|i| {
+				let collator_id = collator_id(i as u8);
+				reputations.bump(para_id, collator_id.clone(), i as u16 + 1);
+				collator_id
+			})
+			.collect::<Vec<_>>();
+		reputations.flush().unwrap();
+
+		let reputations = ReputationDb::load(db, CONFIG).unwrap();
+		assert_eq!(reputations.collator_count(), MAX_STORED_SCORES_PER_PARA);
+		// The collators with the lowest scores were dropped.
+		for collator_id in &collators[..10] {
+			assert!(reputations.score(&para_id, collator_id).is_zero());
+		}
+		for collator_id in &collators[10..] {
+			assert!(!reputations.score(&para_id, collator_id).is_zero());
+		}
+	}
+}
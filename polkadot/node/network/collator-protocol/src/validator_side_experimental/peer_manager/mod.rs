@@ -0,0 +1,307 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Connection management for collator peers.
+//!
+//! Keeps track of the connected peers and of the paras they collate for, along with the
+//! persistent reputation of every collator. The number of collators connected for a single para
+//! is limited to [`CONNECTED_PEERS_PARA_LIMIT`]. Once the limit is reached, a newly declared
+//! collator is only accepted if it has a better reputation than the worst connected one.
+
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+use polkadot_node_network_protocol::PeerId;
+use polkadot_primitives::{CollatorId, Id as ParaId};
+
+use super::{
+	common::{
+		Score, CONNECTED_PEERS_PARA_LIMIT, FAILED_FETCH_SLASH, INACTIVITY_DECAY,
+		INVALID_COLLATION_SLASH, VALID_COLLATION_BUMP,
+	},
+	error::Result,
+};
+
+mod db;
+
+pub use db::ReputationDb;
+
+/// The state of a connected peer.
+#[derive(Debug, Clone)]
+enum PeerState {
+	/// The peer connected but hasn't declared itself as a collator yet.
+	Connected(Instant),
+	/// The peer declared itself as a collator of a para.
+	Collating(CollatorId, ParaId),
+}
+
+/// The outcome of a collator declaring the para it collates for.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeclarationOutcome {
+	/// The collator was accepted. If the para already had the maximum number of collators
+	/// connected, `evicted` holds the collator with the lowest reputation which has to be
+	/// disconnected in exchange.
+	Accepted { evicted: Option<PeerId> },
+	/// The collator was rejected, because the para already has enough connected collators with
+	/// a better reputation.
+	Rejected,
+}
+
+/// Tracks connected collators and their reputations.
+pub struct PeerManager {
+	reputations: ReputationDb,
+	peers: HashMap<PeerId, PeerState>,
+}
+
+impl PeerManager {
+	/// Create a new peer manager using the given reputation storage.
+	pub fn new(reputations: ReputationDb) -> Self {
+		Self { reputations, peers: HashMap::new() }
+	}
+
+	/// A new peer connected.
+	pub fn on_connected(&mut self, peer_id: PeerId, now: Instant) {
+		self.peers.entry(peer_id).or_insert(PeerState::Connected(now));
+	}
+
+	/// A peer disconnected.
+	pub fn on_disconnected(&mut self, peer_id: &PeerId) {
+		self.peers.remove(peer_id);
+	}
+
+	/// The number of connected peers.
+	pub fn peer_count(&self) -> usize {
+		self.peers.len()
+	}
+
+	/// Whether the peer is connected and known to us.
+	pub fn is_connected(&self, peer_id: &PeerId) -> bool {
+		self.peers.contains_key(peer_id)
+	}
+
+	/// The collator id and para of a peer, if it declared itself as a collator.
+	pub fn collator(&self, peer_id: &PeerId) -> Option<(&CollatorId, ParaId)> {
+		match self.peers.get(peer_id) {
+			Some(PeerState::Collating(collator_id, para_id)) => Some((collator_id, *para_id)),
+			_ => None,
+		}
+	}
+
+	/// Whether some connected peer already declared itself using `collator_id`.
+	pub fn is_declared(&self, collator_id: &CollatorId) -> bool {
+		self.peers
+			.values()
+			.any(|state| matches!(state, PeerState::Collating(id, _) if id == collator_id))
+	}
+
+	/// A connected peer declared itself as a collator of `para_id`.
+	///
+	/// The caller is expected to have verified the declaration and that the para is assigned to
+	/// us.
+	pub fn declare(
+		&mut self,
+		peer_id: PeerId,
+		collator_id: CollatorId,
+		para_id: ParaId,
+	) -> DeclarationOutcome {
+		let score = self.reputations.score(&para_id, &collator_id);
+		let mut evicted = None;
+
+		let connected = self
+			.peers
+			.iter()
+			.filter_map(|(peer, state)| match state {
+				PeerState::Collating(id, para) if *para == para_id && *peer != peer_id =>
+					Some((*peer, self.reputations.score(&para_id, id))),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		if connected.len() >= CONNECTED_PEERS_PARA_LIMIT {
+			match connected.into_iter().min_by_key(|(_, score)| *score) {
+				Some((worst, worst_score)) if worst_score < score => {
+					self.peers.remove(&worst);
+					evicted = Some(worst);
+				},
+				_ => return DeclarationOutcome::Rejected,
+			}
+		}
+
+		self.peers.insert(peer_id, PeerState::Collating(collator_id, para_id));
+		DeclarationOutcome::Accepted { evicted }
+	}
+
+	/// Peers which haven't declared themselves as collators within `timeout` after connecting.
+	pub fn undeclared_peers(&self, now: Instant, timeout: Duration) -> Vec<PeerId> {
+		self.peers
+			.iter()
+			.filter_map(|(peer_id, state)| match state {
+				PeerState::Connected(since) if now.saturating_duration_since(*since) >= timeout =>
+					Some(*peer_id),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Collators of paras which are no longer assigned to us.
+	pub fn unneeded_collators(&self, assignments: &HashMap<ParaId, usize>) -> Vec<PeerId> {
+		self.peers
+			.iter()
+			.filter_map(|(peer_id, state)| match state {
+				PeerState::Collating(_, para_id) if !assignments.contains_key(para_id) =>
+					Some(*peer_id),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// The reputation of `collator_id` as a collator of `para_id`.
+	pub fn score(&self, para_id: &ParaId, collator_id: &CollatorId) -> Score {
+		self.reputations.score(para_id, collator_id)
+	}
+
+	/// A collation provided by the collator got seconded.
+	pub fn on_seconded(&mut self, para_id: ParaId, collator_id: CollatorId) {
+		self.reputations.bump(para_id, collator_id, VALID_COLLATION_BUMP);
+	}
+
+	/// The collator provided an invalid or malformed collation.
+	pub fn on_invalid_collation(&mut self, para_id: ParaId, collator_id: &CollatorId) {
+		self.reputations.slash(para_id, collator_id, INVALID_COLLATION_SLASH);
+	}
+
+	/// We failed to fetch a collation advertised by the collator.
+	pub fn on_failed_fetch(&mut self, para_id: ParaId, collator_id: &CollatorId) {
+		self.reputations.slash(para_id, collator_id, FAILED_FETCH_SLASH);
+	}
+
+	/// A new block got finalized. Decays all reputations and persists them.
+	pub fn on_finalized_block(&mut self) -> Result<()> {
+		self.reputations.decay(INACTIVITY_DECAY);
+		self.reputations.flush()
+	}
+
+	/// Persist all pending reputation changes.
+	pub fn flush(&mut self) -> Result<()> {
+		self.reputations.flush()
+	}
+
+	/// The number of collators we keep a reputation for.
+	pub fn reputation_count(&self) -> usize {
+		self.reputations.collator_count()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{super::common::ReputationConfig, *};
+	use polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter;
+	use std::sync::Arc;
+
+	fn make_peer_manager() -> PeerManager {
+		let config = ReputationConfig { col_reputation_data: 0 };
+		let db = Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[0]));
+		PeerManager::new(ReputationDb::load(db, config).unwrap())
+	}
+
+	fn collator_id(seed: u8) -> CollatorId {
+		sp_core::sr25519::Public::from_raw([seed; 32]).into()
+	}
+
+	#[test]
+	fn collators_are_limited_per_para() {
+		let mut peer_manager = make_peer_manager();
+		let para_id = ParaId::from(100);
+		let now = Instant::now();
+
+		let connected = (0..CONNECTED_PEERS_PARA_LIMIT)
+			.map(|i| {
+				let peer_id = PeerId::random();
+				peer_manager.on_connected(peer_id, now);
+				// Every collator except for the first one has got some reputation.
+				if i > 0 {
+					peer_manager.on_seconded(para_id, collator_id(i as u8));
+				}
+				assert_eq!(
+					peer_manager.declare(peer_id, collator_id(i as u8), para_id),
+					DeclarationOutcome::Accepted { evicted: None }
+				);
+				peer_id
+			})
+			.collect::<Vec<_>>();
+
+		// A collator of another para is not affected by the limit.
+		let other = PeerId::random();
+		peer_manager.on_connected(other, now);
+		assert_eq!(
+			peer_manager.declare(other, collator_id(200), ParaId::from(200)),
+			DeclarationOutcome::Accepted { evicted: None }
+		);
+
+		// A collator without reputation is rejected.
+		let unknown = PeerId::random();
+		peer_manager.on_connected(unknown, now);
+		assert_eq!(
+			peer_manager.declare(unknown, collator_id(201), para_id),
+			DeclarationOutcome::Rejected
+		);
+
+		// A collator with a good reputation replaces the worst connected one.
+		let good = PeerId::random();
+		peer_manager.on_connected(good, now);
+		peer_manager.on_seconded(para_id, collator_id(202));
+		peer_manager.on_seconded(para_id, collator_id(202));
+		assert_eq!(
+			peer_manager.declare(good, collator_id(202), para_id),
+			DeclarationOutcome::Accepted { evicted: Some(connected[0]) }
+		);
+		assert!(!peer_manager.is_connected(&connected[0]));
+		assert_eq!(peer_manager.collator(&good).map(|(_, para)| para), Some(para_id));
+
+		// The reputation belongs to the collator id, not to the peer it connected with.
+		let reconnected = PeerId::random();
+		peer_manager.on_disconnected(&good);
+		peer_manager.on_connected(reconnected, now);
+		assert_eq!(
+			peer_manager.declare(reconnected, collator_id(202), para_id),
+			DeclarationOutcome::Accepted { evicted: None }
+		);
+	}
+
+	#[test]
+	fn undeclared_and_unneeded_peers_are_reported() {
+		let mut peer_manager = make_peer_manager();
+		let now = Instant::now();
+		let timeout = Duration::from_secs(1);
+
+		let undeclared = PeerId::random();
+		let needed = PeerId::random();
+		let unneeded = PeerId::random();
+		for peer_id in [undeclared, needed, unneeded] {
+			peer_manager.on_connected(peer_id, now);
+		}
+		peer_manager.declare(needed, collator_id(1), ParaId::from(100));
+		peer_manager.declare(unneeded, collator_id(2), ParaId::from(200));
+
+		assert!(peer_manager.undeclared_peers(now, timeout).is_empty());
+		assert_eq!(peer_manager.undeclared_peers(now + timeout, timeout), vec![undeclared]);
+
+		let assignments = [(ParaId::from(100), 1)].into_iter().collect();
+		assert_eq!(peer_manager.unneeded_collators(&assignments), vec![unneeded]);
+	}
+}
@@ -0,0 +1,747 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bookkeeping of advertisements and collations per relay parent.
+//!
+//! Every seconded collation, as well as every collation being fetched, validated or blocked from
+//! seconding, occupies an entry in the claim queue of our core. Advertisements are only fetched
+//! for unfulfilled claim queue entries, in claim queue order, which gives the paras sharing a core
+//! the share of core time assigned to them by the claim queue. For every entry we fetch the
+//! advertisement of the collator with the best reputation.
+
+use std::{
+	cmp::Reverse,
+	collections::{HashMap, HashSet},
+	time::Instant,
+};
+
+use tokio_util::sync::CancellationToken;
+
+use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange as Rep};
+use polkadot_node_subsystem_util::backing_implicit_view::View as ImplicitView;
+use polkadot_primitives::{CollatorId, CoreIndex, Hash, Id as ParaId, SessionIndex};
+
+use super::{
+	common::{Score, INSTANT_FETCH_REP_THRESHOLD, MAX_FETCH_DELAY, MAX_UNSHARED_DOWNLOAD_TIME},
+	error::{Error, Result},
+	COST_UNEXPECTED_MESSAGE, COST_WRONG_PARA,
+};
+use crate::{
+	validator_side::{
+		claim_queue_state::ClaimQueueState,
+		collation::{
+			BlockedCollationId, CollationEvent, FetchedCollation, PendingCollation,
+			PendingCollationFetch, ProspectiveCandidate,
+		},
+	},
+	LOG_TARGET,
+};
+
+/// An advertisement which passed all the checks and waits to be fetched.
+#[derive(Debug, Clone)]
+pub struct Advertisement {
+	/// The peer which sent the advertisement.
+	pub peer_id: PeerId,
+	/// The collator id the peer declared itself with.
+	pub collator_id: CollatorId,
+	/// The para the collation is for.
+	pub para_id: ParaId,
+	/// The relay parent of the collation.
+	pub relay_parent: Hash,
+	/// The advertised candidate.
+	pub prospective_candidate: ProspectiveCandidate,
+	/// When the advertisement was received.
+	pub received_at: Instant,
+}
+
+impl Advertisement {
+	/// The collation to request from the collator.
+	pub fn pending_collation(&self) -> PendingCollation {
+		PendingCollation::new(
+			self.relay_parent,
+			self.para_id,
+			&self.peer_id,
+			Some(self.prospective_candidate),
+		)
+	}
+}
+
+/// An advertisement was rejected.
+#[derive(Debug)]
+pub enum AdvertisementError {
+	/// Relay parent is unknown.
+	RelayParentUnknown,
+	/// Peer is not present in the subsystem state.
+	UnknownPeer,
+	/// Peer has not declared its para id.
+	UndeclaredCollator,
+	/// We're assigned to a different para at the given relay parent.
+	InvalidAssignment,
+	/// The candidate was already advertised.
+	Duplicate,
+	/// The peer advertised more candidates than the para has claims at the relay parent.
+	PeerLimitReached,
+	/// Para reached a limit of seconded candidates for this relay parent.
+	SecondedLimitReached,
+	/// Seconding not allowed by backing subsystem.
+	BlockedByBacking,
+}
+
+impl AdvertisementError {
+	/// The reputation change the advertising peer deserves.
+	pub fn reputation_changes(&self) -> Option<Rep> {
+		use AdvertisementError::*;
+		match self {
+			InvalidAssignment => Some(COST_WRONG_PARA),
+			RelayParentUnknown | UndeclaredCollator | PeerLimitReached =>
+				Some(COST_UNEXPECTED_MESSAGE),
+			UnknownPeer | Duplicate | SecondedLimitReached | BlockedByBacking => None,
+		}
+	}
+}
+
+/// State tracked per relay parent.
+pub struct PerRelayParent {
+	/// The claim queue of our core at this relay parent.
+	pub assignment: Vec<ParaId>,
+	/// Whether v2 candidate receipts are enabled.
+	pub v2_receipts: bool,
+	/// The core our backing group is assigned to.
+	pub current_core: CoreIndex,
+	/// The session index of the relay parent.
+	pub session_index: SessionIndex,
+	/// Number of seconded collations per para.
+	seconded: HashMap<ParaId, usize>,
+	/// Advertisements which were not fetched yet.
+	advertisements: Vec<Advertisement>,
+}
+
+impl PerRelayParent {
+	/// Create a new relay parent state.
+	pub fn new(
+		assignment: Vec<ParaId>,
+		v2_receipts: bool,
+		current_core: CoreIndex,
+		session_index: SessionIndex,
+	) -> Self {
+		Self {
+			assignment,
+			v2_receipts,
+			current_core,
+			session_index,
+			seconded: HashMap::new(),
+			advertisements: Vec::new(),
+		}
+	}
+}
+
+/// A collation fetch in progress.
+struct Fetch {
+	started_at: Instant,
+	cancellation_token: CancellationToken,
+}
+
+/// Tracks the advertisements and collations at all the relay parents in our view.
+#[derive(Default)]
+pub struct CollationManager {
+	/// Leaves along with their implicit ancestry.
+	pub implicit_view: ImplicitView,
+	/// All active leaves observed by us.
+	pub active_leaves: HashSet<Hash>,
+	/// State tracked per relay parent.
+	pub per_relay_parent: HashMap<Hash, PerRelayParent>,
+	/// Paras we're currently assigned to, including assignments from the implicit view.
+	pub current_assignments: HashMap<ParaId, usize>,
+	/// Collations being fetched.
+	fetching: HashMap<PendingCollation, Fetch>,
+	/// Collations which were fetched and are waiting on validation.
+	pub fetched_candidates: HashMap<FetchedCollation, CollationEvent>,
+	/// Collations which can't be seconded yet, because their parent is not known by
+	/// prospective-parachains.
+	pub blocked_from_seconding: HashMap<BlockedCollationId, Vec<PendingCollationFetch>>,
+}
+
+impl CollationManager {
+	/// Start tracking a relay parent.
+	pub fn insert_relay_parent(&mut self, relay_parent: Hash, per_relay_parent: PerRelayParent) {
+		for para_id in per_relay_parent.assignment.iter() {
+			let entry = self.current_assignments.entry(*para_id).or_default();
+			*entry += 1;
+			if *entry == 1 {
+				gum::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?para_id,
+					"Assigned to a parachain",
+				);
+			}
+		}
+
+		self.per_relay_parent.insert(relay_parent, per_relay_parent);
+	}
+
+	/// Stop tracking a relay parent which left the view. Cancels all the fetches for it.
+	pub fn remove_relay_parent(&mut self, relay_parent: &Hash) {
+		if let Some(per_relay_parent) = self.per_relay_parent.remove(relay_parent) {
+			for para_id in per_relay_parent.assignment {
+				if let Some(count) = self.current_assignments.get_mut(&para_id) {
+					*count -= 1;
+					if *count == 0 {
+						self.current_assignments.remove(&para_id);
+						gum::debug!(target: LOG_TARGET, ?para_id, "Unassigned from a parachain");
+					}
+				}
+			}
+		}
+
+		self.fetching.retain(|pc, fetch| {
+			let keep = pc.relay_parent != *relay_parent;
+			if !keep {
+				fetch.cancellation_token.cancel();
+			}
+			keep
+		});
+		self.fetched_candidates.retain(|k, _| k.relay_parent != *relay_parent);
+		self.blocked_from_seconding.retain(|_, collations| {
+			collations.retain(|c| c.candidate_receipt.descriptor.relay_parent() != *relay_parent);
+			!collations.is_empty()
+		});
+	}
+
+	/// Drop all the advertisements of a disconnected peer.
+	pub fn remove_peer(&mut self, peer_id: &PeerId) {
+		for per_relay_parent in self.per_relay_parent.values_mut() {
+			per_relay_parent.advertisements.retain(|a| a.peer_id != *peer_id);
+		}
+	}
+
+	/// Check whether an advertisement can be accepted.
+	pub fn check_advertisement(
+		&self,
+		advertisement: &Advertisement,
+		now: Instant,
+	) -> std::result::Result<(), AdvertisementError> {
+		let Advertisement { relay_parent, para_id, peer_id, prospective_candidate, .. } =
+			advertisement;
+		let candidate_hash = prospective_candidate.candidate_hash;
+
+		let per_relay_parent = self
+			.per_relay_parent
+			.get(relay_parent)
+			.ok_or(AdvertisementError::RelayParentUnknown)?;
+
+		let claims = per_relay_parent.assignment.iter().filter(|p| *p == para_id).count();
+		if claims == 0 {
+			return Err(AdvertisementError::InvalidAssignment)
+		}
+
+		let already_known =
+			per_relay_parent
+				.advertisements
+				.iter()
+				.any(|a| a.prospective_candidate.candidate_hash == candidate_hash) ||
+				self.fetching.keys().any(|pc| {
+					pc.relay_parent == *relay_parent &&
+						pc.prospective_candidate.map(|c| c.candidate_hash) ==
+							Some(candidate_hash)
+				}) || self.fetched_candidates.keys().any(|fc| fc.candidate_hash == candidate_hash);
+		if already_known {
+			return Err(AdvertisementError::Duplicate)
+		}
+
+		let from_peer = per_relay_parent.advertisements.iter().filter(|a| a.peer_id == *peer_id);
+		let fetching_from_peer = self
+			.fetching
+			.keys()
+			.filter(|pc| pc.relay_parent == *relay_parent && pc.peer_id == *peer_id);
+		if from_peer.count() + fetching_from_peer.count() >= claims {
+			return Err(AdvertisementError::PeerLimitReached)
+		}
+
+		self.ensure_seconding_limit_is_respected(relay_parent, *para_id, now)
+	}
+
+	/// Insert an advertisement. It will be fetched once there is an unfulfilled claim queue entry
+	/// for it and no collator with a better reputation advertised.
+	pub fn insert_advertisement(
+		&mut self,
+		advertisement: Advertisement,
+		now: Instant,
+	) -> std::result::Result<(), AdvertisementError> {
+		self.check_advertisement(&advertisement, now)?;
+
+		if let Some(per_relay_parent) = self.per_relay_parent.get_mut(&advertisement.relay_parent) {
+			per_relay_parent.advertisements.push(advertisement);
+		}
+
+		Ok(())
+	}
+
+	/// Pick the advertisements to fetch now and start tracking them as being fetched.
+	///
+	/// For every unfulfilled claim queue entry the advertisement of the collator with the best
+	/// reputation is picked. Advertisements from collators with a reputation below
+	/// [`INSTANT_FETCH_REP_THRESHOLD`] are only picked once the first advertisement for the para
+	/// at the relay parent is older than [`MAX_FETCH_DELAY`].
+	pub fn pick_advertisements_to_fetch<F>(
+		&mut self,
+		now: Instant,
+		score: F,
+	) -> Vec<(Advertisement, CancellationToken)>
+	where
+		F: Fn(&ParaId, &CollatorId) -> Score,
+	{
+		let mut picked = Vec::new();
+		let relay_parents = self.per_relay_parent.keys().copied().collect::<Vec<_>>();
+
+		for relay_parent in relay_parents {
+			while let Some(advertisement) = self.pick_at(&relay_parent, now, &score) {
+				let cancellation_token = CancellationToken::new();
+				self.fetching.insert(
+					advertisement.pending_collation(),
+					Fetch { started_at: now, cancellation_token: cancellation_token.clone() },
+				);
+				picked.push((advertisement, cancellation_token));
+			}
+		}
+
+		picked
+	}
+
+	fn pick_at<F>(&mut self, relay_parent: &Hash, now: Instant, score: &F) -> Option<Advertisement>
+	where
+		F: Fn(&ParaId, &CollatorId) -> Score,
+	{
+		let unfulfilled_entries = match self.unfulfilled_claim_queue_entries(relay_parent, now) {
+			Ok(entries) => entries,
+			Err(err) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?err,
+					"Failed to get unfulfilled claim queue entries",
+				);
+				return None
+			},
+		};
+		let per_relay_parent = self.per_relay_parent.get_mut(relay_parent)?;
+
+		let mut visited = HashSet::new();
+		for para_id in unfulfilled_entries {
+			if !visited.insert(para_id) {
+				continue
+			}
+
+			let advertisements = &per_relay_parent.advertisements;
+			let best = advertisements
+				.iter()
+				.enumerate()
+				.filter(|(_, a)| a.para_id == para_id)
+				.map(|(index, a)| (index, score(&para_id, &a.collator_id), a.received_at))
+				// Prefer the best score, then the earliest advertisement.
+				.max_by_key(|(index, score, received_at)| {
+					(*score, Reverse(*received_at), Reverse(*index))
+				});
+			let first_received_at = advertisements
+				.iter()
+				.filter(|a| a.para_id == para_id)
+				.map(|a| a.received_at)
+				.min();
+			let (Some((index, best_score, _)), Some(first_received_at)) = (best, first_received_at)
+			else {
+				continue
+			};
+
+			if best_score >= INSTANT_FETCH_REP_THRESHOLD ||
+				now.saturating_duration_since(first_received_at) >= MAX_FETCH_DELAY
+			{
+				return Some(per_relay_parent.advertisements.remove(index))
+			}
+		}
+
+		None
+	}
+
+	/// A fetch concluded, successfully or not. Returns `false` if the fetch was not tracked
+	/// anymore, e.g. because its relay parent went out of view.
+	pub fn note_fetch_concluded(&mut self, pending_collation: &PendingCollation) -> bool {
+		self.fetching.remove(pending_collation).is_some()
+	}
+
+	/// Cancel all the fetches from a peer.
+	pub fn cancel_fetches_from(&mut self, peer_id: &PeerId) {
+		self.fetching.retain(|pc, fetch| {
+			let keep = pc.peer_id != *peer_id;
+			if !keep {
+				fetch.cancellation_token.cancel();
+			}
+			keep
+		});
+	}
+
+	/// A collation got seconded.
+	pub fn note_seconded(&mut self, relay_parent: &Hash, para_id: ParaId) {
+		if let Some(per_relay_parent) = self.per_relay_parent.get_mut(relay_parent) {
+			*per_relay_parent.seconded.entry(para_id).or_default() += 1;
+		}
+	}
+
+	/// Returns the number of seconded and pending collations for a specific `ParaId`. Pending
+	/// collations are:
+	/// 1. Collations being fetched from a collator for less than `MAX_UNSHARED_DOWNLOAD_TIME`.
+	/// 2. Collations waiting for validation from backing subsystem.
+	/// 3. Collations blocked from seconding due to parent not being known by backing subsystem.
+	fn seconded_and_pending_for_para(
+		&self,
+		relay_parent: &Hash,
+		para_id: &ParaId,
+		now: Instant,
+	) -> usize {
+		let seconded = self
+			.per_relay_parent
+			.get(relay_parent)
+			.and_then(|rp_state| rp_state.seconded.get(para_id).copied())
+			.unwrap_or_default();
+
+		let pending_fetch = self
+			.fetching
+			.iter()
+			.filter(|(pc, fetch)| {
+				pc.relay_parent == *relay_parent &&
+					pc.para_id == *para_id &&
+					now.saturating_duration_since(fetch.started_at) < MAX_UNSHARED_DOWNLOAD_TIME
+			})
+			.count();
+
+		let waiting_for_validation = self
+			.fetched_candidates
+			.keys()
+			.filter(|fc| fc.relay_parent == *relay_parent && fc.para_id == *para_id)
+			.count();
+
+		let blocked_from_seconding =
+			self.blocked_from_seconding.values().fold(0, |acc, blocked_collations| {
+				acc + blocked_collations
+					.iter()
+					.filter(|pc| {
+						pc.candidate_receipt.descriptor.para_id() == *para_id &&
+							pc.candidate_receipt.descriptor.relay_parent() == *relay_parent
+					})
+					.count()
+			});
+
+		seconded + pending_fetch + waiting_for_validation + blocked_from_seconding
+	}
+
+	/// Builds the claim queue state for every path from a leaf through `relay_parent`, with
+	/// the claims of `paras` taken by seconded and pending collations.
+	fn claim_queue_states<'a>(
+		&self,
+		relay_parent: &Hash,
+		paras: impl Iterator<Item = &'a ParaId> + Clone,
+		now: Instant,
+	) -> Result<Vec<ClaimQueueState>> {
+		let mut claim_queue_states = Vec::new();
+		for path in self.implicit_view.paths_via_relay_parent(relay_parent) {
+			let mut cq_state = ClaimQueueState::new();
+			for ancestor in &path {
+				cq_state.add_leaf(
+					ancestor,
+					&self
+						.per_relay_parent
+						.get(ancestor)
+						.ok_or(Error::RelayParentStateNotFound)?
+						.assignment,
+				);
+
+				for para_id in paras.clone() {
+					let seconded_and_pending =
+						self.seconded_and_pending_for_para(ancestor, para_id, now);
+					for _ in 0..seconded_and_pending {
+						cq_state.claim_at(ancestor, para_id);
+					}
+				}
+			}
+			claim_queue_states.push(cq_state);
+		}
+
+		Ok(claim_queue_states)
+	}
+
+	fn ensure_seconding_limit_is_respected(
+		&self,
+		relay_parent: &Hash,
+		para_id: ParaId,
+		now: Instant,
+	) -> std::result::Result<(), AdvertisementError> {
+		let claim_queue_states = self
+			.claim_queue_states(relay_parent, std::iter::once(&para_id), now)
+			.map_err(|_| AdvertisementError::RelayParentUnknown)?;
+
+		// If there is a place in the claim queue for the candidate at at least one path we will
+		// accept it.
+		if claim_queue_states
+			.into_iter()
+			.any(|mut cq_state| cq_state.can_claim_at(relay_parent, &para_id))
+		{
+			Ok(())
+		} else {
+			Err(AdvertisementError::SecondedLimitReached)
+		}
+	}
+
+	/// Returns the claim queue entries at `relay_parent` which are not claimed by a seconded or
+	/// pending collation. The earlier an entry is located in the `Vec` the higher its priority
+	/// is.
+	fn unfulfilled_claim_queue_entries(
+		&self,
+		relay_parent: &Hash,
+		now: Instant,
+	) -> Result<Vec<ParaId>> {
+		let scheduled_paras = self
+			.per_relay_parent
+			.get(relay_parent)
+			.ok_or(Error::RelayParentStateNotFound)?
+			.assignment
+			.iter()
+			.collect::<HashSet<_>>();
+
+		// As in the legacy validator side, take the longest list of unfulfilled entries over all
+		// the paths through the relay parent.
+		Ok(self
+			.claim_queue_states(relay_parent, scheduled_paras.iter().copied(), now)?
+			.iter_mut()
+			.map(|cq| cq.unclaimed_at(relay_parent))
+			.max_by(|a, b| a.len().cmp(&b.len()))
+			.unwrap_or_default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_subsystem_util::backing_implicit_view::BlockInfoProspectiveParachains;
+	use polkadot_primitives::CandidateHash;
+	use sp_keyring::Sr25519Keyring;
+	use std::time::Duration;
+
+	const PARA_A: ParaId = ParaId::new(100);
+	const PARA_B: ParaId = ParaId::new(200);
+
+	fn make_manager(claim_queue: Vec<ParaId>) -> (CollationManager, Hash) {
+		let mut manager = CollationManager::default();
+		let leaf = BlockInfoProspectiveParachains {
+			hash: Hash::repeat_byte(1),
+			parent_hash: Hash::repeat_byte(0),
+			number: 1,
+			storage_root: Hash::zero(),
+		};
+		manager
+			.implicit_view
+			.activate_leaf_from_prospective_parachains(leaf.clone(), &[]);
+		manager.active_leaves.insert(leaf.hash);
+		manager.insert_relay_parent(
+			leaf.hash,
+			PerRelayParent::new(claim_queue, true, CoreIndex(0), 1),
+		);
+		(manager, leaf.hash)
+	}
+
+	fn advertisement(
+		relay_parent: Hash,
+		para_id: ParaId,
+		peer_id: PeerId,
+		candidate: u8,
+		received_at: Instant,
+	) -> Advertisement {
+		Advertisement {
+			peer_id,
+			collator_id: Sr25519Keyring::Alice.public().into(),
+			para_id,
+			relay_parent,
+			prospective_candidate: ProspectiveCandidate {
+				candidate_hash: CandidateHash(Hash::repeat_byte(candidate)),
+				parent_head_data_hash: Hash::zero(),
+			},
+			received_at,
+		}
+	}
+
+	fn candidates(picked: &[(Advertisement, CancellationToken)]) -> Vec<(ParaId, CandidateHash)> {
+		picked
+			.iter()
+			.map(|(a, _)| (a.para_id, a.prospective_candidate.candidate_hash))
+			.collect()
+	}
+
+	#[test]
+	fn fetches_follow_the_claim_queue() {
+		let (mut manager, relay_parent) = make_manager(vec![PARA_A, PARA_B]);
+		let now = Instant::now();
+		let peer_a = PeerId::random();
+		let peer_b = PeerId::random();
+		let good_score = |_: &ParaId, _: &CollatorId| INSTANT_FETCH_REP_THRESHOLD;
+
+		manager
+			.insert_advertisement(advertisement(relay_parent, PARA_A, peer_a, 1, now), now)
+			.unwrap();
+		// Only one claim for `PARA_A`, so the peer can't advertise more.
+		assert_matches::assert_matches!(
+			manager.insert_advertisement(advertisement(relay_parent, PARA_A, peer_a, 2, now), now),
+			Err(AdvertisementError::PeerLimitReached)
+		);
+		manager
+			.insert_advertisement(
+				advertisement(relay_parent, PARA_A, PeerId::random(), 2, now),
+				now,
+			)
+			.unwrap();
+		manager
+			.insert_advertisement(advertisement(relay_parent, PARA_B, peer_b, 3, now), now)
+			.unwrap();
+
+		let picked = manager.pick_advertisements_to_fetch(now, good_score);
+		assert_eq!(
+			candidates(&picked),
+			vec![
+				(PARA_A, CandidateHash(Hash::repeat_byte(1))),
+				(PARA_B, CandidateHash(Hash::repeat_byte(3)))
+			]
+		);
+
+		// All claims are being fetched.
+		assert!(manager.pick_advertisements_to_fetch(now, good_score).is_empty());
+
+		// The fetch for `PARA_A` failed, the claim is free again.
+		assert!(manager.note_fetch_concluded(&picked[0].0.pending_collation()));
+		assert_eq!(
+			candidates(&manager.pick_advertisements_to_fetch(now, good_score)),
+			vec![(PARA_A, CandidateHash(Hash::repeat_byte(2)))]
+		);
+
+		// A slow fetch doesn't block the claim forever.
+		let later = now + MAX_UNSHARED_DOWNLOAD_TIME;
+		manager
+			.insert_advertisement(
+				advertisement(relay_parent, PARA_B, PeerId::random(), 4, later),
+				later,
+			)
+			.unwrap();
+		assert_eq!(
+			candidates(&manager.pick_advertisements_to_fetch(later, good_score)),
+			vec![(PARA_B, CandidateHash(Hash::repeat_byte(4)))]
+		);
+	}
+
+	#[test]
+	fn best_collator_is_fetched_first() {
+		let (mut manager, relay_parent) = make_manager(vec![PARA_A]);
+		let now = Instant::now();
+		let (bad, good) = (PeerId::random(), PeerId::random());
+		let good_collator: CollatorId = Sr25519Keyring::Bob.public().into();
+		let score = |_: &ParaId, collator_id: &CollatorId| {
+			if *collator_id == good_collator {
+				INSTANT_FETCH_REP_THRESHOLD
+			} else {
+				Score::default()
+			}
+		};
+
+		manager
+			.insert_advertisement(advertisement(relay_parent, PARA_A, bad, 1, now), now)
+			.unwrap();
+		// The collator without reputation has to wait.
+		assert!(manager.pick_advertisements_to_fetch(now, score).is_empty());
+
+		manager
+			.insert_advertisement(
+				Advertisement {
+					collator_id: good_collator.clone(),
+					..advertisement(relay_parent, PARA_A, good, 2, now)
+				},
+				now,
+			)
+			.unwrap();
+		assert_eq!(
+			candidates(&manager.pick_advertisements_to_fetch(now, score)),
+			vec![(PARA_A, CandidateHash(Hash::repeat_byte(2)))]
+		);
+	}
+
+	#[test]
+	fn unknown_collators_are_fetched_after_a_delay() {
+		let (mut manager, relay_parent) = make_manager(vec![PARA_A]);
+		let now = Instant::now();
+		let no_score = |_: &ParaId, _: &CollatorId| Score::default();
+
+		manager
+			.insert_advertisement(
+				advertisement(relay_parent, PARA_A, PeerId::random(), 1, now),
+				now,
+			)
+			.unwrap();
+		let later = now + Duration::from_millis(10);
+		manager
+			.insert_advertisement(
+				advertisement(relay_parent, PARA_A, PeerId::random(), 2, later),
+				later,
+			)
+			.unwrap();
+
+		assert!(manager.pick_advertisements_to_fetch(later, no_score).is_empty());
+		// The earliest advertisement wins.
+		assert_eq!(
+			candidates(&manager.pick_advertisements_to_fetch(now + MAX_FETCH_DELAY, no_score)),
+			vec![(PARA_A, CandidateHash(Hash::repeat_byte(1)))]
+		);
+	}
+
+	#[test]
+	fn seconding_limit_is_respected() {
+		let (mut manager, relay_parent) = make_manager(vec![PARA_A, PARA_B]);
+		let now = Instant::now();
+
+		assert_matches::assert_matches!(
+			manager.insert_advertisement(
+				advertisement(Hash::repeat_byte(2), PARA_A, PeerId::random(), 1, now),
+				now
+			),
+			Err(AdvertisementError::RelayParentUnknown)
+		);
+		assert_matches::assert_matches!(
+			manager.insert_advertisement(
+				advertisement(relay_parent, ParaId::new(300), PeerId::random(), 1, now),
+				now
+			),
+			Err(AdvertisementError::InvalidAssignment)
+		);
+
+		manager.note_seconded(&relay_parent, PARA_A);
+		assert_matches::assert_matches!(
+			manager.insert_advertisement(
+				advertisement(relay_parent, PARA_A, PeerId::random(), 1, now),
+				now
+			),
+			Err(AdvertisementError::SecondedLimitReached)
+		);
+		manager
+			.insert_advertisement(
+				advertisement(relay_parent, PARA_B, PeerId::random(), 2, now),
+				now,
+			)
+			.unwrap();
+	}
+}
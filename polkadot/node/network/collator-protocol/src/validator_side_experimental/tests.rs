@@ -0,0 +1,661 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the experimental validator side of the collator protocol.
+
+use super::*;
+use assert_matches::assert_matches;
+use futures::{executor, future, Future};
+use sc_network::ProtocolName;
+use sp_core::{crypto::Pair, Encode};
+use sp_keyring::Sr25519Keyring;
+use sp_keystore::Keystore;
+use std::collections::{BTreeMap, VecDeque};
+
+use common::{
+	INSTANT_FETCH_REP_THRESHOLD, INVALID_COLLATION_SLASH, MAX_FETCH_DELAY, VALID_COLLATION_BUMP,
+};
+use polkadot_node_network_protocol::{
+	peer_set::PeerSet, request_response::ResponseSender, ObservedRole,
+};
+use polkadot_node_primitives::{BlockData, PoV, SignedFullStatement};
+use polkadot_node_subsystem::messages::{
+	AllMessages, ChainApiMessage, ProspectiveParachainsMessage, ReportPeerMessage,
+	RuntimeApiMessage, RuntimeApiRequest,
+};
+use polkadot_node_subsystem_test_helpers as test_helpers;
+use polkadot_node_subsystem_util::{database::kvdb_impl::DbAdapter, TimeoutExt};
+use polkadot_primitives::{
+	node_features,
+	vstaging::{CandidateReceiptV2 as CandidateReceipt, CommittedCandidateReceiptV2},
+	CandidateCommitments, CollatorPair, CoreIndex, GroupRotationInfo, Header, NodeFeatures,
+	PersistedValidationData, SigningContext, ValidatorId, ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::dummy_candidate_receipt_bad_sig;
+
+const REPUTATION_CHANGE_TEST_INTERVAL: Duration = Duration::from_millis(10);
+const TIMEOUT: Duration = Duration::from_millis(200);
+const CONFIG: ReputationConfig = ReputationConfig { col_reputation_data: 0 };
+
+const PARA_A: ParaId = ParaId::new(1);
+const PARA_B: ParaId = ParaId::new(2);
+
+fn dummy_pvd() -> PersistedValidationData {
+	PersistedValidationData {
+		parent_head: HeadData(vec![7, 8, 9]),
+		relay_parent_number: 0,
+		max_pov_size: 1024,
+		relay_parent_storage_root: Default::default(),
+	}
+}
+
+fn make_db() -> Arc<dyn Database> {
+	Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[CONFIG.col_reputation_data]))
+}
+
+/// Store a reputation for `collator` before the subsystem is started.
+fn set_reputation(db: &Arc<dyn Database>, para_id: ParaId, collator: &CollatorPair, value: u16) {
+	let mut reputations = ReputationDb::load(db.clone(), CONFIG).unwrap();
+	reputations.bump(para_id, collator.public(), value);
+	reputations.flush().unwrap();
+}
+
+/// The persisted reputation of `collator`.
+fn reputation(db: &Arc<dyn Database>, para_id: ParaId, collator: &CollatorPair) -> u16 {
+	u16::from(
+		ReputationDb::load(db.clone(), CONFIG)
+			.unwrap()
+			.score(&para_id, &collator.public()),
+	)
+}
+
+struct TestState {
+	validator_public: Vec<ValidatorId>,
+	validator_groups: Vec<Vec<ValidatorIndex>>,
+	claim_queue: BTreeMap<CoreIndex, VecDeque<ParaId>>,
+	node_features: NodeFeatures,
+	session_index: SessionIndex,
+}
+
+impl Default for TestState {
+	fn default() -> Self {
+		let validator_public =
+			[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie]
+				.iter()
+				.map(|k| k.public().into())
+				.collect();
+		// We are Alice, so we are assigned to the first core.
+		let validator_groups =
+			vec![vec![ValidatorIndex(0), ValidatorIndex(1)], vec![ValidatorIndex(2)]];
+
+		let mut claim_queue = BTreeMap::new();
+		claim_queue.insert(CoreIndex(0), VecDeque::from([PARA_A, PARA_A, PARA_A]));
+		claim_queue.insert(CoreIndex(1), VecDeque::from([PARA_B, PARA_B, PARA_B]));
+
+		let mut node_features = NodeFeatures::EMPTY;
+		node_features.resize(node_features::FeatureIndex::CandidateReceiptV2 as usize + 1, false);
+		node_features.set(node_features::FeatureIndex::CandidateReceiptV2 as u8 as usize, true);
+
+		Self { validator_public, validator_groups, claim_queue, node_features, session_index: 1 }
+	}
+}
+
+type VirtualOverseer = test_helpers::TestSubsystemContextHandle<CollatorProtocolMessage>;
+
+struct TestHarness {
+	virtual_overseer: VirtualOverseer,
+	keystore: KeystorePtr,
+}
+
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	db: Arc<dyn Database>,
+	test: impl FnOnce(TestHarness) -> T,
+) {
+	sp_tracing::init_for_tests();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+
+	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
+
+	let keystore = Arc::new(sc_keystore::LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(
+		&*keystore,
+		polkadot_primitives::PARACHAIN_KEY_TYPE_ID,
+		Some(&Sr25519Keyring::Alice.to_seed()),
+	)
+	.expect("Insert key into keystore");
+
+	let subsystem = run_inner(
+		context,
+		keystore.clone(),
+		ReputationDb::load(db, CONFIG).unwrap(),
+		Metrics::default(),
+		ReputationAggregator::new(|_| true),
+		REPUTATION_CHANGE_TEST_INTERVAL,
+	);
+
+	let test_fut = test(TestHarness { virtual_overseer, keystore });
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(
+		async move {
+			let mut overseer = test_fut.await;
+			overseer
+				.send(FromOrchestra::Signal(OverseerSignal::Conclude))
+				.timeout(TIMEOUT)
+				.await
+				.expect("Sending the conclude signal should not take long");
+		},
+		subsystem,
+	))
+	.1
+	.unwrap();
+}
+
+async fn overseer_send(overseer: &mut VirtualOverseer, msg: CollatorProtocolMessage) {
+	gum::trace!("Sending message:\n{:?}", &msg);
+	overseer
+		.send(FromOrchestra::Communication { msg })
+		.timeout(TIMEOUT)
+		.await
+		.expect(&format!("{:?} is enough for sending messages.", TIMEOUT));
+}
+
+async fn overseer_recv(overseer: &mut VirtualOverseer) -> AllMessages {
+	overseer_recv_with_timeout(overseer, TIMEOUT)
+		.await
+		.expect(&format!("{:?} is enough to receive messages.", TIMEOUT))
+}
+
+async fn overseer_recv_with_timeout(
+	overseer: &mut VirtualOverseer,
+	timeout: Duration,
+) -> Option<AllMessages> {
+	let msg = overseer.recv().timeout(timeout).await;
+	gum::trace!("Received message:\n{:?}", &msg);
+	msg
+}
+
+/// Activate `leaf` as the only leaf of our view. The leaf is a genesis child, so the implicit view
+/// doesn't contain any ancestors.
+async fn activate_leaf(virtual_overseer: &mut VirtualOverseer, test_state: &TestState, leaf: Hash) {
+	overseer_send(
+		virtual_overseer,
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+			OurView::new([leaf], 0),
+		)),
+	)
+	.await;
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			parent,
+			RuntimeApiRequest::SessionIndexForChild(tx)
+		)) if parent == leaf => {
+			tx.send(Ok(test_state.session_index)).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			parent,
+			RuntimeApiRequest::NodeFeatures(_, tx)
+		)) if parent == leaf => {
+			tx.send(Ok(test_state.node_features.clone())).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			parent,
+			RuntimeApiRequest::Validators(tx)
+		)) if parent == leaf => {
+			tx.send(Ok(test_state.validator_public.clone())).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			parent,
+			RuntimeApiRequest::ValidatorGroups(tx)
+		)) if parent == leaf => {
+			let rotation_info =
+				GroupRotationInfo { session_start_block: 0, group_rotation_frequency: 1, now: 0 };
+			tx.send(Ok((test_state.validator_groups.clone(), rotation_info))).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			parent,
+			RuntimeApiRequest::ClaimQueue(tx)
+		)) if parent == leaf => {
+			tx.send(Ok(test_state.claim_queue.clone())).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::ChainApi(ChainApiMessage::BlockHeader(hash, tx)) if hash == leaf => {
+			let header = Header {
+				parent_hash: Hash::zero(),
+				number: 0,
+				state_root: Hash::zero(),
+				extrinsics_root: Hash::zero(),
+				digest: Default::default(),
+			};
+			tx.send(Ok(Some(header))).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::ProspectiveParachains(
+			ProspectiveParachainsMessage::GetMinimumRelayParents(parent, tx),
+		) if parent == leaf => {
+			tx.send(vec![(PARA_A, 0), (PARA_B, 0)]).unwrap();
+		}
+	);
+}
+
+async fn connect_peer(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	version: CollationVersion,
+) {
+	overseer_send(
+		virtual_overseer,
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
+			peer,
+			ObservedRole::Full,
+			version.into(),
+			None,
+		)),
+	)
+	.await;
+}
+
+async fn send_peer_message(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	msg: protocol_v2::CollatorProtocolMessage,
+) {
+	overseer_send(
+		virtual_overseer,
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+			peer,
+			CollationProtocols::V2(msg),
+		)),
+	)
+	.await;
+}
+
+async fn connect_and_declare_collator(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	collator: &CollatorPair,
+	para_id: ParaId,
+) {
+	connect_peer(virtual_overseer, peer, CollationVersion::V2).await;
+	send_peer_message(
+		virtual_overseer,
+		peer,
+		protocol_v2::CollatorProtocolMessage::Declare(
+			collator.public(),
+			para_id,
+			collator.sign(&protocol_v1::declare_signature_payload(&peer)),
+		),
+	)
+	.await;
+}
+
+/// Advertise `candidate` and allow the backing subsystem to second it.
+async fn advertise_collation(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	relay_parent: Hash,
+	candidate: &CandidateReceipt,
+) {
+	send_peer_message(
+		virtual_overseer,
+		peer,
+		protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+			relay_parent,
+			candidate_hash: candidate.hash(),
+			parent_head_data_hash: Hash::zero(),
+		},
+	)
+	.await;
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::CandidateBacking(CandidateBackingMessage::CanSecond(request, tx)) => {
+			assert_eq!(request.candidate_hash, candidate.hash());
+			assert_eq!(request.candidate_para_id, candidate.descriptor.para_id());
+			tx.send(true).unwrap();
+		}
+	);
+}
+
+/// Assert that the collation of `candidate` is requested from `peer`.
+async fn assert_fetch_collation_request(
+	msg: Option<AllMessages>,
+	peer: PeerId,
+	candidate: &CandidateReceipt,
+) -> ResponseSender {
+	assert_matches!(
+		msg,
+		Some(AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendRequests(
+			reqs,
+			IfDisconnected::ImmediateError,
+		))) => {
+			let req = reqs.into_iter().next().expect("There should be exactly one request");
+			assert_matches!(req, Requests::CollationFetchingV2(req) => {
+				assert_eq!(req.peer, Recipient::Peer(peer));
+				assert_eq!(req.payload.relay_parent, candidate.descriptor.relay_parent());
+				assert_eq!(req.payload.para_id, candidate.descriptor.para_id());
+				assert_eq!(req.payload.candidate_hash, candidate.hash());
+				req.pending_response
+			})
+		}
+	)
+}
+
+/// Respond with the collation and assert it is passed on to the backing subsystem.
+async fn send_collation_and_assert_second(
+	virtual_overseer: &mut VirtualOverseer,
+	response_channel: ResponseSender,
+	candidate: &CandidateReceipt,
+) {
+	let pov = PoV { block_data: BlockData(vec![1]) };
+	response_channel
+		.send(Ok((
+			request_v2::CollationFetchingResponse::Collation(candidate.clone(), pov.clone())
+				.encode(),
+			ProtocolName::from(""),
+		)))
+		.expect("Sending response should succeed");
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::ProspectiveParachains(
+			ProspectiveParachainsMessage::GetProspectiveValidationData(request, tx),
+		) => {
+			assert_eq!(request.candidate_relay_parent, candidate.descriptor.relay_parent());
+			assert_eq!(request.para_id, candidate.descriptor.para_id());
+			tx.send(Some(dummy_pvd())).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::CandidateBacking(CandidateBackingMessage::Second(
+			relay_parent,
+			receipt,
+			pvd,
+			incoming_pov,
+		)) => {
+			assert_eq!(relay_parent, candidate.descriptor.relay_parent());
+			assert_eq!(receipt.hash(), candidate.hash());
+			assert_eq!(pvd, dummy_pvd());
+			assert_eq!(incoming_pov, pov);
+		}
+	);
+}
+
+fn create_candidate(
+	para_id: ParaId,
+	relay_parent: Hash,
+	head: u8,
+) -> (CandidateReceipt, CandidateCommitments) {
+	let mut candidate = dummy_candidate_receipt_bad_sig(relay_parent, Some(Default::default()));
+	candidate.descriptor.para_id = para_id;
+	candidate.descriptor.persisted_validation_data_hash = dummy_pvd().hash();
+	let commitments = CandidateCommitments {
+		head_data: HeadData(vec![head]),
+		horizontal_messages: Default::default(),
+		upward_messages: Default::default(),
+		new_validation_code: None,
+		processed_downward_messages: 0,
+		hrmp_watermark: 0,
+	};
+	candidate.commitments_hash = commitments.hash();
+
+	(candidate.into(), commitments)
+}
+
+async fn assert_report_peer(virtual_overseer: &mut VirtualOverseer, peer: PeerId, expected: Rep) {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+			ReportPeerMessage::Single(reported, rep),
+		)) => {
+			assert_eq!(reported, peer);
+			assert_eq!(rep.value, expected.cost_or_benefit());
+		}
+	);
+}
+
+async fn assert_collator_disconnect(virtual_overseer: &mut VirtualOverseer, expected_peer: PeerId) {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::DisconnectPeer(peer, peer_set)) => {
+			assert_eq!(peer, expected_peer);
+			assert_eq!(peer_set, PeerSet::Collation);
+		}
+	);
+}
+
+#[test]
+fn legacy_collators_are_disconnected() {
+	test_harness(make_db(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+		let peer = PeerId::random();
+
+		connect_peer(&mut virtual_overseer, peer, CollationVersion::V1).await;
+		assert_collator_disconnect(&mut virtual_overseer, peer).await;
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn declare_with_invalid_signature_is_reported() {
+	test_harness(make_db(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+		let peer = PeerId::random();
+		let collator = CollatorPair::generate().0;
+
+		connect_peer(&mut virtual_overseer, peer, CollationVersion::V2).await;
+		send_peer_message(
+			&mut virtual_overseer,
+			peer,
+			protocol_v2::CollatorProtocolMessage::Declare(
+				collator.public(),
+				PARA_A,
+				collator.sign(&[42]),
+			),
+		)
+		.await;
+		assert_report_peer(&mut virtual_overseer, peer, COST_INVALID_SIGNATURE).await;
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn collator_of_unneeded_para_is_disconnected() {
+	let test_state = TestState::default();
+
+	test_harness(make_db(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+		let leaf = Hash::repeat_byte(1);
+		activate_leaf(&mut virtual_overseer, &test_state, leaf).await;
+
+		// We are only assigned to `PARA_A`.
+		let peer = PeerId::random();
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer,
+			&CollatorPair::generate().0,
+			PARA_B,
+		)
+		.await;
+		assert_report_peer(&mut virtual_overseer, peer, COST_UNNEEDED_COLLATOR).await;
+		assert_collator_disconnect(&mut virtual_overseer, peer).await;
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn seconded_collation_increases_reputation() {
+	let test_state = TestState::default();
+	let db = make_db();
+	let collator = CollatorPair::generate().0;
+
+	test_harness(db.clone(), |test_harness| {
+		let collator = collator.clone();
+		async move {
+			let TestHarness { mut virtual_overseer, keystore } = test_harness;
+			let leaf = Hash::repeat_byte(1);
+			activate_leaf(&mut virtual_overseer, &test_state, leaf).await;
+
+			let peer = PeerId::random();
+			connect_and_declare_collator(&mut virtual_overseer, peer, &collator, PARA_A).await;
+
+			let (candidate, commitments) = create_candidate(PARA_A, leaf, 1);
+			advertise_collation(&mut virtual_overseer, peer, leaf, &candidate).await;
+
+			// The collator has no reputation yet, so the fetch is delayed.
+			let msg =
+				overseer_recv_with_timeout(&mut virtual_overseer, MAX_FETCH_DELAY + TIMEOUT).await;
+			let response_channel = assert_fetch_collation_request(msg, peer, &candidate).await;
+			send_collation_and_assert_second(&mut virtual_overseer, response_channel, &candidate)
+				.await;
+
+			let committed = CommittedCandidateReceiptV2 {
+				descriptor: candidate.descriptor.clone(),
+				commitments,
+			};
+			let signing_context = SigningContext { session_index: 0, parent_hash: Hash::zero() };
+			let stmt = SignedFullStatement::sign(
+				&keystore,
+				Statement::Seconded(committed),
+				&signing_context,
+				ValidatorIndex(0),
+				&ValidatorId::from(Sr25519Keyring::Alice.public()),
+			)
+			.ok()
+			.flatten()
+			.expect("should be signed");
+			overseer_send(&mut virtual_overseer, CollatorProtocolMessage::Seconded(leaf, stmt))
+				.await;
+
+			assert_report_peer(&mut virtual_overseer, peer, BENEFIT_NOTIFY_GOOD).await;
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendCollationMessage(
+					peers,
+					CollationProtocols::V2(protocol_v2::CollationProtocol::CollatorProtocol(
+						protocol_v2::CollatorProtocolMessage::CollationSeconded(relay_parent, ..),
+					)),
+				)) => {
+					assert_eq!(peers, vec![peer]);
+					assert_eq!(relay_parent, leaf);
+				}
+			);
+
+			virtual_overseer
+		}
+	});
+
+	// The reputation is persisted when the subsystem concludes.
+	assert_eq!(reputation(&db, PARA_A, &collator), VALID_COLLATION_BUMP);
+}
+
+#[test]
+fn collator_with_reputation_is_fetched_first() {
+	let test_state = TestState::default();
+	let db = make_db();
+	let (good, unknown) = (CollatorPair::generate().0, CollatorPair::generate().0);
+	set_reputation(&db, PARA_A, &good, u16::from(INSTANT_FETCH_REP_THRESHOLD));
+
+	test_harness(db, |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+		let leaf = Hash::repeat_byte(1);
+		activate_leaf(&mut virtual_overseer, &test_state, leaf).await;
+
+		// The reputation follows the collator id, no matter which peer it connects from.
+		let (good_peer, unknown_peer) = (PeerId::random(), PeerId::random());
+		connect_and_declare_collator(&mut virtual_overseer, unknown_peer, &unknown, PARA_A).await;
+		connect_and_declare_collator(&mut virtual_overseer, good_peer, &good, PARA_A).await;
+
+		let (unknown_candidate, _) = create_candidate(PARA_A, leaf, 1);
+		let (good_candidate, _) = create_candidate(PARA_A, leaf, 2);
+		advertise_collation(&mut virtual_overseer, unknown_peer, leaf, &unknown_candidate).await;
+		advertise_collation(&mut virtual_overseer, good_peer, leaf, &good_candidate).await;
+
+		// The collation of the collator with a good reputation is fetched right away.
+		let msg = overseer_recv_with_timeout(&mut virtual_overseer, TIMEOUT).await;
+		let _good_response = assert_fetch_collation_request(msg, good_peer, &good_candidate).await;
+
+		// The other one once the maximum fetch delay is over.
+		let msg =
+			overseer_recv_with_timeout(&mut virtual_overseer, MAX_FETCH_DELAY + TIMEOUT).await;
+		let _unknown_response =
+			assert_fetch_collation_request(msg, unknown_peer, &unknown_candidate).await;
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn invalid_collation_decreases_reputation() {
+	let test_state = TestState::default();
+	let db = make_db();
+	let collator = CollatorPair::generate().0;
+	let initial_reputation = u16::from(INSTANT_FETCH_REP_THRESHOLD) + INVALID_COLLATION_SLASH;
+	set_reputation(&db, PARA_A, &collator, initial_reputation);
+
+	test_harness(db.clone(), |test_harness| {
+		let collator = collator.clone();
+		async move {
+			let TestHarness { mut virtual_overseer, .. } = test_harness;
+			let leaf = Hash::repeat_byte(1);
+			activate_leaf(&mut virtual_overseer, &test_state, leaf).await;
+
+			let peer = PeerId::random();
+			connect_and_declare_collator(&mut virtual_overseer, peer, &collator, PARA_A).await;
+
+			let (candidate, _) = create_candidate(PARA_A, leaf, 1);
+			advertise_collation(&mut virtual_overseer, peer, leaf, &candidate).await;
+
+			let msg = overseer_recv_with_timeout(&mut virtual_overseer, TIMEOUT).await;
+			let response_channel = assert_fetch_collation_request(msg, peer, &candidate).await;
+			send_collation_and_assert_second(&mut virtual_overseer, response_channel, &candidate)
+				.await;
+
+			overseer_send(&mut virtual_overseer, CollatorProtocolMessage::Invalid(leaf, candidate))
+				.await;
+			assert_report_peer(&mut virtual_overseer, peer, COST_REPORT_BAD).await;
+
+			virtual_overseer
+		}
+	});
+
+	assert_eq!(reputation(&db, PARA_A, &collator), initial_reputation - INVALID_COLLATION_SLASH);
+}
@@ -0,0 +1,101 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+
+use polkadot_primitives::{BlockNumber, Hash, Id as ParaId, SessionIndex, ValidatorIndex};
+
+use super::v1::DelayTranche;
+
+/// A page of the approval state of unfinalized relay chain blocks, ordered by block number.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalsInspectionPage {
+	/// The inspected blocks. All blocks of a block number are part of the same page.
+	pub blocks: Vec<BlockApprovalInspection>,
+	/// The block number to continue the inspection from, `None` if this is the last page.
+	pub next: Option<BlockNumber>,
+}
+
+/// The approval state of an unfinalized relay chain block, as seen by approval voting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockApprovalInspection {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session the block belongs to.
+	pub session: SessionIndex,
+	/// Whether all candidates included by the block are approved.
+	pub approved: bool,
+	/// The candidates included by the block, in the order of their cores.
+	pub candidates: Vec<CandidateApprovalInspection>,
+}
+
+impl BlockApprovalInspection {
+	/// The candidates of the block which still lack approvals.
+	pub fn unapproved_candidates(&self) -> impl Iterator<Item = &CandidateApprovalInspection> {
+		self.candidates.iter().filter(|candidate| !candidate.approved)
+	}
+}
+
+/// The approval state of a candidate under a specific relay chain block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApprovalInspection {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate belongs to.
+	pub para_id: ParaId,
+	/// The core the candidate occupies.
+	pub core_index: u32,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The tranche the local clock is currently in, relative to the slot of the block.
+	pub tranche_now: DelayTranche,
+	/// The tranches of assignments required to approve the candidate, `None` if the session
+	/// info needed to compute them is not available.
+	pub required_tranches: Option<TrancheRequirement>,
+	/// The number of validators assigned to check the candidate.
+	pub assignments: u32,
+	/// The number of validators which approved the candidate.
+	pub approvals: u32,
+	/// Assigned validators which did not approve the candidate within the no-show duration.
+	///
+	/// The list is capped, so it may not contain all no-shows of the candidate.
+	pub no_shows: Vec<ValidatorIndex>,
+}
+
+/// How many tranches of assignments are required to approve a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrancheRequirement {
+	/// All validators appear to be required.
+	All,
+	/// More assignments are awaited; tranches up to `considered` have been counted.
+	Pending {
+		/// The highest tranche considered so far.
+		considered: DelayTranche,
+	},
+	/// Assignments up to the `needed` tranche are sufficient, once approved.
+	Exact {
+		/// The tranche to inspect up to.
+		needed: DelayTranche,
+		/// The amount of missing approvals that are tolerated.
+		tolerated_missing: u32,
+	},
+}
@@ -22,6 +22,9 @@ pub mod criteria;
 /// Time utilities for approval voting.
 pub mod time;
 
+/// Types for inspecting the state of approval voting.
+pub mod inspection;
+
 /// A list of primitives introduced in v1.
 pub mod v1 {
 	use sp_consensus_babe as babe_primitives;
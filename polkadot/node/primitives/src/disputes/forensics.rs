@@ -0,0 +1,133 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use polkadot_primitives::{
+	Hash, Id as ParaId, InvalidDisputeStatementKind, SessionIndex, ValidDisputeStatementKind,
+	ValidatorIndex,
+};
+
+use super::{CandidateVotes, DisputeStatus, Timestamp};
+
+/// A page of the recent disputes, sorted by session and candidate hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeForensicsPage {
+	/// The exported disputes.
+	pub disputes: Vec<DisputeForensics>,
+	/// The session and candidate hash to continue the export from, `None` if this is the last
+	/// page.
+	pub next: Option<(SessionIndex, Hash)>,
+}
+
+/// Everything the dispute coordinator knows about a recent dispute.
+///
+/// Serialized to JSON, so exports of different validators can be diffed during an incident.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeForensics {
+	/// The session the candidate appeared in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate belongs to.
+	pub para_id: ParaId,
+	/// The relay parent of the candidate.
+	pub relay_parent: Hash,
+	/// The status of the dispute.
+	pub status: DisputeStatus,
+	/// All statements imported for the candidate, sorted by validator index.
+	pub statements: Vec<DisputeStatementRecord>,
+	/// Validators whose spam slots are taken by this dispute, as long as it is unconfirmed.
+	pub spam_slots: Vec<ValidatorIndex>,
+	/// The result of our own participation, if we participated.
+	pub participation: Option<ParticipationRecord>,
+}
+
+/// A statement of a validator on a disputed candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeStatementRecord {
+	/// The validator who issued the statement.
+	pub validator_index: ValidatorIndex,
+	/// Whether the statement is in favor of the candidate.
+	pub valid: bool,
+	/// How the statement was obtained, e.g. `backingSeconded` or `approvalChecking`.
+	pub kind: String,
+}
+
+impl DisputeStatementRecord {
+	/// Records of all the statements in `votes`, sorted by validator index.
+	pub fn from_votes(votes: &CandidateVotes) -> Vec<Self> {
+		let valid = votes.valid.raw().iter().map(|(validator_index, (kind, _))| Self {
+			validator_index: *validator_index,
+			valid: true,
+			kind: match kind {
+				ValidDisputeStatementKind::Explicit => "explicit",
+				ValidDisputeStatementKind::BackingSeconded(_) => "backingSeconded",
+				ValidDisputeStatementKind::BackingValid(_) => "backingValid",
+				ValidDisputeStatementKind::ApprovalChecking => "approvalChecking",
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_) =>
+					"approvalCheckingMultipleCandidates",
+			}
+			.into(),
+		});
+		let invalid = votes.invalid.iter().map(|(validator_index, (kind, _))| Self {
+			validator_index: *validator_index,
+			valid: false,
+			kind: match kind {
+				InvalidDisputeStatementKind::Explicit => "explicit",
+			}
+			.into(),
+		});
+
+		let mut records: Vec<_> = valid.chain(invalid).collect();
+		records.sort_by_key(|record| (record.validator_index, !record.valid));
+		records
+	}
+}
+
+/// The result of a dispute participation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParticipationResult {
+	/// The candidate was found to be valid.
+	Valid,
+	/// The candidate was found to be invalid.
+	Invalid,
+	/// The available data of the candidate could not be recovered.
+	Unavailable,
+	/// Participation failed for a reason unrelated to the candidate.
+	Error,
+}
+
+/// How our own participation in a dispute went.
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipationRecord {
+	/// The result of the participation.
+	pub result: ParticipationResult,
+	/// Why the candidate was not found valid, e.g. the PVF error kind.
+	pub error: Option<String>,
+	/// Time spent recovering the available data, in milliseconds.
+	pub recovery_millis: Option<u64>,
+	/// Time spent validating the candidate, in milliseconds.
+	pub validation_millis: Option<u64>,
+	/// When the participation concluded.
+	pub concluded_at: Timestamp,
+}
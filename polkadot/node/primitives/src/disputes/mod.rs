@@ -35,6 +35,12 @@ mod message;
 pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDisputeMessage};
 mod status;
 pub use status::{dispute_is_inactive, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS};
+/// Types exported for dispute forensics.
+mod forensics;
+pub use forensics::{
+	DisputeForensics, DisputeForensicsPage, DisputeStatementRecord, ParticipationRecord,
+	ParticipationResult,
+};
 
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
@@ -50,7 +50,7 @@ use polkadot_overseer::{Handle, OverseerConnector};
 use polkadot_primitives::Block;
 use sc_client_api::Backend;
 use sc_network::config::FullNetworkConfiguration;
-use sc_network_sync::WarpSyncConfig;
+use sc_network_sync::{strategy::warp::WarpSyncProvider, WarpSyncConfig};
 use sc_service::{Configuration, RpcHandlers, TaskManager};
 use sc_sysinfo::Metric;
 use sc_telemetry::TelemetryWorkerHandle;
@@ -85,6 +85,8 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_hard_max_num: Option<usize>,
 	/// How long finalized data should be kept in the availability store (in hours)
 	pub keep_finalized_for: Option<u32>,
+	/// Whether prepared PVF artifacts are kept across restarts.
+	pub persist_pvf_artifacts: bool,
 	pub overseer_gen: OverseerGenerator,
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	#[allow(dead_code)]
@@ -92,6 +94,8 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub hwbench: Option<sc_sysinfo::HwBench>,
 	/// Enable approval voting processing in parallel.
 	pub enable_approval_voting_parallel: bool,
+	/// Use the experimental validator side of the collator protocol.
+	pub enable_experimental_collator_protocol: bool,
 }
 
 /// Completely built polkadot node service.
@@ -103,6 +107,7 @@ pub struct NewFull {
 	pub sync_service: Arc<sc_network_sync::SyncingService<Block>>,
 	pub rpc_handlers: RpcHandlers,
 	pub backend: Arc<FullBackend>,
+	pub warp_sync_provider: Arc<dyn WarpSyncProvider<Block>>,
 }
 
 pub struct PolkadotServiceBuilder<OverseerGenerator, Network>
@@ -150,8 +155,15 @@ where
 			SelectRelayChain::new_longest_chain(basics.backend.clone())
 		};
 
-		let partial_components =
-			new_partial::<SelectRelayChain<_>>(&mut config, basics, select_chain)?;
+		// RPCs querying subsystems are only served by validators, which run the full set of them.
+		let rpc_overseer_handle = config.role.is_authority().then(|| overseer_handle.clone());
+		let partial_components = new_partial::<SelectRelayChain<_>>(
+			&mut config,
+			basics,
+			select_chain,
+			rpc_overseer_handle,
+			params.enable_approval_voting_parallel,
+		)?;
 
 		let net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
 			&config.network,
@@ -202,7 +214,9 @@ where
 					prepare_workers_soft_max_num,
 					prepare_workers_hard_max_num,
 					keep_finalized_for,
+					persist_pvf_artifacts,
 					enable_approval_voting_parallel,
+					enable_experimental_collator_protocol,
 				},
 			overseer_connector,
 			partial_components:
@@ -379,11 +393,8 @@ where
 				log::info!("🚀 Using execute-worker binary at: {:?}", exec_worker_path);
 
 				Some(CandidateValidationConfig {
-					artifacts_cache_path: config
-						.database
-						.path()
-						.ok_or(Error::DatabasePathRequired)?
-						.join("pvf-artifacts"),
+					artifacts_cache_path: crate::pvf_artifacts_path(&config)?,
+					persist_artifacts: persist_pvf_artifacts,
 					node_version,
 					secure_validator_mode,
 					prep_worker_path,
@@ -431,7 +442,7 @@ where
 			};
 
 			Some(ExtendedOverseerGenArgs {
-				keystore: keystore_container.local_keystore(),
+				keystore: keystore_container.local_keystore()?,
 				parachains_db,
 				candidate_validation_config,
 				availability_config,
@@ -445,6 +456,7 @@ where
 				chain_selection_config,
 				fetch_chunks_threshold,
 				enable_approval_voting_parallel,
+				enable_experimental_collator_protocol,
 			})
 		};
 
@@ -457,7 +469,7 @@ where
 				spawn_handle: task_manager.spawn_handle(),
 				import_queue,
 				block_announce_validator_builder: None,
-				warp_sync_config: Some(WarpSyncConfig::WithProvider(warp_sync)),
+				warp_sync_config: Some(WarpSyncConfig::WithProvider(warp_sync.clone())),
 				block_relay: None,
 				metrics,
 			})?;
@@ -841,6 +853,7 @@ where
 			sync_service,
 			rpc_handlers,
 			backend,
+			warp_sync_provider: warp_sync,
 		})
 	}
 }
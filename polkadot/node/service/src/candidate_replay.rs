@@ -0,0 +1,396 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline replay of a parachain candidate.
+//!
+//! The [`AvailableData`] of the candidate is loaded from the local availability store, while its
+//! validation code and the session's [`ExecutorParams`] are read from the relay chain state. The
+//! candidate is then prepared and executed by a dedicated PVF validation host, the same way an
+//! approval checker would do it.
+
+use crate::{open_database, parachains_db, workers, Block, FullClient};
+use codec::Encode;
+use futures::{channel::oneshot, future, pin_mut};
+use polkadot_node_core_av_store::{self as av_store, Error as AvailabilityError};
+use polkadot_node_core_pvf::{
+	Config as PvfConfig, Metrics as PvfMetrics, PrepareJobKind, Priority, PvfPrepData,
+	ValidationHost,
+};
+use polkadot_node_primitives::AvailableData;
+use polkadot_node_subsystem::messages::{PvfExecKind, RuntimeApiRequest};
+use polkadot_node_subsystem_util::metrics::Metrics as _;
+use polkadot_primitives::{
+	executor_params::{
+		DEFAULT_APPROVAL_EXECUTION_TIMEOUT, DEFAULT_LENIENT_PREPARATION_TIMEOUT,
+		DEFAULT_PRECHECK_PREPARATION_TIMEOUT,
+	},
+	runtime_api::ParachainHost,
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt},
+	CandidateCommitments, CandidateHash, ExecutorParams, Hash, Id as ParaId,
+	PvfExecKind as RuntimePvfExecKind, PvfPrepKind, SessionIndex, ValidationCodeHash,
+};
+use prometheus_endpoint::{prometheus::proto::MetricType, Registry};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use std::{
+	fmt,
+	path::PathBuf,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Errors that can occur while replaying a candidate.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+	#[error(transparent)]
+	RuntimeApi(#[from] sp_api::ApiError),
+
+	#[error(transparent)]
+	Blockchain(#[from] sp_blockchain::Error),
+
+	#[error(transparent)]
+	Availability(#[from] AvailabilityError),
+
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+
+	#[error(transparent)]
+	Prometheus(#[from] prometheus_endpoint::PrometheusError),
+
+	#[error("Relay chain block {0:?} not found")]
+	BlockNotFound(Hash),
+
+	#[error("Candidate {0:?} was neither backed nor included in the given relay chain block")]
+	CandidateNotFound(CandidateHash),
+
+	#[error("No session info for session {0}")]
+	SessionInfoUnavailable(SessionIndex),
+
+	#[error("No executor parameters for session {0}")]
+	ExecutorParamsUnavailable(SessionIndex),
+
+	#[error("Validation code {0:?} not found")]
+	ValidationCodeUnavailable(ValidationCodeHash),
+
+	#[error("Neither the available data nor enough chunks of candidate {0:?} are stored locally")]
+	AvailableDataUnavailable(CandidateHash),
+
+	#[error("The stored available data does not match the candidate descriptor")]
+	AvailableDataMismatch,
+
+	#[error("PVF validation host: {0}")]
+	ValidationHost(String),
+}
+
+/// Parameters of a candidate replay.
+#[derive(Debug, Clone)]
+pub struct CandidateReplayParams {
+	/// Hash of the candidate to replay.
+	pub candidate_hash: CandidateHash,
+	/// Hash of the relay chain block the candidate was backed or included in.
+	pub relay_block: Hash,
+	/// Directory the replay inputs and the prepared artifact are written to.
+	pub output_dir: PathBuf,
+	/// Path to the directory where the PVF worker binaries reside.
+	pub workers_path: Option<PathBuf>,
+	/// The version of the node, `None` skips the node/worker version check.
+	pub node_version: Option<String>,
+}
+
+/// Report of a candidate replay.
+#[derive(Debug)]
+pub struct CandidateReplayReport {
+	/// The para the candidate belongs to.
+	pub para_id: ParaId,
+	/// The session the candidate was validated in.
+	pub session_index: SessionIndex,
+	/// The executor parameters of the session.
+	pub executor_params: ExecutorParams,
+	/// Size of the (possibly compressed) validation code.
+	pub code_size: usize,
+	/// Encoded size of the PoV.
+	pub pov_size: usize,
+	/// Wall clock time spent preparing the validation code.
+	pub preparation_time: Duration,
+	/// Wall clock time spent executing the candidate, `None` if preparation failed.
+	pub execution_time: Option<Duration>,
+	/// `Ok` if the candidate produced the commitments it was backed with, the reason of the
+	/// failure otherwise.
+	pub outcome: Result<(), String>,
+	/// Sums of the PVF host histograms observed during the replay, e.g. the memory used by the
+	/// preparation.
+	pub metrics: Vec<(String, f64)>,
+	/// Directory the replay inputs and the prepared artifact were written to.
+	pub output_dir: PathBuf,
+}
+
+impl fmt::Display for CandidateReplayReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "Para:                {}", self.para_id)?;
+		writeln!(f, "Session:             {}", self.session_index)?;
+		writeln!(f, "Executor params:     {:?}", self.executor_params)?;
+		writeln!(f, "Code size:           {} bytes", self.code_size)?;
+		writeln!(f, "PoV size:            {} bytes", self.pov_size)?;
+		writeln!(f, "Preparation time:    {:?}", self.preparation_time)?;
+		match self.execution_time {
+			Some(execution_time) => writeln!(f, "Execution time:      {:?}", execution_time)?,
+			None => writeln!(f, "Execution time:      -")?,
+		}
+		match &self.outcome {
+			Ok(()) => writeln!(f, "Outcome:             valid")?,
+			Err(reason) => writeln!(f, "Outcome:             invalid ({reason})")?,
+		}
+		for (name, value) in &self.metrics {
+			writeln!(f, "{name}: {value}")?;
+		}
+		write!(f, "Artifacts written to {}", self.output_dir.display())
+	}
+}
+
+/// Replay the validation of a candidate backed or included in `params.relay_block`.
+///
+/// The validation code, the encoded PoV, the persisted validation data and the candidate receipt
+/// are written to `params.output_dir`, next to the artifact prepared by the PVF host.
+pub async fn replay_candidate(
+	client: Arc<FullClient>,
+	config: &sc_service::Configuration,
+	params: CandidateReplayParams,
+) -> Result<CandidateReplayReport, Error> {
+	let CandidateReplayParams {
+		candidate_hash,
+		relay_block,
+		output_dir,
+		workers_path,
+		node_version,
+	} = params;
+
+	let receipt = client
+		.runtime_api()
+		.candidate_events(relay_block)?
+		.into_iter()
+		.find_map(|event| match event {
+			CandidateEvent::CandidateBacked(receipt, ..) |
+			CandidateEvent::CandidateIncluded(receipt, ..)
+				if receipt.hash() == candidate_hash =>
+				Some(receipt),
+			_ => None,
+		})
+		.ok_or(Error::CandidateNotFound(candidate_hash))?;
+
+	// The candidate is validated in the session of the block it was backed or included in.
+	let parent = client
+		.header(relay_block)?
+		.ok_or(Error::BlockNotFound(relay_block))?
+		.parent_hash;
+	let api = client.runtime_api();
+	let session_index = api.session_index_for_child(parent)?;
+	let n_validators = api
+		.session_info(relay_block, session_index)?
+		.ok_or(Error::SessionInfoUnavailable(session_index))?
+		.validators
+		.len();
+	let executor_params = api
+		.session_executor_params(relay_block, session_index)?
+		.ok_or(Error::ExecutorParamsUnavailable(session_index))?;
+	let validation_code_hash = receipt.descriptor.validation_code_hash();
+	let validation_code = api
+		.validation_code_by_hash(relay_block, validation_code_hash)?
+		.ok_or(Error::ValidationCodeUnavailable(validation_code_hash))?;
+	let validation_code_bomb_limit =
+		if api.api_version::<dyn ParachainHost<Block>>(relay_block)?.unwrap_or_default() >=
+			RuntimeApiRequest::VALIDATION_CODE_BOMB_LIMIT_RUNTIME_REQUIREMENT
+		{
+			api.validation_code_bomb_limit(relay_block)?
+		} else {
+			#[allow(deprecated)]
+			{
+				polkadot_node_primitives::VALIDATION_CODE_BOMB_LIMIT as u32
+			}
+		};
+
+	let parachains_db = open_database(&config.database)
+		.map_err(|err| sp_blockchain::Error::Backend(err.to_string()))?;
+	let av_store_config = av_store::Config {
+		col_data: parachains_db::REAL_COLUMNS.col_availability_data,
+		col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
+		keep_finalized_for: 0,
+	};
+	let AvailableData { pov, validation_data } = av_store::load_available_data_offline(
+		&parachains_db,
+		&av_store_config,
+		&candidate_hash,
+		n_validators,
+	)?
+	.ok_or(Error::AvailableDataUnavailable(candidate_hash))?;
+
+	if pov.hash() != receipt.descriptor.pov_hash() ||
+		validation_data.hash() != receipt.descriptor.persisted_validation_data_hash()
+	{
+		return Err(Error::AvailableDataMismatch)
+	}
+
+	std::fs::create_dir_all(&output_dir)?;
+	std::fs::write(output_dir.join("validation_code.wasm"), &validation_code.0)?;
+	std::fs::write(output_dir.join("pov.bin"), pov.encode())?;
+	std::fs::write(output_dir.join("persisted_validation_data.bin"), validation_data.encode())?;
+	std::fs::write(output_dir.join("candidate_receipt.bin"), receipt.encode())?;
+
+	let (prep_worker_path, exec_worker_path) =
+		workers::determine_workers_paths(workers_path, None, node_version.clone())
+			.map_err(|err| Error::ValidationHost(err.to_string()))?;
+	let registry = Registry::new();
+	let metrics = PvfMetrics::try_register(&registry)?;
+	let pvf_config = PvfConfig::new(
+		output_dir.join("pvf-artifacts"),
+		node_version,
+		false,
+		prep_worker_path,
+		exec_worker_path,
+		1,
+		1,
+		1,
+	);
+	let (host, host_fut) = polkadot_node_core_pvf::start(pvf_config, metrics)
+		.await
+		.map_err(|err| Error::ValidationHost(err.to_string()))?;
+
+	let code_size = validation_code.0.len();
+	let pov_size = pov.encoded_size();
+	let replay = replay_with_host(
+		host,
+		validation_code.0,
+		executor_params.clone(),
+		validation_code_bomb_limit,
+		Arc::new(validation_data),
+		pov,
+		&receipt,
+	);
+	pin_mut!(replay, host_fut);
+	let (preparation_time, execution_time, outcome) = match future::select(replay, host_fut).await {
+		future::Either::Left((result, _)) => result?,
+		future::Either::Right(((), _)) =>
+			return Err(Error::ValidationHost("the validation host exited".into())),
+	};
+
+	Ok(CandidateReplayReport {
+		para_id: receipt.descriptor.para_id(),
+		session_index,
+		executor_params,
+		code_size,
+		pov_size,
+		preparation_time,
+		execution_time,
+		outcome,
+		metrics: histogram_sums(&registry),
+		output_dir,
+	})
+}
+
+/// Prepare and execute the candidate, returning the time spent in both phases and the outcome.
+async fn replay_with_host(
+	mut host: ValidationHost,
+	code: Vec<u8>,
+	executor_params: ExecutorParams,
+	validation_code_bomb_limit: u32,
+	pvd: Arc<polkadot_primitives::PersistedValidationData>,
+	pov: Arc<polkadot_node_primitives::PoV>,
+	receipt: &CandidateReceipt,
+) -> Result<(Duration, Option<Duration>, Result<(), String>), Error> {
+	let prep_timeout = executor_params
+		.pvf_prep_timeout(PvfPrepKind::Precheck)
+		.unwrap_or(DEFAULT_PRECHECK_PREPARATION_TIMEOUT);
+	let pvf = PvfPrepData::from_code(
+		code.clone(),
+		executor_params.clone(),
+		prep_timeout,
+		PrepareJobKind::Prechecking,
+		validation_code_bomb_limit,
+	);
+
+	let started = Instant::now();
+	let (tx, rx) = oneshot::channel();
+	host.precheck_pvf(pvf, tx).await.map_err(Error::ValidationHost)?;
+	let prepared = rx.await.map_err(|_| Error::ValidationHost("precheck canceled".into()))?;
+	let preparation_time = started.elapsed();
+	if let Err(err) = prepared {
+		return Ok((preparation_time, None, Err(err.to_string())))
+	}
+
+	// The artifact has been prepared by the precheck above, so execution won't compile it again.
+	let prep_timeout = executor_params
+		.pvf_prep_timeout(PvfPrepKind::Prepare)
+		.unwrap_or(DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+	let exec_timeout = executor_params
+		.pvf_exec_timeout(RuntimePvfExecKind::Approval)
+		.unwrap_or(DEFAULT_APPROVAL_EXECUTION_TIMEOUT);
+	let pvf = PvfPrepData::from_code(
+		code,
+		executor_params,
+		prep_timeout,
+		PrepareJobKind::Compilation,
+		validation_code_bomb_limit,
+	);
+
+	let started = Instant::now();
+	let (tx, rx) = oneshot::channel();
+	host.execute_pvf(pvf, exec_timeout, pvd, pov, Priority::Critical, PvfExecKind::Approval, tx)
+		.await
+		.map_err(Error::ValidationHost)?;
+	let executed = rx.await.map_err(|_| Error::ValidationHost("execution canceled".into()))?;
+	let execution_time = started.elapsed();
+
+	let outcome = match executed {
+		Err(err) => Err(err.to_string()),
+		Ok(res) if res.head_data.hash() != receipt.descriptor.para_head() =>
+			Err("para head hash mismatch".into()),
+		Ok(res) => {
+			let commitments = CandidateCommitments {
+				head_data: res.head_data,
+				upward_messages: res.upward_messages,
+				horizontal_messages: res.horizontal_messages,
+				new_validation_code: res.new_validation_code,
+				processed_downward_messages: res.processed_downward_messages,
+				hrmp_watermark: res.hrmp_watermark,
+			};
+			if commitments.hash() == receipt.commitments_hash {
+				Ok(())
+			} else {
+				Err("commitments hash mismatch".into())
+			}
+		},
+	};
+
+	Ok((preparation_time, Some(execution_time), outcome))
+}
+
+/// Sums of all the histograms registered in `registry` which have at least one sample.
+fn histogram_sums(registry: &Registry) -> Vec<(String, f64)> {
+	registry
+		.gather()
+		.into_iter()
+		.filter(|family| family.get_field_type() == MetricType::HISTOGRAM)
+		.flat_map(|family| {
+			let name = family.get_name().to_string();
+			family
+				.get_metric()
+				.iter()
+				.map(|metric| metric.get_histogram())
+				.filter(|histogram| histogram.get_sample_count() > 0)
+				.map(|histogram| (name.clone(), histogram.get_sample_sum()))
+				.collect::<Vec<_>>()
+		})
+		.collect()
+}
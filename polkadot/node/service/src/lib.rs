@@ -28,6 +28,8 @@ mod relay_chain_selection;
 #[cfg(feature = "full-node")]
 pub mod builder;
 #[cfg(feature = "full-node")]
+pub mod candidate_replay;
+#[cfg(feature = "full-node")]
 pub mod overseer;
 #[cfg(feature = "full-node")]
 pub mod workers;
@@ -74,7 +76,9 @@ use prometheus_endpoint::Registry;
 use sc_service::SpawnTaskHandle;
 
 pub use chain_spec::{GenericChainSpec, RococoChainSpec, WestendChainSpec};
-pub use polkadot_primitives::{Block, BlockId, BlockNumber, CollatorPair, Hash, Id as ParaId};
+pub use polkadot_primitives::{
+	Block, BlockId, BlockNumber, CandidateHash, CollatorPair, Hash, Id as ParaId,
+};
 pub use sc_client_api::{Backend, CallExecutor};
 pub use sc_consensus::{BlockImport, LongestChain};
 pub use sc_executor::NativeExecutionDispatch;
@@ -209,6 +213,14 @@ pub enum Error {
 	#[error(transparent)]
 	Availability(#[from] AvailabilityError),
 
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	CandidateReplay(#[from] candidate_replay::Error),
+
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	PvfArtifactBundle(#[from] polkadot_node_core_pvf::BundleError),
+
 	#[error("Authorities require the real overseer implementation")]
 	AuthoritiesRequireRealOverseer,
 
@@ -220,6 +232,10 @@ pub enum Error {
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
 
+	#[cfg(feature = "full-node")]
+	#[error("Failed to read the GRANDPA authorities of the genesis block: {0}")]
+	GrandpaGenesis(String),
+
 	#[cfg(feature = "full-node")]
 	#[error("Worker binaries not executable, prepare binary: {prep_worker_path:?}, execute binary: {exec_worker_path:?}")]
 	InvalidWorkerBinaries { prep_worker_path: PathBuf, exec_worker_path: PathBuf },
@@ -362,6 +378,71 @@ pub fn open_database(db_source: &DatabaseSource) -> Result<Arc<dyn Database>, Er
 	Ok(parachains_db)
 }
 
+/// Returns the directory in which the PVF host caches prepared artifacts.
+#[cfg(feature = "full-node")]
+pub fn pvf_artifacts_path(config: &Configuration) -> Result<PathBuf, Error> {
+	Ok(config.database.path().ok_or(Error::DatabasePathRequired)?.join("pvf-artifacts"))
+}
+
+/// Exports the persisted PVF artifacts of the node into a bundle.
+///
+/// Returns the number of exported artifacts.
+#[cfg(feature = "full-node")]
+pub fn export_pvf_artifacts(
+	config: &Configuration,
+	bundle_path: &std::path::Path,
+) -> Result<usize, Error> {
+	Ok(polkadot_node_core_pvf::export_bundle(&pvf_artifacts_path(config)?, bundle_path)?)
+}
+
+/// Imports PVF artifacts from a bundle into the persisted artifacts of the node.
+///
+/// The artifacts are used on the next start with `--persist-pvf-artifacts`. Returns the number of
+/// imported artifacts.
+#[cfg(feature = "full-node")]
+pub fn import_pvf_artifacts(
+	config: &Configuration,
+	bundle_path: &std::path::Path,
+) -> Result<usize, Error> {
+	Ok(polkadot_node_core_pvf::import_bundle(&pvf_artifacts_path(config)?, bundle_path)?)
+}
+
+/// Creates a verifier of GRANDPA warp proofs of the relay chain with the given chain spec.
+///
+/// Proofs are verified starting at the GRANDPA authorities of the genesis block. Callers pass
+/// `genesis_authorities` if they could read them from a relay chain node. Otherwise they are read
+/// from the chain spec, which requires building its genesis storage. So no state of the relay
+/// chain is required.
+#[cfg(feature = "full-node")]
+pub fn grandpa_warp_proof_verifier(
+	chain_spec: &Box<dyn ChainSpec>,
+	genesis_authorities: Option<sp_consensus_grandpa::AuthorityList>,
+) -> Result<sc_consensus_grandpa::warp_proof::WarpProofVerifier<Block>, Error> {
+	use codec::Decode;
+	use sp_core::hashing::twox_128;
+	use sp_runtime::BuildStorage;
+
+	let authorities = match genesis_authorities {
+		Some(authorities) => authorities,
+		None => {
+			let storage =
+				chain_spec.as_storage_builder().build_storage().map_err(Error::GrandpaGenesis)?;
+			let key = [twox_128(b"Grandpa"), twox_128(b"Authorities")].concat();
+			let authorities = storage
+				.top
+				.get(&key)
+				.ok_or_else(|| Error::GrandpaGenesis("Missing GRANDPA authorities".into()))?;
+			sp_consensus_grandpa::AuthorityList::decode(&mut &authorities[..])
+				.map_err(|e| Error::GrandpaGenesis(format!("Invalid GRANDPA authorities: {e}")))?
+		},
+	};
+
+	let hard_forks =
+		if chain_spec.is_kusama() { grandpa_support::kusama_hard_forks() } else { Vec::new() };
+
+	Ok(sc_consensus_grandpa::warp_proof::WarpProofVerifier::new(authorities, hard_forks))
+}
+
 /// Is this node running as in-process node for a parachain node?
 #[cfg(feature = "full-node")]
 #[derive(Clone)]
@@ -417,7 +498,13 @@ macro_rules! chain_ops {
 		let chain_selection = LongestChain::new(basics.backend.clone());
 
 		let sc_service::PartialComponents { client, backend, import_queue, task_manager, .. } =
-			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection)?;
+			new_partial::<LongestChain<_, Block>>(
+				&mut config,
+				basics,
+				chain_selection,
+				None,
+				false,
+			)?;
 		Ok((client, backend, import_queue, task_manager))
 	}};
 }
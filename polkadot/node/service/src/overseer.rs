@@ -142,6 +142,44 @@ pub struct ExtendedOverseerGenArgs {
 	/// Enable approval-voting-parallel subsystem and disable the standalone approval-voting and
 	/// approval-distribution subsystems.
 	pub enable_approval_voting_parallel: bool,
+	/// Use the experimental, reputation based, validator side of the collator protocol.
+	pub enable_experimental_collator_protocol: bool,
+}
+
+/// The validator side of the collator protocol to run.
+fn validator_collator_protocol_side(
+	keystore: Arc<LocalKeystore>,
+	#[allow(unused_variables)] parachains_db: Arc<
+		dyn polkadot_node_subsystem_util::database::Database,
+	>,
+	enable_experimental_collator_protocol: bool,
+	registry: Option<&Registry>,
+) -> Result<ProtocolSide, Error> {
+	use polkadot_node_subsystem_util::metrics::Metrics;
+
+	if enable_experimental_collator_protocol {
+		#[cfg(feature = "experimental-collator-protocol")]
+		return Ok(ProtocolSide::ValidatorExperimental {
+			keystore,
+			db: parachains_db,
+			reputation_config: polkadot_collator_protocol::ReputationConfig {
+				col_reputation_data: crate::parachains_db::REAL_COLUMNS
+					.col_collator_reputation_data,
+			},
+			metrics: Metrics::register(registry)?,
+		});
+
+		#[cfg(not(feature = "experimental-collator-protocol"))]
+		gum::warn!(
+			"The experimental collator protocol is not compiled in, falling back to the default one"
+		);
+	}
+
+	Ok(ProtocolSide::Validator {
+		keystore,
+		eviction_policy: Default::default(),
+		metrics: Metrics::register(registry)?,
+	})
 }
 
 /// Obtain a prepared validator `Overseer`, that is initialized with all default values.
@@ -181,6 +219,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		enable_experimental_collator_protocol,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -299,11 +338,12 @@ where
 					return Err(Error::Overseer(SubsystemError::Context(
 						"build validator overseer for parachain node".to_owned(),
 					))),
-				IsParachainNode::No => ProtocolSide::Validator {
-					keystore: keystore.clone(),
-					eviction_policy: Default::default(),
-					metrics: Metrics::register(registry)?,
-				},
+				IsParachainNode::No => validator_collator_protocol_side(
+					keystore.clone(),
+					parachains_db.clone(),
+					enable_experimental_collator_protocol,
+					registry,
+				)?,
 			};
 			CollatorProtocolSubsystem::new(side)
 		})
@@ -403,6 +443,7 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		enable_experimental_collator_protocol,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -520,11 +561,12 @@ where
 					return Err(Error::Overseer(SubsystemError::Context(
 						"build validator overseer for parachain node".to_owned(),
 					))),
-				IsParachainNode::No => ProtocolSide::Validator {
-					keystore: keystore.clone(),
-					eviction_policy: Default::default(),
-					metrics: Metrics::register(registry)?,
-				},
+				IsParachainNode::No => validator_collator_protocol_side(
+					keystore.clone(),
+					parachains_db.clone(),
+					enable_experimental_collator_protocol,
+					registry,
+				)?,
 			};
 			CollatorProtocolSubsystem::new(side)
 		})
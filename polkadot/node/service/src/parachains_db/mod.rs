@@ -73,6 +73,8 @@ pub struct ColumnsConfig {
 	pub col_chain_selection_data: u32,
 	/// The column used by dispute coordinator for data.
 	pub col_dispute_coordinator_data: u32,
+	/// The column used by the collator protocol for collator reputations.
+	pub col_collator_reputation_data: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -83,6 +85,9 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_approval_data: columns::v4::COL_APPROVAL_DATA,
 	col_chain_selection_data: columns::v4::COL_CHAIN_SELECTION_DATA,
 	col_dispute_coordinator_data: columns::v4::COL_DISPUTE_COORDINATOR_DATA,
+	// Collator reputations are few and stored under their own key prefix, so they share the
+	// ordered column of the dispute coordinator instead of requiring a database migration.
+	col_collator_reputation_data: columns::v4::COL_DISPUTE_COORDINATOR_DATA,
 };
 
 #[derive(PartialEq, Copy, Clone)]
@@ -0,0 +1,294 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks the validator side of the collator-protocol subsystem.
+//!
+//! Emulated peers act as collators of the parachain assigned to the node under test. In every
+//! block all of them advertise a collation and the node fetches and seconds as many as its claim
+//! queue allows.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::{
+		candidate_backing::MockCandidateBacking,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		prospective_parachains::MockProspectiveParachains,
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::{
+	self as net_protocol, peer_set::CollationVersion, v1 as protocol_v1, v2 as protocol_v2,
+	CollationProtocols, ObservedRole, OurView,
+};
+use polkadot_node_subsystem::messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent};
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{ValidatorId, ValidatorIndex};
+use sc_keystore::LocalKeystore;
+use sc_network_types::PeerId;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_application_crypto::AppCrypto;
+use sp_core::Pair;
+use sp_keystore::{Keystore, KeystorePtr};
+use std::{
+	ops::Sub,
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::collation";
+
+/// The number of collations the node under test fetches in each block, the claim queue holds a
+/// single claim for [`test_state::PARA_ID`].
+const EXPECTED_FETCHES_PER_BLOCK: usize = 1;
+
+/// How long to wait for the expected collations of a block to be fetched.
+const MAX_FETCH_WAIT: Duration = Duration::from_secs(30);
+
+/// Parameters specific to the collator protocol benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct CollatorProtocolOptions {
+	#[clap(short, long, default_value_t = 10)]
+	/// The number of collators advertising a collation in each block, must be lower than
+	/// `n_validators`.
+	pub n_collators: usize,
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(state.test_authorities.key_seeds.get(NODE_UNDER_TEST as usize).unwrap().as_str()),
+	)
+	.expect("Insert key into keystore");
+
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		Default::default(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let mock_prospective_parachains =
+		MockProspectiveParachains::with_validation_data(state.pvd.clone());
+	let mock_candidate_backing = MockCandidateBacking::new(
+		state.config.clone(),
+		state
+			.test_authorities
+			.validator_pairs
+			.get(NODE_UNDER_TEST as usize)
+			.unwrap()
+			.clone(),
+		state.pvd.clone(),
+		vec![ValidatorIndex(NODE_UNDER_TEST)],
+	);
+	let subsystem = CollatorProtocolSubsystem::new(ProtocolSide::Validator {
+		keystore,
+		eviction_policy: Default::default(),
+		metrics: Metrics::try_register(&dependencies.registry).unwrap(),
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx = MockNetworkBridgeRx::new(network_receiver, None, false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_prospective_parachains(|_| mock_prospective_parachains)
+		.replace_candidate_backing(|_| mock_candidate_backing)
+		.replace_collator_protocol(|_| subsystem)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+fn collator_protocol_message(
+	event: NetworkBridgeEvent<net_protocol::CollatorProtocolMessage>,
+) -> AllMessages {
+	AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdate(event))
+}
+
+pub async fn benchmark_collator_protocol(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Collators are emulated by the peers connected to our node.
+	let collator_peers: Vec<PeerId> = state
+		.test_authorities
+		.validator_authority_id
+		.iter()
+		.enumerate()
+		.filter(|(index, id)| {
+			*index != NODE_UNDER_TEST as usize && env.network().is_peer_connected(id)
+		})
+		.map(|(index, _)| state.test_authorities.peer_ids[index])
+		.take(state.options.n_collators)
+		.collect_vec();
+	assert_eq!(
+		collator_peers.len(),
+		state.options.n_collators,
+		"Not enough connected peers to emulate all collators"
+	);
+
+	let test_start = Instant::now();
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+
+		let block_start_ts = Instant::now();
+		env.import_block(block_info.clone()).await;
+		env.send_message(collator_protocol_message(NetworkBridgeEvent::OurViewChange(
+			OurView::new(vec![block_info.hash], 0),
+		)))
+		.await;
+
+		// Collators can declare only once we are assigned to their parachain.
+		if block_num == 1 {
+			for (peer_id, collator) in collator_peers.iter().zip(state.collators.iter()) {
+				env.send_message(collator_protocol_message(NetworkBridgeEvent::PeerConnected(
+					*peer_id,
+					ObservedRole::Full,
+					CollationVersion::V2.into(),
+					None,
+				)))
+				.await;
+				env.send_message(collator_protocol_message(NetworkBridgeEvent::PeerMessage(
+					*peer_id,
+					CollationProtocols::V2(protocol_v2::CollatorProtocolMessage::Declare(
+						collator.public(),
+						test_state::PARA_ID,
+						collator.sign(&protocol_v1::declare_signature_payload(peer_id)),
+					)),
+				)))
+				.await;
+			}
+		}
+
+		let advertisements = state.advertisements.get(&block_info.hash).expect("pregenerated");
+		for (peer_id, candidate_hash) in collator_peers.iter().zip(advertisements.iter()) {
+			env.send_message(collator_protocol_message(NetworkBridgeEvent::PeerMessage(
+				*peer_id,
+				CollationProtocols::V2(protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+					relay_parent: block_info.hash,
+					candidate_hash: *candidate_hash,
+					parent_head_data_hash: state.pvd.parent_head.hash(),
+				}),
+			)))
+			.await;
+		}
+
+		gum::info!(target: LOG_TARGET, "Waiting for the advertised collations to be fetched ...");
+
+		let fetches_tracker = state.fetches_tracker.get(&block_info.hash).expect("pregenerated");
+		loop {
+			let fetches = fetches_tracker.load(Ordering::SeqCst);
+			if fetches >= EXPECTED_FETCHES_PER_BLOCK {
+				break
+			}
+			if block_start_ts.elapsed() > MAX_FETCH_WAIT {
+				panic!(
+					"Only {} of {} expected collations fetched in block {} within {}ms",
+					fetches,
+					EXPECTED_FETCHES_PER_BLOCK,
+					block_num,
+					MAX_FETCH_WAIT.as_millis()
+				);
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+
+		let block_time = Instant::now().sub(block_start_ts).as_millis() as u64;
+		env.metrics().set_block_time(block_time);
+		gum::info!(target: LOG_TARGET, "All work for block completed in {}", format!("{:?}ms", block_time).cyan());
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+
+	env.stop().await;
+	env.collect_resource_usage(&["collator-protocol"], false)
+}
@@ -0,0 +1,206 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	collation::CollatorProtocolOptions,
+	configuration::{TestAuthorities, TestConfiguration},
+	network::{HandleNetworkMessage, NetworkMessage},
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{v2::CollationFetchingResponse, Requests};
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateCommitments, CandidateHash, CollatorPair, CoreIndex, Hash, Header, Id,
+	PersistedValidationData,
+};
+use polkadot_primitives_test_helpers::{
+	dummy_committed_candidate_receipt_v2, dummy_hash, dummy_head_data, dummy_pvd,
+};
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: u32 = 0;
+
+/// The parachain assigned to the core of the node under test.
+pub const PARA_ID: Id = Id::new(1);
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Collator protocol specific options
+	pub options: CollatorProtocolOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// PersistedValidationData, we use one for all candidates
+	pub pvd: PersistedValidationData,
+	// Map from generated candidate receipts, one per core and block, used to build the claim
+	// queue
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// Keys of the emulated collators
+	pub collators: Vec<CollatorPair>,
+	// Collations advertised in each block, one per collator
+	pub advertisements: HashMap<H256, Vec<CandidateHash>>,
+	// Collations served by the emulated collators
+	pub collations: HashMap<CandidateHash, (CandidateReceipt, PoV)>,
+	// Tracks how many collations were fetched from the emulated collators in each block
+	pub fetches_tracker: HashMap<H256, Arc<AtomicUsize>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &CollatorProtocolOptions) -> Self {
+		assert!(
+			options.n_collators < config.n_validators,
+			"Collators are emulated by the peers of the network"
+		);
+
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities: config.generate_authorities(),
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			pvd: dummy_pvd(dummy_head_data(), 0),
+			candidate_receipts: Default::default(),
+			collators: (0..options.n_collators)
+				.map(|index| {
+					CollatorPair::from_string(&format!("//Collator{}", index), None)
+						.expect("Seed is valid")
+				})
+				.collect(),
+			advertisements: Default::default(),
+			collations: Default::default(),
+			fetches_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let pov_sizes = config.pov_sizes();
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let receipt = state.generate_receipt(
+					block_info.hash,
+					Id::new(core_idx as u32 + 1),
+					CoreIndex(core_idx as u32),
+					dummy_hash(),
+				);
+				state.candidate_receipts.entry(block_info.hash).or_default().push(receipt);
+			}
+
+			// All collators compete for the core of the node under test.
+			for collator_index in 0..options.n_collators {
+				let pov_size = pov_sizes[collator_index % pov_sizes.len()];
+				let pov = PoV { block_data: BlockData(vec![collator_index as u8; pov_size]) };
+				let receipt =
+					state.generate_receipt(block_info.hash, PARA_ID, CoreIndex(0), pov.hash());
+				let candidate_hash = receipt.hash();
+
+				state.advertisements.entry(block_info.hash).or_default().push(candidate_hash);
+				state.collations.insert(candidate_hash, (receipt, pov));
+			}
+
+			state.fetches_tracker.insert(block_info.hash, Arc::new(AtomicUsize::new(0)));
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.fetches_tracker.values().for_each(|v| v.store(0, Ordering::SeqCst));
+	}
+
+	// Collations have empty commitments, which is what the candidate backing mock expects.
+	fn generate_receipt(
+		&self,
+		relay_parent: Hash,
+		para_id: Id,
+		core_index: CoreIndex,
+		pov_hash: Hash,
+	) -> CandidateReceipt {
+		let mut receipt = dummy_committed_candidate_receipt_v2(dummy_hash());
+		receipt.descriptor.set_para_id(para_id);
+		receipt.descriptor.set_relay_parent(relay_parent);
+		receipt.descriptor.set_core_index(core_index);
+		receipt.descriptor.set_session_index(SESSION_INDEX);
+		receipt.descriptor.set_persisted_validation_data_hash(self.pvd.hash());
+		receipt.descriptor.set_pov_hash(pov_hash);
+
+		CandidateReceipt {
+			descriptor: receipt.descriptor,
+			commitments_hash: CandidateCommitments::default().hash(),
+		}
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::CollationFetchingV2(req)) => {
+				let payload = req.payload;
+				let (receipt, pov) = self
+					.collations
+					.get(&payload.candidate_hash)
+					.expect("Only pregenerated collations are advertised")
+					.clone();
+				self.fetches_tracker
+					.get(&payload.relay_parent)
+					.expect("Collations are advertised only for known blocks")
+					.fetch_add(1, Ordering::SeqCst);
+
+				let response = CollationFetchingResponse::Collation(receipt, pov);
+				let _ = req.pending_response.send(Ok((response.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -0,0 +1,225 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	configuration::{TestAuthorities, TestConfiguration},
+	disputes::DisputesOptions,
+	mock::runtime_api::session_info_for_peers,
+	network::{HandleNetworkMessage, NetworkMessage},
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{
+	v1::{DisputeRequest, DisputeResponse},
+	Requests,
+};
+use polkadot_node_primitives::{DisputeMessage, SignedDisputeStatement};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateHash, CoreIndex, DisputeStatement, GroupIndex, Hash, Header, Id,
+	InvalidDisputeStatementKind, PersistedValidationData, SessionInfo, ValidDisputeStatementKind,
+	ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::{
+	dummy_committed_candidate_receipt_v2, dummy_hash, dummy_head_data, dummy_pvd,
+};
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: u32 = 0;
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Disputes specific options
+	pub options: DisputesOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Session info
+	pub session_info: SessionInfo,
+	// PersistedValidationData, we use one for all candidates
+	pub pvd: PersistedValidationData,
+	// Map from generated candidate receipts, one per core and block
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// Inclusion events for all generated candidates
+	pub candidate_events: HashMap<H256, Vec<CandidateEvent>>,
+	// Pregenerated dispute requests sent by emulated peers in each block
+	pub dispute_requests: HashMap<H256, Vec<DisputeRequest>>,
+	// Tracks to how many peers the node under test sent its vote for each disputed candidate
+	pub votes_tracker: HashMap<CandidateHash, Arc<AtomicUsize>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &DisputesOptions) -> Self {
+		assert!(
+			options.n_disputes <= config.n_cores,
+			"Only candidates included in a block can be disputed"
+		);
+		assert!(config.n_validators > 2, "Two emulated peers are needed to raise a dispute");
+
+		let test_authorities = config.generate_authorities();
+		let session_info = session_info_for_peers(config, &test_authorities);
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			session_info,
+			pvd: dummy_pvd(dummy_head_data(), 0),
+			candidate_receipts: Default::default(),
+			candidate_events: Default::default(),
+			dispute_requests: Default::default(),
+			votes_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let mut dispute_index = 0;
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let mut receipt = dummy_committed_candidate_receipt_v2(dummy_hash());
+				receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+				receipt.descriptor.set_relay_parent(block_info.hash);
+				receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+				receipt.descriptor.set_persisted_validation_data_hash(state.pvd.hash());
+				let receipt = receipt.to_plain();
+
+				state.candidate_events.entry(block_info.hash).or_default().push(
+					CandidateEvent::CandidateIncluded(
+						receipt.clone(),
+						dummy_head_data(),
+						CoreIndex(core_idx as u32),
+						GroupIndex(core_idx as u32),
+					),
+				);
+				state.candidate_receipts.entry(block_info.hash).or_default().push(receipt);
+			}
+
+			for receipt in
+				state.candidate_receipts[&block_info.hash].iter().take(options.n_disputes)
+			{
+				// Emulated peers disagree about the candidate, the node under test is not
+				// involved until it participates.
+				let n_peers = config.n_validators - 1;
+				let invalid_index = ValidatorIndex((1 + dispute_index % n_peers) as u32);
+				let valid_index = ValidatorIndex((1 + (dispute_index + 1) % n_peers) as u32);
+				dispute_index += 1;
+
+				let request = DisputeMessage::from_signed_statements(
+					state.sign_dispute_statement(receipt.hash(), valid_index, true),
+					valid_index,
+					state.sign_dispute_statement(receipt.hash(), invalid_index, false),
+					invalid_index,
+					receipt.clone(),
+					&state.session_info,
+				)
+				.expect("Pregenerated dispute messages are valid")
+				.into();
+
+				state.dispute_requests.entry(block_info.hash).or_default().push(request);
+				state.votes_tracker.insert(receipt.hash(), Arc::new(AtomicUsize::new(0)));
+			}
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.votes_tracker.values().for_each(|v| v.store(0, Ordering::SeqCst));
+	}
+
+	fn sign_dispute_statement(
+		&self,
+		candidate_hash: CandidateHash,
+		validator_index: ValidatorIndex,
+		valid: bool,
+	) -> SignedDisputeStatement {
+		let statement = if valid {
+			DisputeStatement::Valid(ValidDisputeStatementKind::Explicit)
+		} else {
+			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit)
+		};
+		let payload = statement
+			.payload_data(candidate_hash, SESSION_INDEX)
+			.expect("Explicit statements always have payload");
+		let pair = &self.test_authorities.validator_pairs[validator_index.0 as usize];
+
+		SignedDisputeStatement::new_checked(
+			statement,
+			candidate_hash,
+			SESSION_INDEX,
+			pair.public(),
+			pair.sign(&payload[..]),
+		)
+		.expect("Signature is valid")
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::DisputeSendingV1(req)) => {
+				let candidate_hash = req.payload.0.candidate_receipt.hash();
+				if let Some(tracker) = self.votes_tracker.get(&candidate_hash) {
+					tracker.fetch_add(1, Ordering::SeqCst);
+				}
+
+				let _ = req
+					.pending_response
+					.send(Ok((DisputeResponse::Confirmed.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
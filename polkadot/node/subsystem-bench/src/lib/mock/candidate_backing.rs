@@ -18,13 +18,21 @@
 
 use crate::{configuration::TestConfiguration, NODE_UNDER_TEST};
 use futures::FutureExt;
-use polkadot_node_primitives::{SignedFullStatementWithPVD, Statement, StatementWithPVD};
+use polkadot_node_primitives::{
+	SignedFullStatement, SignedFullStatementWithPVD, Statement, StatementWithPVD,
+};
 use polkadot_node_subsystem::{
-	messages::CandidateBackingMessage, overseer, SpawnedSubsystem, SubsystemError,
+	messages::{CandidateBackingMessage, CollatorProtocolMessage},
+	overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
 use polkadot_primitives::{
-	CandidateHash, Hash, PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
+	vstaging::{
+		CandidateReceiptV2 as CandidateReceipt,
+		CommittedCandidateReceiptV2 as CommittedCandidateReceipt,
+	},
+	CandidateCommitments, CandidateHash, Hash, PersistedValidationData, SigningContext,
+	ValidatorIndex, ValidatorPair,
 };
 use sp_core::Pair;
 use std::collections::HashMap;
@@ -123,6 +131,36 @@ impl MockCandidateBacking {
 
 		messages
 	}
+
+	// Seconds a fetched collation. Benchmarks only generate collations with empty commitments,
+	// anything else is reported as invalid.
+	fn handle_second(
+		&self,
+		relay_parent: Hash,
+		candidate_receipt: CandidateReceipt,
+	) -> CollatorProtocolMessage {
+		let receipt = CommittedCandidateReceipt {
+			descriptor: candidate_receipt.descriptor.clone(),
+			commitments: CandidateCommitments::default(),
+		};
+		if receipt.hash() != candidate_receipt.hash() {
+			return CollatorProtocolMessage::Invalid(relay_parent, candidate_receipt)
+		}
+
+		let statement = Statement::Seconded(receipt);
+		let context = SigningContext { parent_hash: relay_parent, session_index: 0 };
+		let payload = statement.to_compact().signing_payload(&context);
+		let statement = SignedFullStatement::new(
+			statement,
+			ValidatorIndex(NODE_UNDER_TEST),
+			self.state.pair.sign(&payload[..]),
+			&context,
+			&self.state.pair.public(),
+		)
+		.unwrap();
+
+		CollatorProtocolMessage::Seconded(relay_parent, statement)
+	}
 }
 
 #[overseer::subsystem(CandidateBacking, error=SubsystemError, prefix=self::overseer)]
@@ -160,6 +198,13 @@ impl MockCandidateBacking {
 								ctx.send_message(message).await;
 							}
 						},
+						CandidateBackingMessage::CanSecond(_request, tx) => {
+							let _ = tx.send(true);
+						},
+						CandidateBackingMessage::Second(relay_parent, candidate_receipt, _, _) => {
+							let message = self.handle_second(relay_parent, candidate_receipt);
+							ctx.send_message(message).await;
+						},
 						_ => {
 							unimplemented!("Unexpected candidate-backing message")
 						},
@@ -21,13 +21,21 @@ use polkadot_node_subsystem::{
 	messages::ProspectiveParachainsMessage, overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
-use polkadot_primitives::Hash;
+use polkadot_primitives::{Hash, PersistedValidationData};
 
-pub struct MockProspectiveParachains {}
+pub struct MockProspectiveParachains {
+	// Validation data returned for all prospective candidates.
+	validation_data: Option<PersistedValidationData>,
+}
 
 impl MockProspectiveParachains {
 	pub fn new() -> Self {
-		Self {}
+		Self { validation_data: None }
+	}
+
+	/// Creates a mock which answers `GetProspectiveValidationData` with `validation_data`.
+	pub fn with_validation_data(validation_data: PersistedValidationData) -> Self {
+		Self { validation_data: Some(validation_data) }
 	}
 }
 
@@ -64,6 +72,9 @@ impl MockProspectiveParachains {
 						)
 						.unwrap();
 					},
+					ProspectiveParachainsMessage::GetProspectiveValidationData(_req, tx) => {
+						tx.send(self.validation_data.clone()).unwrap();
+					},
 					_ => {
 						unimplemented!("Unexpected chain-api message")
 					},
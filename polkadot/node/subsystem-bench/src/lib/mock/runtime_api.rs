@@ -327,6 +327,19 @@ impl MockRuntimeApi {
 							if let Err(err) = tx.send(Ok(ApprovalVotingParams::default())) {
 								gum::error!(target: LOG_TARGET, ?err, "Voting params weren't received");
 							},
+						RuntimeApiMessage::Request(
+							_parent,
+							RuntimeApiRequest::FetchOnChainVotes(tx),
+						) => {
+							// No disputes are ever concluded on chain.
+							tx.send(Ok(None)).unwrap();
+						},
+						RuntimeApiMessage::Request(
+							_parent,
+							RuntimeApiRequest::UnappliedSlashes(tx),
+						) => {
+							tx.send(Ok(Vec::new())).unwrap();
+						},
 						RuntimeApiMessage::Request(_parent, RuntimeApiRequest::ClaimQueue(tx)) => {
 							tx.send(Ok(self.state.claim_queue.clone())).unwrap();
 						},
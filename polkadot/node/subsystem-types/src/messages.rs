@@ -33,13 +33,14 @@ use polkadot_node_network_protocol::{
 };
 use polkadot_node_primitives::{
 	approval::{
+		inspection::ApprovalsInspectionPage,
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2},
 	},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
-	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
-	SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD, SubmitCollationParams,
-	ValidationResult,
+	CollationSecondedSignal, DisputeForensicsPage, DisputeMessage, DisputeStatus, ErasureChunk,
+	PoV, SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD,
+	SubmitCollationParams, ValidationResult,
 };
 use polkadot_primitives::{
 	async_backing, slashing,
@@ -322,6 +323,13 @@ pub enum DisputeCoordinatorMessage {
 	/// Fetch a list of all active disputes that the coordinator is aware of.
 	/// These disputes are either not yet concluded or recently concluded.
 	ActiveDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash, DisputeStatus)>>),
+	/// Export forensic data about the recent disputes from the given session and candidate hash
+	/// on, sorted by session and candidate hash.
+	///
+	/// Includes the statements of every validator, the spam slots taken by each dispute and the
+	/// outcome of our own participation. The number of disputes exported per request is bounded,
+	/// the returned page tells where to continue.
+	ExportDisputes((SessionIndex, CandidateHash), oneshot::Sender<DisputeForensicsPage>),
 	/// Get candidate votes for a candidate.
 	QueryCandidateVotes(
 		Vec<(SessionIndex, CandidateHash)>,
@@ -1008,6 +1016,8 @@ pub enum ApprovalVotingParallelMessage {
 		CandidateHash,
 		oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 	),
+	/// Gets mapped into `ApprovalVotingMessage::InspectApprovals`
+	InspectApprovals(BlockNumber, oneshot::Sender<ApprovalsInspectionPage>),
 	/// Gets mapped into `ApprovalDistributionMessage::NewBlocks`
 	NewBlocks(Vec<BlockApprovalMeta>),
 	/// Gets mapped into `ApprovalDistributionMessage::DistributeAssignment`
@@ -1037,6 +1047,8 @@ impl TryFrom<ApprovalVotingParallelMessage> for ApprovalVotingMessage {
 				Ok(ApprovalVotingMessage::ApprovedAncestor(hash, number, tx)),
 			ApprovalVotingParallelMessage::GetApprovalSignaturesForCandidate(candidate, tx) =>
 				Ok(ApprovalVotingMessage::GetApprovalSignaturesForCandidate(candidate, tx)),
+			ApprovalVotingParallelMessage::InspectApprovals(from, tx) =>
+				Ok(ApprovalVotingMessage::InspectApprovals(from, tx)),
 			_ => Err(()),
 		}
 	}
@@ -1176,6 +1188,13 @@ pub enum ApprovalVotingMessage {
 		CandidateHash,
 		oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 	),
+
+	/// Inspect the approval state of the unfinalized blocks, starting at the given block number.
+	///
+	/// Reports for each candidate whether it is approved, the current tranche and the validators
+	/// which no-showed. Meant for diagnosing slow finality. The number of blocks inspected per
+	/// request is bounded, the returned page tells where to continue.
+	InspectApprovals(BlockNumber, oneshot::Sender<ApprovalsInspectionPage>),
 }
 
 /// Message to the Approval Distribution subsystem.
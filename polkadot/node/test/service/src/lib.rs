@@ -106,7 +106,9 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					enable_experimental_collator_protocol: false,
 					keep_finalized_for: None,
+					persist_pvf_artifacts: false,
 				},
 			),
 		sc_network::config::NetworkBackendType::Litep2p =>
@@ -129,7 +131,9 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					enable_experimental_collator_protocol: false,
 					keep_finalized_for: None,
+					persist_pvf_artifacts: false,
 				},
 			),
 	}
@@ -110,7 +110,9 @@ fn main() -> Result<()> {
 						prepare_workers_hard_max_num: None,
 						prepare_workers_soft_max_num: None,
 						enable_approval_voting_parallel: false,
+						enable_experimental_collator_protocol: false,
 						keep_finalized_for: None,
+						persist_pvf_artifacts: false,
 					},
 				)
 				.map_err(|e| e.to_string())?;
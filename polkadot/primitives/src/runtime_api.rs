@@ -120,7 +120,7 @@ use crate::{
 		CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CoreState, ScrapedOnChainVotes,
 	},
 	ApprovalVotingParams, AsyncBackingParams, BlockNumber, CandidateCommitments, CandidateHash,
-	CoreIndex, DisputeState, ExecutorParams, GroupRotationInfo, Hash, NodeFeatures,
+	CoreIndex, DisputeState, ExecutorParams, GroupRotationInfo, Hash, HrmpChannelId, NodeFeatures,
 	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, SessionIndex, SessionInfo,
 	ValidatorId, ValidatorIndex, ValidatorSignature,
 };
@@ -314,6 +314,12 @@ sp_api::decl_runtime_apis! {
 		#[api_version(13)]
 		fn scheduling_lookahead() -> u32;
 
+		/***** Added in v14 *****/
+		/// Returns the HRMP channels that can be closed for being idle, together with the
+		/// session in which they were last active.
+		#[api_version(14)]
+		fn idle_hrmp_channels() -> Vec<(HrmpChannelId, SessionIndex)>;
+
 
 	}
 }
@@ -0,0 +1,141 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the state of approval voting.
+
+use std::time::Duration;
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::async_trait,
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+	Extensions,
+};
+use polkadot_node_primitives::approval::inspection::ApprovalsInspectionPage;
+use polkadot_node_subsystem_types::messages::{
+	ApprovalVotingMessage, ApprovalVotingParallelMessage,
+};
+use polkadot_overseer::Handle;
+use polkadot_primitives::BlockNumber;
+use sc_rpc_api::{check_if_safe, UnsafeRpcError};
+
+const APPROVALS_ERROR: i32 = 9110;
+
+/// How long to wait for approval voting to answer an inspection request.
+///
+/// Approval voting answers from its main loop, which may be busy when finality is lagging.
+const INSPECT_APPROVALS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Provides RPC methods for inspecting approval voting.
+#[rpc(client, server)]
+pub trait ApprovalsApi {
+	/// Returns the approval state of the unfinalized blocks from block number `from` on, ordered
+	/// by block number.
+	///
+	/// For every candidate this reports whether it is approved, the current tranche, the
+	/// tranches required for approval and the validators which no-showed. The number of blocks
+	/// per call is bounded, the remaining blocks are fetched by calling again with the returned
+	/// `next` block number.
+	#[method(name = "parachain_inspectApprovals", with_extensions)]
+	async fn inspect_approvals(
+		&self,
+		from: Option<BlockNumber>,
+	) -> Result<ApprovalsInspectionPage, Error>;
+}
+
+/// Error type for the approvals RPC.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Approval voting dropped the request without answering.
+	#[error("Approval voting did not answer the request")]
+	NoResponse,
+	/// Approval voting did not answer in time.
+	#[error("Approval voting did not answer the request in time")]
+	Timeout,
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::NoResponse =>
+				ErrorObject::owned(APPROVALS_ERROR + 1, error.to_string(), None::<()>),
+			Error::Timeout =>
+				ErrorObject::owned(APPROVALS_ERROR + 2, error.to_string(), None::<()>),
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
+
+/// Implements the [`ApprovalsApiServer`] by querying approval voting over the overseer.
+pub struct Approvals {
+	overseer_handle: Handle,
+	approval_voting_parallel_enabled: bool,
+}
+
+impl Approvals {
+	/// Creates a new instance of the approvals RPC handler.
+	///
+	/// `approval_voting_parallel_enabled` has to match the approval voting subsystem the node
+	/// runs.
+	pub fn new(overseer_handle: Handle, approval_voting_parallel_enabled: bool) -> Self {
+		Self { overseer_handle, approval_voting_parallel_enabled }
+	}
+}
+
+#[async_trait]
+impl ApprovalsApiServer for Approvals {
+	async fn inspect_approvals(
+		&self,
+		ext: &Extensions,
+		from: Option<BlockNumber>,
+	) -> Result<ApprovalsInspectionPage, Error> {
+		check_if_safe(ext)?;
+
+		let from = from.unwrap_or_default();
+		let mut overseer_handle = self.overseer_handle.clone();
+		let approval_voting_parallel_enabled = self.approval_voting_parallel_enabled;
+		let request = async move {
+			let (tx, rx) = oneshot::channel();
+			if approval_voting_parallel_enabled {
+				overseer_handle
+					.send_msg(
+						ApprovalVotingParallelMessage::InspectApprovals(from, tx),
+						"ApprovalsRpc",
+					)
+					.await;
+			} else {
+				overseer_handle
+					.send_msg(ApprovalVotingMessage::InspectApprovals(from, tx), "ApprovalsRpc")
+					.await;
+			}
+
+			rx.await.map_err(|_| Error::NoResponse)
+		};
+
+		match future::select(Box::pin(request), Delay::new(INSPECT_APPROVALS_TIMEOUT)).await {
+			Either::Left((response, _)) => response,
+			Either::Right(_) => Err(Error::Timeout),
+		}
+	}
+}
@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for exporting dispute forensics from the dispute coordinator.
+
+use std::time::Duration;
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::async_trait,
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+	Extensions,
+};
+use polkadot_node_primitives::DisputeForensicsPage;
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{CandidateHash, Hash, SessionIndex};
+use sc_rpc_api::{check_if_safe, UnsafeRpcError};
+
+const DISPUTES_ERROR: i32 = 9100;
+
+/// How long to wait for the dispute coordinator to answer an export request.
+///
+/// The dispute coordinator answers from its main loop, which may be busy importing votes.
+const EXPORT_DISPUTES_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Provides RPC methods for inspecting disputes seen by the node.
+#[rpc(client, server)]
+pub trait DisputesApi {
+	/// Returns the recent disputes known to the dispute coordinator from session and candidate
+	/// hash `from` on, together with the recorded statements, spam slot occupancy and the outcome
+	/// of local participation.
+	///
+	/// The output is sorted by session and candidate hash, so exports of different nodes can be
+	/// diffed directly. The number of disputes per call is bounded, the remaining disputes are
+	/// fetched by calling again with the returned `next` session and candidate hash.
+	#[method(name = "parachain_exportDisputes", with_extensions)]
+	async fn export_disputes(
+		&self,
+		from: Option<(SessionIndex, Hash)>,
+	) -> Result<DisputeForensicsPage, Error>;
+}
+
+/// Error type for the disputes RPC.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The dispute coordinator dropped the request without answering.
+	#[error("The dispute coordinator did not answer the request")]
+	NoResponse,
+	/// The dispute coordinator did not answer in time.
+	#[error("The dispute coordinator did not answer the request in time")]
+	Timeout,
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::NoResponse =>
+				ErrorObject::owned(DISPUTES_ERROR + 1, error.to_string(), None::<()>),
+			Error::Timeout => ErrorObject::owned(DISPUTES_ERROR + 2, error.to_string(), None::<()>),
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
+
+/// Implements the [`DisputesApiServer`] by querying the dispute coordinator over the overseer.
+pub struct Disputes {
+	overseer_handle: Handle,
+}
+
+impl Disputes {
+	/// Creates a new instance of the disputes RPC handler.
+	pub fn new(overseer_handle: Handle) -> Self {
+		Self { overseer_handle }
+	}
+}
+
+#[async_trait]
+impl DisputesApiServer for Disputes {
+	async fn export_disputes(
+		&self,
+		ext: &Extensions,
+		from: Option<(SessionIndex, Hash)>,
+	) -> Result<DisputeForensicsPage, Error> {
+		check_if_safe(ext)?;
+
+		let (session, candidate_hash) = from.unwrap_or_default();
+		let from = (session, CandidateHash(candidate_hash));
+		let mut overseer_handle = self.overseer_handle.clone();
+		let request = async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(DisputeCoordinatorMessage::ExportDisputes(from, tx), "DisputesRpc")
+				.await;
+
+			rx.await.map_err(|_| Error::NoResponse)
+		};
+
+		match future::select(Box::pin(request), Delay::new(EXPORT_DISPUTES_TIMEOUT)).await {
+			Either::Left((response, _)) => response,
+			Either::Right(_) => Err(Error::Timeout),
+		}
+	}
+}
@@ -234,6 +234,11 @@ pub struct HostConfiguration<BlockNumber> {
 	pub approval_voting_params: ApprovalVotingParams,
 	/// Scheduler parameters
 	pub scheduler_params: SchedulerParams<BlockNumber>,
+	/// The number of sessions without any message sent over an HRMP channel after which the
+	/// channel is considered idle and can be closed by anyone.
+	///
+	/// `None` disables closing idle channels.
+	pub hrmp_idle_channel_sessions: Option<SessionIndex>,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -277,6 +282,7 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			minimum_backing_votes: LEGACY_MIN_BACKING_VOTES,
 			node_features: NodeFeatures::EMPTY,
 			scheduler_params: Default::default(),
+			hrmp_idle_channel_sessions: None,
 		};
 
 		#[cfg(feature = "runtime-benchmarks")]
@@ -346,6 +352,8 @@ pub enum InconsistentError<BlockNumber> {
 	OnDemandQueueSizeTooLarge,
 	/// Number of delay tranches cannot be 0.
 	ZeroDelayTranches,
+	/// `hrmp_idle_channel_sessions` is set to `Some(0)`.
+	ZeroHrmpIdleChannelSessions,
 }
 
 impl<BlockNumber> HostConfiguration<BlockNumber>
@@ -447,6 +455,10 @@ where
 			return Err(ZeroDelayTranches)
 		}
 
+		if self.hrmp_idle_channel_sessions == Some(0) {
+			return Err(ZeroHrmpIdleChannelSessions)
+		}
+
 		Ok(())
 	}
 
@@ -525,7 +537,7 @@ pub mod pallet {
 	/// v9-v10: <https://github.com/paritytech/polkadot-sdk/pull/2177>
 	/// v10-11: <https://github.com/paritytech/polkadot-sdk/pull/1191>
 	/// v11-12: <https://github.com/paritytech/polkadot-sdk/pull/3181>
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(12);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(13);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -1244,6 +1256,23 @@ pub mod pallet {
 				config.scheduler_params = new;
 			})
 		}
+
+		/// Sets the number of sessions without messages after which an HRMP channel can be closed
+		/// for being idle.
+		#[pallet::call_index(56)]
+		#[pallet::weight((
+			T::WeightInfo::set_config_with_option_u32(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_hrmp_idle_channel_sessions(
+			origin: OriginFor<T>,
+			new: Option<SessionIndex>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::schedule_config_update(|config| {
+				config.hrmp_idle_channel_sessions = new;
+			})
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
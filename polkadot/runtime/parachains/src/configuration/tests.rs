@@ -249,6 +249,11 @@ fn invariants() {
 			Configuration::set_validation_upgrade_delay(RuntimeOrigin::root(), 0),
 			Error::<Test>::InvalidNewValue
 		);
+
+		assert_err!(
+			Configuration::set_hrmp_idle_channel_sessions(RuntimeOrigin::root(), Some(0)),
+			Error::<Test>::InvalidNewValue
+		);
 	});
 }
 
@@ -330,6 +335,7 @@ fn setting_pending_config_members() {
 				on_demand_target_queue_utilization: Perbill::from_percent(25),
 				ttl: 5u32,
 			},
+			hrmp_idle_channel_sessions: Some(168),
 		};
 
 		Configuration::set_validation_upgrade_cooldown(
@@ -486,6 +492,11 @@ fn setting_pending_config_members() {
 			new_config.minimum_backing_votes,
 		)
 		.unwrap();
+		Configuration::set_hrmp_idle_channel_sessions(
+			RuntimeOrigin::root(),
+			new_config.hrmp_idle_channel_sessions,
+		)
+		.unwrap();
 		Configuration::set_node_feature(RuntimeOrigin::root(), 1, true).unwrap();
 		Configuration::set_node_feature(RuntimeOrigin::root(), 1, true).unwrap();
 		Configuration::set_node_feature(RuntimeOrigin::root(), 3, true).unwrap();
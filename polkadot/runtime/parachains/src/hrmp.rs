@@ -16,7 +16,7 @@
 
 use crate::{
 	configuration::{self, HostConfiguration},
-	dmp, ensure_parachain, initializer, paras,
+	dmp, ensure_parachain, initializer, paras, shared,
 };
 use alloc::{
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
@@ -67,6 +67,7 @@ pub trait WeightInfo {
 	fn establish_system_channel() -> Weight;
 	fn poke_channel_deposits() -> Weight;
 	fn establish_channel_with_system() -> Weight;
+	fn close_idle_channel() -> Weight;
 }
 
 /// A weight info that is only suitable for testing.
@@ -109,6 +110,9 @@ impl WeightInfo for TestWeightInfo {
 	fn establish_channel_with_system() -> Weight {
 		Weight::MAX
 	}
+	fn close_idle_channel() -> Weight {
+		Weight::MAX
+	}
 }
 
 /// A description of a request to open an HRMP channel.
@@ -324,6 +328,8 @@ pub mod pallet {
 		},
 		/// An HRMP channel's deposits were updated.
 		OpenChannelDepositsUpdated { sender: ParaId, recipient: ParaId },
+		/// An idle HRMP channel is going to be closed on the next session change.
+		IdleChannelCloseRequested { channel_id: HrmpChannelId, last_active_session: SessionIndex },
 	}
 
 	#[pallet::error]
@@ -368,6 +374,12 @@ pub mod pallet {
 		WrongWitness,
 		/// The channel between these two chains cannot be authorized.
 		ChannelCreationNotAuthorized,
+		/// Closing idle channels is not enabled.
+		IdleChannelClosingDisabled,
+		/// The channel is not idle or it can not be closed for being idle.
+		ChannelNotIdle,
+		/// The channel still has messages that were not received by the recipient.
+		ChannelNotEmpty,
 	}
 
 	/// The set of pending HRMP open channel requests.
@@ -472,6 +484,20 @@ pub mod pallet {
 	pub type HrmpChannelDigests<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, Vec<(BlockNumberFor<T>, Vec<ParaId>)>, ValueQuery>;
 
+	/// The last session in which a message was sent over an HRMP channel, or in which the channel
+	/// was opened.
+	///
+	/// Invariant: cannot be `Some` if the corresponding channel in `HrmpChannels` is `None`.
+	#[pallet::storage]
+	pub type HrmpChannelLastActivity<T: Config> =
+		StorageMap<_, Twox64Concat, HrmpChannelId, SessionIndex>;
+
+	/// The session since which the activity of HRMP channels is tracked.
+	///
+	/// Channels without an entry in `HrmpChannelLastActivity` were not active since then.
+	#[pallet::storage]
+	pub type HrmpActivityTrackedSince<T: Config> = StorageValue<_, SessionIndex>;
+
 	/// Preopen the given HRMP channels.
 	///
 	/// The values in the tuple corresponds to
@@ -898,6 +924,47 @@ pub mod pallet {
 
 			Ok(Pays::No.into())
 		}
+
+		/// Close an HRMP channel over which no message was sent for at least
+		/// `hrmp_idle_channel_sessions` sessions, as set in the host configuration.
+		///
+		/// The channel is closed on the next session change and both deposits are returned.
+		/// Channels with a system chain are never considered idle and channels with messages that
+		/// were not received yet can not be closed.
+		///
+		/// Any signed origin can call this function, it is free of charge if the channel gets
+		/// closed.
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config>::WeightInfo::close_idle_channel())]
+		pub fn close_idle_channel(
+			origin: OriginFor<T>,
+			channel_id: HrmpChannelId,
+		) -> DispatchResultWithPostInfo {
+			let _caller = ensure_signed(origin)?;
+
+			let idle_sessions = configuration::ActiveConfig::<T>::get()
+				.hrmp_idle_channel_sessions
+				.ok_or(Error::<T>::IdleChannelClosingDisabled)?;
+			ensure!(
+				HrmpChannels::<T>::contains_key(&channel_id),
+				Error::<T>::CloseHrmpChannelDoesntExist,
+			);
+			ensure!(
+				HrmpCloseChannelRequests::<T>::get(&channel_id).is_none(),
+				Error::<T>::CloseHrmpChannelAlreadyUnderway,
+			);
+			let last_active_session = Self::idle_channel_last_activity(&channel_id, idle_sessions)?;
+
+			HrmpCloseChannelRequests::<T>::insert(&channel_id, ());
+			HrmpCloseChannelRequestsList::<T>::append(channel_id.clone());
+
+			Self::deposit_event(Event::IdleChannelCloseRequested {
+				channel_id,
+				last_active_session,
+			});
+
+			Ok(Pays::No.into())
+		}
 	}
 }
 
@@ -911,6 +978,7 @@ fn initialize_storage<T: Config>(preopen_hrmp_channels: &[(ParaId, ParaId, u32,
 		}
 	}
 	Pallet::<T>::process_hrmp_open_channel_requests(&host_config);
+	HrmpActivityTrackedSince::<T>::put(shared::CurrentSessionIndex::<T>::get());
 }
 
 fn preopen_hrmp_channel<T: Config>(
@@ -942,12 +1010,18 @@ impl<T: Config> Pallet<T> {
 		let w1 = Self::perform_outgoing_para_cleanup(&notification.prev_config, outgoing_paras);
 		Self::process_hrmp_open_channel_requests(&notification.prev_config);
 		Self::process_hrmp_close_channel_requests();
-		w1.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_open(
-			outgoing_paras.len() as u32
-		))
-		.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_close(
-			outgoing_paras.len() as u32,
-		))
+		// Channels that existed before the activity tracking was introduced count as active in
+		// the first session it is in place.
+		if !HrmpActivityTrackedSince::<T>::exists() {
+			HrmpActivityTrackedSince::<T>::put(notification.session_index);
+		}
+		w1.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_open(
+				outgoing_paras.len() as u32,
+			))
+			.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_close(
+				outgoing_paras.len() as u32,
+			))
 	}
 
 	/// Iterate over all paras that were noted for offboarding and remove all the data
@@ -1107,6 +1181,10 @@ impl<T: Config> Pallet<T> {
 							v.insert(i, channel_id.recipient);
 						}
 					});
+					HrmpChannelLastActivity::<T>::insert(
+						&channel_id,
+						shared::CurrentSessionIndex::<T>::get(),
+					);
 				}
 
 				Self::decrease_open_channel_request_count(channel_id.sender);
@@ -1150,6 +1228,7 @@ impl<T: Config> Pallet<T> {
 		}
 
 		HrmpChannelContents::<T>::remove(channel_id);
+		HrmpChannelLastActivity::<T>::remove(channel_id);
 
 		HrmpEgressChannelsIndex::<T>::mutate(&channel_id.sender, |v| {
 			if let Ok(i) = v.binary_search(&channel_id.recipient) {
@@ -1362,6 +1441,7 @@ impl<T: Config> Pallet<T> {
 	/// Process the outbound HRMP messages by putting them into the appropriate recipient queues.
 	pub(crate) fn queue_outbound_hrmp(sender: ParaId, out_hrmp_msgs: HorizontalMessages) {
 		let now = frame_system::Pallet::<T>::block_number();
+		let session_index = shared::CurrentSessionIndex::<T>::get();
 
 		for out_msg in out_hrmp_msgs {
 			let channel_id = HrmpChannelId { sender, recipient: out_msg.recipient };
@@ -1392,6 +1472,7 @@ impl<T: Config> Pallet<T> {
 
 			HrmpChannels::<T>::insert(&channel_id, channel);
 			HrmpChannelContents::<T>::append(&channel_id, inbound);
+			HrmpChannelLastActivity::<T>::insert(&channel_id, session_index);
 
 			// The digests are sorted in ascending by block number order. There are only two
 			// possible scenarios here ("the current" is the block of candidate's inclusion):
@@ -1651,6 +1732,55 @@ impl<T: Config> Pallet<T> {
 		mqc_heads
 	}
 
+	/// Returns the session in which the given open channel was last active, if no message was
+	/// sent over it for at least `idle_sessions` sessions.
+	///
+	/// Fails if one of the participants of the channel is a system chain or if the channel still
+	/// has messages that were not received by the recipient.
+	fn idle_channel_last_activity(
+		channel_id: &HrmpChannelId,
+		idle_sessions: SessionIndex,
+	) -> Result<SessionIndex, Error<T>> {
+		ensure!(
+			!channel_id.sender.is_system() && !channel_id.recipient.is_system(),
+			Error::<T>::ChannelNotIdle,
+		);
+		let last_active_session = HrmpChannelLastActivity::<T>::get(channel_id)
+			.or_else(HrmpActivityTrackedSince::<T>::get)
+			.ok_or(Error::<T>::ChannelNotIdle)?;
+		let current_session = shared::CurrentSessionIndex::<T>::get();
+		ensure!(
+			current_session.saturating_sub(last_active_session) >= idle_sessions,
+			Error::<T>::ChannelNotIdle,
+		);
+		ensure!(
+			HrmpChannelContents::<T>::decode_len(channel_id).unwrap_or_default() == 0,
+			Error::<T>::ChannelNotEmpty,
+		);
+		Ok(last_active_session)
+	}
+
+	/// Returns all channels that can be closed for being idle, paired with the session in which
+	/// they were last active. Channels that are already going to be closed are not included.
+	pub fn idle_channels() -> Vec<(HrmpChannelId, SessionIndex)> {
+		let Some(idle_sessions) =
+			configuration::ActiveConfig::<T>::get().hrmp_idle_channel_sessions
+		else {
+			return Vec::new()
+		};
+
+		let mut idle_channels = HrmpChannels::<T>::iter_keys()
+			.filter(|channel_id| !HrmpCloseChannelRequests::<T>::contains_key(channel_id))
+			.filter_map(|channel_id| {
+				Self::idle_channel_last_activity(&channel_id, idle_sessions)
+					.ok()
+					.map(|session| (channel_id, session))
+			})
+			.collect::<Vec<_>>();
+		idle_channels.sort();
+		idle_channels
+	}
+
 	/// Returns contents of all channels addressed to the given recipient. Channels that have no
 	/// messages in them are also included.
 	pub(crate) fn inbound_hrmp_channels_contents(
@@ -1773,6 +1903,11 @@ impl<T: Config> Pallet<T> {
 			assert!(!contents.is_empty());
 		}
 
+		// Only open channels can have activity recorded.
+		for channel in HrmpChannelLastActivity::<T>::iter_keys() {
+			assert!(HrmpChannels::<T>::contains_key(&channel));
+		}
+
 		// Senders and recipients must be onboarded. Otherwise, all channels associated with them
 		// are removed.
 		assert_contains_only_onboarded(
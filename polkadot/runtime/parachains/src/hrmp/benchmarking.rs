@@ -561,6 +561,53 @@ mod benchmarks {
 		);
 	}
 
+	#[benchmark]
+	fn close_idle_channel() {
+		// enable closing idle channels and enact the config.
+		let idle_sessions = 1;
+		assert_ok!(Configuration::<T>::set_hrmp_idle_channel_sessions(
+			frame_system::RawOrigin::Root.into(),
+			Some(idle_sessions)
+		));
+		Configuration::<T>::initializer_on_new_session(&Shared::<T>::scheduled_session());
+
+		let sender_id: ParaId = 2000u32.into();
+		let recipient_id: ParaId = 2001u32.into();
+		let channel_id = HrmpChannelId { sender: sender_id, recipient: recipient_id };
+		let caller: T::AccountId = whitelisted_caller();
+		let config = configuration::ActiveConfig::<T>::get();
+
+		register_parachain_with_balance::<T>(sender_id, Zero::zero());
+		register_parachain_with_balance::<T>(recipient_id, Zero::zero());
+		HrmpChannels::<T>::insert(
+			&channel_id,
+			HrmpChannel {
+				sender_deposit: 0,
+				recipient_deposit: 0,
+				max_capacity: config.hrmp_channel_max_capacity,
+				max_total_size: config.hrmp_channel_max_total_size,
+				max_message_size: config.hrmp_channel_max_message_size,
+				msg_count: 0,
+				total_size: 0,
+				mqc_head: None,
+			},
+		);
+		HrmpChannelLastActivity::<T>::insert(&channel_id, 0);
+		Shared::<T>::set_session_index(idle_sessions);
+
+		#[extrinsic_call]
+		_(frame_system::RawOrigin::Signed(caller), channel_id.clone());
+
+		assert_last_event::<T>(
+			Event::<T>::IdleChannelCloseRequested {
+				channel_id: channel_id.clone(),
+				last_active_session: 0,
+			}
+			.into(),
+		);
+		assert!(HrmpCloseChannelRequests::<T>::contains_key(&channel_id));
+	}
+
 	impl_benchmark_test_suite!(
 		Hrmp,
 		crate::mock::new_test_ext(crate::hrmp::tests::GenesisConfigBuilder::default().build()),
@@ -83,6 +83,7 @@ pub(super) struct GenesisConfigBuilder {
 	hrmp_channel_max_total_size: u32,
 	hrmp_sender_deposit: Balance,
 	hrmp_recipient_deposit: Balance,
+	hrmp_idle_channel_sessions: Option<SessionIndex>,
 }
 
 impl Default for GenesisConfigBuilder {
@@ -96,6 +97,7 @@ impl Default for GenesisConfigBuilder {
 			hrmp_channel_max_total_size: 16,
 			hrmp_sender_deposit: 100,
 			hrmp_recipient_deposit: 100,
+			hrmp_idle_channel_sessions: None,
 		}
 	}
 }
@@ -112,6 +114,7 @@ impl GenesisConfigBuilder {
 		config.hrmp_channel_max_total_size = self.hrmp_channel_max_total_size;
 		config.hrmp_sender_deposit = self.hrmp_sender_deposit;
 		config.hrmp_recipient_deposit = self.hrmp_recipient_deposit;
+		config.hrmp_idle_channel_sessions = self.hrmp_idle_channel_sessions;
 		genesis
 	}
 }
@@ -492,6 +495,110 @@ fn close_channel_works() {
 	});
 }
 
+#[test]
+fn close_idle_channel_works() {
+	let para_a = 2032.into();
+	let para_b = 2064.into();
+	let channel_id = HrmpChannelId { sender: para_a, recipient: para_b };
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	genesis.hrmp_idle_channel_sessions = Some(2);
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain_with_balance(para_a, 100);
+		register_parachain_with_balance(para_b, 110);
+
+		// The channel is opened in session 3.
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		run_to_block(6, Some(vec![6]));
+		assert!(channel_exists(para_a, para_b));
+		assert_noop!(
+			Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id.clone()),
+			Error::<Test>::ChannelNotIdle
+		);
+
+		// A message sent in session 4 keeps the channel active.
+		run_to_block(7, Some(vec![7]));
+		let msgs: HorizontalMessages =
+			vec![OutboundHrmpMessage { recipient: para_b, data: b"knock".to_vec() }]
+				.try_into()
+				.unwrap();
+		Hrmp::queue_outbound_hrmp(para_a, msgs);
+		assert_eq!(HrmpChannelLastActivity::<Test>::get(&channel_id), Some(4));
+
+		run_to_block(8, Some(vec![8]));
+		assert!(Hrmp::idle_channels().is_empty());
+		assert_noop!(
+			Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id.clone()),
+			Error::<Test>::ChannelNotIdle
+		);
+
+		// Two sessions without messages, but the message was not received yet.
+		run_to_block(9, Some(vec![9]));
+		assert!(Hrmp::idle_channels().is_empty());
+		assert_noop!(
+			Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id.clone()),
+			Error::<Test>::ChannelNotEmpty
+		);
+
+		// Once the recipient processed the message, anyone can close the channel.
+		Hrmp::prune_hrmp(para_b, 7);
+		assert_eq!(Hrmp::idle_channels(), vec![(channel_id.clone(), 4)]);
+		assert_ok!(Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id.clone()));
+		System::assert_last_event(MockEvent::Hrmp(Event::IdleChannelCloseRequested {
+			channel_id: channel_id.clone(),
+			last_active_session: 4,
+		}));
+		assert!(Hrmp::idle_channels().is_empty());
+		assert_noop!(
+			Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id.clone()),
+			Error::<Test>::CloseHrmpChannelAlreadyUnderway
+		);
+		Hrmp::assert_storage_consistency_exhaustive();
+
+		// The channel is closed on the next session change and the deposits are returned.
+		run_to_block(10, Some(vec![10]));
+		assert!(!channel_exists(para_a, para_b));
+		assert!(HrmpChannelLastActivity::<Test>::get(&channel_id).is_none());
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&para_a.into_account_truncating()),
+			100
+		);
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&para_b.into_account_truncating()),
+			110
+		);
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
+#[test]
+fn close_idle_channel_is_disabled_by_default() {
+	let para_a = 2032.into();
+	let para_b = 2064.into();
+	let channel_id = HrmpChannelId { sender: para_a, recipient: para_b };
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		run_to_block(10, Some(vec![6, 7, 8, 9, 10]));
+		assert!(channel_exists(para_a, para_b));
+
+		assert!(Hrmp::idle_channels().is_empty());
+		assert_noop!(
+			Hrmp::close_idle_channel(RuntimeOrigin::signed(1), channel_id),
+			Error::<Test>::IdleChannelClosingDisabled
+		);
+	});
+}
+
 #[test]
 fn send_recv_messages() {
 	let para_a = 2032.into();
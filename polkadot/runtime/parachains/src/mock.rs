@@ -409,15 +409,25 @@ parameter_types! {
 	// defined Timeslice for Coretime.
 	pub const MaxHistoricalRevenue: BlockNumber = 2 * 5;
 	pub const OnDemandPalletId: PalletId = PalletId(*b"py/ondmd");
+	pub const MaxStandingOrders: u32 = 100;
+	pub const MaxStandingOrdersPerAccount: u32 = 5;
+	pub const StandingOrderDeposit: Balance = 1_000;
+	pub const MaxStandingOrderFills: u32 = 3;
 }
 
 impl on_demand::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type Fungible = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type TrafficDefaultValue = OnDemandTrafficDefaultValue;
 	type WeightInfo = crate::on_demand::TestWeightInfo;
 	type MaxHistoricalRevenue = MaxHistoricalRevenue;
 	type PalletId = OnDemandPalletId;
+	type MaxStandingOrders = MaxStandingOrders;
+	type MaxStandingOrdersPerAccount = MaxStandingOrdersPerAccount;
+	type StandingOrderDeposit = StandingOrderDeposit;
+	type MaxStandingOrderFills = MaxStandingOrderFills;
 }
 
 impl assigner_coretime::Config for Test {}
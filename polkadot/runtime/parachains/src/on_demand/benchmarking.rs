@@ -58,6 +58,48 @@ where
 	);
 }
 
+// Insert `n` standing orders of distinct owners which are due now, all of which can be filled.
+fn insert_standing_orders<T: Config>(para_id: ParaId, n: u32) {
+	let now = frame_system::Pallet::<T>::block_number();
+	let deposit = T::StandingOrderDeposit::get();
+	for id in 0..n {
+		let owner: T::AccountId = account("owner", id, 0);
+		T::Currency::make_free_balance_be(
+			&owner,
+			T::Currency::minimum_balance()
+				.saturating_add(deposit)
+				.saturating_mul(2u32.into()),
+		);
+		T::Fungible::hold(&HoldReason::StandingOrderDeposit.into(), &owner, deposit)
+			.expect("The owner can pay the deposit");
+		Credits::<T>::insert(&owner, BalanceOf::<T>::max_value());
+		// The spend cap is updated on every fill.
+		SpendCaps::<T>::insert(
+			&owner,
+			SpendCap {
+				limit: BalanceOf::<T>::max_value(),
+				period: 100u32.into(),
+				spent: Zero::zero(),
+				period_start: now,
+			},
+		);
+		StandingOrdersOf::<T>::insert(&owner, 1);
+		StandingOrders::<T>::insert(
+			id,
+			StandingOrder {
+				owner,
+				para_id,
+				max_price: BalanceOf::<T>::max_value(),
+				expires_at: now + 10u32.into(),
+				recurrence: OrderRecurrence::Once,
+				next_fill_at: now,
+				deposit,
+			},
+		);
+	}
+	NextStandingOrderId::<T>::put(n);
+}
+
 #[benchmarks]
 mod benchmarks {
 	/// We want to fill the queue to the maximum, so exactly one more item fits.
@@ -105,6 +147,90 @@ mod benchmarks {
 		_(RawOrigin::Signed(caller.into()), BalanceOf::<T>::max_value(), para_id)
 	}
 
+	#[benchmark]
+	fn place_standing_order() {
+		// Setup
+		let caller: T::AccountId = whitelisted_caller();
+		let para_id = ParaId::from(111u32);
+		init_parathread::<T>(para_id);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Credits::<T>::insert(&caller, BalanceOf::<T>::max_value());
+		// Place the last order possible, both overall and for the caller.
+		let existing = T::MaxStandingOrders::get() - 1;
+		insert_standing_orders::<T>(para_id, existing);
+		StandingOrdersOf::<T>::insert(&caller, T::MaxStandingOrdersPerAccount::get() - 1);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		let recurrence = OrderRecurrence::Every { period: 2u32.into(), count: 5 };
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller.into()),
+			para_id,
+			BalanceOf::<T>::max_value(),
+			expires_at,
+			recurrence,
+		);
+
+		assert_eq!(StandingOrders::<T>::count(), existing + 1);
+	}
+
+	#[benchmark]
+	fn cancel_standing_order() {
+		// Setup
+		let caller: T::AccountId = whitelisted_caller();
+		let para_id = ParaId::from(111u32);
+		init_parathread::<T>(para_id);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Credits::<T>::insert(&caller, BalanceOf::<T>::max_value());
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		Pallet::<T>::place_standing_order(
+			RawOrigin::Signed(caller.clone()).into(),
+			para_id,
+			BalanceOf::<T>::max_value(),
+			expires_at,
+			OrderRecurrence::Once,
+		)
+		.expect("Placing a standing order works");
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.into()), 0);
+
+		assert_eq!(StandingOrders::<T>::count(), 0);
+	}
+
+	#[benchmark]
+	fn set_spend_cap() {
+		// Setup
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), Some(BalanceOf::<T>::max_value()), 10u32.into());
+
+		assert!(SpendCaps::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn on_initialize(s: Linear<0, { T::MaxStandingOrderFills::get() }>) {
+		// Setup
+		let para_id = ParaId::from(111u32);
+		init_parathread::<T>(para_id);
+		// Every fill has to push to a queue which is as large as possible.
+		Pallet::<T>::populate_queue(
+			para_id,
+			ON_DEMAND_DEFAULT_QUEUE_MAX_SIZE.saturating_sub(T::MaxStandingOrderFills::get()),
+		);
+		// Completing an order is the most expensive outcome.
+		insert_standing_orders::<T>(para_id, s);
+		let now = frame_system::Pallet::<T>::block_number();
+
+		#[block]
+		{
+			Pallet::<T>::on_initialize(now);
+		}
+
+		assert_eq!(StandingOrders::<T>::count(), 0);
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(
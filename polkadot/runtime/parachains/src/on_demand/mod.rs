@@ -25,6 +25,13 @@
 //! a specific `ParaId`, orders for blockspace for that `ParaId` will only be assigned to
 //! that `CoreIndex`.
 //!
+//! Besides orders at the current spot price, accounts can place standing orders: limit orders
+//! which are filled once the spot price drops to a given maximum before they expire, optionally
+//! recurring every so many blocks. Every standing order holds a deposit of its owner until it is
+//! removed. Standing orders are filled on block initialization, a bounded number per block, and end
+//! up in the same queue as regular orders. Accounts can additionally cap how much they spend on
+//! on-demand coretime per period.
+//!
 //! NOTE: Once we have elastic scaling implemented we might want to extend this module to support
 //! ignoring core affinity up to a certain extend. This should be opt-in though as the parachain
 //! needs to support multiple cores in the same block. If we want to enable a single parachain
@@ -43,12 +50,14 @@ extern crate alloc;
 mod tests;
 
 use crate::{configuration, paras, scheduler::common::Assignment};
-use alloc::collections::BinaryHeap;
+use alloc::{collections::BinaryHeap, vec::Vec};
 use core::mem::take;
 use frame_support::{
 	pallet_prelude::*,
 	traits::{
 		defensive_prelude::*,
+		fungible::MutateHold,
+		tokens::Precision,
 		Currency,
 		ExistenceRequirement::{self, AllowDeath, KeepAlive},
 		WithdrawReasons,
@@ -66,6 +75,8 @@ use types::{
 	SpotTrafficCalculationErr,
 };
 
+pub use types::{OrderRecurrence, SpendCap, StandingOrder, StandingOrderId};
+
 const LOG_TARGET: &str = "runtime::parachains::on-demand";
 
 pub use pallet::*;
@@ -74,6 +85,10 @@ pub trait WeightInfo {
 	fn place_order_allow_death(s: u32) -> Weight;
 	fn place_order_keep_alive(s: u32) -> Weight;
 	fn place_order_with_credits(s: u32) -> Weight;
+	fn place_standing_order() -> Weight;
+	fn cancel_standing_order() -> Weight;
+	fn set_spend_cap() -> Weight;
+	fn on_initialize(s: u32) -> Weight;
 }
 
 /// A weight info that is only suitable for testing.
@@ -91,6 +106,23 @@ impl WeightInfo for TestWeightInfo {
 	fn place_order_with_credits(_: u32) -> Weight {
 		Weight::MAX
 	}
+
+	fn place_standing_order() -> Weight {
+		Weight::MAX
+	}
+
+	fn cancel_standing_order() -> Weight {
+		Weight::MAX
+	}
+
+	fn set_spend_cap() -> Weight {
+		Weight::MAX
+	}
+
+	fn on_initialize(s: u32) -> Weight {
+		// Grows with the number of standing orders looked at, so that tests can check it.
+		Weight::from_parts(s.into(), 0)
+	}
 }
 
 /// Defines how the account wants to pay for on-demand.
@@ -123,6 +155,16 @@ pub mod pallet {
 		/// The runtime's definition of a Currency.
 		type Currency: Currency<Self::AccountId>;
 
+		/// The fungible holding the deposits of standing orders.
+		type Fungible: MutateHold<
+			Self::AccountId,
+			Reason = Self::RuntimeHoldReason,
+			Balance = BalanceOf<Self>,
+		>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
 		/// Something that provides the weight of this pallet.
 		type WeightInfo: WeightInfo;
 
@@ -138,6 +180,22 @@ pub mod pallet {
 		/// Identifier for the internal revenue balance.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
+
+		/// The maximum number of standing orders that can exist at the same time.
+		#[pallet::constant]
+		type MaxStandingOrders: Get<u32>;
+
+		/// The maximum number of standing orders a single account can have at the same time.
+		#[pallet::constant]
+		type MaxStandingOrdersPerAccount: Get<u32>;
+
+		/// The deposit held for every standing order, returned once the order is removed.
+		#[pallet::constant]
+		type StandingOrderDeposit: Get<BalanceOf<Self>>;
+
+		/// The maximum number of standing orders looked at on block initialization.
+		#[pallet::constant]
+		type MaxStandingOrderFills: Get<u32>;
 	}
 
 	/// Creates an empty queue status for an empty queue with initial traffic value.
@@ -189,6 +247,47 @@ pub mod pallet {
 	pub type Credits<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 
+	/// Standing orders waiting to be filled, by their identifier.
+	#[pallet::storage]
+	pub type StandingOrders<T: Config> = CountedStorageMap<
+		_,
+		Twox64Concat,
+		StandingOrderId,
+		StandingOrder<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The identifier to use for the next standing order.
+	#[pallet::storage]
+	pub type NextStandingOrderId<T: Config> = StorageValue<_, StandingOrderId, ValueQuery>;
+
+	/// The number of standing orders of each account.
+	#[pallet::storage]
+	pub type StandingOrdersOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The last standing order looked at on block initialization. The next block continues after
+	/// it.
+	#[pallet::storage]
+	pub(super) type StandingOrderCursor<T: Config> = StorageValue<_, StandingOrderId, OptionQuery>;
+
+	/// Spend caps set by ordering accounts.
+	#[pallet::storage]
+	pub type SpendCaps<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		SpendCap<BalanceOf<T>, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// A reason for this pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held as deposit of a standing order.
+		StandingOrderDeposit,
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -198,6 +297,18 @@ pub mod pallet {
 		SpotPriceSet { spot_price: BalanceOf<T> },
 		/// An account was given credits.
 		AccountCredited { who: T::AccountId, amount: BalanceOf<T> },
+		/// A standing order was placed by `ordered_by`.
+		StandingOrderPlaced { id: StandingOrderId, para_id: ParaId, ordered_by: T::AccountId },
+		/// A standing order was filled and an order was added to the queue.
+		StandingOrderFilled { id: StandingOrderId },
+		/// A standing order was filled for the last time and removed.
+		StandingOrderCompleted { id: StandingOrderId },
+		/// A standing order expired before it was (completely) filled and was removed.
+		StandingOrderExpired { id: StandingOrderId },
+		/// A standing order was cancelled by its owner.
+		StandingOrderCancelled { id: StandingOrderId },
+		/// An account set or removed its spend cap.
+		SpendCapSet { who: T::AccountId, limit: Option<BalanceOf<T>>, period: BlockNumberFor<T> },
 	}
 
 	#[pallet::error]
@@ -209,11 +320,25 @@ pub mod pallet {
 		SpotPriceHigherThanMaxAmount,
 		/// The account doesn't have enough credits to purchase on-demand coretime.
 		InsufficientCredits,
+		/// The expiry block of a standing order is not in the future.
+		InvalidExpiry,
+		/// A recurring standing order or a spend cap needs a non-zero period and count.
+		InvalidPeriod,
+		/// The maximum number of standing orders has been reached.
+		TooManyStandingOrders,
+		/// The account has reached the maximum number of standing orders per account.
+		TooManyAccountStandingOrders,
+		/// There is no standing order with the given identifier.
+		UnknownStandingOrder,
+		/// Only the owner of a standing order can cancel it.
+		NotStandingOrderOwner,
+		/// Placing the order would exceed the spend cap of the account.
+		SpendCapExceeded,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(_now: BlockNumberFor<T>) -> Weight {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
 			// Update revenue information storage.
 			Revenue::<T>::mutate(|revenue| {
 				if let Some(overdue) =
@@ -234,9 +359,15 @@ pub mod pallet {
 				Self::update_spot_traffic(&config, queue_status);
 			});
 
-			// Reads: `Revenue`, `ActiveConfig`, `QueueStatus`
-			// Writes: `Revenue`, `QueueStatus`
-			T::DbWeight::get().reads_writes(3, 2)
+			let processed = Self::fill_standing_orders(now);
+			<T as Config>::WeightInfo::on_initialize(processed)
+		}
+
+		fn integrity_test() {
+			assert!(
+				T::MaxStandingOrderFills::get() > 0,
+				"Standing orders must be looked at on block initialization"
+			);
 		}
 	}
 
@@ -343,6 +474,160 @@ pub mod pallet {
 				PaymentType::Credits,
 			)
 		}
+
+		/// Place a standing order, which is filled with credits once the spot price is at most
+		/// `max_price`.
+		///
+		/// Standing orders are checked on block initialization, starting in the next block. At
+		/// most `MaxStandingOrderFills` orders are looked at per block, taking turns. An order
+		/// that can not be filled, because of its price, the queue being full, missing credits or
+		/// the spend cap of the owner, is retried in the following blocks until it expires.
+		///
+		/// `StandingOrderDeposit` is held from the sender until the order is completed,
+		/// expires or is cancelled.
+		///
+		/// Parameters:
+		/// - `origin`: The sender of the call, on-demand credits will be withdrawn from this
+		///   account whenever the order is filled.
+		/// - `para_id`: A `ParaId` the origin wants to provide blockspace for.
+		/// - `max_price`: The maximum number of credits to spend on a single fill.
+		/// - `expires_at`: The block from which the order is no longer filled.
+		/// - `recurrence`: Whether the order is filled once or recurs every so many blocks.
+		///
+		/// Errors:
+		/// - `InvalidExpiry`
+		/// - `InvalidPeriod`
+		/// - `InsufficientCredits`
+		/// - `TooManyStandingOrders`
+		/// - `TooManyAccountStandingOrders`
+		/// - `FundsUnavailable`: from the Fungible implementation
+		///
+		/// Events:
+		/// - `StandingOrderPlaced`
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::place_standing_order())]
+		pub fn place_standing_order(
+			origin: OriginFor<T>,
+			para_id: ParaId,
+			max_price: BalanceOf<T>,
+			expires_at: BlockNumberFor<T>,
+			recurrence: OrderRecurrence<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let now = System::<T>::block_number();
+
+			ensure!(expires_at > now, Error::<T>::InvalidExpiry);
+			if let OrderRecurrence::Every { period, count } = recurrence {
+				ensure!(!period.is_zero() && count > 0, Error::<T>::InvalidPeriod);
+			}
+			// Don't let accounts occupy standing order slots without being able to pay for at
+			// least a single fill.
+			ensure!(Credits::<T>::get(&sender) >= max_price, Error::<T>::InsufficientCredits);
+			ensure!(
+				StandingOrders::<T>::count() < T::MaxStandingOrders::get(),
+				Error::<T>::TooManyStandingOrders
+			);
+			ensure!(
+				StandingOrdersOf::<T>::get(&sender) < T::MaxStandingOrdersPerAccount::get(),
+				Error::<T>::TooManyAccountStandingOrders
+			);
+
+			let deposit = T::StandingOrderDeposit::get();
+			T::Fungible::hold(&HoldReason::StandingOrderDeposit.into(), &sender, deposit)?;
+			StandingOrdersOf::<T>::mutate(&sender, |count| *count += 1);
+
+			let id = NextStandingOrderId::<T>::mutate(|next_id| {
+				let id = *next_id;
+				*next_id = next_id.wrapping_add(1);
+				id
+			});
+			StandingOrders::<T>::insert(
+				id,
+				StandingOrder {
+					owner: sender.clone(),
+					para_id,
+					max_price,
+					expires_at,
+					recurrence,
+					next_fill_at: now.saturating_add(One::one()),
+					deposit,
+				},
+			);
+
+			Pallet::<T>::deposit_event(Event::<T>::StandingOrderPlaced {
+				id,
+				para_id,
+				ordered_by: sender,
+			});
+			Ok(())
+		}
+
+		/// Cancel a standing order and return its deposit.
+		///
+		/// Parameters:
+		/// - `origin`: The owner of the standing order.
+		/// - `id`: The identifier of the standing order.
+		///
+		/// Errors:
+		/// - `UnknownStandingOrder`
+		/// - `NotStandingOrderOwner`
+		///
+		/// Events:
+		/// - `StandingOrderCancelled`
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_standing_order())]
+		pub fn cancel_standing_order(origin: OriginFor<T>, id: StandingOrderId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let order = StandingOrders::<T>::get(id).ok_or(Error::<T>::UnknownStandingOrder)?;
+			ensure!(order.owner == sender, Error::<T>::NotStandingOrderOwner);
+
+			Pallet::<T>::remove_standing_order(id, &order);
+			Pallet::<T>::deposit_event(Event::<T>::StandingOrderCancelled { id });
+			Ok(())
+		}
+
+		/// Set or remove the spend cap of the sender.
+		///
+		/// The cap applies to all orders of the account, including standing orders. Changing the
+		/// cap keeps the amount already spent in the current period.
+		///
+		/// Parameters:
+		/// - `origin`: The account to set the spend cap for.
+		/// - `limit`: The maximum amount to spend per period, `None` removes the cap.
+		/// - `period`: The length of a period in blocks.
+		///
+		/// Errors:
+		/// - `InvalidPeriod`
+		///
+		/// Events:
+		/// - `SpendCapSet`
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_spend_cap())]
+		pub fn set_spend_cap(
+			origin: OriginFor<T>,
+			limit: Option<BalanceOf<T>>,
+			period: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			match limit {
+				Some(limit) => {
+					ensure!(!period.is_zero(), Error::<T>::InvalidPeriod);
+					let now = System::<T>::block_number();
+					SpendCaps::<T>::mutate(&sender, |maybe_cap| {
+						let (spent, period_start) = match maybe_cap {
+							Some(cap) => (cap.spent, cap.period_start),
+							None => (Zero::zero(), now),
+						};
+						*maybe_cap = Some(SpendCap { limit, period, spent, period_start });
+					});
+				},
+				None => SpendCaps::<T>::remove(&sender),
+			}
+
+			Pallet::<T>::deposit_event(Event::<T>::SpendCapSet { who: sender, limit, period });
+			Ok(())
+		}
 	}
 }
 
@@ -479,6 +764,8 @@ where
 				Error::<T>::QueueFull
 			);
 
+			Self::charge_spend_cap(&sender, spot_price)?;
+
 			match payment_type {
 				PaymentType::Balance => {
 					// Charge the sending account the spot price. The amount will be teleported to
@@ -536,6 +823,119 @@ where
 		})
 	}
 
+	/// Account `amount` towards the spend cap of `who`, if any.
+	///
+	/// Starts a new period if the current one is over.
+	///
+	/// Errors:
+	/// - `SpendCapExceeded`
+	fn charge_spend_cap(who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		SpendCaps::<T>::try_mutate_exists(who, |maybe_cap| {
+			let Some(cap) = maybe_cap else { return Ok(()) };
+
+			let now = System::<T>::block_number();
+			if now >= cap.period_start.saturating_add(cap.period) {
+				cap.spent = Zero::zero();
+				cap.period_start = now;
+			}
+
+			let spent = cap.spent.saturating_add(amount);
+			ensure!(spent <= cap.limit, Error::<T>::SpendCapExceeded);
+			cap.spent = spent;
+			Ok(())
+		})
+	}
+
+	/// Fill the standing orders that are due and whose maximum price is met, and remove expired
+	/// ones.
+	///
+	/// At most `MaxStandingOrderFills` orders are looked at, continuing after the last order
+	/// looked at in the previous block and wrapping around, so that all orders take turns. An
+	/// order that can not be filled is left untouched and retried later.
+	///
+	/// Returns the number of orders looked at.
+	fn fill_standing_orders(now: BlockNumberFor<T>) -> u32 {
+		let max_orders = T::MaxStandingOrderFills::get() as usize;
+
+		let mut orders: Vec<_> = match StandingOrderCursor::<T>::get() {
+			Some(cursor) =>
+				StandingOrders::<T>::iter_from(StandingOrders::<T>::hashed_key_for(cursor))
+					.take(max_orders)
+					.collect(),
+			None => Vec::new(),
+		};
+		if orders.len() < max_orders {
+			let wrapped: Vec<_> = StandingOrders::<T>::iter()
+				.take(max_orders - orders.len())
+				.take_while(|(id, _)| orders.iter().all(|(seen, _)| seen != id))
+				.collect();
+			orders.extend(wrapped);
+		}
+		StandingOrderCursor::<T>::set(orders.last().map(|(id, _)| *id));
+
+		let processed = orders.len() as u32;
+		for (id, mut order) in orders {
+			if now >= order.expires_at {
+				Pallet::<T>::remove_standing_order(id, &order);
+				Pallet::<T>::deposit_event(Event::<T>::StandingOrderExpired { id });
+				continue
+			}
+			if now < order.next_fill_at {
+				continue
+			}
+
+			let filled = frame_support::storage::with_storage_layer(|| {
+				Pallet::<T>::do_place_order(
+					order.owner.clone(),
+					order.max_price,
+					order.para_id,
+					KeepAlive,
+					PaymentType::Credits,
+				)
+			});
+			if let Err(err) = filled {
+				log::trace!(
+					target: LOG_TARGET,
+					"Standing order {} not filled at block {:?}: {:?}", id, now, err
+				);
+				continue
+			}
+
+			Pallet::<T>::deposit_event(Event::<T>::StandingOrderFilled { id });
+			match order.recurrence {
+				OrderRecurrence::Every { period, count } if count > 1 => {
+					order.recurrence = OrderRecurrence::Every { period, count: count - 1 };
+					order.next_fill_at = now.saturating_add(period);
+					StandingOrders::<T>::insert(id, order);
+				},
+				_ => {
+					Pallet::<T>::remove_standing_order(id, &order);
+					Pallet::<T>::deposit_event(Event::<T>::StandingOrderCompleted { id });
+				},
+			}
+		}
+
+		processed
+	}
+
+	/// Remove a standing order and return its deposit to the owner.
+	fn remove_standing_order(
+		id: StandingOrderId,
+		order: &StandingOrder<T::AccountId, BalanceOf<T>, BlockNumberFor<T>>,
+	) {
+		StandingOrders::<T>::remove(id);
+		let _ = T::Fungible::release(
+			&HoldReason::StandingOrderDeposit.into(),
+			&order.owner,
+			order.deposit,
+			Precision::BestEffort,
+		)
+		.defensive();
+		StandingOrdersOf::<T>::mutate_exists(&order.owner, |count| {
+			*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+		});
+	}
+
 	/// Calculate and update spot traffic.
 	fn update_spot_traffic(
 		config: &configuration::HostConfiguration<BlockNumberFor<T>>,
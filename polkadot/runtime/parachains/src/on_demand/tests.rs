@@ -31,12 +31,12 @@ use crate::{
 	paras::{ParaGenesisArgs, ParaKind},
 };
 use core::cmp::{Ord, Ordering};
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::fungible::InspectHold};
 use pallet_balances::Error as BalancesError;
 use polkadot_primitives::{
 	BlockNumber, SessionIndex, ValidationCode, ON_DEMAND_MAX_QUEUE_MAX_SIZE,
 };
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::{traits::BadOrigin, TokenError};
 
 fn schedule_blank_para(id: ParaId, parakind: ParaKind) {
 	let validation_code: ValidationCode = vec![1, 2, 3].into();
@@ -400,6 +400,355 @@ fn place_order_with_credits() {
 	});
 }
 
+#[test]
+fn standing_order_is_filled_once_price_is_met() {
+	let alice = 1u64;
+	let initial_credit = 10_000_000u128;
+	let para_id = ParaId::from(111);
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		OnDemand::credit_account(alice, initial_credit);
+		Balances::make_free_balance_be(&alice, 1_000_000u128);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		// The spot price is at the base fee of 10_000, this order is never filled.
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			9_999u128,
+			105,
+			OrderRecurrence::Once,
+		));
+		// This one is filled in the next block.
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			10_000u128,
+			105,
+			OrderRecurrence::Once,
+		));
+		assert_eq!(StandingOrders::<Test>::count(), 2);
+
+		run_to_block(101, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 10_000);
+		assert!(StandingOrders::<Test>::get(0).is_some());
+		assert!(StandingOrders::<Test>::get(1).is_none());
+		System::assert_has_event(Event::<Test>::StandingOrderCompleted { id: 1 }.into());
+
+		run_to_block(105, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 10_000);
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+		System::assert_has_event(Event::<Test>::StandingOrderExpired { id: 0 }.into());
+
+		// Revenue is accounted for like for any other order.
+		assert_eq!(OnDemand::claim_revenue_until(106), 10_000);
+	});
+}
+
+#[test]
+fn recurring_standing_order_is_filled_every_period() {
+	let alice = 1u64;
+	let initial_credit = 10_000_000u128;
+	let para_id = ParaId::from(111);
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		OnDemand::credit_account(alice, initial_credit);
+		Balances::make_free_balance_be(&alice, 1_000_000u128);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			10_000u128,
+			200,
+			OrderRecurrence::Every { period: 3, count: 3 },
+		));
+
+		// Filled at 101, 104 and 107.
+		run_to_block(103, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 10_000);
+		run_to_block(104, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 20_000);
+		assert_eq!(
+			StandingOrders::<Test>::get(0).map(|order| order.recurrence),
+			Some(OrderRecurrence::Every { period: 3, count: 1 })
+		);
+		run_to_block(110, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 30_000);
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+	});
+}
+
+#[test]
+fn standing_order_checks_work() {
+	let alice = 1u64;
+	let bob = 2u64;
+	let para_id = ParaId::from(111);
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		run_to_block(10, |_| None);
+		OnDemand::credit_account(alice, 10_000u128);
+		Balances::make_free_balance_be(&alice, 1_000_000u128);
+
+		assert_noop!(
+			OnDemand::place_standing_order(
+				RuntimeOrigin::signed(alice),
+				para_id,
+				10_000u128,
+				10,
+				OrderRecurrence::Once,
+			),
+			Error::<Test>::InvalidExpiry
+		);
+		assert_noop!(
+			OnDemand::place_standing_order(
+				RuntimeOrigin::signed(alice),
+				para_id,
+				10_000u128,
+				20,
+				OrderRecurrence::Every { period: 0, count: 2 },
+			),
+			Error::<Test>::InvalidPeriod
+		);
+		assert_noop!(
+			OnDemand::place_standing_order(
+				RuntimeOrigin::signed(bob),
+				para_id,
+				10_000u128,
+				20,
+				OrderRecurrence::Once,
+			),
+			Error::<Test>::InsufficientCredits
+		);
+
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			10_000u128,
+			20,
+			OrderRecurrence::Once,
+		));
+		assert_noop!(
+			OnDemand::cancel_standing_order(RuntimeOrigin::signed(bob), 0),
+			Error::<Test>::NotStandingOrderOwner
+		);
+		assert_noop!(
+			OnDemand::cancel_standing_order(RuntimeOrigin::signed(alice), 1),
+			Error::<Test>::UnknownStandingOrder
+		);
+		assert_ok!(OnDemand::cancel_standing_order(RuntimeOrigin::signed(alice), 0));
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+	});
+}
+
+#[test]
+fn standing_orders_hold_a_deposit() {
+	let alice = 1u64;
+	let para_id = ParaId::from(111);
+	let deposit = <Test as Config>::StandingOrderDeposit::get();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		OnDemand::credit_account(alice, 10_000_000u128);
+		Balances::make_free_balance_be(&alice, deposit - 1);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		// The deposit has to be paid.
+		assert_noop!(
+			OnDemand::place_standing_order(
+				RuntimeOrigin::signed(alice),
+				para_id,
+				10_000u128,
+				120,
+				OrderRecurrence::Once,
+			),
+			TokenError::FundsUnavailable
+		);
+
+		let max_orders = <Test as Config>::MaxStandingOrdersPerAccount::get();
+		Balances::make_free_balance_be(&alice, 1_000_000u128);
+		for _ in 0..max_orders {
+			// Never filled.
+			assert_ok!(OnDemand::place_standing_order(
+				RuntimeOrigin::signed(alice),
+				para_id,
+				1u128,
+				120,
+				OrderRecurrence::Once,
+			));
+		}
+		assert_eq!(
+			Balances::balance_on_hold(&HoldReason::StandingOrderDeposit.into(), &alice),
+			deposit * max_orders as u128
+		);
+		assert_eq!(StandingOrdersOf::<Test>::get(alice), max_orders);
+		assert_noop!(
+			OnDemand::place_standing_order(
+				RuntimeOrigin::signed(alice),
+				para_id,
+				1u128,
+				120,
+				OrderRecurrence::Once,
+			),
+			Error::<Test>::TooManyAccountStandingOrders
+		);
+
+		// Deposits are returned on cancellation and expiry.
+		assert_ok!(OnDemand::cancel_standing_order(RuntimeOrigin::signed(alice), 0));
+		assert_eq!(Balances::total_balance_on_hold(&alice), deposit * (max_orders - 1) as u128);
+		run_to_block(125, |_| None);
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+		assert_eq!(Balances::total_balance_on_hold(&alice), 0);
+		assert!(!StandingOrdersOf::<Test>::contains_key(alice));
+
+		// And once the order is completed.
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			10_000u128,
+			130,
+			OrderRecurrence::Once,
+		));
+		assert_eq!(Balances::total_balance_on_hold(&alice), deposit);
+		run_to_block(126, |_| None);
+		System::assert_has_event(Event::<Test>::StandingOrderCompleted { id: max_orders }.into());
+		assert_eq!(Balances::total_balance_on_hold(&alice), 0);
+	});
+}
+
+#[test]
+fn standing_order_fills_are_bounded_per_block() {
+	let para_id = ParaId::from(111);
+	let max_fills = <Test as Config>::MaxStandingOrderFills::get();
+	let orders = max_fills + 2;
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		for owner in 0..orders as u64 {
+			OnDemand::credit_account(owner, 10_000_000u128);
+			Balances::make_free_balance_be(&owner, 1_000_000u128);
+			assert_ok!(OnDemand::place_standing_order(
+				RuntimeOrigin::signed(owner),
+				para_id,
+				1_000_000u128,
+				200,
+				OrderRecurrence::Once,
+			));
+		}
+
+		let filled = || {
+			(0..orders as u64)
+				.filter(|owner| Credits::<Test>::get(owner) < 10_000_000)
+				.count()
+		};
+
+		// Only `MaxStandingOrderFills` orders are looked at in a block.
+		run_to_block(101, |_| None);
+		assert_eq!(StandingOrders::<Test>::count(), orders - max_fills);
+		assert_eq!(filled(), max_fills as usize);
+
+		// The remaining orders get their turn in the next block.
+		run_to_block(102, |_| None);
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+		assert_eq!(filled(), orders as usize);
+	});
+}
+
+#[test]
+fn on_initialize_weight_accounts_for_bounded_fills() {
+	let para_id = ParaId::from(111);
+	let max_fills = <Test as Config>::MaxStandingOrderFills::get();
+	let orders = max_fills + 2;
+	let weight = <Test as Config>::WeightInfo::on_initialize;
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+		assert_eq!(OnDemand::on_initialize(100), weight(0));
+
+		for owner in 0..orders as u64 {
+			OnDemand::credit_account(owner, 10_000_000u128);
+			Balances::make_free_balance_be(&owner, 1_000_000u128);
+			assert_ok!(OnDemand::place_standing_order(
+				RuntimeOrigin::signed(owner),
+				para_id,
+				1_000_000u128,
+				200,
+				OrderRecurrence::Once,
+			));
+		}
+
+		// No more than `MaxStandingOrderFills` orders are looked at and paid for in a block.
+		System::set_block_number(101);
+		assert_eq!(OnDemand::on_initialize(101), weight(max_fills));
+		assert_eq!(StandingOrders::<Test>::count(), orders - max_fills);
+
+		System::set_block_number(102);
+		assert_eq!(OnDemand::on_initialize(102), weight(orders - max_fills));
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+	});
+}
+
+#[test]
+fn spend_cap_limits_orders() {
+	let alice = 1u64;
+	let initial_credit = 10_000_000u128;
+	let para_id = ParaId::from(111);
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		OnDemand::credit_account(alice, initial_credit);
+		Balances::make_free_balance_be(&alice, 1_000_000u128);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		assert_noop!(
+			OnDemand::set_spend_cap(RuntimeOrigin::signed(alice), Some(25_000u128), 0),
+			Error::<Test>::InvalidPeriod
+		);
+		assert_ok!(OnDemand::set_spend_cap(RuntimeOrigin::signed(alice), Some(25_000u128), 10));
+
+		assert_ok!(OnDemand::place_order_with_credits(
+			RuntimeOrigin::signed(alice),
+			10_000u128,
+			para_id
+		));
+		assert_ok!(OnDemand::place_order_with_credits(
+			RuntimeOrigin::signed(alice),
+			10_000u128,
+			para_id
+		));
+		assert_noop!(
+			OnDemand::place_order_with_credits(RuntimeOrigin::signed(alice), 10_000u128, para_id),
+			Error::<Test>::SpendCapExceeded
+		);
+
+		// Standing orders are held back by the cap as well.
+		assert_ok!(OnDemand::place_standing_order(
+			RuntimeOrigin::signed(alice),
+			para_id,
+			10_000u128,
+			120,
+			OrderRecurrence::Once,
+		));
+		run_to_block(109, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 20_000);
+		assert_eq!(StandingOrders::<Test>::count(), 1);
+
+		// A new period starts at block 110.
+		run_to_block(110, |_| None);
+		assert_eq!(Credits::<Test>::get(alice), initial_credit - 30_000);
+		assert_eq!(StandingOrders::<Test>::count(), 0);
+
+		// Removing the cap lifts the limit.
+		assert_ok!(OnDemand::set_spend_cap(RuntimeOrigin::signed(alice), None, 0));
+		assert!(SpendCaps::<Test>::get(alice).is_none());
+	});
+}
+
 #[test]
 fn pop_assignment_for_core_works() {
 	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
@@ -236,3 +236,49 @@ pub enum SpotTrafficCalculationErr {
 	/// Arithmetic error during division, either division by 0 or over/underflow.
 	Division,
 }
+
+/// Identifier of a standing order.
+pub type StandingOrderId = u32;
+
+/// How often a standing order is filled.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, PartialEq, Eq, Clone, Copy)]
+pub enum OrderRecurrence<BlockNumber> {
+	/// The order is removed once it has been filled.
+	Once,
+	/// The order is filled at most `count` times, with at least `period` blocks between two fills.
+	Every { period: BlockNumber, count: u32 },
+}
+
+/// An order which is kept around until the spot price drops to `max_price` or below.
+///
+/// Standing orders are paid for with the credits of their owner at the time they are filled.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct StandingOrder<AccountId, Balance, BlockNumber> {
+	/// The account that placed the order and pays for it.
+	pub owner: AccountId,
+	/// The para the order provides blockspace for.
+	pub para_id: ParaId,
+	/// The maximum spot price the owner is willing to pay for a single fill.
+	pub max_price: Balance,
+	/// The block from which the order is no longer filled and gets removed.
+	pub expires_at: BlockNumber,
+	/// How often the order is filled.
+	pub recurrence: OrderRecurrence<BlockNumber>,
+	/// The first block in which the order may be filled (again).
+	pub next_fill_at: BlockNumber,
+	/// The deposit held from the owner, returned once the order is removed.
+	pub deposit: Balance,
+}
+
+/// Limits how much an account can spend on on-demand orders within a period.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct SpendCap<Balance, BlockNumber> {
+	/// The maximum amount the account can spend within one period.
+	pub limit: Balance,
+	/// The length of a period in blocks.
+	pub period: BlockNumber,
+	/// The amount spent in the current period.
+	pub spent: Balance,
+	/// The block the current period started at.
+	pub period_start: BlockNumber,
+}
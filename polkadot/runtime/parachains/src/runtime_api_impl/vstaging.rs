@@ -16,9 +16,12 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::{configuration, initializer};
+use crate::{configuration, hrmp, initializer};
+use alloc::vec::Vec;
 use frame_system::pallet_prelude::*;
-use polkadot_primitives::{vstaging::async_backing::Constraints, Id as ParaId};
+use polkadot_primitives::{
+	vstaging::async_backing::Constraints, HrmpChannelId, Id as ParaId, SessionIndex,
+};
 
 /// Implementation for `constraints` function from the runtime API
 pub fn backing_constraints<T: initializer::Config>(
@@ -56,3 +59,8 @@ pub fn validation_code_bomb_limit<T: initializer::Config>() -> u32 {
 	configuration::ActiveConfig::<T>::get().max_code_size *
 		configuration::MAX_VALIDATION_CODE_COMPRESSION_RATIO
 }
+
+/// Implementation for `idle_hrmp_channels` function from the runtime API
+pub fn idle_hrmp_channels<T: initializer::Config>() -> Vec<(HrmpChannelId, SessionIndex)> {
+	hrmp::Pallet::<T>::idle_channels()
+}
@@ -139,6 +139,8 @@ fn default_parachains_host_configuration(
 			paras_availability_period: 4,
 			..Default::default()
 		},
+		// Sessions last an hour, channels without messages for a week can be closed.
+		hrmp_idle_channel_sessions: Some(7 * 24),
 		..Default::default()
 	}
 }
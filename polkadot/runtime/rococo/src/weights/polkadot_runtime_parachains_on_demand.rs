@@ -123,4 +123,96 @@ impl<T: frame_system::Config> polkadot_runtime_parachains::on_demand::WeightInfo
 			.saturating_add(T::DbWeight::get().writes(4))
 			.saturating_add(Weight::from_parts(0, 8).saturating_mul(s.into()))
 	}
+	/// Storage: `OnDemandAssignmentProvider::Credits` (r:1 w:0)
+	/// Proof: `OnDemandAssignmentProvider::Credits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::CounterForStandingOrders` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::CounterForStandingOrders` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrdersOf` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrdersOf` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `OnDemandAssignmentProvider::NextStandingOrderId` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::NextStandingOrderId` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrders` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn place_standing_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `320`
+		//  Estimated: `3785`
+		// Minimum execution time: 33_127_000 picoseconds.
+		Weight::from_parts(34_318_000, 0)
+			.saturating_add(Weight::from_parts(0, 3785))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: `OnDemandAssignmentProvider::StandingOrders` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `OnDemandAssignmentProvider::CounterForStandingOrders` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::CounterForStandingOrders` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrdersOf` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrdersOf` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn cancel_standing_order() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `352`
+		//  Estimated: `3817`
+		// Minimum execution time: 27_904_000 picoseconds.
+		Weight::from_parts(28_733_000, 0)
+			.saturating_add(Weight::from_parts(0, 3817))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	/// Storage: `OnDemandAssignmentProvider::SpendCaps` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::SpendCaps` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_spend_cap() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `3541`
+		// Minimum execution time: 11_874_000 picoseconds.
+		Weight::from_parts(12_275_000, 0)
+			.saturating_add(Weight::from_parts(0, 3541))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `OnDemandAssignmentProvider::Revenue` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::Revenue` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Configuration::ActiveConfig` (r:1 w:0)
+	/// Proof: `Configuration::ActiveConfig` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::QueueStatus` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::QueueStatus` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrderCursor` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrderCursor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrders` (r:26 w:25)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::SpendCaps` (r:25 w:25)
+	/// Proof: `OnDemandAssignmentProvider::SpendCaps` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::Credits` (r:25 w:25)
+	/// Proof: `OnDemandAssignmentProvider::Credits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::ParaIdAffinity` (r:1 w:0)
+	/// Proof: `OnDemandAssignmentProvider::ParaIdAffinity` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::FreeEntries` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::FreeEntries` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `OnDemandAssignmentProvider::CounterForStandingOrders` (r:1 w:1)
+	/// Proof: `OnDemandAssignmentProvider::CounterForStandingOrders` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:25 w:25)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `OnDemandAssignmentProvider::StandingOrdersOf` (r:25 w:25)
+	/// Proof: `OnDemandAssignmentProvider::StandingOrdersOf` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `s` is `[0, 25]`.
+	fn on_initialize(s: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `80_126 + s * (215 ±0)`
+		//  Estimated: `83_591 + s * (2603 ±0)`
+		// Minimum execution time: 12_613_000 picoseconds.
+		Weight::from_parts(16_204_118, 0)
+			.saturating_add(Weight::from_parts(0, 83591))
+			// Standard Error: 3_411
+			.saturating_add(Weight::from_parts(281_772_415, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(s.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(s.into()))
+	}
 }
@@ -605,6 +605,10 @@ parameter_types! {
 	// Keep 2 timeslices worth of revenue information.
 	pub const MaxHistoricalRevenue: BlockNumber = 2 * 5;
 	pub const OnDemandPalletId: PalletId = PalletId(*b"py/ondmd");
+	pub const OnDemandMaxStandingOrders: u32 = 100;
+	pub const OnDemandMaxStandingOrdersPerAccount: u32 = 10;
+	pub const OnDemandStandingOrderDeposit: Balance = 1 * DOLLARS;
+	pub const OnDemandMaxStandingOrderFills: u32 = 25;
 }
 
 impl parachains_dmp::Config for Runtime {}
@@ -629,10 +633,16 @@ impl parachains_hrmp::Config for Runtime {
 impl parachains_on_demand::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type Fungible = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type TrafficDefaultValue = OnDemandTrafficDefaultValue;
 	type WeightInfo = parachains_on_demand::TestWeightInfo;
 	type MaxHistoricalRevenue = MaxHistoricalRevenue;
 	type PalletId = OnDemandPalletId;
+	type MaxStandingOrders = OnDemandMaxStandingOrders;
+	type MaxStandingOrdersPerAccount = OnDemandMaxStandingOrdersPerAccount;
+	type StandingOrderDeposit = OnDemandStandingOrderDeposit;
+	type MaxStandingOrderFills = OnDemandMaxStandingOrderFills;
 }
 
 impl parachains_assigner_coretime::Config for Runtime {}
@@ -143,6 +143,8 @@ fn default_parachains_host_configuration(
 			..Default::default()
 		},
 		approval_voting_params: ApprovalVotingParams { max_approval_coalesce_count: 5 },
+		// Sessions last an hour, channels without messages for a week can be closed.
+		hrmp_idle_channel_sessions: Some(7 * 24),
 		..Default::default()
 	}
 }
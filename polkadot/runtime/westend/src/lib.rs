@@ -56,10 +56,11 @@ use polkadot_primitives::{
 		CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CoreState, ScrapedOnChainVotes,
 	},
 	AccountId, AccountIndex, ApprovalVotingParams, Balance, BlockNumber, CandidateHash, CoreIndex,
-	DisputeState, ExecutorParams, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
-	InboundHrmpMessage, Moment, NodeFeatures, Nonce, OccupiedCoreAssumption,
-	PersistedValidationData, PvfCheckStatement, SessionInfo, Signature, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature, PARACHAIN_KEY_TYPE_ID,
+	DisputeState, ExecutorParams, GroupRotationInfo, Hash, HrmpChannelId, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, Moment, NodeFeatures, Nonce,
+	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, SessionInfo, Signature,
+	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	PARACHAIN_KEY_TYPE_ID,
 };
 use polkadot_runtime_common::{
 	assigned_slots, auctions, crowdloan,
@@ -1376,15 +1377,26 @@ parameter_types! {
 	// Keep 2 timeslices worth of revenue information.
 	pub const MaxHistoricalRevenue: BlockNumber = 2 * TIMESLICE_PERIOD;
 	pub const OnDemandPalletId: PalletId = PalletId(*b"py/ondmd");
+	pub const OnDemandMaxStandingOrders: u32 = 100;
+	pub const OnDemandMaxStandingOrdersPerAccount: u32 = 10;
+	// A standing order takes up to 85 bytes of storage.
+	pub const OnDemandStandingOrderDeposit: Balance = deposit(1, 85);
+	pub const OnDemandMaxStandingOrderFills: u32 = 25;
 }
 
 impl parachains_on_demand::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type Fungible = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type TrafficDefaultValue = OnDemandTrafficDefaultValue;
 	type WeightInfo = weights::polkadot_runtime_parachains_on_demand::WeightInfo<Runtime>;
 	type MaxHistoricalRevenue = MaxHistoricalRevenue;
 	type PalletId = OnDemandPalletId;
+	type MaxStandingOrders = OnDemandMaxStandingOrders;
+	type MaxStandingOrdersPerAccount = OnDemandMaxStandingOrdersPerAccount;
+	type StandingOrderDeposit = OnDemandStandingOrderDeposit;
+	type MaxStandingOrderFills = OnDemandMaxStandingOrderFills;
 }
 
 impl parachains_assigner_coretime::Config for Runtime {}
@@ -1902,6 +1914,7 @@ pub mod migrations {
 			Runtime,
 			pallet_staking::migrations::v17::MigrateDisabledToSession<Runtime>,
 		>,
+		parachains_configuration::migration::v13::MigrateToV13<Runtime>,
 		// permanent
 		pallet_xcm::migration::MigrateToLatestXcmVersion<Runtime>,
 	);
@@ -2064,7 +2077,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(13)]
+	#[api_version(14)]
 	impl polkadot_primitives::runtime_api::ParachainHost<Block> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			parachains_runtime_api_impl::validators::<Runtime>()
@@ -2242,6 +2255,10 @@ sp_api::impl_runtime_apis! {
 		fn validation_code_bomb_limit() -> u32 {
 			parachains_staging_runtime_api_impl::validation_code_bomb_limit::<Runtime>()
 		}
+
+		fn idle_hrmp_channels() -> Vec<(HrmpChannelId, SessionIndex)> {
+			parachains_staging_runtime_api_impl::idle_hrmp_channels::<Runtime>()
+		}
 	}
 
 	#[api_version(5)]
@@ -92,9 +92,19 @@ pub enum Subcommand {
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Export a snapshot of the state and the latest blocks.
+	ExportSnapshot(sc_cli::ExportSnapshotCmd),
+
+	/// Bootstrap an empty database from a snapshot.
+	ImportSnapshot(sc_cli::ImportSnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
+	/// Database utilities.
+	#[command(subcommand)]
+	Db(sc_cli::DbSubcommand),
+
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
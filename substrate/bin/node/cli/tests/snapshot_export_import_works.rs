@@ -0,0 +1,89 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use std::{fs, path::Path, process::Command};
+use tempfile::tempdir;
+
+use substrate_cli_test_utils as common;
+
+/// Runs a `substrate-node` subcommand on the dev chain and returns its stdout.
+fn run(args: &[&str], database: &str, base_path: &Path) -> Result<Vec<u8>, String> {
+	let output = Command::new(cargo_bin("substrate-node"))
+		.args(args)
+		.args(["--dev", "--database", database, "-d"])
+		.arg(base_path)
+		.output()
+		.unwrap();
+	if output.status.success() {
+		Ok(output.stdout)
+	} else {
+		Err(String::from_utf8_lossy(&output.stderr).to_string())
+	}
+}
+
+async fn snapshot_export_import(database: &str) {
+	let base_path = tempdir().expect("could not create a temp dir");
+	let snapshot = base_path.path().join("snapshot");
+	let snapshot = snapshot.to_str().unwrap();
+
+	common::run_node_for_a_while(
+		base_path.path(),
+		&["--dev", "--database", database, "--no-hardware-benchmarks"],
+	)
+	.await;
+
+	// The state is exported at #2, #3 is executed on import.
+	run(&["export-snapshot", "--at", "3", "--blocks", "1", snapshot], database, base_path.path())
+		.unwrap();
+
+	let import_path = tempdir().expect("could not create a temp dir");
+	run(&["import-snapshot", snapshot], database, import_path.path()).unwrap();
+
+	// The best block of the imported database is #3, with the same header and state.
+	let block_3 = |path: &Path| run(&["export-blocks", "--from", "3", "--to", "3"], database, path);
+	assert_eq!(block_3(base_path.path()).unwrap(), block_3(import_path.path()).unwrap());
+	assert_eq!(
+		run(&["export-state", "3"], database, base_path.path()).unwrap(),
+		run(&["export-state"], database, import_path.path()).unwrap(),
+	);
+
+	// A snapshot with a modified state is rejected.
+	let mut data = fs::read(snapshot).unwrap();
+	let corrupted = base_path.path().join("corrupted");
+	let corrupted = corrupted.to_str().unwrap();
+	let middle = data.len() / 2;
+	data[middle] ^= 0xff;
+	fs::write(corrupted, &data).unwrap();
+	let import_path = tempdir().expect("could not create a temp dir");
+	assert!(run(&["import-snapshot", corrupted], database, import_path.path()).is_err());
+
+	// So is a truncated snapshot.
+	data.truncate(middle);
+	fs::write(corrupted, &data).unwrap();
+	let import_path = tempdir().expect("could not create a temp dir");
+	assert!(run(&["import-snapshot", corrupted], database, import_path.path()).is_err());
+}
+
+#[tokio::test]
+async fn snapshot_export_import_works() {
+	snapshot_export_import("rocksdb").await;
+	snapshot_export_import("paritydb").await;
+}
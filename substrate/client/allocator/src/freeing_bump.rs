@@ -582,31 +582,6 @@ impl FreeingBumpHeapAllocator {
 ///
 /// The linear memory can grow in size with the wasm page granularity (64KiB), but it cannot shrink.
 trait MemoryExt: Memory {
-	/// Read a u64 from the heap in LE form. Returns an error if any of the bytes read are out of
-	/// bounds.
-	fn read_le_u64(&self, ptr: u32) -> Result<u64, Error> {
-		self.with_access(|memory| {
-			let range =
-				heap_range(ptr, 8, memory.len()).ok_or_else(|| error("read out of heap bounds"))?;
-			let bytes = memory[range]
-				.try_into()
-				.expect("[u8] slice of length 8 must be convertible to [u8; 8]");
-			Ok(u64::from_le_bytes(bytes))
-		})
-	}
-
-	/// Write a u64 to the heap in LE form. Returns an error if any of the bytes written are out of
-	/// bounds.
-	fn write_le_u64(&mut self, ptr: u32, val: u64) -> Result<(), Error> {
-		self.with_access_mut(|memory| {
-			let range = heap_range(ptr, 8, memory.len())
-				.ok_or_else(|| error("write out of heap bounds"))?;
-			let bytes = val.to_le_bytes();
-			memory[range].copy_from_slice(&bytes[..]);
-			Ok(())
-		})
-	}
-
 	/// Returns the full size of the memory in bytes.
 	fn size(&self) -> u64 {
 		debug_assert!(self.pages() <= MAX_WASM_PAGES);
@@ -617,6 +592,24 @@ trait MemoryExt: Memory {
 
 impl<T: Memory> MemoryExt for T {}
 
+/// Read a u64 from the raw `memory` in LE form, see [`Memory::read_le_u64`].
+pub(crate) fn read_le_u64(memory: &[u8], ptr: u32) -> Result<u64, Error> {
+	let range = heap_range(ptr, 8, memory.len()).ok_or_else(|| error("read out of heap bounds"))?;
+	let bytes = memory[range]
+		.try_into()
+		.expect("[u8] slice of length 8 must be convertible to [u8; 8]");
+	Ok(u64::from_le_bytes(bytes))
+}
+
+/// Write a u64 to the raw `memory` in LE form, see [`Memory::write_le_u64`].
+pub(crate) fn write_le_u64(memory: &mut [u8], ptr: u32, val: u64) -> Result<(), Error> {
+	let range =
+		heap_range(ptr, 8, memory.len()).ok_or_else(|| error("write out of heap bounds"))?;
+	let bytes = val.to_le_bytes();
+	memory[range].copy_from_slice(&bytes[..]);
+	Ok(())
+}
+
 fn heap_range(offset: u32, length: u32, heap_len: usize) -> Option<Range<usize>> {
 	let start = offset as usize;
 	let end = offset.checked_add(length)? as usize;
@@ -982,7 +975,7 @@ mod tests {
 		mem.write_le_u64(40, 4480113).unwrap();
 
 		// then
-		let value = MemoryExt::read_le_u64(&mut mem, 40).unwrap();
+		let value = Memory::read_le_u64(&mem, 40).unwrap();
 		assert_eq!(value, 4480113);
 	}
 
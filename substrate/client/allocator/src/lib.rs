@@ -58,4 +58,20 @@ pub trait Memory {
 	///
 	/// If `None` is returned, there is no maximum (besides the maximum defined in the wasm spec).
 	fn max_pages(&self) -> Option<u32>;
+
+	/// Read a u64 from the memory in LE form. Returns an error if any of the bytes read are out of
+	/// bounds.
+	///
+	/// The allocator only accesses the memory through this method and [`Self::write_le_u64`].
+	/// Memories which can't grant access to the raw memory should override both, in which case
+	/// [`Self::with_access`] and [`Self::with_access_mut`] are never called.
+	fn read_le_u64(&self, ptr: u32) -> Result<u64, Error> {
+		self.with_access(|memory| freeing_bump::read_le_u64(memory, ptr))
+	}
+
+	/// Write a u64 to the memory in LE form. Returns an error if any of the bytes written are out
+	/// of bounds.
+	fn write_le_u64(&mut self, ptr: u32, val: u64) -> Result<(), Error> {
+		self.with_access_mut(|memory| freeing_bump::write_le_u64(memory, ptr, val))
+	}
 }
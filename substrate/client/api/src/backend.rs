@@ -635,6 +635,19 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 
 	/// Tells whether the backend requires full-sync mode.
 	fn requires_full_sync(&self) -> bool;
+
+	/// Writes already built state trie nodes straight to the state database.
+	///
+	/// Used to import a large state in batches ahead of the block it belongs to. The nodes are not
+	/// tracked for pruning and are only reachable once that block is imported with its root node.
+	fn import_state_nodes(
+		&self,
+		_nodes: BackendTransaction<HashingFor<Block>>,
+	) -> sp_blockchain::Result<()> {
+		Err(sp_blockchain::Error::Backend(
+			"Importing state nodes is not supported by this backend".into(),
+		))
+	}
 }
 
 /// Mark for all Backend implementations, that are making use of state data, stored locally.
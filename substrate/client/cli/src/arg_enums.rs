@@ -54,6 +54,9 @@ pub enum WasmExecutionMethod {
 	Interpreted,
 	/// Uses a compiled runtime.
 	Compiled,
+	/// Uses PolkaVM. Only runtimes compiled for PolkaVM can be executed.
+	#[clap(name = "polkavm")]
+	PolkaVm,
 }
 
 impl std::fmt::Display for WasmExecutionMethod {
@@ -61,6 +64,7 @@ impl std::fmt::Display for WasmExecutionMethod {
 		match self {
 			Self::Interpreted => write!(f, "Interpreted"),
 			Self::Compiled => write!(f, "Compiled"),
+			Self::PolkaVm => write!(f, "PolkaVM"),
 		}
 	}
 }
@@ -77,6 +81,10 @@ pub fn execution_method_from_cli(
 		);
 	}
 
+	if let WasmExecutionMethod::PolkaVm = execution_method {
+		return sc_service::config::WasmExecutionMethod::PolkaVm
+	}
+
 	sc_service::config::WasmExecutionMethod::Compiled {
 		instantiation_strategy: match instantiation_strategy {
 			WasmtimeInstantiationStrategy::PoolingCopyOnWrite =>
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	error,
+	params::{DatabaseParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use sc_service::DatabaseSource;
+use sp_runtime::traits::Block as BlockT;
+use std::path::PathBuf;
+
+/// The `db migrate` command used to move a RocksDB database to ParityDb.
+///
+/// The migration can be interrupted and continues where it stopped when the command is run
+/// again. The migration is one-way: ParityDb databases can not be migrated back, because ParityDb
+/// can not enumerate the state. The source database is left untouched.
+#[derive(Debug, Clone, Parser)]
+pub struct DbMigrateCmd {
+	/// Path of the target database.
+	///
+	/// Defaults to the path the node uses for ParityDb, so the node can be started with
+	/// `--database paritydb` once the migration is complete.
+	#[arg(long, value_name = "PATH")]
+	pub target_path: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DbMigrateCmd {
+	/// Run the migrate command
+	pub fn run<B: BlockT>(&self, source: DatabaseSource) -> error::Result<()> {
+		let source = match source {
+			#[cfg(feature = "rocksdb")]
+			DatabaseSource::RocksDb { .. } => source,
+			#[cfg(feature = "rocksdb")]
+			DatabaseSource::Auto { rocksdb_path, cache_size, .. } =>
+				DatabaseSource::RocksDb { path: rocksdb_path, cache_size },
+			_ => return Err(error::Error::Input(
+				"Only RocksDB databases can be migrated, migrating from ParityDb is not supported"
+					.into(),
+			)),
+		};
+		let config_dir = source
+			.path()
+			.and_then(|p| p.parent())
+			.and_then(|p| p.parent())
+			.ok_or_else(|| error::Error::Input("Cannot migrate custom database".into()))?;
+		let target = DatabaseSource::ParityDb {
+			path: self
+				.target_path
+				.clone()
+				.unwrap_or_else(|| config_dir.join("paritydb").join("full")),
+		};
+		if source.path() == target.path() {
+			return Err(error::Error::Input("Source and target database are the same".into()))
+		}
+
+		sc_client_db::migrate_database::<B>(&source, &target).map_err(Into::into)
+	}
+}
+
+impl CliConfiguration for DbMigrateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -18,7 +18,7 @@
 
 use crate::{error, error::Result};
 use clap::Args;
-use sc_service::config::KeystoreConfig;
+use sc_service::config::{KeystoreConfig, RemoteSignerEndpoint};
 use sp_core::crypto::SecretString;
 use std::{
 	fs,
@@ -56,6 +56,18 @@ pub struct KeystoreParams {
 		conflicts_with_all = &["password_interactive", "password"]
 	)]
 	pub password_filename: Option<PathBuf>,
+
+	/// Forward all key management and signing requests to a remote signer.
+	///
+	/// The signer is reached over a Unix socket (`unix:<PATH>`). No keys are stored locally when
+	/// this is set. Ring VRF signing, as used for Sassafras tickets, isn't supported by the
+	/// signer.
+	#[arg(
+		long,
+		value_name = "ENDPOINT",
+		conflicts_with_all = &["keystore_path", "password_interactive", "password", "password_filename"]
+	)]
+	pub keystore_remote: Option<RemoteSignerEndpoint>,
 }
 
 /// Parse a secret string, returning a displayable error.
@@ -66,6 +78,10 @@ pub fn secret_string_from_str(s: &str) -> std::result::Result<SecretString, Stri
 impl KeystoreParams {
 	/// Get the keystore configuration for the parameters
 	pub fn keystore_config(&self, config_dir: &Path) -> Result<KeystoreConfig> {
+		if let Some(ref endpoint) = self.keystore_remote {
+			return Ok(KeystoreConfig::Remote { endpoint: endpoint.clone() })
+		}
+
 		let password = if self.password_interactive {
 			Some(SecretString::new(input_keystore_password()?))
 		} else if let Some(ref file) = self.password_filename {
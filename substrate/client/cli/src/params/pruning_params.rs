@@ -18,7 +18,7 @@
 
 use crate::error;
 use clap::Args;
-use sc_service::{BlocksPruning, PruningMode};
+use sc_service::{BlocksPruning, MixedConstraints, PruningMode};
 
 /// Parameters to define the pruning mode
 #[derive(Debug, Clone, Args)]
@@ -45,6 +45,22 @@ pub struct PruningParams {
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
 	pub state_pruning: Option<DatabasePruningMode>,
 
+	/// Keep the history of all the top trie keys starting with the given hex encoded prefix.
+	///
+	/// The state of all other keys is pruned according to `--state-pruning`, which has to be
+	/// `NUMBER` in this case. The archived keys can still be queried at blocks that left the
+	/// pruning window, while querying any other key at these blocks fails. Can be passed
+	/// multiple times. Like the pruning mode, the archived
+	/// prefixes can only be set on the first creation of the database.
+	#[arg(long, value_name = "HEX_PREFIX", value_parser = parse_hex_prefix)]
+	pub state_pruning_archive_prefix: Vec<Vec<u8>>,
+
+	/// Keep the history of the default child trie with the given hex encoded storage key.
+	///
+	/// See `--state-pruning-archive-prefix`.
+	#[arg(long, value_name = "HEX_STORAGE_KEY", value_parser = parse_hex_prefix)]
+	pub state_pruning_archive_child: Vec<Vec<u8>>,
+
 	/// Specify the blocks pruning mode.
 	///
 	/// This mode specifies when the block's body (including justifications)
@@ -69,7 +85,30 @@ pub struct PruningParams {
 impl PruningParams {
 	/// Get the pruning value from the parameters
 	pub fn state_pruning(&self) -> error::Result<Option<PruningMode>> {
-		Ok(self.state_pruning.map(|v| v.into()))
+		if self.state_pruning_archive_prefix.is_empty() &&
+			self.state_pruning_archive_child.is_empty()
+		{
+			return Ok(self.state_pruning.map(|v| v.into()))
+		}
+
+		let mut mixed = MixedConstraints::default();
+		match self.state_pruning {
+			None => {},
+			Some(DatabasePruningMode::Custom(n)) => mixed.constraints.max_blocks = Some(n),
+			Some(_) =>
+				return Err(error::Error::Input(
+					"Archived state prefixes require `--state-pruning` to be a number of blocks"
+						.into(),
+				)),
+		}
+		for prefix in &self.state_pruning_archive_prefix {
+			mixed.archive_prefix(prefix.clone());
+		}
+		for storage_key in &self.state_pruning_archive_child {
+			mixed.archive_child_trie(storage_key);
+		}
+
+		Ok(Some(PruningMode::Mixed(mixed)))
 	}
 
 	/// Get the block pruning value from the parameters
@@ -78,6 +117,10 @@ impl PruningParams {
 	}
 }
 
+fn parse_hex_prefix(s: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(s).map_err(|e| format!("Invalid hex prefix {s:?}: {e:?}"))
+}
+
 /// Specifies the pruning mode of the database.
 ///
 /// This specifies when the block's data (either state via `--state-pruning`
@@ -162,4 +205,27 @@ mod tests {
 		assert!(matches!(dbg!(pruning.state_pruning), Some(DatabasePruningMode::ArchiveCanonical)));
 		assert!(matches!(pruning.blocks_pruning, DatabasePruningMode::ArchiveCanonical));
 	}
+
+	#[test]
+	fn archived_prefixes_parse_works() {
+		let Cli { pruning } = Cli::parse_from([
+			"",
+			"--state-pruning=1000",
+			"--state-pruning-archive-prefix=0x1234",
+			"--state-pruning-archive-child=abcd",
+		]);
+
+		let mut expected = MixedConstraints::default();
+		expected.constraints.max_blocks = Some(1000);
+		expected.archive_prefix(vec![0x12, 0x34]);
+		expected.archive_child_trie(&[0xab, 0xcd]);
+		assert_eq!(pruning.state_pruning().unwrap(), Some(PruningMode::Mixed(expected)));
+
+		let Cli { pruning } = Cli::parse_from([
+			"",
+			"--state-pruning=archive",
+			"--state-pruning-archive-prefix=0x1234",
+		]);
+		assert!(pruning.state_pruning().is_err());
+	}
 }
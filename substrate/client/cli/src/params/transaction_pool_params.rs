@@ -17,7 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::{Args, ValueEnum};
-use sc_transaction_pool::TransactionPoolOptions;
+use sc_transaction_pool::{SenderLimits, TransactionPoolOptions};
+use sp_runtime::Percent;
+use std::path::PathBuf;
 
 /// Type of transaction pool to be used
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -59,6 +61,35 @@ pub struct TransactionPoolParams {
 	/// The type of transaction pool to be instantiated.
 	#[arg(long, value_enum, default_value_t = TransactionPoolType::SingleState)]
 	pub pool_type: TransactionPoolType,
+
+	/// Maximum number of ready transactions of a single sender.
+	///
+	/// Only enforced if the node identifies the senders of transactions. Not limited by default.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_sender_ready_limit: Option<usize>,
+
+	/// Maximum number of future transactions of a single sender.
+	///
+	/// Only enforced if the node identifies the senders of transactions. Not limited by default.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_sender_future_limit: Option<usize>,
+
+	/// Minimal priority increase, in percent, required to replace a transaction providing the
+	/// same tag.
+	#[arg(
+		long,
+		value_name = "PERCENT",
+		default_value_t = 0,
+		value_parser = clap::value_parser!(u8).range(0..=100)
+	)]
+	pub pool_replacement_bump: u8,
+
+	/// Path of the file used to persist the transaction pool contents across restarts.
+	///
+	/// The pool contents are periodically written to this file and on shutdown. On startup
+	/// the stored transactions are revalidated and resubmitted to the pool. Disabled by default.
+	#[arg(long, value_name = "PATH")]
+	pub pool_journal: Option<PathBuf>,
 }
 
 impl TransactionPoolParams {
@@ -71,5 +102,11 @@ impl TransactionPoolParams {
 			self.pool_type.into(),
 			is_dev,
 		)
+		.with_sender_limits(SenderLimits {
+			ready: self.pool_sender_ready_limit,
+			future: self.pool_sender_future_limit,
+		})
+		.with_replacement_priority_bump(Percent::from_percent(self.pool_replacement_bump))
+		.with_journal(self.pool_journal.clone())
 	}
 }
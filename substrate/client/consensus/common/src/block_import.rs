@@ -126,13 +126,29 @@ pub enum StorageChanges<Block: BlockT> {
 	Import(ImportedState<Block>),
 }
 
-/// Imported state data. A vector of key-value pairs that should form a trie.
+/// Imported state data.
 #[derive(PartialEq, Eq, Clone)]
 pub struct ImportedState<B: BlockT> {
 	/// Target block hash.
 	pub block: B::Hash,
-	/// State keys and values.
-	pub state: sp_state_machine::KeyValueStates,
+	/// State of the target block.
+	pub state: ImportedStateData<B>,
+}
+
+/// State of an imported block.
+#[derive(PartialEq, Eq, Clone)]
+pub enum ImportedStateData<B: BlockT> {
+	/// State keys and values that should form a trie.
+	KeyValues(sp_state_machine::KeyValueStates),
+	/// Nodes of the already built state trie, the nodes of child tries are stored in the
+	/// keyspace of their trie.
+	Trie(sp_state_machine::BackendTransaction<HashingFor<B>>),
+}
+
+impl<B: BlockT> From<sp_state_machine::KeyValueStates> for ImportedStateData<B> {
+	fn from(state: sp_state_machine::KeyValueStates) -> Self {
+		Self::KeyValues(state)
+	}
 }
 
 impl<B: BlockT> std::fmt::Debug for ImportedState<B> {
@@ -24,8 +24,8 @@ pub mod metrics;
 
 pub use block_import::{
 	BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
-	ImportedAux, ImportedState, JustificationImport, JustificationSyncLink, StateAction,
-	StorageChanges,
+	ImportedAux, ImportedState, ImportedStateData, JustificationImport, JustificationSyncLink,
+	StateAction, StorageChanges,
 };
 pub use import_queue::{
 	import_single_block, BasicQueue, BlockImportError, BlockImportStatus, BoxBlockImport,
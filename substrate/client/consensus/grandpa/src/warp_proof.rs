@@ -301,25 +301,7 @@ where
 		set_id: SetId,
 		authorities: AuthorityList,
 	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
-		let EncodedProof(proof) = proof;
-		let proof = WarpSyncProof::<Block>::decode_all(&mut proof.as_slice())
-			.map_err(|e| format!("Proof decoding error: {:?}", e))?;
-		let last_header = proof
-			.proofs
-			.last()
-			.map(|p| p.header.clone())
-			.ok_or_else(|| "Empty proof".to_string())?;
-		let (next_set_id, next_authorities) =
-			proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
-		if proof.is_finished {
-			Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
-		} else {
-			Ok(VerificationResult::<Block>::Partial(
-				next_set_id,
-				next_authorities,
-				last_header.hash(),
-			))
-		}
+		verify_encoded_proof(proof, set_id, authorities, &self.hard_forks)
 	}
 
 	fn current_authorities(&self) -> AuthorityList {
@@ -327,17 +309,97 @@ where
 	}
 }
 
+/// Verifies warp sync proofs of a chain without having access to the chain.
+///
+/// Proofs are verified starting at the authority set of the genesis block, so they can be
+/// verified by nodes that don't import the chain, e.g. to prove the finality of a relay chain
+/// block to a parachain node. Generating proofs is not supported.
+pub struct WarpProofVerifier<Block: BlockT> {
+	genesis_authorities: AuthorityList,
+	hard_forks: HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+}
+
+impl<Block: BlockT> WarpProofVerifier<Block> {
+	/// Create a new instance for the given authorities of the genesis block and authority set
+	/// hard forks of the chain.
+	pub fn new(
+		genesis_authorities: AuthorityList,
+		hard_forks: Vec<AuthoritySetHardFork<Block>>,
+	) -> Self {
+		WarpProofVerifier {
+			genesis_authorities,
+			hard_forks: hard_forks
+				.into_iter()
+				.map(|fork| (fork.block, (fork.set_id, fork.authorities)))
+				.collect(),
+		}
+	}
+}
+
+impl<Block: BlockT> WarpSyncProvider<Block> for WarpProofVerifier<Block>
+where
+	NumberFor<Block>: BlockNumberOps,
+{
+	fn generate(
+		&self,
+		_start: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		Err("Generating warp proofs is not supported".into())
+	}
+
+	fn verify(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		verify_encoded_proof(proof, set_id, authorities, &self.hard_forks)
+	}
+
+	fn current_authorities(&self) -> AuthorityList {
+		self.genesis_authorities.clone()
+	}
+}
+
+fn verify_encoded_proof<Block: BlockT>(
+	proof: &EncodedProof,
+	set_id: SetId,
+	authorities: AuthorityList,
+	hard_forks: &HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>>
+where
+	NumberFor<Block>: BlockNumberOps,
+{
+	let EncodedProof(proof) = proof;
+	let proof = WarpSyncProof::<Block>::decode_all(&mut proof.as_slice())
+		.map_err(|e| format!("Proof decoding error: {:?}", e))?;
+	let last_header = proof
+		.proofs
+		.last()
+		.map(|p| p.header.clone())
+		.ok_or_else(|| "Empty proof".to_string())?;
+	let (next_set_id, next_authorities) =
+		proof.verify(set_id, authorities, hard_forks).map_err(Box::new)?;
+	if proof.is_finished {
+		Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
+	} else {
+		Ok(VerificationResult::<Block>::Partial(next_set_id, next_authorities, last_header.hash()))
+	}
+}
+
 #[cfg(test)]
 mod tests {
-	use super::WarpSyncProof;
+	use super::{WarpProofVerifier, WarpSyncProof};
 	use crate::{AuthoritySetChanges, GrandpaJustification};
 	use codec::Encode;
 	use rand::prelude::*;
 	use sc_block_builder::BlockBuilderBuilder;
+	use sc_network_sync::strategy::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
 	use sp_blockchain::HeaderBackend;
 	use sp_consensus::BlockOrigin;
 	use sp_consensus_grandpa::GRANDPA_ENGINE_ID;
 	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::traits::Header as _;
 	use std::sync::Arc;
 	use substrate_test_runtime_client::{
 		BlockBuilderExt, ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt,
@@ -446,8 +508,9 @@ mod tests {
 			WarpSyncProof::generate(&*backend, genesis_hash, &authority_set_changes).unwrap();
 
 		// verifying the proof should yield the last set id and authorities
-		let (new_set_id, new_authorities) =
-			warp_sync_proof.verify(0, genesis_authorities, &Default::default()).unwrap();
+		let (new_set_id, new_authorities) = warp_sync_proof
+			.verify(0, genesis_authorities.clone(), &Default::default())
+			.unwrap();
 
 		let expected_authorities = current_authorities
 			.iter()
@@ -456,5 +519,20 @@ mod tests {
 
 		assert_eq!(new_set_id, current_set_id);
 		assert_eq!(new_authorities, expected_authorities);
+
+		// the proof can also be verified without access to the chain
+		let verifier = WarpProofVerifier::new(genesis_authorities.clone(), Vec::new());
+		assert_eq!(verifier.current_authorities(), genesis_authorities);
+		assert!(verifier.generate(genesis_hash).is_err());
+
+		let proof = EncodedProof(warp_sync_proof.encode());
+		match verifier.verify(&proof, 0, verifier.current_authorities()).unwrap() {
+			VerificationResult::Complete(set_id, authorities, header) => {
+				assert_eq!(set_id, current_set_id);
+				assert_eq!(authorities, expected_authorities);
+				assert_eq!(header.hash(), client.info().finalized_hash);
+			},
+			VerificationResult::Partial(..) => panic!("The proof is complete"),
+		}
 	}
 }
@@ -0,0 +1,199 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras authority selection, slot claiming and tickets generation.
+
+use super::{Epoch, LOG_TARGET};
+use codec::Encode;
+use log::{debug, trace};
+use sc_consensus_epochs::Epoch as EpochT;
+use sp_application_crypto::AppCrypto;
+use sp_consensus_sassafras::{
+	digests::SlotClaim,
+	ticket_id_threshold,
+	vrf::{self, RingContext},
+	AuthorityId, AuthorityIndex, Randomness, Slot, TicketBody, TicketClaim, TicketEnvelope,
+	TicketId,
+};
+use sp_core::{
+	bandersnatch,
+	crypto::{ByteArray, Pair},
+	ed25519, U256,
+};
+use sp_crypto_hashing::blake2_256;
+use sp_keystore::KeystorePtr;
+
+/// Get the index of the authority expected to claim the given slot when no ticket
+/// has been assigned to it. This should always assign the slot to some authority
+/// unless the authorities list is empty.
+pub(super) fn fallback_authority_index(slot: Slot, epoch: &Epoch) -> Option<AuthorityIndex> {
+	if epoch.authorities.is_empty() {
+		return None
+	}
+
+	let rand = U256::from_big_endian(&(epoch.randomness, slot).using_encoded(blake2_256));
+	let idx = rand % U256::from(epoch.authorities.len());
+
+	Some(idx.as_u32())
+}
+
+/// Data signed with the ticket's erased key to prove the ticket ownership
+/// when claiming a slot.
+pub(super) fn ticket_claim_data(randomness: &Randomness, slot: Slot, epoch_index: u64) -> Vec<u8> {
+	(b"sassafras-ticket-claim", randomness, slot, epoch_index).encode()
+}
+
+/// Try to claim the given slot, returning the slot claim to use when authoring
+/// the block together with the claiming authority, or `None` if it is not our
+/// turn to propose.
+///
+/// If a ticket has been assigned to the slot, the slot can only be claimed by the
+/// ticket owner. Otherwise the slot falls back to the authority selected via
+/// [`fallback_authority_index`].
+pub(super) fn claim_slot(
+	slot: Slot,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+	keystore: &KeystorePtr,
+) -> Option<(SlotClaim, AuthorityId)> {
+	if epoch.authorities.is_empty() {
+		return None
+	}
+
+	let mut epoch_index = epoch.index;
+	if epoch.end_slot() <= slot {
+		// Slot doesn't strictly belong to the epoch, create a clone with fixed values.
+		epoch_index = epoch.clone_for_slot(slot).index;
+	}
+
+	let (authority_idx, ticket_claim) = match maybe_ticket {
+		Some((ticket_id, _)) => {
+			trace!(target: LOG_TARGET, "Slot {} has ticket {:032x}", slot, ticket_id);
+			// If the ticket wasn't generated by us the lookup fails and we are done.
+			let (authority_idx, erased_seed) = epoch.tickets_aux.get(&ticket_id)?;
+			let erased_pair = ed25519::Pair::from_seed(erased_seed);
+			let data = ticket_claim_data(&epoch.randomness, slot, epoch_index);
+			let erased_signature = erased_pair.sign(&data);
+			(*authority_idx, Some(TicketClaim { erased_signature }))
+		},
+		None => (fallback_authority_index(slot, epoch)?, None),
+	};
+
+	let authority_id = epoch.authorities.get(authority_idx as usize)?;
+
+	let data = vrf::slot_claim_sign_data(&epoch.randomness, slot, epoch_index);
+	let vrf_signature = keystore
+		.bandersnatch_vrf_sign(AuthorityId::ID, authority_id.as_ref(), &data)
+		.ok()
+		.flatten()?;
+
+	let claim = SlotClaim { authority_idx, slot, vrf_signature, ticket_claim };
+
+	Some((claim, authority_id.clone()))
+}
+
+/// Generate the tickets of the given epoch for all the epoch authorities whose keys
+/// are found in the keystore.
+///
+/// Only tickets with an identifier below the epoch threshold are returned. The
+/// secrets required to later claim the slots assigned to them are recorded in the
+/// epoch `tickets_aux`.
+pub(super) fn generate_epoch_tickets(
+	epoch: &mut Epoch,
+	keystore: &KeystorePtr,
+	ring_context: &RingContext,
+) -> Vec<TicketEnvelope> {
+	let mut tickets = Vec::new();
+
+	let threshold = ticket_id_threshold(
+		epoch.config.redundancy_factor,
+		epoch.length,
+		epoch.config.attempts_number,
+		epoch.authorities.len() as u32,
+	);
+	debug!(
+		target: LOG_TARGET,
+		"Generating tickets for epoch {} @ slot {} (threshold: {:032x})",
+		epoch.index,
+		epoch.start,
+		threshold,
+	);
+
+	let ring: Vec<bandersnatch::Public> =
+		epoch.authorities.iter().map(|authority_id| *authority_id.as_ref()).collect();
+
+	for (authority_idx, authority_id) in epoch.authorities.iter().enumerate() {
+		if !keystore.has_keys(&[(authority_id.to_raw_vec(), AuthorityId::ID)]) {
+			continue
+		}
+
+		let prover = ring_context.prover(&ring, authority_idx);
+
+		for attempt_idx in 0..epoch.config.attempts_number {
+			let ticket_id_input = vrf::ticket_id_input(&epoch.randomness, attempt_idx, epoch.index);
+
+			let Some(pre_output) = keystore
+				.bandersnatch_vrf_pre_output(
+					AuthorityId::ID,
+					authority_id.as_ref(),
+					&ticket_id_input,
+				)
+				.ok()
+				.flatten()
+			else {
+				continue
+			};
+
+			let ticket_id = vrf::make_ticket_id(&pre_output);
+			if ticket_id >= threshold {
+				continue
+			}
+
+			let (erased_pair, erased_seed) = ed25519::Pair::generate();
+			// The revealed key is bound to the ticket VRF output, which is known only to
+			// the ticket owner (the ticket identifier uses just a part of it).
+			let revealed_seed =
+				(b"sassafras-revealed-key", pre_output.make_bytes()).using_encoded(blake2_256);
+			let revealed_pair = ed25519::Pair::from_seed(&revealed_seed);
+
+			let body = TicketBody {
+				attempt_idx,
+				erased_public: erased_pair.public(),
+				revealed_public: revealed_pair.public(),
+			};
+
+			let data = vrf::ticket_body_sign_data(&body, ticket_id_input);
+			let Some(signature) = keystore
+				.bandersnatch_ring_vrf_sign(AuthorityId::ID, authority_id.as_ref(), &data, &prover)
+				.ok()
+				.flatten()
+			else {
+				continue
+			};
+
+			trace!(target: LOG_TARGET, "Generated ticket {:032x} (attempt {})", ticket_id, attempt_idx);
+
+			tickets.push(TicketEnvelope { body, signature });
+			epoch
+				.tickets_aux
+				.insert(ticket_id, (authority_idx as AuthorityIndex, erased_seed));
+		}
+	}
+
+	tickets
+}
@@ -0,0 +1,922 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras testsuite
+
+use super::*;
+use authorship::{claim_slot, fallback_authority_index, generate_epoch_tickets};
+use parking_lot::Mutex;
+use sc_block_builder::{BlockBuilder, BlockBuilderBuilder};
+use sc_client_api::{
+	FinalityNotifications, ImportNotifications, OnFinalityAction, OnImportAction,
+	StorageEventStream, StorageKey,
+};
+use sc_consensus::BoxBlockImport;
+use sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging;
+use sc_network_test::{Block as TestBlock, *};
+use sc_transaction_pool_api::RejectAllTxPool;
+use sp_api::ApiRef;
+use sp_blockchain::{CachedHeaderMetadata, Info};
+use sp_consensus::{DisableProofRecording, NoNetwork as DummyOracle, Proposal};
+use sp_consensus_sassafras::{vrf::RingContext, EquivocationProof, OpaqueKeyOwnershipProof};
+use sp_core::{bandersnatch, crypto::Pair, ed25519};
+use sp_crypto_hashing::blake2_256;
+use sp_inherents::{CheckInherentsResult, InherentIdentifier};
+use sp_keystore::{testing::MemoryKeystore, Keystore};
+use sp_runtime::{
+	generic::{Digest, DigestItem},
+	traits::Header as _,
+	ApplyExtrinsicResult,
+};
+use sp_timestamp::Timestamp;
+use std::cell::RefCell;
+use substrate_test_runtime_client::runtime::Extrinsic;
+use verification::{check_header, VerificationParams};
+
+type TestHeader = <TestBlock as BlockT>::Header;
+
+type TestSelectChain =
+	substrate_test_runtime_client::LongestChain<substrate_test_runtime_client::Backend, TestBlock>;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Stage {
+	PreSeal,
+	PostSeal,
+}
+
+type Mutator = Arc<dyn Fn(&mut TestHeader, Stage) + Send + Sync>;
+
+type SassafrasBlockImport = PanickingBlockImport<
+	crate::SassafrasBlockImport<TestBlock, SassafrasTestClient, Arc<PeersFullClient>>,
+>;
+
+const SLOT_DURATION_MS: u64 = 1000;
+
+const EPOCH_LENGTH: u32 = 6;
+
+const AUTHORITIES: [&str; 3] = ["//Alice", "//Bob", "//Charlie"];
+
+fn authority_id(seed: &str) -> AuthorityId {
+	AuthorityPair::from_string(seed, None).expect("Valid authority seed").public()
+}
+
+fn create_keystore(seed: &str) -> KeystorePtr {
+	let keystore = MemoryKeystore::new();
+	keystore
+		.bandersnatch_generate_new(AuthorityId::ID, Some(seed))
+		.expect("Generates authority key");
+	keystore.into()
+}
+
+fn test_config() -> SassafrasConfiguration {
+	SassafrasConfiguration {
+		slot_duration: SlotDuration::from_millis(SLOT_DURATION_MS),
+		genesis_epoch: sp_consensus_sassafras::Epoch {
+			index: 0,
+			start: 0.into(),
+			length: EPOCH_LENGTH,
+			randomness: [0; 32],
+			authorities: AUTHORITIES.iter().map(|seed| authority_id(seed)).collect(),
+			config: EpochConfiguration { redundancy_factor: 2, attempts_number: 4 },
+		},
+	}
+}
+
+#[derive(Clone)]
+struct TestApi {
+	genesis_epoch: sp_consensus_sassafras::Epoch,
+}
+
+// compiler gets confused and warns us about unused inner
+#[allow(dead_code)]
+struct RuntimeApi {
+	inner: TestApi,
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl SassafrasApi<TestBlock> for RuntimeApi {
+		fn ring_context() -> Option<RingContext> {
+			None
+		}
+
+		fn submit_tickets_unsigned_extrinsic(_tickets: Vec<TicketEnvelope>) -> bool {
+			false
+		}
+
+		fn slot_ticket_id(_slot: Slot) -> Option<TicketId> {
+			None
+		}
+
+		fn slot_ticket(_slot: Slot) -> Option<(TicketId, TicketBody)> {
+			None
+		}
+
+		fn current_epoch() -> sp_consensus_sassafras::Epoch {
+			self.inner.genesis_epoch.clone()
+		}
+
+		fn next_epoch() -> sp_consensus_sassafras::Epoch {
+			self.inner.genesis_epoch.clone()
+		}
+
+		fn generate_key_ownership_proof(
+			_authority_id: AuthorityId,
+		) -> Option<OpaqueKeyOwnershipProof> {
+			None
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			_equivocation_proof: EquivocationProof<TestHeader>,
+			_key_owner_proof: OpaqueKeyOwnershipProof,
+		) -> bool {
+			false
+		}
+	}
+
+	impl sp_block_builder::BlockBuilder<TestBlock> for RuntimeApi {
+		fn apply_extrinsic(_extrinsic: Extrinsic) -> ApplyExtrinsicResult {
+			unimplemented!("Not required for testing!")
+		}
+
+		fn finalize_block() -> TestHeader {
+			unimplemented!("Not required for testing!")
+		}
+
+		fn inherent_extrinsics(_data: InherentData) -> Vec<Extrinsic> {
+			unimplemented!("Not required for testing!")
+		}
+
+		fn check_inherents(_block: TestBlock, _data: InherentData) -> CheckInherentsResult {
+			CheckInherentsResult::new()
+		}
+	}
+}
+
+/// The test runtime doesn't implement the Sassafras runtime API.
+///
+/// This client serves the runtime API calls using a mock and delegates everything
+/// else to the inner test client.
+struct SassafrasTestClient {
+	inner: Arc<PeersFullClient>,
+	api: TestApi,
+}
+
+impl SassafrasTestClient {
+	fn new(inner: Arc<PeersFullClient>) -> Self {
+		SassafrasTestClient { inner, api: TestApi { genesis_epoch: test_config().genesis_epoch } }
+	}
+}
+
+impl ProvideRuntimeApi<TestBlock> for SassafrasTestClient {
+	type Api = RuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		RuntimeApi { inner: self.api.clone() }.into()
+	}
+}
+
+impl HeaderBackend<TestBlock> for SassafrasTestClient {
+	fn header(&self, hash: Hash) -> ClientResult<Option<TestHeader>> {
+		HeaderBackend::header(&*self.inner, hash)
+	}
+
+	fn info(&self) -> Info<TestBlock> {
+		HeaderBackend::info(&*self.inner)
+	}
+
+	fn status(&self, hash: Hash) -> ClientResult<BlockStatus> {
+		HeaderBackend::status(&*self.inner, hash)
+	}
+
+	fn number(&self, hash: Hash) -> ClientResult<Option<NumberFor<TestBlock>>> {
+		HeaderBackend::number(&*self.inner, hash)
+	}
+
+	fn hash(&self, number: NumberFor<TestBlock>) -> ClientResult<Option<Hash>> {
+		HeaderBackend::hash(&*self.inner, number)
+	}
+}
+
+impl HeaderMetadata<TestBlock> for SassafrasTestClient {
+	type Error = ClientError;
+
+	fn header_metadata(&self, hash: Hash) -> ClientResult<CachedHeaderMetadata<TestBlock>> {
+		self.inner.header_metadata(hash)
+	}
+
+	fn insert_header_metadata(&self, hash: Hash, metadata: CachedHeaderMetadata<TestBlock>) {
+		self.inner.insert_header_metadata(hash, metadata)
+	}
+
+	fn remove_header_metadata(&self, hash: Hash) {
+		self.inner.remove_header_metadata(hash)
+	}
+}
+
+impl AuxStore for SassafrasTestClient {
+	fn insert_aux<
+		'a,
+		'b: 'a,
+		'c: 'a,
+		I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+		D: IntoIterator<Item = &'a &'b [u8]>,
+	>(
+		&self,
+		insert: I,
+		delete: D,
+	) -> ClientResult<()> {
+		self.inner.insert_aux(insert, delete)
+	}
+
+	fn get_aux(&self, key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		self.inner.get_aux(key)
+	}
+}
+
+impl PreCommitActions<TestBlock> for SassafrasTestClient {
+	fn register_import_action(&self, op: OnImportAction<TestBlock>) {
+		self.inner.register_import_action(op)
+	}
+
+	fn register_finality_action(&self, op: OnFinalityAction<TestBlock>) {
+		self.inner.register_finality_action(op)
+	}
+}
+
+impl BlockchainEvents<TestBlock> for SassafrasTestClient {
+	fn import_notification_stream(&self) -> ImportNotifications<TestBlock> {
+		self.inner.import_notification_stream()
+	}
+
+	fn every_import_notification_stream(&self) -> ImportNotifications<TestBlock> {
+		self.inner.every_import_notification_stream()
+	}
+
+	fn finality_notification_stream(&self) -> FinalityNotifications<TestBlock> {
+		self.inner.finality_notification_stream()
+	}
+
+	fn storage_changes_notification_stream(
+		&self,
+		filter_keys: Option<&[StorageKey]>,
+		child_filter_keys: Option<&[(StorageKey, Option<Vec<StorageKey>>)]>,
+	) -> ClientResult<StorageEventStream<Hash>> {
+		self.inner.storage_changes_notification_stream(filter_keys, child_filter_keys)
+	}
+}
+
+/// Inherent data provider only exposing the slot to the slot worker.
+struct SlotProvider(Slot);
+
+impl Deref for SlotProvider {
+	type Target = Slot;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+#[async_trait::async_trait]
+impl InherentDataProvider for SlotProvider {
+	async fn provide_inherent_data(
+		&self,
+		_inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		Ok(())
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		None
+	}
+}
+
+#[derive(Clone)]
+struct DummyFactory {
+	client: Arc<PeersFullClient>,
+	epoch_changes: SharedEpochChanges<TestBlock, Epoch>,
+	config: SassafrasConfiguration,
+	mutator: Mutator,
+}
+
+struct DummyProposer {
+	factory: DummyFactory,
+	parent_header: TestHeader,
+}
+
+impl Environment<TestBlock> for DummyFactory {
+	type CreateProposer = future::Ready<Result<DummyProposer, ClientError>>;
+	type Proposer = DummyProposer;
+	type Error = ClientError;
+
+	fn init(&mut self, parent_header: &TestHeader) -> Self::CreateProposer {
+		future::ready(Ok(DummyProposer {
+			factory: self.clone(),
+			parent_header: parent_header.clone(),
+		}))
+	}
+}
+
+impl DummyProposer {
+	fn propose_with(
+		&mut self,
+		mut pre_digests: Digest,
+	) -> future::Ready<Result<Proposal<TestBlock, ()>, ClientError>> {
+		let parent_hash = self.parent_header.hash();
+		let parent_slot = find_slot::<TestBlock>(&self.parent_header).expect("parent has a slot");
+		let slot = pre_digests
+			.logs
+			.iter()
+			.find_map(|log| SlotClaim::try_from(log).ok())
+			.expect("slot claim is always provided")
+			.slot;
+
+		let epoch_descriptor = self
+			.factory
+			.epoch_changes
+			.shared_data()
+			.epoch_descriptor_for_child_of(
+				descendent_query(&*self.factory.client),
+				&parent_hash,
+				*self.parent_header.number(),
+				slot,
+			)
+			.expect("epoch changes tree is consistent")
+			.expect("genesis epoch is always available");
+
+		// announce the next epoch in the first block of each epoch, as the runtime would do.
+		if parent_slot < epoch_descriptor.start_slot() {
+			let descriptor = NextEpochDescriptor {
+				randomness: blake2_256(&slot.encode()),
+				authorities: self.factory.config.genesis_epoch.authorities.clone(),
+				config: None,
+			};
+			pre_digests.push(DigestItem::Consensus(
+				SASSAFRAS_ENGINE_ID,
+				ConsensusLog::NextEpochData(descriptor).encode(),
+			));
+		}
+
+		let block_builder = BlockBuilderBuilder::new(&*self.factory.client)
+			.on_parent_block(parent_hash)
+			.fetch_parent_block_number(&*self.factory.client)
+			.unwrap()
+			.with_inherent_digests(pre_digests)
+			.build()
+			.unwrap();
+
+		let mut block = match block_builder.build().map_err(|e| e.into()) {
+			Ok(b) => b.block,
+			Err(e) => return future::ready(Err(e)),
+		};
+
+		// mutate the block header according to the mutator.
+		(self.factory.mutator)(&mut block.header, Stage::PreSeal);
+
+		future::ready(Ok(Proposal { block, proof: (), storage_changes: Default::default() }))
+	}
+}
+
+impl Proposer<TestBlock> for DummyProposer {
+	type Error = ClientError;
+	type Proposal = future::Ready<Result<Proposal<TestBlock, ()>, ClientError>>;
+	type ProofRecording = DisableProofRecording;
+	type Proof = ();
+
+	fn propose(
+		mut self,
+		_: InherentData,
+		pre_digests: Digest,
+		_: Duration,
+		_: Option<usize>,
+	) -> Self::Proposal {
+		self.propose_with(pre_digests)
+	}
+}
+
+thread_local! {
+	static MUTATOR: RefCell<Mutator> = RefCell::new(Arc::new(|_, _|()));
+}
+
+#[derive(Clone)]
+struct PanickingBlockImport<B>(B);
+
+#[async_trait::async_trait]
+impl<BI> BlockImport<TestBlock> for PanickingBlockImport<BI>
+where
+	BI: BlockImport<TestBlock> + Send + Sync,
+{
+	type Error = BI::Error;
+
+	async fn import_block(
+		&self,
+		block: BlockImportParams<TestBlock>,
+	) -> Result<ImportResult, Self::Error> {
+		Ok(self.0.import_block(block).await.expect("importing block failed"))
+	}
+
+	async fn check_block(
+		&self,
+		block: BlockCheckParams<TestBlock>,
+	) -> Result<ImportResult, Self::Error> {
+		Ok(self.0.check_block(block).await.expect("checking block failed"))
+	}
+}
+
+type SassafrasPeer = Peer<Option<PeerData>, SassafrasBlockImport>;
+
+#[derive(Default)]
+struct SassafrasTestNet {
+	peers: Vec<SassafrasPeer>,
+}
+
+struct TestVerifier {
+	inner: SassafrasVerifier<
+		TestBlock,
+		SassafrasTestClient,
+		TestSelectChain,
+		Box<
+			dyn CreateInherentDataProviders<TestBlock, (), InherentDataProviders = (SlotProvider,)>,
+		>,
+	>,
+	mutator: Mutator,
+}
+
+#[async_trait::async_trait]
+impl Verifier<TestBlock> for TestVerifier {
+	async fn verify(
+		&self,
+		mut block: BlockImportParams<TestBlock>,
+	) -> Result<BlockImportParams<TestBlock>, String> {
+		// apply post-sealing mutations (i.e. stripping seal, if desired).
+		(self.mutator)(&mut block.header, Stage::PostSeal);
+		self.inner.verify(block).await
+	}
+}
+
+struct PeerData {
+	link: SassafrasLink<TestBlock>,
+	block_import: Mutex<Option<BoxBlockImport<TestBlock>>>,
+}
+
+impl TestNetFactory for SassafrasTestNet {
+	type Verifier = TestVerifier;
+	type PeerData = Option<PeerData>;
+	type BlockImport = SassafrasBlockImport;
+
+	fn make_block_import(
+		&self,
+		client: PeersClient,
+	) -> (
+		BlockImportAdapter<Self::BlockImport>,
+		Option<BoxJustificationImport<Block>>,
+		Option<PeerData>,
+	) {
+		let client = client.as_client();
+
+		let (block_import, link) = crate::block_import(
+			test_config(),
+			client.clone(),
+			Arc::new(SassafrasTestClient::new(client)),
+		)
+		.expect("can initialize block-import");
+
+		let block_import = PanickingBlockImport(block_import);
+
+		let data_block_import =
+			Mutex::new(Some(Box::new(block_import.clone()) as BoxBlockImport<_>));
+		(
+			BlockImportAdapter::new(block_import),
+			None,
+			Some(PeerData { link, block_import: data_block_import }),
+		)
+	}
+
+	fn make_verifier(&self, client: PeersClient, maybe_link: &Option<PeerData>) -> Self::Verifier {
+		use substrate_test_runtime_client::DefaultTestClientBuilderExt;
+
+		let client = Arc::new(SassafrasTestClient::new(client.as_client()));
+		trace!(target: LOG_TARGET, "Creating a verifier");
+
+		// ensure block import and verifier are linked correctly.
+		let data = maybe_link
+			.as_ref()
+			.expect("sassafras link always provided to verifier instantiation");
+
+		let (_, longest_chain) = TestClientBuilder::new().build_with_longest_chain();
+
+		TestVerifier {
+			inner: SassafrasVerifier {
+				client,
+				select_chain: longest_chain,
+				create_inherent_data_providers: Box::new(|_, _| async {
+					let slot = Slot::from_timestamp(
+						Timestamp::current(),
+						SlotDuration::from_millis(SLOT_DURATION_MS),
+					);
+					Ok((SlotProvider(slot),))
+				}),
+				config: data.link.config.clone(),
+				epoch_changes: data.link.epoch_changes.clone(),
+				telemetry: None,
+				offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+					RejectAllTxPool::default(),
+				),
+			},
+			mutator: MUTATOR.with(|m| m.borrow().clone()),
+		}
+	}
+
+	fn peer(&mut self, i: usize) -> &mut SassafrasPeer {
+		trace!(target: LOG_TARGET, "Retrieving a peer");
+		&mut self.peers[i]
+	}
+
+	fn peers(&self) -> &Vec<SassafrasPeer> {
+		trace!(target: LOG_TARGET, "Retrieving peers");
+		&self.peers
+	}
+
+	fn peers_mut(&mut self) -> &mut Vec<SassafrasPeer> {
+		trace!(target: LOG_TARGET, "Retrieving peers, mutable");
+		&mut self.peers
+	}
+
+	fn mut_peers<F: FnOnce(&mut Vec<SassafrasPeer>)>(&mut self, closure: F) {
+		closure(&mut self.peers);
+	}
+}
+
+#[tokio::test]
+#[should_panic(expected = "valid sassafras headers must contain a slot claim")]
+async fn rejects_empty_block() {
+	sp_tracing::try_init_simple();
+	let mut net = SassafrasTestNet::new(3);
+	let block_builder = |builder: BlockBuilder<_, _>| builder.build().unwrap().block;
+	net.mut_peers(|peer| {
+		peer[0].generate_blocks(1, BlockOrigin::NetworkInitialSync, block_builder);
+	})
+}
+
+async fn run_one_test(mutator: impl Fn(&mut TestHeader, Stage) + Send + Sync + 'static) {
+	sp_tracing::try_init_simple();
+	let mutator = Arc::new(mutator) as Mutator;
+
+	MUTATOR.with(|m| *m.borrow_mut() = mutator.clone());
+
+	let net = SassafrasTestNet::new(3);
+
+	let net = Arc::new(Mutex::new(net));
+	let mut import_notifications = Vec::new();
+	let mut sassafras_futures = Vec::new();
+
+	for (peer_id, seed) in AUTHORITIES.iter().enumerate() {
+		let mut net = net.lock();
+		let peer = net.peer(peer_id);
+		let client = peer.client().as_client();
+		let select_chain = peer.select_chain().expect("Full client has select_chain");
+
+		let keystore = create_keystore(seed);
+
+		let mut got_own = false;
+		let mut got_other = false;
+
+		let data = peer.data.as_ref().expect("sassafras link set up during initialization");
+
+		let environ = DummyFactory {
+			client: client.clone(),
+			epoch_changes: data.link.epoch_changes.clone(),
+			config: data.link.config.clone(),
+			mutator: mutator.clone(),
+		};
+
+		import_notifications.push(
+			// run each future until we get one of our own blocks with number higher than 5
+			// that was produced locally.
+			client
+				.import_notification_stream()
+				.take_while(move |n| {
+					future::ready(
+						n.header.number() < &5 || {
+							if n.origin == BlockOrigin::Own {
+								got_own = true;
+							} else {
+								got_other = true;
+							}
+
+							// continue until we have at least one block of our own
+							// and one of another peer.
+							!(got_own && got_other)
+						},
+					)
+				})
+				.for_each(|_| future::ready(())),
+		);
+
+		let client_clone = client.clone();
+		sassafras_futures.push(
+			start_sassafras(SassafrasParams {
+				block_import: data.block_import.lock().take().expect("import set up during init"),
+				select_chain,
+				client: Arc::new(SassafrasTestClient::new(client)),
+				env: environ,
+				sync_oracle: DummyOracle,
+				create_inherent_data_providers: Box::new(move |parent, _| {
+					// Get the slot of the parent header and just increase this slot.
+					//
+					// Below we will running everything in one big future. If we would use
+					// time based slot, it can happen that one instance imports a block from
+					// another instance and then tries to build a block in the same slot making
+					// this test fail.
+					let parent_header = client_clone.header(parent).ok().flatten().unwrap();
+					let slot = Slot::from(*find_slot::<TestBlock>(&parent_header).unwrap() + 1);
+
+					async move { Ok((SlotProvider(slot),)) }
+				}),
+				force_authoring: false,
+				backoff_authoring_blocks: Some(BackoffAuthoringOnFinalizedHeadLagging::default()),
+				sassafras_link: data.link.clone(),
+				keystore,
+				justification_sync_link: (),
+				block_proposal_slot_portion: SlotProportion::new(0.5),
+				max_block_proposal_slot_portion: None,
+				telemetry: None,
+				offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+					RejectAllTxPool::default(),
+				),
+				spawner: sp_core::testing::TaskExecutor::new(),
+			})
+			.expect("Starts sassafras"),
+		);
+	}
+	future::select(
+		futures::future::poll_fn(move |cx| {
+			let mut net = net.lock();
+			net.poll(cx);
+			for p in net.peers() {
+				if let Some((h, e)) = p.failed_verifications().into_iter().next() {
+					panic!("Verification failed for {:?}: {}", h, e);
+				}
+			}
+
+			Poll::<()>::Pending
+		}),
+		future::select(future::join_all(import_notifications), future::join_all(sassafras_futures)),
+	)
+	.await;
+}
+
+#[tokio::test]
+async fn authoring_blocks() {
+	run_one_test(|_, _| ()).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "valid sassafras headers must contain a slot claim")]
+async fn rejects_missing_slot_claim() {
+	run_one_test(|header: &mut TestHeader, stage| {
+		let v = std::mem::take(&mut header.digest_mut().logs);
+		header.digest_mut().logs = v
+			.into_iter()
+			.filter(|v| stage == Stage::PostSeal || SlotClaim::try_from(v).is_err())
+			.collect()
+	})
+	.await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "has a bad seal")]
+async fn rejects_missing_seals() {
+	run_one_test(|header: &mut TestHeader, stage| {
+		let v = std::mem::take(&mut header.digest_mut().logs);
+		header.digest_mut().logs = v
+			.into_iter()
+			.filter(|v| stage == Stage::PreSeal || AuthoritySignature::try_from(v).is_err())
+			.collect()
+	})
+	.await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Expected epoch change to happen")]
+async fn rejects_missing_consensus_digests() {
+	run_one_test(|header: &mut TestHeader, stage| {
+		let v = std::mem::take(&mut header.digest_mut().logs);
+		header.digest_mut().logs = v
+			.into_iter()
+			.filter(|v| {
+				stage == Stage::PostSeal ||
+					v.try_to::<ConsensusLog>(OpaqueDigestItemId::Consensus(&SASSAFRAS_ENGINE_ID))
+						.is_none()
+			})
+			.collect()
+	})
+	.await;
+}
+
+fn make_epoch(authorities: Vec<AuthorityId>) -> Epoch {
+	sp_consensus_sassafras::Epoch {
+		index: 1,
+		start: 100.into(),
+		length: EPOCH_LENGTH,
+		randomness: [1; 32],
+		authorities,
+		config: EpochConfiguration { redundancy_factor: 2, attempts_number: 4 },
+	}
+	.into()
+}
+
+fn make_header(claim: &SlotClaim, author: &AuthorityId, keystore: &KeystorePtr) -> TestHeader {
+	let mut header = TestHeader::new(
+		1,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Digest { logs: vec![DigestItem::from(claim)] },
+	);
+	let signature: AuthoritySignature = keystore
+		.bandersnatch_sign(AuthorityId::ID, author.as_ref(), header.hash().as_ref())
+		.unwrap()
+		.unwrap()
+		.into();
+	header.digest_mut().push(DigestItem::from(&signature));
+	header
+}
+
+fn verify_header(
+	header: &TestHeader,
+	claim: &SlotClaim,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+) -> Result<CheckedHeader<TestHeader, verification::VerifiedHeaderInfo>, crate::Error<TestBlock>> {
+	check_header::<TestBlock>(VerificationParams {
+		header: header.clone(),
+		claim,
+		slot_now: claim.slot,
+		epoch,
+		maybe_ticket,
+	})
+}
+
+#[test]
+fn epoch_increment_and_clone_for_slot() {
+	let epoch = make_epoch(vec![authority_id("//Alice")]);
+
+	let next = epoch.increment(NextEpochDescriptor {
+		randomness: [2; 32],
+		authorities: vec![authority_id("//Bob")],
+		config: None,
+	});
+	assert_eq!(next.index, 2);
+	assert_eq!(next.start, epoch.start + EPOCH_LENGTH as u64);
+	assert_eq!(next.randomness, [2; 32]);
+	assert_eq!(next.authorities, vec![authority_id("//Bob")]);
+	assert_eq!(next.config, epoch.config);
+
+	// the slot belongs to the epoch following the next one.
+	let cloned = epoch.clone_for_slot(epoch.start + 2 * EPOCH_LENGTH as u64 + 1);
+	assert_eq!(cloned.index, 3);
+	assert_eq!(cloned.start, epoch.start + 2 * EPOCH_LENGTH as u64);
+
+	// slots preceding the epoch start leave the epoch untouched.
+	assert_eq!(epoch.clone_for_slot(0.into()), epoch);
+}
+
+#[test]
+fn claim_and_verify_fallback_slots() {
+	let keystore = create_keystore("//Alice");
+	let alice = authority_id("//Alice");
+	let epoch = make_epoch(vec![alice.clone(), authority_id("//Bob")]);
+
+	let slots = (*epoch.start..*epoch.start + EPOCH_LENGTH as u64).map(Slot::from);
+	let (own_slots, other_slots): (Vec<_>, Vec<_>) =
+		slots.partition(|slot| fallback_authority_index(*slot, &epoch) == Some(0));
+	assert!(!own_slots.is_empty() && !other_slots.is_empty());
+
+	for slot in own_slots {
+		let (claim, author) = claim_slot(slot, &epoch, None, &keystore).expect("our slot");
+		assert_eq!(author, alice);
+		assert!(claim.ticket_claim.is_none());
+
+		let header = make_header(&claim, &author, &keystore);
+		assert!(matches!(
+			verify_header(&header, &claim, &epoch, None),
+			Ok(CheckedHeader::Checked(..))
+		));
+
+		// claims from the future are deferred.
+		let res = check_header::<TestBlock>(VerificationParams {
+			header: header.clone(),
+			claim: &claim,
+			slot_now: Slot::from(*slot - 1),
+			epoch: &epoch,
+			maybe_ticket: None,
+		});
+		assert!(matches!(res, Ok(CheckedHeader::Deferred(..))));
+
+		// a ticket assigned to the slot requires a ticket claim.
+		let erased_public = ed25519::Pair::generate().0.public();
+		let ticket_body =
+			TicketBody { attempt_idx: 0, erased_public, revealed_public: erased_public };
+		assert!(matches!(
+			verify_header(&header, &claim, &epoch, Some((0, ticket_body))),
+			Err(crate::Error::MissingTicketClaim)
+		));
+	}
+
+	for slot in other_slots {
+		assert!(claim_slot(slot, &epoch, None, &keystore).is_none());
+
+		// forge a claim for a slot assigned to someone else.
+		let data = vrf::slot_claim_sign_data(&epoch.randomness, slot, epoch.index);
+		let vrf_signature = keystore
+			.bandersnatch_vrf_sign(AuthorityId::ID, alice.as_ref(), &data)
+			.unwrap()
+			.unwrap();
+		let claim = SlotClaim { authority_idx: 0, slot, vrf_signature, ticket_claim: None };
+
+		let header = make_header(&claim, &alice, &keystore);
+		assert!(matches!(
+			verify_header(&header, &claim, &epoch, None),
+			Err(crate::Error::InvalidAuthor(..))
+		));
+	}
+}
+
+#[test]
+fn generate_and_claim_epoch_tickets() {
+	let keystore = create_keystore("//Alice");
+	let alice = authority_id("//Alice");
+	let mut epoch = make_epoch(vec![alice.clone(), authority_id("//Bob")]);
+	let ring_context = RingContext::new_testing();
+
+	// with this configuration the tickets threshold saturates, thus all our attempts
+	// produce a ticket. No ticket is produced for the authority we don't have the key of.
+	let tickets = generate_epoch_tickets(&mut epoch, &keystore, &ring_context);
+	assert_eq!(tickets.len(), epoch.config.attempts_number as usize);
+	assert_eq!(epoch.tickets_aux.len(), tickets.len());
+	assert!(epoch.tickets_aux.values().all(|(authority_idx, _)| *authority_idx == 0));
+
+	let ring: Vec<bandersnatch::Public> =
+		epoch.authorities.iter().map(|authority_id| *authority_id.as_ref()).collect();
+	let verifier = ring_context.verifier(&ring);
+	for ticket in &tickets {
+		let input = vrf::ticket_id_input(&epoch.randomness, ticket.body.attempt_idx, epoch.index);
+		let data = vrf::ticket_body_sign_data(&ticket.body, input);
+		assert!(ticket.signature.ring_vrf_verify(&data, &verifier));
+		let ticket_id = vrf::make_ticket_id(&ticket.signature.pre_output);
+		assert!(epoch.tickets_aux.contains_key(&ticket_id));
+	}
+
+	let ticket = &tickets[0];
+	let ticket_id = vrf::make_ticket_id(&ticket.signature.pre_output);
+	let maybe_ticket = Some((ticket_id, ticket.body.clone()));
+	let slot = epoch.start + 1;
+
+	let (claim, author) =
+		claim_slot(slot, &epoch, maybe_ticket.clone(), &keystore).expect("our ticket");
+	assert_eq!(author, alice);
+	assert!(claim.ticket_claim.is_some());
+
+	let header = make_header(&claim, &author, &keystore);
+	assert!(matches!(
+		verify_header(&header, &claim, &epoch, maybe_ticket.clone()),
+		Ok(CheckedHeader::Checked(..))
+	));
+
+	// the ticket claim is not expected if the slot has no assigned ticket.
+	assert!(matches!(
+		verify_header(&header, &claim, &epoch, None),
+		Err(crate::Error::UnexpectedTicketClaim)
+	));
+
+	// the ticket claim must be verified by the ticket erased key.
+	let mut other_body = ticket.body.clone();
+	other_body.erased_public = ed25519::Pair::generate().0.public();
+	assert!(matches!(
+		verify_header(&header, &claim, &epoch, Some((ticket_id, other_body))),
+		Err(crate::Error::TicketClaimVerificationFailed)
+	));
+
+	// tickets generated by someone else can't be claimed.
+	epoch.tickets_aux.clear();
+	assert!(claim_slot(slot, &epoch, maybe_ticket, &keystore).is_none());
+}
@@ -0,0 +1,149 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Verification for Sassafras headers.
+
+use crate::{
+	authorship::{fallback_authority_index, ticket_claim_data},
+	sassafras_err, BlockT, Epoch, Error, LOG_TARGET,
+};
+use log::{debug, trace};
+use sc_consensus_epochs::Epoch as EpochT;
+use sc_consensus_slots::CheckedHeader;
+use sp_consensus_sassafras::{
+	digests::SlotClaim, vrf, AuthorityId, AuthorityPair, AuthoritySignature, Slot, TicketBody,
+	TicketId,
+};
+use sp_core::{
+	crypto::{VrfPublic, Wraps},
+	ed25519, Pair,
+};
+use sp_runtime::{traits::Header, DigestItem};
+
+/// Sassafras verification parameters
+pub(super) struct VerificationParams<'a, B: 'a + BlockT> {
+	/// The header being verified.
+	pub(super) header: B::Header,
+	/// The slot claim of the header being verified.
+	pub(super) claim: &'a SlotClaim,
+	/// The slot number of the current time.
+	pub(super) slot_now: Slot,
+	/// Epoch descriptor of the epoch this block _should_ be under, if it's valid.
+	pub(super) epoch: &'a Epoch,
+	/// Ticket assigned by the runtime to the claimed slot, if any.
+	pub(super) maybe_ticket: Option<(TicketId, TicketBody)>,
+}
+
+pub(super) struct VerifiedHeaderInfo {
+	pub(super) seal: DigestItem,
+	pub(super) author: AuthorityId,
+}
+
+/// Check a header has been signed by the right key. If the slot is too far in
+/// the future, an error will be returned. If successful, returns the pre-header
+/// and the digest item containing the seal.
+///
+/// The seal must be the last digest. Otherwise, the whole header is considered
+/// unsigned. This is required for security and must not be changed.
+///
+/// A slot with an assigned ticket can only be claimed by the ticket owner, while
+/// a slot without a ticket can only be claimed by the fallback authority.
+pub(super) fn check_header<B: BlockT + Sized>(
+	params: VerificationParams<B>,
+) -> Result<CheckedHeader<B::Header, VerifiedHeaderInfo>, Error<B>> {
+	let VerificationParams { mut header, claim, slot_now, epoch, maybe_ticket } = params;
+
+	trace!(target: LOG_TARGET, "Checking header");
+	let seal = header
+		.digest_mut()
+		.pop()
+		.ok_or_else(|| sassafras_err(Error::HeaderUnsealed(header.hash())))?;
+
+	let signature = AuthoritySignature::try_from(&seal)
+		.map_err(|_| sassafras_err(Error::HeaderBadSeal(header.hash())))?;
+
+	// the pre-hash of the header doesn't include the seal
+	// and that's what we sign
+	let pre_hash = header.hash();
+
+	if claim.slot > slot_now {
+		header.digest_mut().push(seal);
+		return Ok(CheckedHeader::Deferred(header, claim.slot))
+	}
+
+	let author = epoch
+		.authorities
+		.get(claim.authority_idx as usize)
+		.ok_or_else(|| sassafras_err(Error::SlotAuthorNotFound))?;
+
+	if !AuthorityPair::verify(&signature, pre_hash, author) {
+		return Err(sassafras_err(Error::BadSignature(pre_hash)))
+	}
+
+	let mut epoch_index = epoch.index;
+	if epoch.end_slot() <= claim.slot {
+		// Slot doesn't strictly belong to this epoch, create a clone with fixed values.
+		epoch_index = epoch.clone_for_slot(claim.slot).index;
+	}
+
+	let data = vrf::slot_claim_sign_data(&epoch.randomness, claim.slot, epoch_index);
+	if !author.as_inner_ref().vrf_verify(&data, &claim.vrf_signature) {
+		return Err(sassafras_err(Error::VrfVerificationFailed))
+	}
+
+	match (&maybe_ticket, &claim.ticket_claim) {
+		(Some((ticket_id, ticket_body)), Some(ticket_claim)) => {
+			debug!(
+				target: LOG_TARGET,
+				"Verifying primary block #{} at slot: {} (ticket: {:032x})",
+				header.number(),
+				claim.slot,
+				ticket_id,
+			);
+
+			let data = ticket_claim_data(&epoch.randomness, claim.slot, epoch_index);
+			if !ed25519::Pair::verify(
+				&ticket_claim.erased_signature,
+				&data,
+				&ticket_body.erased_public,
+			) {
+				return Err(sassafras_err(Error::TicketClaimVerificationFailed))
+			}
+		},
+		(None, None) => {
+			debug!(
+				target: LOG_TARGET,
+				"Verifying secondary block #{} at slot: {}",
+				header.number(),
+				claim.slot,
+			);
+
+			let expected_idx = fallback_authority_index(claim.slot, epoch)
+				.ok_or_else(|| sassafras_err(Error::SlotAuthorNotFound))?;
+			if expected_idx != claim.authority_idx {
+				let expected = epoch.authorities[expected_idx as usize].clone();
+				return Err(sassafras_err(Error::InvalidAuthor(expected, author.clone())))
+			}
+		},
+		(Some(_), None) => return Err(sassafras_err(Error::MissingTicketClaim)),
+		(None, Some(_)) => return Err(sassafras_err(Error::UnexpectedTicketClaim)),
+	}
+
+	let info = VerifiedHeaderInfo { seal, author: author.clone() };
+	Ok(CheckedHeader::Checked(header, info))
+}
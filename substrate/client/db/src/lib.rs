@@ -33,6 +33,7 @@ pub mod offchain;
 pub mod bench;
 
 mod children;
+mod migrate;
 mod parity_db;
 mod pinned_blocks_cache;
 mod record_stats_state;
@@ -95,10 +96,11 @@ use sp_trie::{cache::SharedTrieCache, prefixed_key, MemoryDB, MerkleValue, Prefi
 use utils::BLOCK_GAP_CURRENT_VERSION;
 
 // Re-export the Database trait so that one can pass an implementation of it.
-pub use sc_state_db::PruningMode;
+pub use sc_state_db::{MixedConstraints, PruningMode};
 pub use sp_database::Database;
 
 pub use bench::BenchmarkingState;
+pub use migrate::migrate_database;
 
 const CACHE_HEADERS: usize = 8;
 
@@ -2561,6 +2563,27 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		)
 	}
 
+	fn import_state_nodes(
+		&self,
+		mut nodes: PrefixedMemoryDB<HashingFor<Block>>,
+	) -> ClientResult<()> {
+		let mut transaction = Transaction::new();
+		let mut ops: u64 = 0;
+		let mut bytes: u64 = 0;
+		for (mut key, (val, rc)) in nodes.drain() {
+			self.storage.db.sanitize_key(&mut key);
+			// Reference counted databases count every insertion of a node.
+			for _ in 0..rc.max(0) {
+				ops += 1;
+				bytes += key.len() as u64 + val.len() as u64;
+				transaction.set(columns::STATE, &key, &val);
+			}
+		}
+		self.state_usage.tally_writes_nodes(ops, bytes);
+		self.storage.db.commit(transaction)?;
+		Ok(())
+	}
+
 	fn pin_block(&self, hash: <Block as BlockT>::Hash) -> sp_blockchain::Result<()> {
 		let hint = || {
 			let header_metadata = self.blockchain.header_metadata(hash);
@@ -2874,6 +2897,69 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn mixed_pruning_keeps_archived_state() {
+		let child_info = ChildInfo::new_default(b"child");
+		let mut mixed = MixedConstraints::default();
+		mixed.constraints.max_blocks = Some(1);
+		mixed.archive_prefix(vec![0x11]);
+		mixed.archive_child_trie(child_info.storage_key());
+
+		let backend = Backend::<Block>::new(
+			DatabaseSettings {
+				trie_cache_maximum_size: None,
+				state_pruning: Some(PruningMode::Mixed(mixed)),
+				source: DatabaseSource::Custom {
+					db: sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS)),
+					require_create_flag: true,
+				},
+				blocks_pruning: BlocksPruning::KeepAll,
+			},
+			0,
+		)
+		.unwrap();
+
+		let mut hashes = Vec::new();
+		let mut parent_hash = Default::default();
+		for number in 0..5u64 {
+			let value = vec![number as u8];
+			let top = vec![(vec![0x11], Some(value.clone())), (vec![0x22], Some(value.clone()))];
+			let child = vec![(vec![0x33], Some(value))];
+
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, parent_hash).unwrap();
+			let (root, overlay) = op.old_state.full_storage_root(
+				top.iter().map(|(k, v)| (&k[..], v.as_deref())),
+				std::iter::once((&child_info, child.iter().map(|(k, v)| (&k[..], v.as_deref())))),
+				StateVersion::V1,
+			);
+			op.update_db_storage(overlay).unwrap();
+			let header = Header {
+				number,
+				parent_hash,
+				state_root: root,
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			op.set_block_data(header.clone(), Some(vec![]), None, None, NewBlockState::Best)
+				.unwrap();
+			backend.commit_operation(op).unwrap();
+
+			parent_hash = header.hash();
+			hashes.push(parent_hash);
+		}
+
+		// Block 1 left the pruning window, but its archived keys can still be read.
+		assert!(backend.have_state_at(hashes[1], 1));
+		let state = backend.state_at(hashes[1]).unwrap();
+		assert_eq!(state.storage(&[0x11]).unwrap(), Some(vec![1]));
+		assert_eq!(state.child_storage(&child_info, &[0x33]).unwrap(), Some(vec![1]));
+		assert!(state.storage(&[0x22]).is_err());
+
+		let state = backend.state_at(hashes[4]).unwrap();
+		assert_eq!(state.storage(&[0x22]).unwrap(), Some(vec![4]));
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		sp_tracing::try_init_simple();
@@ -0,0 +1,384 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Migration of the whole database between backends.
+//!
+//! All the columns of the source database are streamed into the target database in batches.
+//! The progress is committed to the target together with every batch, so an interrupted
+//! migration continues where it stopped when it is started again.
+//!
+//! The state and transaction columns are converted when the target database counts references
+//! itself (ParityDb). The path prefix is removed from the trie node keys, the pruning journals of
+//! the state are converted to match and the reference counters of the indexed transactions are
+//! moved into the target database.
+//!
+//! The source database must be able to enumerate its columns, which ParityDb can not do for its
+//! hash indexed columns. Only RocksDB databases can therefore be migrated, the migration to
+//! ParityDb is one-way.
+
+use crate::{
+	columns,
+	utils::{self, meta_keys, DatabaseType, NUM_COLUMNS},
+	DatabaseSource, DbHash, DB_HASH_LEN,
+};
+use codec::{Decode, Encode};
+use log::info;
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use sp_database::{error::DatabaseError, Database, Transaction};
+use sp_runtime::traits::Block as BlockT;
+
+/// Key of the migration progress in the meta column of the target database.
+const MIGRATION_PROGRESS: &[u8] = b"migration_progress";
+
+/// Number of entries committed to the target database at once.
+const BATCH_SIZE: u64 = 10_000;
+
+/// Progress of a database migration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+struct Progress {
+	/// Index into the migration order of the column being copied.
+	column: u32,
+	/// Number of entries of the column copied so far.
+	entries: u64,
+}
+
+/// Order in which the columns are copied.
+///
+/// The meta column goes last, so the target database is only recognized as a complete database
+/// once everything else has been copied.
+fn migration_order() -> Vec<u32> {
+	(0..NUM_COLUMNS)
+		.filter(|col| *col != columns::META)
+		.chain(std::iter::once(columns::META))
+		.collect()
+}
+
+/// State meta column of the source database.
+struct SourceMetaDb<'a>(&'a dyn Database<DbHash>);
+
+impl<'a> sc_state_db::MetaDb for SourceMetaDb<'a> {
+	type Error = DatabaseError;
+
+	fn get_meta(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		Ok(self.0.get(columns::STATE_META, key))
+	}
+}
+
+/// Migrate the database at `source` into the database at `target`.
+///
+/// The target database is created if it does not exist. A target database that was not created
+/// by a previous, interrupted, migration must be empty.
+pub fn migrate_database<Block: BlockT>(
+	source: &DatabaseSource,
+	target: &DatabaseSource,
+) -> ClientResult<()> {
+	if let DatabaseSource::ParityDb { .. } = source {
+		return Err(ClientError::Backend(
+			"ParityDb databases can not be migrated, the migration to ParityDb is one-way".into(),
+		))
+	}
+
+	let source_db = utils::open_database::<Block>(source, DatabaseType::Full, false)?;
+	let target_db = utils::open_database::<Block>(target, DatabaseType::Full, true)?;
+
+	info!("Migrating database from {} to {}", source, target);
+	migrate_columns::<Block>(&*source_db, &*target_db)
+}
+
+/// Copy all the columns of `source` into `target`.
+fn migrate_columns<Block: BlockT>(
+	source: &dyn Database<DbHash>,
+	target: &dyn Database<DbHash>,
+) -> ClientResult<()> {
+	if source.supports_ref_counting() {
+		return Err(ClientError::Backend(
+			"Databases that count references themselves can not be migrated".into(),
+		))
+	}
+	let convert = target.supports_ref_counting();
+	// Converted before anything is written, so an unsupported pruning mode leaves the target
+	// untouched.
+	let journals = if convert {
+		sc_state_db::convert_journals_to_ref_counting::<Block::Hash, Vec<u8>, _>(
+			&SourceMetaDb(source),
+			sanitize_key,
+		)
+		.map_err(ClientError::from_state_db)?
+	} else {
+		Vec::new()
+	};
+
+	let resume = match target.get(columns::META, MIGRATION_PROGRESS) {
+		Some(progress) => {
+			let progress = Progress::decode(&mut &progress[..])
+				.map_err(|e| ClientError::Backend(format!("Invalid migration progress: {}", e)))?;
+			info!(
+				"Resuming migration at column #{} ({} entries)",
+				progress.column, progress.entries
+			);
+			progress
+		},
+		None if target.get(columns::META, meta_keys::GENESIS_HASH).is_some() =>
+			return Err(ClientError::Backend("The target database is not empty".into())),
+		None => Progress::default(),
+	};
+
+	let order = migration_order();
+	for (index, col) in order.iter().copied().enumerate().skip(resume.column as usize) {
+		let skip = if index as u32 == resume.column { resume.entries } else { 0 };
+		let mut progress = Progress { column: index as u32, entries: skip };
+		let entries = source.iter(col).ok_or_else(|| {
+			ClientError::Backend(format!(
+				"Column {} of the source database can not be iterated",
+				col
+			))
+		})?;
+
+		let mut transaction = Transaction::new();
+		let mut pending = 0;
+		for (key, value) in entries.skip(skip as usize) {
+			progress.entries += 1;
+			match col {
+				columns::STATE if convert => {
+					let mut key = key;
+					sanitize_key(&mut key);
+					transaction.store(col, DbHash::from_slice(&key), value);
+				},
+				// The reference counter is stored next to the transaction, under the key of the
+				// transaction followed by a zero byte.
+				columns::TRANSACTION if convert && key.len() == DB_HASH_LEN => {
+					let hash = DbHash::from_slice(&key);
+					let references = counter(source, &key)?;
+					transaction.store(col, hash, value);
+					for _ in 1..references {
+						transaction.reference(col, hash);
+					}
+				},
+				columns::TRANSACTION if convert => (),
+				_ => transaction.set_from_vec(col, &key, value),
+			}
+
+			pending += 1;
+			if pending == BATCH_SIZE {
+				transaction.set_from_vec(columns::META, MIGRATION_PROGRESS, progress.encode());
+				target.commit(std::mem::take(&mut transaction))?;
+				pending = 0;
+				if progress.entries % (BATCH_SIZE * 100) == 0 {
+					info!("Column {}: {} entries migrated", col, progress.entries);
+				}
+			}
+		}
+
+		if col == columns::STATE_META {
+			for (key, value) in journals.iter() {
+				transaction.set(col, key, value);
+			}
+		}
+		let next = Progress { column: index as u32 + 1, entries: 0 };
+		transaction.set_from_vec(columns::META, MIGRATION_PROGRESS, next.encode());
+		target.commit(transaction)?;
+		info!("Column {}: migrated {} entries", col, progress.entries);
+	}
+
+	let mut transaction = Transaction::new();
+	transaction.remove(columns::META, MIGRATION_PROGRESS);
+	target.commit(transaction)?;
+	info!("🎉 Database migration complete");
+	Ok(())
+}
+
+/// Remove the path prefix from the key of a trie node.
+fn sanitize_key(key: &mut Vec<u8>) {
+	key.drain(..key.len().saturating_sub(DB_HASH_LEN));
+}
+
+/// Read the reference counter of the indexed transaction with the given key.
+fn counter(source: &dyn Database<DbHash>, key: &[u8]) -> ClientResult<u32> {
+	let mut counter_key = key.to_vec();
+	counter_key.push(0);
+	match source.get(columns::TRANSACTION, &counter_key) {
+		Some(counter) => counter
+			.try_into()
+			.map(u32::from_le_bytes)
+			.map_err(|_| ClientError::Backend("Invalid transaction reference counter".into())),
+		None => Ok(1),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		tests::{insert_header, Block},
+		Backend, BlocksPruning, DatabaseSettings,
+	};
+	use sc_client_api::backend::Backend as _;
+	use sp_core::H256;
+	use sp_database::MemDb;
+	use sp_state_machine::Backend as _;
+	use std::sync::Arc;
+
+	fn make_source() -> MemDb {
+		let db = MemDb::new();
+		let mut transaction = Transaction::<DbHash>::new();
+		for col in 0..NUM_COLUMNS {
+			for i in 0..10u32 {
+				transaction.set(col, &i.to_le_bytes(), &[col as u8; 4]);
+			}
+		}
+		transaction.set(columns::META, meta_keys::GENESIS_HASH, &[0; 32]);
+		db.commit(transaction).unwrap();
+		db
+	}
+
+	fn column(db: &MemDb, col: u32) -> Vec<(Vec<u8>, Vec<u8>)> {
+		Database::<DbHash>::iter(db, col).unwrap().collect()
+	}
+
+	fn parity_db(path: &std::path::Path) -> Arc<dyn Database<DbHash>> {
+		crate::parity_db::open(path, DatabaseType::Full, true, false).unwrap()
+	}
+
+	#[test]
+	fn migration_copies_all_columns() {
+		let source = make_source();
+		let target = MemDb::new();
+		migrate_columns::<Block>(&source, &target).unwrap();
+
+		for col in 0..NUM_COLUMNS {
+			assert_eq!(column(&source, col), column(&target, col));
+		}
+		assert!(migrate_columns::<Block>(&source, &target).is_err());
+	}
+
+	#[test]
+	fn migration_resumes() {
+		let source = make_source();
+		let target = MemDb::new();
+		let mut transaction = Transaction::<DbHash>::new();
+		let order = migration_order();
+		transaction.set_from_vec(
+			columns::META,
+			MIGRATION_PROGRESS,
+			Progress { column: 2, entries: 5 }.encode(),
+		);
+		target.commit(transaction).unwrap();
+		migrate_columns::<Block>(&source, &target).unwrap();
+
+		assert!(column(&target, order[0]).is_empty());
+		assert!(column(&target, order[1]).is_empty());
+		assert_eq!(column(&target, order[2]), column(&source, order[2])[5..]);
+		assert_eq!(column(&target, order[3]), column(&source, order[3]));
+		assert_eq!(column(&target, columns::META), column(&source, columns::META));
+	}
+
+	#[test]
+	fn pruned_database_migrates_to_parity_db() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(BlocksPruning::Some(4), 2);
+		let mut hashes = Vec::new();
+		let mut insert_blocks = |backend: &Backend<Block>, numbers: std::ops::Range<u64>| {
+			for number in numbers {
+				let parent = hashes.last().copied().unwrap_or_default();
+				hashes.push(insert_header(backend, number, parent, None, Default::default()));
+			}
+			hashes.clone()
+		};
+		// Blocks #4 to #7 are in the pruning window, #8 and #9 are not canonicalized yet.
+		let hashes_before = insert_blocks(&backend, 0..10);
+		backend.finalize_block(hashes_before[7], None).unwrap();
+
+		let dir = tempfile::tempdir().unwrap();
+		let target = parity_db(dir.path());
+		migrate_columns::<Block>(&*backend.storage.db, &*target).unwrap();
+		drop(backend);
+
+		let backend = Backend::<Block>::new(
+			DatabaseSettings {
+				trie_cache_maximum_size: None,
+				state_pruning: None,
+				source: DatabaseSource::Custom { db: target, require_create_flag: false },
+				blocks_pruning: BlocksPruning::Some(4),
+			},
+			2,
+		)
+		.unwrap();
+		let info = backend.blockchain().info();
+		assert_eq!(info.best_hash, hashes_before[9]);
+		assert_eq!(info.finalized_hash, hashes_before[7]);
+
+		// The state of every block contains the hashes of all its ancestors.
+		let check_state = |hashes: &[H256], number: usize| {
+			let state = backend.state_at(hashes[number]).unwrap();
+			for parent in &hashes[..number] {
+				assert_eq!(state.storage(parent.as_ref()).unwrap(), Some(parent.as_ref().to_vec()));
+			}
+		};
+		for number in 4..10 {
+			check_state(&hashes_before, number);
+		}
+		assert!(backend.state_at(hashes_before[3]).is_err());
+
+		// The converted journals prune the migrated state without touching the live state.
+		let hashes = insert_blocks(&backend, 10..20);
+		backend.finalize_block(hashes[17], None).unwrap();
+		for number in 14..20 {
+			check_state(&hashes, number);
+		}
+		assert!(backend.state_at(hashes[13]).is_err());
+	}
+
+	#[test]
+	fn transaction_references_are_migrated() {
+		let source: Arc<dyn Database<DbHash>> =
+			sp_database::as_database(kvdb_memorydb::create(NUM_COLUMNS));
+		let hash = DbHash::repeat_byte(1);
+		for _ in 0..2 {
+			let mut transaction = Transaction::new();
+			transaction.store(columns::TRANSACTION, hash, vec![1, 2, 3]);
+			source.commit(transaction).unwrap();
+		}
+
+		let dir = tempfile::tempdir().unwrap();
+		let target = parity_db(dir.path());
+		migrate_columns::<Block>(&*source, &*target).unwrap();
+
+		for expected in [Some(vec![1, 2, 3]), None] {
+			let mut transaction = Transaction::new();
+			transaction.release(columns::TRANSACTION, hash);
+			target.commit(transaction).unwrap();
+			assert_eq!(target.get(columns::TRANSACTION, hash.as_ref()), expected);
+		}
+	}
+
+	#[test]
+	fn unsupported_databases_are_rejected_before_writing() {
+		let dir = tempfile::tempdir().unwrap();
+		let parity = parity_db(dir.path());
+		let target = MemDb::new();
+		assert!(migrate_columns::<Block>(&*parity, &target).is_err());
+		assert!(column(&target, columns::META).is_empty());
+
+		let source = make_source();
+		let mut transaction = Transaction::new();
+		transaction.set(columns::STATE_META, b"mode", b"mixed");
+		source.commit(transaction).unwrap();
+		assert!(migrate_columns::<Block>(&source, &*parity).is_err());
+		assert!(parity.get(columns::META, MIGRATION_PROGRESS).is_none());
+		assert!(parity.get(columns::HEADER, &0u32.to_le_bytes()).is_none());
+	}
+}
@@ -17,19 +17,23 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use polkavm::{CallError, Caller, Reg};
+use sc_allocator::{AllocationStats, FreeingBumpHeapAllocator};
 use sc_executor_common::{
-	error::{Error, WasmError},
-	wasm_runtime::{AllocationStats, WasmInstance, WasmModule},
+	error::{Error, MessageWithBacktrace, WasmError},
+	wasm_runtime::{WasmInstance, WasmModule},
 };
 use sp_wasm_interface::{
 	Function, FunctionContext, HostFunctions, Pointer, Value, ValueType, WordSize,
 };
 
+/// The granularity in which the heap is grown, as expected by the allocator.
+const PAGE_SIZE: u32 = 64 * 1024;
+
 #[repr(transparent)]
-pub struct InstancePre(polkavm::InstancePre<(), String>);
+pub struct InstancePre(polkavm::InstancePre<HostState, String>);
 
 #[repr(transparent)]
-pub struct Instance(polkavm::Instance<(), String>);
+pub struct Instance(polkavm::Instance<HostState, String>);
 
 impl WasmModule for InstancePre {
 	fn new_instance(&self) -> Result<Box<dyn WasmInstance>, Error> {
@@ -37,43 +41,236 @@ impl WasmModule for InstancePre {
 	}
 }
 
-impl WasmInstance for Instance {
-	fn call_with_allocation_stats(
+/// Access to the memory of a guest instance.
+trait GuestMemory {
+	/// Reads `buffer.len()` bytes at `address` into `buffer`.
+	fn read_bytes(&self, address: u32, buffer: &mut [u8]) -> Result<(), String>;
+
+	/// Writes `data` at `address`.
+	fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), String>;
+
+	/// Grows the guest heap by `size` bytes and returns the new end of the heap.
+	fn grow_heap(&mut self, size: u32) -> Option<u32>;
+}
+
+impl GuestMemory for polkavm::RawInstance {
+	fn read_bytes(&self, address: u32, buffer: &mut [u8]) -> Result<(), String> {
+		self.read_memory_into(address, buffer)
+			.map(|_| ())
+			.map_err(|error| error.to_string())
+	}
+
+	fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), String> {
+		self.write_memory(address, data).map_err(|error| error.to_string())
+	}
+
+	fn grow_heap(&mut self, size: u32) -> Option<u32> {
+		self.sbrk(size).ok().flatten()
+	}
+}
+
+impl GuestMemory for polkavm::Instance<HostState, String> {
+	fn read_bytes(&self, address: u32, buffer: &mut [u8]) -> Result<(), String> {
+		self.read_memory_into(address, buffer)
+			.map(|_| ())
+			.map_err(|error| error.to_string())
+	}
+
+	fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), String> {
+		self.write_memory(address, data).map_err(|error| error.to_string())
+	}
+
+	fn grow_heap(&mut self, size: u32) -> Option<u32> {
+		self.sbrk(size).ok().flatten()
+	}
+}
+
+/// The guest heap managed by the allocator.
+///
+/// The guest heap is grown with `sbrk` and the allocator keeps its headers directly in the guest
+/// memory. The addresses used by the allocator are relative to the start of the heap.
+struct Heap {
+	start: u32,
+	size: u32,
+}
+
+/// Wrapper around the [`Heap`] and the memory of the guest that implements
+/// [`sc_allocator::Memory`].
+struct HeapMemory<'a, M> {
+	heap: &'a mut Heap,
+	memory: &'a mut M,
+}
+
+impl<M> HeapMemory<'_, M> {
+	/// Returns the guest address of the `length` bytes at `offset` into the heap.
+	fn address(&self, offset: u32, length: u32) -> Option<u32> {
+		(offset.checked_add(length)? <= self.heap.size).then(|| self.heap.start + offset)
+	}
+}
+
+impl<M: GuestMemory> sc_allocator::Memory for HeapMemory<'_, M> {
+	fn with_access_mut<R>(&mut self, _: impl FnOnce(&mut [u8]) -> R) -> R {
+		unreachable!("the allocator only accesses the memory through `write_le_u64`; qed")
+	}
+
+	fn with_access<R>(&self, _: impl FnOnce(&[u8]) -> R) -> R {
+		unreachable!("the allocator only accesses the memory through `read_le_u64`; qed")
+	}
+
+	fn read_le_u64(&self, ptr: u32) -> Result<u64, sc_allocator::Error> {
+		let address = self
+			.address(ptr, 8)
+			.ok_or(sc_allocator::Error::Other("read out of heap bounds"))?;
+		let mut bytes = [0; 8];
+		self.memory
+			.read_bytes(address, &mut bytes)
+			.map_err(|_| sc_allocator::Error::Other("failed to read the guest memory"))?;
+		Ok(u64::from_le_bytes(bytes))
+	}
+
+	fn write_le_u64(&mut self, ptr: u32, val: u64) -> Result<(), sc_allocator::Error> {
+		let address = self
+			.address(ptr, 8)
+			.ok_or(sc_allocator::Error::Other("write out of heap bounds"))?;
+		self.memory
+			.write_bytes(address, &val.to_le_bytes())
+			.map_err(|_| sc_allocator::Error::Other("failed to write the guest memory"))
+	}
+
+	fn grow(&mut self, additional: u32) -> Result<(), ()> {
+		let size = additional.checked_mul(PAGE_SIZE).ok_or(())?;
+		let end = self.heap.start.checked_add(self.heap.size).ok_or(())?;
+		let new_size = self.heap.size.checked_add(size).ok_or(())?;
+
+		// The heap was grown behind our back, so it doesn't start right after the allocations.
+		if self.memory.grow_heap(0) != Some(end) {
+			return Err(())
+		}
+
+		self.memory.grow_heap(size).ok_or(())?;
+		self.heap.size = new_size;
+		Ok(())
+	}
+
+	fn pages(&self) -> u32 {
+		self.heap.size / PAGE_SIZE
+	}
+
+	fn max_pages(&self) -> Option<u32> {
+		None
+	}
+}
+
+/// The state shared by all host functions called during a single runtime call.
+pub struct HostState {
+	allocator: FreeingBumpHeapAllocator,
+	heap: Heap,
+	panic_message: Option<String>,
+}
+
+impl HostState {
+	fn new(heap_start: u32) -> Self {
+		Self {
+			allocator: FreeingBumpHeapAllocator::new(0),
+			heap: Heap { start: heap_start, size: 0 },
+			panic_message: None,
+		}
+	}
+
+	fn allocate(
+		&mut self,
+		memory: &mut impl GuestMemory,
+		size: WordSize,
+	) -> Result<Pointer<u8>, String> {
+		let pointer = self
+			.allocator
+			.allocate(&mut HeapMemory { heap: &mut self.heap, memory }, size)
+			.map_err(|error| error.to_string())?;
+
+		u32::from(pointer)
+			.checked_add(self.heap.start)
+			.map(Pointer::new)
+			.ok_or_else(|| String::from("allocation is out of the address space"))
+	}
+
+	fn deallocate(
+		&mut self,
+		memory: &mut impl GuestMemory,
+		pointer: Pointer<u8>,
+	) -> Result<(), String> {
+		let pointer = u32::from(pointer)
+			.checked_sub(self.heap.start)
+			.ok_or_else(|| String::from("invalid pointer for deallocation"))?;
+
+		self.allocator
+			.deallocate(&mut HeapMemory { heap: &mut self.heap, memory }, Pointer::new(pointer))
+			.map_err(|error| error.to_string())
+	}
+}
+
+impl Instance {
+	fn call(
 		&mut self,
+		state: &mut HostState,
 		name: &str,
 		raw_data: &[u8],
-	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
+	) -> Result<Vec<u8>, Error> {
 		let pc = match self.0.module().exports().find(|e| e.symbol() == name) {
 			Some(export) => export.program_counter(),
 			None =>
-				return (
-					Err(format!("cannot call into the runtime: export not found: '{name}'").into()),
-					None,
+				return Err(
+					format!("cannot call into the runtime: export not found: '{name}'").into()
 				),
 		};
 
 		let Ok(raw_data_length) = u32::try_from(raw_data.len()) else {
-			return (
-				Err(format!("cannot call runtime method '{name}': input payload is too big").into()),
-				None,
+			return Err(
+				format!("cannot call runtime method '{name}': input payload is too big").into()
 			);
 		};
 
-		// TODO: This will leak guest memory; find a better solution.
+		// Allocate space for the input payload on the freshly reset heap.
+		let data_pointer = state
+			.allocate(&mut self.0, raw_data_length)
+			.map_err(|err| {
+				format!("call into the runtime method '{name}' failed: failed to allocate the input payload: {err}")
+			})?;
 
-		// Make sure that the memory is cleared...
-		if let Err(err) = self.0.reset_memory() {
-			return (
-				Err(format!(
-					"call into the runtime method '{name}' failed: reset memory failed: {err}"
-				)
-				.into()),
-				None,
-			);
+		if let Err(err) = self.0.write_memory(u32::from(data_pointer), raw_data) {
+			return Err(format!("call into the runtime method '{name}': failed to write the input payload into guest memory: {err}").into());
 		}
 
-		// ... and allocate space for the input payload.
-		if let Err(err) = self.0.sbrk(raw_data_length) {
+		match self.0.call_typed(state, pc, (u32::from(data_pointer), raw_data_length)) {
+			Ok(()) => {},
+			Err(CallError::Trap) =>
+				return Err(match state.panic_message.take() {
+					Some(message) =>
+						Error::AbortedDueToPanic(MessageWithBacktrace { message, backtrace: None }),
+					None => format!("call into the runtime method '{name}' failed: trap").into(),
+				}),
+			Err(CallError::Error(err)) =>
+				return Err(format!("call into the runtime method '{name}' failed: {err}").into()),
+			Err(CallError::User(err)) =>
+				return Err(format!("call into the runtime method '{name}' failed: {err}").into()),
+			Err(CallError::NotEnoughGas) => unreachable!("gas metering is never enabled"),
+		};
+
+		let result_pointer = self.0.reg(Reg::A0);
+		let result_length = self.0.reg(Reg::A1);
+		self.0.read_memory(result_pointer as u32, result_length as u32).map_err(|error| {
+			format!("call into the runtime method '{name}' failed: failed to read the return payload: {error}").into()
+		})
+	}
+}
+
+impl WasmInstance for Instance {
+	fn call_with_allocation_stats(
+		&mut self,
+		name: &str,
+		raw_data: &[u8],
+	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
+		// Make sure that the memory is cleared, so every call starts with an empty heap.
+		if let Err(err) = self.0.reset_memory() {
 			return (
 				Err(format!(
 					"call into the runtime method '{name}' failed: reset memory failed: {err}"
@@ -83,48 +280,26 @@ impl WasmInstance for Instance {
 			);
 		}
 
-		// Grab the address of where the guest's heap starts; that's where we've just allocated
-		// the memory for the input payload.
-		let data_pointer = self.0.module().memory_map().heap_base();
-
-		if let Err(err) = self.0.write_memory(data_pointer, raw_data) {
-			return (Err(format!("call into the runtime method '{name}': failed to write the input payload into guest memory: {err}").into()), None);
-		}
-
-		match self.0.call_typed(&mut (), pc, (data_pointer, raw_data_length)) {
-			Ok(()) => {},
-			Err(CallError::Trap) =>
-				return (
-					Err(format!("call into the runtime method '{name}' failed: trap").into()),
-					None,
-				),
-			Err(CallError::Error(err)) =>
-				return (
-					Err(format!("call into the runtime method '{name}' failed: {err}").into()),
-					None,
-				),
-			Err(CallError::User(err)) =>
+		let heap_start = match self.0.sbrk(0) {
+			Ok(pointer) => pointer.expect("fetching the current heap pointer never fails"),
+			Err(err) =>
 				return (
-					Err(format!("call into the runtime method '{name}' failed: {err}").into()),
+					Err(format!(
+						"call into the runtime method '{name}' failed: sbrk failed: {err}"
+					)
+					.into()),
 					None,
 				),
-			Err(CallError::NotEnoughGas) => unreachable!("gas metering is never enabled"),
 		};
 
-		let result_pointer = self.0.reg(Reg::A0);
-		let result_length = self.0.reg(Reg::A1);
-		let output = match self.0.read_memory(result_pointer as u32, result_length as u32) {
-			Ok(output) => output,
-			Err(error) => {
-				return (Err(format!("call into the runtime method '{name}' failed: failed to read the return payload: {error}").into()), None)
-			},
-		};
+		let mut state = HostState::new(heap_start);
+		let result = self.call(&mut state, name, raw_data);
 
-		(Ok(output), None)
+		(result, Some(state.allocator.stats()))
 	}
 }
 
-struct Context<'r, 'a>(&'r mut polkavm::Caller<'a, ()>);
+struct Context<'r, 'a>(&'r mut polkavm::Caller<'a, HostState>);
 
 impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	fn read_memory_into(
@@ -147,32 +322,22 @@ impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	}
 
 	fn allocate_memory(&mut self, size: WordSize) -> sp_wasm_interface::Result<Pointer<u8>> {
-		let pointer = match self.0.instance.sbrk(0) {
-			Ok(pointer) => pointer.expect("fetching the current heap pointer never fails"),
-			Err(err) => return Err(format!("sbrk failed: {err}")),
-		};
-
-		// TODO: This will leak guest memory; find a better solution.
-		match self.0.instance.sbrk(size) {
-			Ok(Some(_)) => (),
-			Ok(None) => return Err(String::from("allocation error")),
-			Err(err) => return Err(format!("sbrk failed: {err}")),
-		}
-
-		Ok(Pointer::new(pointer))
+		self.0.user_data.allocate(&mut *self.0.instance, size)
 	}
 
-	fn deallocate_memory(&mut self, _ptr: Pointer<u8>) -> sp_wasm_interface::Result<()> {
-		// This is only used by the allocator host function, which is unused under PolkaVM.
-		unimplemented!("'deallocate_memory' is never used when running under PolkaVM");
+	fn deallocate_memory(&mut self, ptr: Pointer<u8>) -> sp_wasm_interface::Result<()> {
+		self.0.user_data.deallocate(&mut *self.0.instance, ptr)
 	}
 
-	fn register_panic_error_message(&mut self, _message: &str) {
-		unimplemented!("'register_panic_error_message' is never used when running under PolkaVM");
+	fn register_panic_error_message(&mut self, message: &str) {
+		self.0.user_data.panic_message = Some(message.to_owned());
 	}
 }
 
-fn call_host_function(caller: &mut Caller<()>, function: &dyn Function) -> Result<(), String> {
+fn call_host_function(
+	caller: &mut Caller<HostState>,
+	function: &dyn Function,
+) -> Result<(), String> {
 	let mut args = [Value::I64(0); Reg::ARG_REGS.len()];
 	let mut nth_reg = 0;
 	for (nth_arg, kind) in function.signature().args.iter().enumerate() {
@@ -284,10 +449,268 @@ where
 	let mut linker = polkavm::Linker::new();
 
 	for function in H::host_functions() {
-		linker.define_untyped(function.name(), |mut caller: Caller<()>| {
+		linker.define_untyped(function.name(), |mut caller: Caller<HostState>| {
 			call_host_function(&mut caller, function)
 		})?;
 	}
 	let instance_pre = linker.instantiate_pre(&module)?;
 	Ok(Box::new(InstancePre(instance_pre)))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkavm_common::{
+		program::{asm, Instruction, Reg},
+		writer::ProgramBlobBuilder,
+	};
+	use sp_wasm_interface::Signature;
+
+	/// Host function that allocates the number of bytes given in its argument.
+	struct Malloc;
+
+	impl Function for Malloc {
+		fn name(&self) -> &str {
+			"malloc"
+		}
+
+		fn signature(&self) -> Signature {
+			Signature::new(vec![ValueType::I32], Some(ValueType::I32))
+		}
+
+		fn execute(
+			&self,
+			context: &mut dyn FunctionContext,
+			args: &mut dyn Iterator<Item = Value>,
+		) -> sp_wasm_interface::Result<Option<Value>> {
+			let Some(Value::I32(size)) = args.next() else { return Err("invalid size".into()) };
+			let pointer = context.allocate_memory(size as u32)?;
+			Ok(Some(Value::I32(u32::from(pointer) as i32)))
+		}
+	}
+
+	/// Host function that deallocates the pointer given in its argument.
+	struct Free;
+
+	impl Function for Free {
+		fn name(&self) -> &str {
+			"free"
+		}
+
+		fn signature(&self) -> Signature {
+			Signature::new_with_args(vec![ValueType::I32])
+		}
+
+		fn execute(
+			&self,
+			context: &mut dyn FunctionContext,
+			args: &mut dyn Iterator<Item = Value>,
+		) -> sp_wasm_interface::Result<Option<Value>> {
+			let Some(Value::I32(pointer)) = args.next() else {
+				return Err("invalid pointer".into())
+			};
+			context.deallocate_memory(Pointer::new(pointer as u32))?;
+			Ok(None)
+		}
+	}
+
+	/// Host function that registers a panic message, like the runtime does before it aborts.
+	struct Panic;
+
+	impl Function for Panic {
+		fn name(&self) -> &str {
+			"panic"
+		}
+
+		fn signature(&self) -> Signature {
+			Signature::new_with_args(&[] as &[ValueType])
+		}
+
+		fn execute(
+			&self,
+			context: &mut dyn FunctionContext,
+			_: &mut dyn Iterator<Item = Value>,
+		) -> sp_wasm_interface::Result<Option<Value>> {
+			context.register_panic_error_message("test panic");
+			Ok(None)
+		}
+	}
+
+	struct TestHostFunctions;
+
+	impl HostFunctions for TestHostFunctions {
+		fn host_functions() -> Vec<&'static dyn Function> {
+			vec![&Malloc as &dyn Function, &Free, &Panic]
+		}
+
+		sp_wasm_interface::if_wasmtime_is_enabled! {
+			fn register_static<T>(_: &mut T) -> core::result::Result<(), T::Error>
+			where
+				T: sp_wasm_interface::HostFunctionRegistry,
+			{
+				unimplemented!("only used by the wasmtime executor")
+			}
+		}
+	}
+
+	const MALLOC: u32 = 0;
+	const FREE: u32 = 1;
+	const PANIC: u32 = 2;
+
+	/// Builds a runtime that exports `code` as `test`.
+	fn runtime(code: &[Instruction]) -> Box<dyn WasmInstance> {
+		let mut builder = ProgramBlobBuilder::new();
+		builder.add_import(b"malloc");
+		builder.add_import(b"free");
+		builder.add_import(b"panic");
+		builder.add_export_by_basic_block(0, b"test");
+		builder.set_code(code, &[]);
+
+		let blob = polkavm::ProgramBlob::parse(builder.into_vec().into()).unwrap();
+		create_runtime::<TestHostFunctions>(&blob).unwrap().new_instance().unwrap()
+	}
+
+	/// The memory of a guest whose heap starts at `start`.
+	struct TestMemory {
+		start: u32,
+		heap: Vec<u8>,
+	}
+
+	impl TestMemory {
+		fn new(start: u32) -> Self {
+			Self { start, heap: Vec::new() }
+		}
+
+		fn range(&self, address: u32, length: usize) -> Result<std::ops::Range<usize>, String> {
+			let start = address.checked_sub(self.start).ok_or("out of bounds")? as usize;
+			(start + length <= self.heap.len())
+				.then(|| start..start + length)
+				.ok_or_else(|| "out of bounds".into())
+		}
+	}
+
+	impl GuestMemory for TestMemory {
+		fn read_bytes(&self, address: u32, buffer: &mut [u8]) -> Result<(), String> {
+			buffer.copy_from_slice(&self.heap[self.range(address, buffer.len())?]);
+			Ok(())
+		}
+
+		fn write_bytes(&mut self, address: u32, data: &[u8]) -> Result<(), String> {
+			let range = self.range(address, data.len())?;
+			self.heap[range].copy_from_slice(data);
+			Ok(())
+		}
+
+		fn grow_heap(&mut self, size: u32) -> Option<u32> {
+			self.heap.resize(self.heap.len() + size as usize, 0);
+			self.start.checked_add(self.heap.len() as u32)
+		}
+	}
+
+	#[test]
+	fn allocations_are_relative_to_the_heap_start() {
+		let mut memory = TestMemory::new(0x1000);
+		let mut state = HostState::new(0x1000);
+
+		let first = state.allocate(&mut memory, 16).unwrap();
+		assert_eq!(u32::from(first), 0x1008);
+		assert_eq!(state.heap.size, PAGE_SIZE);
+		assert_eq!(memory.heap.len(), PAGE_SIZE as usize);
+
+		let second = state.allocate(&mut memory, 16).unwrap();
+		assert_eq!(u32::from(second), 0x1020);
+
+		state.deallocate(&mut memory, first).unwrap();
+		assert_eq!(state.allocate(&mut memory, 16).unwrap(), first);
+	}
+
+	#[test]
+	fn headers_are_kept_in_guest_memory() {
+		let mut memory = TestMemory::new(0x1000);
+		let mut state = HostState::new(0x1000);
+
+		// The header of the first allocation of order 0 (8 bytes) marks it as occupied.
+		state.allocate(&mut memory, 8).unwrap();
+		assert_eq!(memory.heap[..8], (1u64 << 32).to_le_bytes());
+	}
+
+	#[test]
+	fn deallocating_a_pointer_outside_of_the_heap_fails() {
+		let mut memory = TestMemory::new(0x1000);
+		let mut state = HostState::new(0x1000);
+		state.allocate(&mut memory, 16).unwrap();
+
+		assert_eq!(
+			state.deallocate(&mut memory, Pointer::new(0x10)),
+			Err("invalid pointer for deallocation".into()),
+		);
+	}
+
+	#[test]
+	fn allocation_fails_if_the_heap_was_grown_by_someone_else() {
+		let mut memory = TestMemory::new(0x1000);
+		memory.grow_heap(PAGE_SIZE);
+		let mut state = HostState::new(0x1000);
+
+		assert!(state.allocate(&mut memory, 16).is_err());
+		assert_eq!(state.heap.size, 0);
+	}
+
+	#[test]
+	fn runtime_allocates_and_deallocates_memory() {
+		let mut instance = runtime(&[
+			asm::load_imm(Reg::A0, 32),
+			asm::ecalli(MALLOC),
+			asm::ecalli(FREE),
+			asm::load_imm(Reg::A0, 32),
+			asm::ecalli(MALLOC),
+			asm::load_imm(Reg::A1, 32),
+			asm::ret(),
+		]);
+
+		let (result, stats) = instance.call_with_allocation_stats("test", &[]);
+		assert_eq!(result.unwrap(), vec![0; 32]);
+
+		// The input payload takes 8 bytes and every allocation has an 8 byte header.
+		let stats = stats.unwrap();
+		assert_eq!(stats.bytes_allocated, 16 + 40);
+		assert_eq!(stats.bytes_allocated_sum, 16 + 40 + 40);
+		// The freed memory was reused by the second allocation.
+		assert_eq!(stats.address_space_used, 16 + 40);
+
+		// Every call starts with an empty heap.
+		let (result, stats) = instance.call_with_allocation_stats("test", &[]);
+		assert_eq!(result.unwrap(), vec![0; 32]);
+		assert_eq!(stats.unwrap().bytes_allocated_sum, 16 + 40 + 40);
+	}
+
+	#[test]
+	fn runtime_deallocating_an_invalid_pointer_fails() {
+		let mut instance = runtime(&[asm::load_imm(Reg::A0, 0), asm::ecalli(FREE), asm::ret()]);
+
+		let error = instance.call_with_allocation_stats("test", &[]).0.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Other: call into the runtime method 'test' failed: call into the host function \
+			 'free' failed: invalid pointer for deallocation",
+		);
+	}
+
+	#[test]
+	fn runtime_panic_message_is_reported() {
+		let mut instance = runtime(&[asm::ecalli(PANIC), asm::trap()]);
+
+		match instance.call_with_allocation_stats("test", &[]).0 {
+			Err(Error::AbortedDueToPanic(error)) => assert_eq!(error.message, "test panic"),
+			result => panic!("unexpected result: {result:?}"),
+		}
+	}
+
+	#[test]
+	fn runtime_trap_without_panic_message() {
+		let mut instance = runtime(&[asm::trap()]);
+
+		let error = instance.call_with_allocation_stats("test", &[]).0.unwrap_err();
+		assert_eq!(error.to_string(), "Other: call into the runtime method 'test' failed: trap");
+	}
+}
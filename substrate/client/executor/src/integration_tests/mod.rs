@@ -121,6 +121,7 @@ fn call_not_existing_function(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"call to a missing function env:missing_external",
+				WasmExecutionMethod::PolkaVm => unreachable!("only Wasm runtimes are tested"),
 			};
 			assert_eq!(error.message, expected);
 		},
@@ -140,6 +141,7 @@ fn call_yet_another_not_existing_function(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"call to a missing function env:yet_another_missing_external",
+				WasmExecutionMethod::PolkaVm => unreachable!("only Wasm runtimes are tested"),
 			};
 			assert_eq!(error.message, expected);
 		},
@@ -730,6 +732,7 @@ fn unreachable_intrinsic(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"wasm trap: wasm `unreachable` instruction executed",
+				WasmExecutionMethod::PolkaVm => unreachable!("only Wasm runtimes are tested"),
 			};
 			assert_eq!(error.message, expected);
 		},
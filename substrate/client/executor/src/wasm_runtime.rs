@@ -48,6 +48,10 @@ pub enum WasmExecutionMethod {
 		/// The instantiation strategy to use.
 		instantiation_strategy: sc_executor_wasmtime::InstantiationStrategy,
 	},
+	/// Uses PolkaVM and only accepts PolkaVM runtime blobs.
+	///
+	/// PolkaVM runtime blobs are always executed with PolkaVM, regardless of the chosen method.
+	PolkaVm,
 }
 
 impl Default for WasmExecutionMethod {
@@ -322,6 +326,8 @@ where
 				},
 			)
 			.map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
+		WasmExecutionMethod::PolkaVm =>
+			Err(WasmError::Other("the PolkaVM execution method requires a PolkaVM blob".into())),
 	}
 }
 
@@ -532,6 +538,21 @@ mod tests {
 		assert_eq!(4, version.system_version);
 	}
 
+	#[test]
+	fn polkavm_method_rejects_wasm_runtimes() {
+		let blob = RuntimeBlob::uncompress_if_needed(substrate_test_runtime::wasm_binary_unwrap())
+			.unwrap();
+
+		let result = create_wasm_runtime_with_code::<sp_io::SubstrateHostFunctions>(
+			WasmExecutionMethod::PolkaVm,
+			HeapAllocStrategy::Static { extra_pages: 0 },
+			blob,
+			false,
+			None,
+		);
+		assert!(result.is_err());
+	}
+
 	#[test]
 	fn embed_runtime_version_works() {
 		let wasm = sp_maybe_compressed_blob::decompress(
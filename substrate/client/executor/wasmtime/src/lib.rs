@@ -37,9 +37,9 @@ mod util;
 mod tests;
 
 pub use runtime::{
-	create_runtime, create_runtime_from_artifact, create_runtime_from_artifact_bytes,
-	prepare_runtime_artifact, Config, DeterministicStackLimit, InstantiationStrategy, Semantics,
-	WasmtimeRuntime,
+	artifact_compatibility_hash, create_runtime, create_runtime_from_artifact,
+	create_runtime_from_artifact_bytes, prepare_runtime_artifact, Config, DeterministicStackLimit,
+	InstantiationStrategy, Semantics, WasmtimeRuntime,
 };
 pub use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
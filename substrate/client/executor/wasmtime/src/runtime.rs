@@ -35,6 +35,7 @@ use sc_executor_common::{
 use sp_runtime_interface::unpack_ptr_and_len;
 use sp_wasm_interface::{HostFunctions, Pointer, WordSize};
 use std::{
+	hash::{Hash, Hasher},
 	path::{Path, PathBuf},
 	sync::{
 		atomic::{AtomicBool, Ordering},
@@ -676,6 +677,23 @@ pub fn prepare_runtime_artifact(
 		.map_err(|e| WasmError::Other(format!("cannot precompile module: {:#}", e)))
 }
 
+/// Returns a hash identifying the artifacts produced by [`prepare_runtime_artifact`] with the given
+/// semantics.
+///
+/// Artifacts can only be loaded if the hash is the same as when they were prepared. It covers the
+/// `wasmtime` version as well as the parts of its configuration which affect the artifacts.
+pub fn artifact_compatibility_hash(semantics: &Semantics) -> std::result::Result<u64, WasmError> {
+	let mut semantics = semantics.clone();
+	replace_strategy_if_broken(&mut semantics.instantiation_strategy);
+
+	let engine = Engine::new(&common_config(&semantics)?)
+		.map_err(|e| WasmError::Other(format!("cannot create the engine: {:#}", e)))?;
+
+	let mut hasher = std::collections::hash_map::DefaultHasher::new();
+	engine.precompile_compatibility_hash().hash(&mut hasher);
+	Ok(hasher.finish())
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: &mut InstanceWrapper,
@@ -26,6 +26,9 @@ use std::io;
 /// Local keystore implementation
 mod local;
 pub use local::LocalKeystore;
+/// Remote keystore implementation
+mod remote;
+pub use remote::{RemoteKeystore, RemoteSignerEndpoint, SignerDaemon};
 pub use sp_keystore::Keystore;
 
 /// Keystore error.
@@ -55,6 +58,9 @@ pub enum Error {
 	/// Keystore unavailable
 	#[error("Keystore unavailable")]
 	Unavailable,
+	/// The remote signer failed to process a request
+	#[error("Remote signer error: {0}")]
+	RemoteSigner(String),
 }
 
 /// Keystore Result
@@ -69,6 +75,7 @@ impl From<Error> for TraitError {
 			Error::Unavailable => TraitError::Unavailable,
 			Error::Io(e) => TraitError::Other(e.to_string()),
 			Error::Json(e) => TraitError::Other(e.to_string()),
+			Error::RemoteSigner(e) => TraitError::Other(e),
 		}
 	}
 }
@@ -77,6 +77,15 @@ impl LocalKeystore {
 		self.0.read().key_pair::<Pair>(public)
 	}
 
+	/// Get a key pair for the given public key and key type.
+	pub(crate) fn key_pair_by_type<Pair: CorePair>(
+		&self,
+		public: &Pair::Public,
+		key_type: KeyTypeId,
+	) -> Result<Option<Pair>> {
+		self.0.read().key_pair_by_type::<Pair>(public, key_type)
+	}
+
 	fn public_keys<T: CorePair>(&self, key_type: KeyTypeId) -> Vec<T::Public> {
 		self.0
 			.read()
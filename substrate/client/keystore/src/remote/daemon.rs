@@ -0,0 +1,338 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference implementation of a remote signer.
+
+use super::{from_hex, to_hex};
+use crate::LocalKeystore;
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId},
+	ecdsa, ed25519, sr25519, Encode,
+};
+use sp_keystore::Keystore;
+use std::sync::Arc;
+
+#[cfg(feature = "bandersnatch-experimental")]
+use sp_core::{bandersnatch, Decode};
+#[cfg(feature = "bls-experimental")]
+use sp_core::{bls381, ecdsa_bls381};
+
+#[cfg(unix)]
+use super::{transport, LOG_TARGET};
+#[cfg(unix)]
+use std::io::{self, BufReader};
+
+/// A signer answering requests of a [`RemoteKeystore`](super::RemoteKeystore) using the keys of
+/// the wrapped keystore.
+///
+/// This implements the protocol described in the [module documentation](super) and is mainly
+/// meant for tests and as a reference for other signer implementations. It doesn't do any
+/// authentication of its peers, so the socket must only be accessible by trusted processes.
+#[derive(Clone)]
+pub struct SignerDaemon {
+	keystore: Arc<LocalKeystore>,
+}
+
+impl SignerDaemon {
+	/// Create a signer using the keys stored in `keystore`.
+	pub fn new(keystore: Arc<LocalKeystore>) -> Self {
+		Self { keystore }
+	}
+
+	/// Serve requests received on the given Unix socket.
+	///
+	/// Every connection is handled on its own thread. This function only returns when accepting
+	/// a connection fails.
+	#[cfg(unix)]
+	pub fn serve_unix(&self, listener: std::os::unix::net::UnixListener) -> io::Result<()> {
+		loop {
+			let (stream, _) = listener.accept()?;
+			let daemon = self.clone();
+			std::thread::spawn(move || {
+				if let Err(e) = daemon.handle_unix_connection(stream) {
+					log::debug!(target: LOG_TARGET, "Unix connection failed: {e}");
+				}
+			});
+		}
+	}
+
+	#[cfg(unix)]
+	fn handle_unix_connection(&self, stream: std::os::unix::net::UnixStream) -> io::Result<()> {
+		let mut writer = stream.try_clone()?;
+		let mut reader = BufReader::new(stream);
+		while let Some(request) = transport::read_line_message(&mut reader)? {
+			transport::write_line_message(&mut writer, &self.handle(&request))?;
+		}
+		Ok(())
+	}
+
+	/// Handle a single request, returning the response to send back.
+	pub fn handle(&self, request: &Value) -> Value {
+		let id = request.get("id").cloned().unwrap_or(Value::Null);
+		let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+		let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+		match self.dispatch(method, &params) {
+			Ok(result) => json!({ "id": id, "result": result }),
+			Err(error) => json!({ "id": id, "error": error }),
+		}
+	}
+
+	fn dispatch(&self, method: &str, params: &Value) -> Result<Value, String> {
+		match method {
+			"public_keys" => {
+				let keys = self.public_keys(crypto(params)?, key_type(params)?)?;
+				Ok(keys.iter().map(|key| to_hex(key)).collect())
+			},
+			"keys" => {
+				let keys = self.keystore.keys(key_type(params)?).map_err(|e| e.to_string())?;
+				Ok(keys.iter().map(|key| to_hex(key)).collect())
+			},
+			"has_keys" => {
+				let keys = params
+					.get("keys")
+					.and_then(Value::as_array)
+					.ok_or("Missing `keys`")?
+					.iter()
+					.map(|pair| match pair.as_array().map(Vec::as_slice) {
+						Some([public, key_type]) => Ok((
+							from_hex(public).map_err(|e| e.to_string())?,
+							parse_key_type(from_hex(key_type).map_err(|e| e.to_string())?)?,
+						)),
+						_ => Err("Expected `[public, key_type]` pair".to_string()),
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(self.keystore.has_keys(&keys).into())
+			},
+			"generate" => {
+				let seed = params.get("seed").and_then(Value::as_str);
+				let public = self.generate(crypto(params)?, key_type(params)?, seed)?;
+				Ok(to_hex(&public).into())
+			},
+			"insert" => {
+				let suri = params.get("suri").and_then(Value::as_str).ok_or("Missing `suri`")?;
+				self.keystore
+					.insert(key_type(params)?, suri, &bytes(params, "public")?)
+					.map_err(|_| "Failed to insert key")?;
+				Ok(Value::Null)
+			},
+			"sign" => {
+				let signature = self
+					.keystore
+					.sign_with(
+						key_type(params)?,
+						crypto_id(crypto(params)?)?,
+						&bytes(params, "public")?,
+						&bytes(params, "message")?,
+					)
+					.map_err(|e| e.to_string())?;
+				Ok(signature.map(|s| to_hex(&s)).into())
+			},
+			"sign_prehashed" => {
+				if crypto(params)? != "ecdsa" {
+					return Err("`sign_prehashed` is only supported for `ecdsa`".into())
+				}
+				let public = ecdsa::Public::from_slice(&bytes(params, "public")?)
+					.map_err(|_| "Invalid public key")?;
+				let message = <[u8; 32]>::try_from(bytes(params, "message")?)
+					.map_err(|_| "Expected 32 byte message")?;
+				let signature = self
+					.keystore
+					.ecdsa_sign_prehashed(key_type(params)?, &public, &message)
+					.map_err(|e| e.to_string())?;
+				Ok(signature.map(|s| to_hex(s.as_ref())).into())
+			},
+			#[cfg(feature = "bls-experimental")]
+			"sign_with_keccak256" => {
+				if crypto(params)? != "ecdsa_bls381" {
+					return Err("`sign_with_keccak256` is only supported for `ecdsa_bls381`".into())
+				}
+				let public = ecdsa_bls381::Public::from_slice(&bytes(params, "public")?)
+					.map_err(|_| "Invalid public key")?;
+				let signature = self
+					.keystore
+					.ecdsa_bls381_sign_with_keccak256(
+						key_type(params)?,
+						&public,
+						&bytes(params, "message")?,
+					)
+					.map_err(|e| e.to_string())?;
+				Ok(signature.map(|s| to_hex(s.as_ref())).into())
+			},
+			"vrf_sign" => self.vrf_sign(params),
+			"vrf_pre_output" => self.vrf_pre_output(params),
+			_ => Err(format!("Unknown method `{method}`")),
+		}
+	}
+
+	fn vrf_sign(&self, params: &Value) -> Result<Value, String> {
+		let key_type = key_type(params)?;
+		let public = bytes(params, "public")?;
+		let input = bytes(params, "input")?;
+
+		let signature = match crypto(params)? {
+			"sr25519" => {
+				let public =
+					sr25519::Public::from_slice(&public).map_err(|_| "Invalid public key")?;
+				let point = input_point(input)?;
+				self.keystore
+					.key_pair_by_type::<sr25519::Pair>(&public, key_type)
+					.map_err(|e| e.to_string())?
+					.map(|pair| pair.vrf_sign_input_point(&point))
+					.transpose()
+					.map_err(|e| e.to_string())?
+					.map(|signature| signature.encode())
+			},
+			#[cfg(feature = "bandersnatch-experimental")]
+			"bandersnatch" => {
+				let public =
+					bandersnatch::Public::from_slice(&public).map_err(|_| "Invalid public key")?;
+				let data = bandersnatch::vrf::VrfSignData {
+					vrf_input: bandersnatch_input(input)?,
+					aux_data: bytes(params, "aux_data")?,
+				};
+				self.keystore
+					.bandersnatch_vrf_sign(key_type, &public, &data)
+					.map_err(|e| e.to_string())?
+					.map(|signature| signature.encode())
+			},
+			crypto => return Err(format!("VRF signing is not supported for `{crypto}`")),
+		};
+		Ok(signature.map(|s| to_hex(&s)).into())
+	}
+
+	fn vrf_pre_output(&self, params: &Value) -> Result<Value, String> {
+		let key_type = key_type(params)?;
+		let public = bytes(params, "public")?;
+		let input = bytes(params, "input")?;
+
+		let pre_output = match crypto(params)? {
+			"sr25519" => {
+				let public =
+					sr25519::Public::from_slice(&public).map_err(|_| "Invalid public key")?;
+				let point = input_point(input)?;
+				self.keystore
+					.key_pair_by_type::<sr25519::Pair>(&public, key_type)
+					.map_err(|e| e.to_string())?
+					.map(|pair| pair.vrf_pre_output_from_input_point(&point))
+					.transpose()
+					.map_err(|e| e.to_string())?
+					.map(|pre_output| pre_output.encode())
+			},
+			#[cfg(feature = "bandersnatch-experimental")]
+			"bandersnatch" => {
+				let public =
+					bandersnatch::Public::from_slice(&public).map_err(|_| "Invalid public key")?;
+				self.keystore
+					.bandersnatch_vrf_pre_output(key_type, &public, &bandersnatch_input(input)?)
+					.map_err(|e| e.to_string())?
+					.map(|pre_output| pre_output.encode())
+			},
+			crypto => return Err(format!("VRF signing is not supported for `{crypto}`")),
+		};
+		Ok(pre_output.map(|p| to_hex(&p)).into())
+	}
+
+	fn public_keys(&self, crypto: &str, key_type: KeyTypeId) -> Result<Vec<Vec<u8>>, String> {
+		fn to_vec(keys: Vec<impl ByteArray>) -> Vec<Vec<u8>> {
+			keys.into_iter().map(|key| key.to_raw_vec()).collect()
+		}
+
+		match crypto {
+			"sr25519" => Ok(to_vec(self.keystore.sr25519_public_keys(key_type))),
+			"ed25519" => Ok(to_vec(self.keystore.ed25519_public_keys(key_type))),
+			"ecdsa" => Ok(to_vec(self.keystore.ecdsa_public_keys(key_type))),
+			#[cfg(feature = "bandersnatch-experimental")]
+			"bandersnatch" => Ok(to_vec(self.keystore.bandersnatch_public_keys(key_type))),
+			#[cfg(feature = "bls-experimental")]
+			"bls381" => Ok(to_vec(self.keystore.bls381_public_keys(key_type))),
+			#[cfg(feature = "bls-experimental")]
+			"ecdsa_bls381" => Ok(to_vec(self.keystore.ecdsa_bls381_public_keys(key_type))),
+			_ => Err(format!("Unsupported crypto `{crypto}`")),
+		}
+	}
+
+	fn generate(
+		&self,
+		crypto: &str,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<Vec<u8>, String> {
+		let keystore = &self.keystore;
+		let public = match crypto {
+			"sr25519" => keystore.sr25519_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			"ed25519" => keystore.ed25519_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			"ecdsa" => keystore.ecdsa_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			#[cfg(feature = "bandersnatch-experimental")]
+			"bandersnatch" => keystore.bandersnatch_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			#[cfg(feature = "bls-experimental")]
+			"bls381" => keystore.bls381_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			#[cfg(feature = "bls-experimental")]
+			"ecdsa_bls381" => keystore.ecdsa_bls381_generate_new(key_type, seed).map(|k| k.to_raw_vec()),
+			_ => return Err(format!("Unsupported crypto `{crypto}`")),
+		};
+		public.map_err(|e| e.to_string())
+	}
+}
+
+fn crypto(params: &Value) -> Result<&str, String> {
+	params
+		.get("crypto")
+		.and_then(Value::as_str)
+		.ok_or_else(|| "Missing `crypto`".into())
+}
+
+fn crypto_id(crypto: &str) -> Result<CryptoTypeId, String> {
+	match crypto {
+		"sr25519" => Ok(sr25519::CRYPTO_ID),
+		"ed25519" => Ok(ed25519::CRYPTO_ID),
+		"ecdsa" => Ok(ecdsa::CRYPTO_ID),
+		#[cfg(feature = "bandersnatch-experimental")]
+		"bandersnatch" => Ok(bandersnatch::CRYPTO_ID),
+		#[cfg(feature = "bls-experimental")]
+		"bls381" => Ok(bls381::CRYPTO_ID),
+		#[cfg(feature = "bls-experimental")]
+		"ecdsa_bls381" => Ok(ecdsa_bls381::CRYPTO_ID),
+		_ => Err(format!("Unsupported crypto `{crypto}`")),
+	}
+}
+
+fn input_point(input: Vec<u8>) -> Result<[u8; 32], String> {
+	<[u8; 32]>::try_from(input).map_err(|_| "Expected 32 byte input point".into())
+}
+
+#[cfg(feature = "bandersnatch-experimental")]
+fn bandersnatch_input(input: Vec<u8>) -> Result<bandersnatch::vrf::VrfInput, String> {
+	bandersnatch::vrf::VrfInput::decode(&mut &input[..]).map_err(|_| "Invalid VRF input".into())
+}
+
+fn key_type(params: &Value) -> Result<KeyTypeId, String> {
+	parse_key_type(bytes(params, "key_type")?)
+}
+
+fn parse_key_type(key_type: Vec<u8>) -> Result<KeyTypeId, String> {
+	<[u8; 4]>::try_from(key_type)
+		.map(KeyTypeId)
+		.map_err(|_| "Invalid key type".into())
+}
+
+fn bytes(params: &Value, name: &str) -> Result<Vec<u8>, String> {
+	from_hex(params.get(name).ok_or_else(|| format!("Missing `{name}`"))?)
+		.map_err(|e| e.to_string())
+}
@@ -0,0 +1,656 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote keystore implementation.
+//!
+//! [`RemoteKeystore`] forwards key management and signing requests to an external signer, so
+//! that the secret keys never have to be stored on the node host. The signer is reached over a
+//! Unix domain socket, see [`RemoteSignerEndpoint`]. Access to the signer is controlled by the
+//! file system permissions of the socket, so it should only be accessible by the node's user.
+//!
+//! # Protocol
+//!
+//! Every request is a JSON object of the form
+//!
+//! ```json
+//! {"id": 1, "method": "sign", "params": { ... }}
+//! ```
+//!
+//! which is answered with either `{"id": 1, "result": ... }` or `{"id": 1, "error": "reason"}`.
+//!
+//! Requests and responses are newline-delimited and a single connection may carry any number of
+//! requests.
+//!
+//! Key types, public keys, messages and signatures are `0x`-prefixed hex strings. The `crypto`
+//! parameter names the signature scheme and is one of `sr25519`, `ed25519`, `ecdsa`, `bls381`,
+//! `ecdsa_bls381` or `bandersnatch`. The supported methods are:
+//!
+//! | Method | Parameters | Result |
+//! |--------|------------|--------|
+//! | `public_keys` | `crypto`, `key_type` | array of public keys |
+//! | `keys` | `key_type` | array of public keys of any scheme |
+//! | `has_keys` | `keys`: array of `[public, key_type]` pairs | boolean |
+//! | `generate` | `crypto`, `key_type`, optional `seed` string | public key |
+//! | `insert` | `key_type`, `suri` string, `public` | `null` |
+//! | `sign` | `crypto`, `key_type`, `public`, `message` | signature, `null` if the key is unknown |
+//! | `sign_prehashed` | `crypto` (`ecdsa`), `key_type`, `public`, 32 byte `message` | signature or `null` |
+//! | `sign_with_keccak256` | `crypto` (`ecdsa_bls381`), `key_type`, `public`, `message` | signature or `null` |
+//! | `vrf_sign` | `crypto`, `key_type`, `public`, `input`, `aux_data` (`bandersnatch` only) | SCALE encoded VRF signature or `null` |
+//! | `vrf_pre_output` | `crypto`, `key_type`, `public`, `input` | SCALE encoded VRF pre-output or `null` |
+//!
+//! The VRF methods are supported for `sr25519` and `bandersnatch`. As `sr25519` VRF inputs are
+//! transcripts which can't be transferred, the `input` is the 32 byte point the transcript is
+//! hashed to (see [`sr25519::Public::vrf_input_point`]), and sign data with extra data can't be
+//! signed. The `bandersnatch` `input` is the SCALE encoded VRF input. Ring VRF signing is not
+//! supported, as the ring prover can't be transferred to the signer.
+//!
+//! A reference implementation of the signer is provided by [`SignerDaemon`].
+
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{ByteArray, KeyTypeId, Pair as CorePair},
+	ecdsa, ed25519, sr25519, Decode,
+};
+use sp_keystore::{Error as TraitError, Keystore, KeystorePtr};
+use std::{
+	fmt,
+	path::PathBuf,
+	str::FromStr,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+};
+
+sp_keystore::bandersnatch_experimental_enabled! {
+use sp_core::{bandersnatch, Encode};
+}
+
+sp_keystore::bls_experimental_enabled! {
+use sp_core::{bls381, ecdsa_bls381};
+}
+
+use crate::{Error, Result};
+
+mod daemon;
+#[cfg(unix)]
+mod transport;
+
+pub use daemon::SignerDaemon;
+
+const LOG_TARGET: &str = "keystore::remote";
+
+/// Endpoint of a remote signer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteSignerEndpoint {
+	/// Unix domain socket at the given path.
+	Unix(PathBuf),
+}
+
+impl FromStr for RemoteSignerEndpoint {
+	type Err = String;
+
+	/// Parses `unix:<path>`.
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		let Some(path) = s.strip_prefix("unix:") else {
+			return Err(format!("Unsupported remote signer endpoint `{s}`, expected `unix:<PATH>`"))
+		};
+		if path.is_empty() {
+			return Err("Missing Unix socket path".into())
+		}
+		Ok(Self::Unix(path.into()))
+	}
+}
+
+impl fmt::Display for RemoteSignerEndpoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Unix(path) => write!(f, "unix:{}", path.display()),
+		}
+	}
+}
+
+fn to_hex(data: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", data)
+}
+
+fn from_hex(value: &Value) -> Result<Vec<u8>> {
+	value
+		.as_str()
+		.and_then(|s| array_bytes::hex2bytes(s).ok())
+		.ok_or_else(|| Error::RemoteSigner(format!("Expected hex string, got {value}")))
+}
+
+/// A keystore forwarding all requests to a remote signer.
+///
+/// See the [module documentation](self) for the protocol.
+pub struct RemoteKeystore {
+	endpoint: RemoteSignerEndpoint,
+	#[cfg(unix)]
+	connection: transport::UnixConnection,
+	next_id: AtomicU64,
+}
+
+impl RemoteKeystore {
+	/// Create a keystore forwarding requests to the signer at `endpoint`.
+	///
+	/// No connection is made until the first request. The connection is then kept open and
+	/// reused for all following requests.
+	pub fn new(endpoint: RemoteSignerEndpoint) -> Self {
+		#[cfg(unix)]
+		let connection = match &endpoint {
+			RemoteSignerEndpoint::Unix(path) => transport::UnixConnection::new(path.clone()),
+		};
+		Self {
+			endpoint,
+			#[cfg(unix)]
+			connection,
+			next_id: AtomicU64::new(0),
+		}
+	}
+
+	/// Returns the endpoint of the remote signer.
+	pub fn endpoint(&self) -> &RemoteSignerEndpoint {
+		&self.endpoint
+	}
+
+	/// Sends a request to the remote signer and returns the result.
+	fn request(&self, method: &str, params: Value) -> Result<Value> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let request = json!({ "id": id, "method": method, "params": params });
+
+		#[cfg(unix)]
+		let response = self.connection.request(&request);
+		#[cfg(not(unix))]
+		let response = Err::<Value, _>(std::io::Error::new(
+			std::io::ErrorKind::Unsupported,
+			"Unix sockets are not supported",
+		));
+		let response = response.inspect_err(
+			|e| log::debug!(target: LOG_TARGET, "Request {method} to {} failed: {e}", self.endpoint),
+		)?;
+
+		if response.get("id") != Some(&json!(id)) {
+			return Err(Error::RemoteSigner(format!("Unexpected response id to request {id}")))
+		}
+		if let Some(error) = response.get("error") {
+			let error =
+				error.as_str().map(ToString::to_string).unwrap_or_else(|| error.to_string());
+			return Err(Error::RemoteSigner(error))
+		}
+		response
+			.get("result")
+			.cloned()
+			.ok_or_else(|| Error::RemoteSigner("Missing result".into()))
+	}
+
+	fn public_keys<T: CorePair>(&self, crypto: &str, key_type: KeyTypeId) -> Vec<T::Public> {
+		let result = self
+			.request("public_keys", json!({ "crypto": crypto, "key_type": to_hex(&key_type.0) }));
+		match result {
+			Ok(Value::Array(keys)) => keys
+				.iter()
+				.filter_map(|key| from_hex(key).ok())
+				.filter_map(|key| T::Public::from_slice(&key).ok())
+				.collect(),
+			Ok(result) => {
+				log::warn!(target: LOG_TARGET, "Unexpected public keys result: {result}");
+				Vec::new()
+			},
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Failed to fetch {crypto} public keys: {e}");
+				Vec::new()
+			},
+		}
+	}
+
+	fn generate_new<T: CorePair>(
+		&self,
+		crypto: &str,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<T::Public, TraitError> {
+		let result = self.request(
+			"generate",
+			json!({ "crypto": crypto, "key_type": to_hex(&key_type.0), "seed": seed }),
+		)?;
+		T::Public::from_slice(&from_hex(&result)?)
+			.map_err(|_| TraitError::ValidationError("Invalid public key returned".into()))
+	}
+
+	fn sign<T: CorePair>(
+		&self,
+		method: &str,
+		crypto: &str,
+		key_type: KeyTypeId,
+		public: &T::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<T::Signature>, TraitError> {
+		let result = self.request(
+			method,
+			json!({
+				"crypto": crypto,
+				"key_type": to_hex(&key_type.0),
+				"public": to_hex(public.as_slice()),
+				"message": to_hex(msg),
+			}),
+		)?;
+		if result.is_null() {
+			return Ok(None)
+		}
+		T::Signature::from_slice(&from_hex(&result)?)
+			.map(Some)
+			.map_err(|_| TraitError::ValidationError("Invalid signature returned".into()))
+	}
+
+	fn vrf_request<T: Decode>(
+		&self,
+		method: &str,
+		crypto: &str,
+		key_type: KeyTypeId,
+		public: &[u8],
+		input: &[u8],
+		aux_data: Option<&[u8]>,
+	) -> std::result::Result<Option<T>, TraitError> {
+		let result = self.request(
+			method,
+			json!({
+				"crypto": crypto,
+				"key_type": to_hex(&key_type.0),
+				"public": to_hex(public),
+				"input": to_hex(input),
+				"aux_data": aux_data.map(to_hex),
+			}),
+		)?;
+		if result.is_null() {
+			return Ok(None)
+		}
+		T::decode(&mut &from_hex(&result)?[..])
+			.map(Some)
+			.map_err(|_| TraitError::ValidationError(format!("Invalid {method} result returned")))
+	}
+
+	fn unsupported<T>(&self, operation: &str) -> std::result::Result<T, TraitError> {
+		Err(TraitError::Other(format!("{operation} is not supported by the remote signer")))
+	}
+}
+
+impl Keystore for RemoteKeystore {
+	fn insert(
+		&self,
+		key_type: KeyTypeId,
+		suri: &str,
+		public: &[u8],
+	) -> std::result::Result<(), ()> {
+		self.request(
+			"insert",
+			json!({ "key_type": to_hex(&key_type.0), "suri": suri, "public": to_hex(public) }),
+		)
+		.map(|_| ())
+		.map_err(|e| log::warn!(target: LOG_TARGET, "Failed to insert key: {e}"))
+	}
+
+	fn keys(&self, key_type: KeyTypeId) -> std::result::Result<Vec<Vec<u8>>, TraitError> {
+		match self.request("keys", json!({ "key_type": to_hex(&key_type.0) }))? {
+			Value::Array(keys) => Ok(keys.iter().map(from_hex).collect::<Result<_>>()?),
+			result => Err(Error::RemoteSigner(format!("Unexpected keys result: {result}")).into()),
+		}
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys
+			.iter()
+			.map(|(public, key_type)| json!([to_hex(public), to_hex(&key_type.0)]))
+			.collect::<Vec<_>>();
+		match self.request("has_keys", json!({ "keys": keys })) {
+			Ok(result) => result.as_bool().unwrap_or_default(),
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Failed to check keys: {e}");
+				false
+			},
+		}
+	}
+
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys::<sr25519::Pair>("sr25519", key_type)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		self.generate_new::<sr25519::Pair>("sr25519", key_type, seed)
+	}
+
+	fn sr25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<sr25519::Signature>, TraitError> {
+		self.sign::<sr25519::Pair>("sign", "sr25519", key_type, public, msg)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		data: &sr25519::vrf::VrfSignData,
+	) -> std::result::Result<Option<sr25519::vrf::VrfSignature>, TraitError> {
+		if data.has_extra() {
+			return self.unsupported("VRF signing with extra data")
+		}
+		let point = public
+			.vrf_input_point(data.as_ref())
+			.map_err(|e| TraitError::ValidationError(e.to_string()))?;
+		self.vrf_request("vrf_sign", "sr25519", key_type, public.as_slice(), &point, None)
+	}
+
+	fn sr25519_vrf_pre_output(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &sr25519::vrf::VrfInput,
+	) -> std::result::Result<Option<sr25519::vrf::VrfPreOutput>, TraitError> {
+		let point = public
+			.vrf_input_point(input)
+			.map_err(|e| TraitError::ValidationError(e.to_string()))?;
+		self.vrf_request("vrf_pre_output", "sr25519", key_type, public.as_slice(), &point, None)
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys::<ed25519::Pair>("ed25519", key_type)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		self.generate_new::<ed25519::Pair>("ed25519", key_type, seed)
+	}
+
+	fn ed25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ed25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ed25519::Signature>, TraitError> {
+		self.sign::<ed25519::Pair>("sign", "ed25519", key_type, public, msg)
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys::<ecdsa::Pair>("ecdsa", key_type)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		self.generate_new::<ecdsa::Pair>("ecdsa", key_type, seed)
+	}
+
+	fn ecdsa_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign::<ecdsa::Pair>("sign", "ecdsa", key_type, public, msg)
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign::<ecdsa::Pair>("sign_prehashed", "ecdsa", key_type, public, msg)
+	}
+
+	sp_keystore::bandersnatch_experimental_enabled! {
+		fn bandersnatch_public_keys(&self, key_type: KeyTypeId) -> Vec<bandersnatch::Public> {
+			self.public_keys::<bandersnatch::Pair>("bandersnatch", key_type)
+		}
+
+		fn bandersnatch_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bandersnatch::Public, TraitError> {
+			self.generate_new::<bandersnatch::Pair>("bandersnatch", key_type, seed)
+		}
+
+		fn bandersnatch_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bandersnatch::Signature>, TraitError> {
+			self.sign::<bandersnatch::Pair>("sign", "bandersnatch", key_type, public, msg)
+		}
+
+		fn bandersnatch_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			data: &bandersnatch::vrf::VrfSignData,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfSignature>, TraitError> {
+			self.vrf_request(
+				"vrf_sign",
+				"bandersnatch",
+				key_type,
+				public.as_slice(),
+				&data.vrf_input.encode(),
+				Some(&data.aux_data),
+			)
+		}
+
+		fn bandersnatch_vrf_pre_output(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			input: &bandersnatch::vrf::VrfInput,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfPreOutput>, TraitError> {
+			self.vrf_request(
+				"vrf_pre_output",
+				"bandersnatch",
+				key_type,
+				public.as_slice(),
+				&input.encode(),
+				None,
+			)
+		}
+
+		fn bandersnatch_ring_vrf_sign(
+			&self,
+			_key_type: KeyTypeId,
+			_public: &bandersnatch::Public,
+			_data: &bandersnatch::vrf::VrfSignData,
+			_prover: &bandersnatch::ring_vrf::RingProver,
+		) -> std::result::Result<Option<bandersnatch::ring_vrf::RingVrfSignature>, TraitError> {
+			self.unsupported("Ring VRF signing")
+		}
+	}
+
+	sp_keystore::bls_experimental_enabled! {
+		fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+			self.public_keys::<bls381::Pair>("bls381", key_type)
+		}
+
+		fn bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bls381::Public, TraitError> {
+			self.generate_new::<bls381::Pair>("bls381", key_type, seed)
+		}
+
+		fn bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bls381::Signature>, TraitError> {
+			self.sign::<bls381::Pair>("sign", "bls381", key_type, public, msg)
+		}
+
+		fn ecdsa_bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa_bls381::Public> {
+			self.public_keys::<ecdsa_bls381::Pair>("ecdsa_bls381", key_type)
+		}
+
+		fn ecdsa_bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<ecdsa_bls381::Public, TraitError> {
+			self.generate_new::<ecdsa_bls381::Pair>("ecdsa_bls381", key_type, seed)
+		}
+
+		fn ecdsa_bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			self.sign::<ecdsa_bls381::Pair>("sign", "ecdsa_bls381", key_type, public, msg)
+		}
+
+		fn ecdsa_bls381_sign_with_keccak256(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			self.sign::<ecdsa_bls381::Pair>(
+				"sign_with_keccak256",
+				"ecdsa_bls381",
+				key_type,
+				public,
+				msg,
+			)
+		}
+	}
+}
+
+impl Into<KeystorePtr> for RemoteKeystore {
+	fn into(self) -> KeystorePtr {
+		Arc::new(self)
+	}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+	use crate::LocalKeystore;
+	use sp_core::{crypto::VrfPublic, testing::SR25519};
+
+	fn spawn_daemon() -> (SignerDaemon, KeystorePtr) {
+		let keystore = Arc::new(LocalKeystore::in_memory());
+		(SignerDaemon::new(keystore.clone()), keystore as KeystorePtr)
+	}
+
+	fn unix_keystore() -> (RemoteKeystore, KeystorePtr, tempfile::TempDir) {
+		let (daemon, keystore) = spawn_daemon();
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let path = temp_dir.path().join("signer.sock");
+		let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+		std::thread::spawn(move || daemon.serve_unix(listener));
+
+		(RemoteKeystore::new(RemoteSignerEndpoint::Unix(path)), keystore, temp_dir)
+	}
+
+	fn generate_and_sign(remote: &RemoteKeystore, local: &KeystorePtr) {
+		let public = remote.sr25519_generate_new(SR25519, None).unwrap();
+		assert_eq!(remote.sr25519_public_keys(SR25519), vec![public]);
+		assert_eq!(local.sr25519_public_keys(SR25519), vec![public]);
+
+		let signature = remote.sr25519_sign(SR25519, &public, b"message").unwrap().unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"message", &public));
+
+		let public = remote.ecdsa_generate_new(SR25519, Some("//Alice")).unwrap();
+		assert_eq!(public, ecdsa::Pair::from_string("//Alice", None).unwrap().public());
+		let signature = remote.ecdsa_sign_prehashed(SR25519, &public, &[1; 32]).unwrap().unwrap();
+		assert_eq!(signature.recover_prehashed(&[1; 32]), Some(public));
+	}
+
+	#[test]
+	fn unix_socket_works() {
+		let (remote, local, _temp_dir) = unix_keystore();
+		generate_and_sign(&remote, &local);
+	}
+
+	#[test]
+	fn unknown_keys_are_not_found() {
+		let (remote, _, _temp_dir) = unix_keystore();
+		let public = ed25519::Pair::from_string("//Bob", None).unwrap().public();
+
+		assert!(remote.ed25519_sign(SR25519, &public, b"message").unwrap().is_none());
+		assert!(!remote.has_keys(&[(public.to_raw_vec(), SR25519)]));
+		assert!(remote.keys(SR25519).unwrap().is_empty());
+	}
+
+	#[test]
+	fn insert_works() {
+		let (remote, local, _temp_dir) = unix_keystore();
+		let public = ed25519::Pair::from_string("//Bob", None).unwrap().public();
+
+		remote.insert(SR25519, "//Bob", public.as_slice()).unwrap();
+
+		assert!(remote.has_keys(&[(public.to_raw_vec(), SR25519)]));
+		assert_eq!(local.ed25519_public_keys(SR25519), vec![public]);
+		assert_eq!(remote.keys(SR25519).unwrap(), vec![public.to_raw_vec()]);
+	}
+
+	#[test]
+	fn sr25519_vrf_works() {
+		let (remote, local, _temp_dir) = unix_keystore();
+		let public = remote.sr25519_generate_new(SR25519, None).unwrap();
+		let input = sr25519::vrf::VrfInput::new(b"label", &[(b"domain", b"data")]);
+
+		let pre_output = remote.sr25519_vrf_pre_output(SR25519, &public, &input).unwrap();
+		assert_eq!(pre_output, local.sr25519_vrf_pre_output(SR25519, &public, &input).unwrap());
+
+		let data = input.clone().into_sign_data();
+		let signature = remote.sr25519_vrf_sign(SR25519, &public, &data).unwrap().unwrap();
+		assert!(public.vrf_verify(&data, &signature));
+
+		// Extra data can't be transferred to the signer.
+		let extra = sr25519::vrf::VrfTranscript::new(b"extra", &[]);
+		assert!(remote.sr25519_vrf_sign(SR25519, &public, &data.with_extra(extra)).is_err());
+
+		let unknown = sr25519::Pair::from_string("//Bob", None).unwrap().public();
+		assert!(remote.sr25519_vrf_pre_output(SR25519, &unknown, &input).unwrap().is_none());
+	}
+
+	#[test]
+	fn endpoint_parsing_works() {
+		assert_eq!(
+			"unix:/tmp/signer.sock".parse(),
+			Ok(RemoteSignerEndpoint::Unix("/tmp/signer.sock".into()))
+		);
+		assert_eq!(
+			"unix:/tmp/signer.sock".parse::<RemoteSignerEndpoint>().unwrap().to_string(),
+			"unix:/tmp/signer.sock"
+		);
+		assert!("unix:".parse::<RemoteSignerEndpoint>().is_err());
+		assert!("http://127.0.0.1:8000".parse::<RemoteSignerEndpoint>().is_err());
+	}
+}
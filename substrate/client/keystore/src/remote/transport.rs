@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transport used to exchange messages with a remote signer over a Unix socket.
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::{
+	io::{self, BufRead, BufReader, Read, Write},
+	os::unix::net::UnixStream,
+	path::PathBuf,
+	time::Duration,
+};
+
+use super::LOG_TARGET;
+
+/// Timeout of a single request to the remote signer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximal size of a single message accepted from the remote peer.
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Reads a single newline-delimited JSON message.
+///
+/// Returns `None` if the stream was closed before any data was received.
+pub(super) fn read_line_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+	let mut line = Vec::new();
+	let read = reader.take(MAX_MESSAGE_SIZE as u64).read_until(b'\n', &mut line)?;
+	if read == 0 {
+		return Ok(None)
+	}
+	if line.last() != Some(&b'\n') && read == MAX_MESSAGE_SIZE {
+		return Err(invalid_data("Message too large"))
+	}
+	serde_json::from_slice(&line).map(Some).map_err(Into::into)
+}
+
+/// Writes a single newline-delimited JSON message.
+pub(super) fn write_line_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+	let mut data = serde_json::to_vec(message)?;
+	data.push(b'\n');
+	writer.write_all(&data)?;
+	writer.flush()
+}
+
+/// Runs `f`, which blocks on the signer.
+///
+/// The keystore methods are synchronous but mostly called from async tasks. When called on a
+/// worker of a multi-threaded tokio runtime, its other tasks are moved to another worker first,
+/// so that waiting for the signer doesn't stall them.
+fn blocking<R>(f: impl FnOnce() -> R) -> R {
+	match tokio::runtime::Handle::try_current() {
+		Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread =>
+			tokio::task::block_in_place(f),
+		_ => f(),
+	}
+}
+
+/// A persistent connection to the signer listening on a Unix socket.
+///
+/// The connection is established on the first request and reused by the following ones, which
+/// are sent one at a time. A broken connection, e.g. after a restart of the signer, is replaced on
+/// the next request.
+pub(super) struct UnixConnection {
+	path: PathBuf,
+	stream: Mutex<Option<BufReader<UnixStream>>>,
+}
+
+impl UnixConnection {
+	/// Create a connection to the signer at `path`.
+	///
+	/// No connection is made until the first request.
+	pub(super) fn new(path: PathBuf) -> Self {
+		Self { path, stream: Mutex::new(None) }
+	}
+
+	fn connect(&self) -> io::Result<BufReader<UnixStream>> {
+		let stream = UnixStream::connect(&self.path)?;
+		stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+		stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+		Ok(BufReader::new(stream))
+	}
+
+	/// Sends the request to the signer and waits for the response.
+	pub(super) fn request(&self, request: &Value) -> io::Result<Value> {
+		blocking(|| {
+			let mut stream = self.stream.lock();
+
+			// A stale connection is only noticed when using it, in which case the request is sent
+			// once more on a new one. Timeouts are not retried, the signer may still be working
+			// on the request.
+			if let Some(reader) = stream.as_mut() {
+				match exchange(reader, request) {
+					Ok(response) => return Ok(response),
+					Err(e) if is_stale(&e) => {
+						log::debug!(target: LOG_TARGET, "Reconnecting to the remote signer: {e}")
+					},
+					Err(e) => {
+						*stream = None;
+						return Err(e)
+					},
+				}
+			}
+
+			*stream = None;
+			let mut reader = self.connect()?;
+			let response = exchange(&mut reader, request)?;
+			*stream = Some(reader);
+			Ok(response)
+		})
+	}
+}
+
+fn exchange(reader: &mut BufReader<UnixStream>, request: &Value) -> io::Result<Value> {
+	write_line_message(reader.get_mut(), request)?;
+	read_line_message(reader)?.ok_or_else(|| {
+		io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed by the remote signer")
+	})
+}
+
+fn is_stale(error: &io::Error) -> bool {
+	matches!(
+		error.kind(),
+		io::ErrorKind::BrokenPipe |
+			io::ErrorKind::ConnectionReset |
+			io::ErrorKind::ConnectionAborted |
+			io::ErrorKind::UnexpectedEof
+	)
+}
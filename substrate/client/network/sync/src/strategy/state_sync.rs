@@ -25,7 +25,7 @@ use crate::{
 use codec::{Decode, Encode};
 use log::debug;
 use sc_client_api::{CompactProof, KeyValueStates, ProofProvider};
-use sc_consensus::ImportedState;
+use sc_consensus::{ImportedState, ImportedStateData};
 use smallvec::SmallVec;
 use sp_core::storage::well_known_keys;
 use sp_runtime::{
@@ -307,7 +307,10 @@ where
 			ImportResult::Import(
 				target_hash,
 				self.metadata.target_header.clone(),
-				ImportedState { block: target_hash, state: std::mem::take(&mut self.state).into() },
+				ImportedState {
+					block: target_hash,
+					state: ImportedStateData::KeyValues(std::mem::take(&mut self.state).into()),
+				},
 				self.metadata.target_body.clone(),
 				self.metadata.target_justifications.clone(),
 			)
@@ -57,4 +57,16 @@ pub trait StatementApi {
 	/// Remove a statement from the store.
 	#[method(name = "statement_remove")]
 	fn remove(&self, statement_hash: [u8; 32]) -> RpcResult<()>;
+
+	/// Subscribe to the data of new statements which include all topics and whose decryption key
+	/// is identified as `dest`, or which have no `DecryptionKey` field if `dest` is not given.
+	///
+	/// Only statements accepted into the store after the subscription was made are reported. Use
+	/// `statement_broadcasts` or `statement_posted` to query the already known ones.
+	#[subscription(
+		name = "statement_subscribe" => "statement_statement",
+		unsubscribe = "statement_unsubscribe",
+		item = Bytes,
+	)]
+	fn subscribe(&self, match_all_topics: Vec<[u8; 32]>, dest: Option<[u8; 32]>);
 }
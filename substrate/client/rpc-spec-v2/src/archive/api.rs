@@ -116,6 +116,10 @@ pub trait ArchiveApi<Hash> {
 
 	/// Returns the storage difference between two blocks.
 	///
+	/// If `previous_hash` is not provided, the difference is computed against the parent block.
+	/// If no `items` are provided, the difference of the whole main trie is returned, followed by
+	/// the difference of every child trie whose root has changed between the two blocks.
+	///
 	/// # Unstable
 	///
 	/// This method is unstable and can change in minor or patch releases.
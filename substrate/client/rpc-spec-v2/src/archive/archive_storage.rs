@@ -25,6 +25,7 @@ use std::{
 
 use itertools::Itertools;
 use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sp_core::storage::well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
 use sp_runtime::traits::Block as BlockT;
 
 use super::error::Error as ArchiveError;
@@ -37,6 +38,7 @@ use crate::{
 		},
 		storage::Storage,
 	},
+	hex_string,
 };
 use tokio::sync::mpsc;
 
@@ -214,9 +216,9 @@ where
 		// Iterator over the current block and previous block
 		// at the same time to compare the keys. This approach effectively
 		// leverages backpressure to avoid memory consumption.
-		let keys_iter = self.client.raw_keys_iter(hash, maybe_child_trie.clone())?;
+		let keys_iter = self.client.raw_keys_iter(hash, maybe_child_trie.clone(), None)?;
 		let previous_keys_iter =
-			self.client.raw_keys_iter(previous_hash, maybe_child_trie.clone())?;
+			self.client.raw_keys_iter(previous_hash, maybe_child_trie.clone(), None)?;
 
 		let mut diff_iter = lexicographic_diff(keys_iter, previous_keys_iter);
 
@@ -286,6 +288,46 @@ where
 		Ok(())
 	}
 
+	/// Returns the query items for every default child trie whose root differs between the two
+	/// blocks, including the child tries that were created or removed.
+	///
+	/// The roots of the default child tries are stored in the main trie under the
+	/// `:child_storage:default:` prefix.
+	fn changed_child_tries(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+	) -> Result<Vec<Vec<DiffDetails>>, String> {
+		let prefix = StorageKey(DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec());
+		let keys_iter = self.client.raw_keys_iter(hash, None, Some(&prefix))?;
+		let previous_keys_iter = self.client.raw_keys_iter(previous_hash, None, Some(&prefix))?;
+
+		let mut child_tries = Vec::new();
+		for item in lexicographic_diff(keys_iter, previous_keys_iter) {
+			let key = match item {
+				Diff::Added(key) | Diff::Deleted(key) => key,
+				Diff::Equal(key) => {
+					let root = self.client.query_value(hash, &key, None)?;
+					let previous_root = self.client.query_value(previous_hash, &key, None)?;
+					if root == previous_root {
+						continue
+					}
+					key
+				},
+			};
+
+			let child_trie_key = key.0[prefix.0.len()..].to_vec();
+			child_tries.push(vec![DiffDetails {
+				key: StorageKey(Vec::new()),
+				return_type: ArchiveStorageDiffType::Value,
+				child_trie_key_string: Some(hex_string(&child_trie_key)),
+				child_trie_key: Some(ChildInfo::new_default_from_vec(child_trie_key)),
+			}]);
+		}
+
+		Ok(child_tries)
+	}
+
 	/// This method will iterate over the keys of the main trie or a child trie and fetch the
 	/// given keys. The fetched keys will be sent to the provided `tx` sender to leverage
 	/// the backpressure mechanism.
@@ -307,9 +349,18 @@ where
 					return
 				},
 			};
-			// Default to using the main storage trie if no items are provided.
+			// Default to using the main storage trie and all the changed child tries if no items
+			// are provided.
 			if trie_items.is_empty() {
 				trie_items.push(Vec::new());
+
+				match this.changed_child_tries(hash, previous_hash) {
+					Ok(child_tries) => trie_items.extend(child_tries),
+					Err(error) => {
+						let _ = tx.blocking_send(ArchiveStorageDiffEvent::err(error));
+						return
+					},
+				}
 			}
 			log::trace!(target: LOG_TARGET, "Storage diff deduplicated items: {:?}", trie_items);
 
@@ -36,14 +36,16 @@ use jsonrpsee::{
 };
 
 use sc_block_builder::BlockBuilderBuilder;
-use sc_client_api::ChildInfo;
+use sc_client_api::{
+	Backend as _, BlockImportOperation as _, ChildInfo, NewBlockState, StateBackend,
+};
 use sc_rpc::testing::TokioTestExecutor;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
 use sp_core::{Blake2Hasher, Hasher};
 use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT},
-	SaturatedConversion,
+	SaturatedConversion, StateVersion,
 };
 use std::{collections::HashMap, sync::Arc};
 use substrate_test_runtime::Transfer;
@@ -62,6 +64,12 @@ type Header = substrate_test_runtime_client::runtime::Header;
 type Block = substrate_test_runtime_client::runtime::Block;
 
 fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let (client, _backend, api) = setup_api_with_backend();
+	(client, api)
+}
+
+fn setup_api_with_backend(
+) -> (Arc<Client<Backend>>, Arc<Backend>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
 	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
 	let builder = TestClientBuilder::new().add_extra_child_storage(
 		&child_info,
@@ -73,13 +81,13 @@ fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Clien
 
 	let api = Archive::new(
 		client.clone(),
-		backend,
+		backend.clone(),
 		CHAIN_GENESIS,
 		Arc::new(TokioTestExecutor::default()),
 	)
 	.into_rpc();
 
-	(client, api)
+	(client, backend, api)
 }
 
 async fn get_next_event<T: serde::de::DeserializeOwned>(sub: &mut RpcSubscription) -> T {
@@ -999,3 +1007,105 @@ async fn archive_storage_diff_invalid_params() {
 		ArchiveStorageDiffEvent::StorageDiffError(ref err) if err.error.contains("Header was not found")
 	);
 }
+
+#[tokio::test]
+async fn archive_storage_diff_all_keys_skips_unchanged_child_tries() {
+	let (client, api) = setup_api();
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":A".to_vec(), Some(b"B".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Query all the keys against the parent block. The child trie set in `setup_api` is not
+	// modified by the block, so only main trie changes are reported.
+	let items: Vec<ArchiveStorageDiffItem<String>> = Vec::new();
+	let mut sub = api
+		.subscribe_unbounded("archive_v1_storageDiff", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	let mut added_key = false;
+	loop {
+		match get_next_event::<ArchiveStorageDiffEvent>(&mut sub).await {
+			ArchiveStorageDiffEvent::StorageDiff(result) => {
+				assert_eq!(result.child_trie_key, None);
+				if result.key == hex_string(b":A") {
+					assert_eq!(result.result, StorageResultType::Value(hex_string(b"B")));
+					assert_eq!(result.operation_type, ArchiveStorageDiffOperationType::Added);
+					added_key = true;
+				}
+			},
+			ArchiveStorageDiffEvent::StorageDiffDone => break,
+			event => panic!("Unexpected event {:?}", event),
+		}
+	}
+	assert!(added_key);
+}
+
+#[tokio::test]
+async fn archive_storage_diff_all_keys_reports_changed_child_tries() {
+	let (client, backend, api) = setup_api_with_backend();
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	// The test runtime can't modify child tries, so import a block which modifies the child trie
+	// set in `setup_api` directly through the backend.
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let child_delta = [(KEY, Some(&b"new child value"[..])), (&b":B"[..], Some(&b"C"[..]))];
+	let (state_root, transaction) = backend.state_at(genesis_hash).unwrap().full_storage_root(
+		std::iter::empty(),
+		std::iter::once((&child_info, child_delta.into_iter())),
+		StateVersion::V1,
+	);
+	let header = Header::new(1, Default::default(), state_root, genesis_hash, Default::default());
+	let block_hash = format!("{:?}", header.hash());
+
+	let mut op = backend.begin_operation().unwrap();
+	backend.begin_state_operation(&mut op, genesis_hash).unwrap();
+	op.update_db_storage(transaction).unwrap();
+	op.set_block_data(header, Some(vec![]), None, None, NewBlockState::Best)
+		.unwrap();
+	backend.commit_operation(op).unwrap();
+
+	let items: Vec<ArchiveStorageDiffItem<String>> = Vec::new();
+	let mut sub = api
+		.subscribe_unbounded("archive_v1_storageDiff", rpc_params![&block_hash, items])
+		.await
+		.unwrap();
+
+	let child_trie_key = Some(hex_string(&CHILD_STORAGE_KEY));
+	let mut child_results = Vec::new();
+	loop {
+		match get_next_event::<ArchiveStorageDiffEvent>(&mut sub).await {
+			ArchiveStorageDiffEvent::StorageDiff(result) if result.child_trie_key.is_some() =>
+				child_results.push(result),
+			// The root of the child trie is stored in the main trie and changes as well.
+			ArchiveStorageDiffEvent::StorageDiff(_) => {},
+			ArchiveStorageDiffEvent::StorageDiffDone => break,
+			event => panic!("Unexpected event {:?}", event),
+		}
+	}
+
+	assert_eq!(
+		child_results,
+		vec![
+			ArchiveStorageDiffResult {
+				key: hex_string(b":B"),
+				result: StorageResultType::Value(hex_string(b"C")),
+				operation_type: ArchiveStorageDiffOperationType::Added,
+				child_trie_key: child_trie_key.clone(),
+			},
+			ArchiveStorageDiffResult {
+				key: hex_string(&KEY),
+				result: StorageResultType::Value(hex_string(b"new child value")),
+				operation_type: ArchiveStorageDiffOperationType::Modified,
+				child_trie_key,
+			},
+		]
+	);
+}
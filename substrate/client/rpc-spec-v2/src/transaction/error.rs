@@ -96,6 +96,12 @@ impl<Hash> From<Error> for TransactionEvent<Hash> {
 				TransactionEvent::Invalid(TransactionError {
 					error: "The pool is not accepting future transactions".into(),
 				}),
+			Error::Pool(PoolError::SenderLimitReached) =>
+				TransactionEvent::Invalid(TransactionError {
+					error:
+						"The transaction could not enter the pool because of the per-sender limit"
+							.into(),
+				}),
 		}
 	}
 }
@@ -18,29 +18,47 @@
 
 //! Substrate statement store API.
 
+use crate::{
+	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	SubscriptionTaskExecutor,
+};
 use codec::{Decode, Encode};
+use futures::StreamExt;
 use jsonrpsee::{
 	core::{async_trait, RpcResult},
-	Extensions,
+	Extensions, PendingSubscriptionSink,
 };
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::statement::{error::Error, StatementApiServer};
 use sp_core::Bytes;
-use sp_statement_store::{StatementSource, SubmitResult};
+use sp_statement_store::{Statement, StatementSource, SubmitResult, Topic, MAX_TOPICS};
 use std::sync::Arc;
 
 /// Statement store API
 pub struct StatementStore {
 	store: Arc<dyn sp_statement_store::StatementStore>,
+	executor: SubscriptionTaskExecutor,
 }
 
 impl StatementStore {
 	/// Create new instance of Offchain API.
-	pub fn new(store: Arc<dyn sp_statement_store::StatementStore>) -> Self {
-		StatementStore { store }
+	pub fn new(
+		store: Arc<dyn sp_statement_store::StatementStore>,
+		executor: SubscriptionTaskExecutor,
+	) -> Self {
+		StatementStore { store, executor }
 	}
 }
 
+/// Returns `true` if the statement includes all of `match_all_topics` and its decryption key is
+/// `dest`.
+fn matches(statement: &Statement, match_all_topics: &[Topic], dest: Option<[u8; 32]>) -> bool {
+	statement.decryption_key() == dest &&
+		match_all_topics
+			.iter()
+			.all(|topic| (0..MAX_TOPICS).any(|i| statement.topic(i) == Some(*topic)))
+}
+
 #[async_trait]
 impl StatementApiServer for StatementStore {
 	fn dump(&self, ext: &Extensions) -> RpcResult<Vec<Bytes>> {
@@ -103,4 +121,28 @@ impl StatementApiServer for StatementStore {
 	fn remove(&self, hash: [u8; 32]) -> RpcResult<()> {
 		Ok(self.store.remove(&hash).map_err(|e| Error::StatementStore(e.to_string()))?)
 	}
+
+	fn subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		match_all_topics: Vec<[u8; 32]>,
+		dest: Option<[u8; 32]>,
+	) {
+		let stream = self.store.subscribe().filter_map(move |statement| {
+			let data = if matches(&statement, &match_all_topics, dest) {
+				statement.into_data().map(Bytes::from)
+			} else {
+				None
+			};
+			futures::future::ready(data)
+		});
+
+		let fut = async move {
+			PendingSubscription::from(pending)
+				.pipe_from_stream(stream, BoundedVecDeque::default())
+				.await;
+		};
+
+		spawn_subscription_task(&self.executor, fut);
+	}
 }
@@ -40,7 +40,7 @@ use sc_executor::{
 	sp_wasm_interface::HostFunctions, HeapAllocStrategy, NativeExecutionDispatch, RuntimeVersionOf,
 	WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
 };
-use sc_keystore::LocalKeystore;
+use sc_keystore::{LocalKeystore, RemoteKeystore};
 use sc_network::{
 	config::{FullNetworkConfiguration, ProtocolId, SyncMode},
 	multiaddr::Protocol,
@@ -109,29 +109,44 @@ pub type TFullCallExecutor<TBl, TExec> = crate::client::LocalCallExecutor<TBl, B
 type TFullParts<TBl, TRtApi, TExec> =
 	(TFullClient<TBl, TRtApi, TExec>, Arc<TFullBackend<TBl>>, KeystoreContainer, TaskManager);
 
-/// Construct a local keystore shareable container
-pub struct KeystoreContainer(Arc<LocalKeystore>);
+/// Construct a keystore shareable container
+pub struct KeystoreContainer {
+	keystore: KeystorePtr,
+	local: Option<Arc<LocalKeystore>>,
+}
 
 impl KeystoreContainer {
 	/// Construct KeystoreContainer
 	pub fn new(config: &KeystoreConfig) -> Result<Self, Error> {
-		let keystore = Arc::new(match config {
-			KeystoreConfig::Path { path, password } =>
-				LocalKeystore::open(path.clone(), password.clone())?,
-			KeystoreConfig::InMemory => LocalKeystore::in_memory(),
-		});
+		let (keystore, local) = match config {
+			KeystoreConfig::Path { path, password } => {
+				let local = Arc::new(LocalKeystore::open(path.clone(), password.clone())?);
+				(local.clone() as KeystorePtr, Some(local))
+			},
+			KeystoreConfig::InMemory => {
+				let local = Arc::new(LocalKeystore::in_memory());
+				(local.clone() as KeystorePtr, Some(local))
+			},
+			KeystoreConfig::Remote { endpoint } =>
+				(Arc::new(RemoteKeystore::new(endpoint.clone())) as KeystorePtr, None),
+		};
 
-		Ok(Self(keystore))
+		Ok(Self { keystore, local })
 	}
 
 	/// Returns a shared reference to a dynamic `Keystore` trait implementation.
 	pub fn keystore(&self) -> KeystorePtr {
-		self.0.clone()
+		self.keystore.clone()
 	}
 
-	/// Returns a shared reference to the local keystore .
-	pub fn local_keystore(&self) -> Arc<LocalKeystore> {
-		self.0.clone()
+	/// Returns a shared reference to the local keystore.
+	///
+	/// Fails if the keys are managed by a remote signer, as the secret keys are not available to
+	/// the node in this case.
+	pub fn local_keystore(&self) -> Result<Arc<LocalKeystore>, Error> {
+		self.local.clone().ok_or_else(|| {
+			Error::Other("The local keystore is not available when using a remote signer".into())
+		})
 	}
 }
 
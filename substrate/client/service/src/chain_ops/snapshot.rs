@@ -0,0 +1,638 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export and import of database snapshots.
+//!
+//! A snapshot contains the full state of a base block followed by the blocks built on top of it,
+//! up to the snapshot block. It is written as a stream of SCALE encoded [`SnapshotItem`]s,
+//! prefixed with [`SNAPSHOT_MAGIC`]:
+//!
+//! - a single [`SnapshotItem::Header`] with the base block,
+//! - any number of [`SnapshotItem::TopStorage`] and [`SnapshotItem::ChildStorage`] chunks,
+//! - any number of [`SnapshotItem::Block`]s, in ascending order,
+//! - a single [`SnapshotItem::End`].
+//!
+//! On import the state is streamed into the state trie of the base block, whose nodes are written
+//! to the database in bounded batches. The trie is checked against the state root of the base block
+//! before the block is imported. All the following blocks are then executed, so the whole snapshot
+//! is verified before it is used.
+
+use crate::error::Error;
+use codec::{Decode, Encode, IoReader as CodecIoReader};
+use futures::{future, task::Poll};
+use futures_timer::Delay;
+use hash_db::{AsHashDB, HashDB, Hasher, Prefix};
+use log::info;
+use sc_client_api::{backend::Backend, BlockBackend, HeaderBackend, StorageProvider};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState, ImportedStateData,
+};
+use sp_api::CallApiAt;
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys, ChildInfo, StateVersion},
+};
+use sp_runtime::{
+	generic::SignedBlock,
+	traits::{Block as BlockT, HashingFor, Header as HeaderT, NumberFor, One, Saturating, Zero},
+};
+use sp_state_machine::BackendTransaction;
+use sp_trie::{KeySpacedDBMut, LayoutV0, LayoutV1, TrieLayout, EMPTY_PREFIX};
+use std::{
+	collections::BTreeMap,
+	io::{Read, Write},
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use trie_db::{trie_visit, DBValue, TrieBuilder};
+
+/// Magic bytes every snapshot starts with.
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"substsnp";
+
+/// Version of the snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Maximum number of key value pairs in a single storage chunk.
+const CHUNK_SIZE: usize = 4096;
+
+/// Maximum size in bytes of the state trie nodes that are kept in memory during an import.
+const MAX_STATE_BATCH_SIZE: usize = 64 * 1024 * 1024;
+
+/// Maximum number of blocks that are queued for import at the same time.
+const MAX_PENDING_BLOCKS: u64 = 1_000;
+
+/// Number of milliseconds to wait until next poll of the import queue.
+const DELAY_TIME: u64 = 200;
+
+/// A single item of the snapshot stream.
+#[derive(Encode, Decode)]
+pub enum SnapshotItem<B: BlockT> {
+	/// The first item of the snapshot.
+	Header {
+		/// Version of the snapshot format.
+		version: u32,
+		/// Genesis hash of the chain the snapshot belongs to.
+		genesis_hash: B::Hash,
+		/// Version of the state trie of the base block.
+		state_version: StateVersion,
+		/// The block whose state is contained in the snapshot.
+		base: SignedBlock<B>,
+	},
+	/// A chunk of key value pairs of the top trie of the base block.
+	TopStorage(Vec<(Vec<u8>, Vec<u8>)>),
+	/// A chunk of key value pairs of a default child trie of the base block.
+	ChildStorage {
+		/// Unprefixed storage key of the child trie.
+		storage_key: Vec<u8>,
+		/// Key value pairs of the child trie.
+		entries: Vec<(Vec<u8>, Vec<u8>)>,
+	},
+	/// A block built on top of the base block.
+	Block(SignedBlock<B>),
+	/// The last item of the snapshot.
+	End,
+}
+
+/// Export a snapshot of the block with the given `hash`.
+///
+/// The state is exported at the block `blocks` before the snapshot block, the blocks in between
+/// are appended so they are re-executed on import.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	hash: B::Hash,
+	blocks: NumberFor<B>,
+	mut output: impl Write,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BA> + CallApiAt<B>,
+{
+	let info = client.info();
+	let number = client
+		.number(hash)?
+		.ok_or_else(|| format!("Unknown snapshot block {:?}", hash))?;
+	if number > info.finalized_number {
+		return Err(format!("Snapshot block #{} is not finalized", number).into())
+	}
+
+	let signed_block = |number: NumberFor<B>| -> Result<SignedBlock<B>, Error> {
+		client
+			.hash(number)?
+			.map(|hash| client.block(hash))
+			.transpose()?
+			.flatten()
+			.ok_or_else(|| format!("Missing block #{}", number).into())
+	};
+
+	let base = signed_block(number.saturating_sub(blocks))?;
+	let base_hash = base.block.header().hash();
+	let base_number = *base.block.header().number();
+	let state_version = client
+		.runtime_version_at(base_hash)
+		.map_err(|e| format!("Failed to get the runtime version of #{}: {}", base_number, e))?
+		.state_version();
+	info!("Exporting snapshot with the state of #{} and blocks up to #{}", base_number, number);
+
+	output.write_all(SNAPSHOT_MAGIC)?;
+	SnapshotItem::<B>::Header {
+		version: SNAPSHOT_VERSION,
+		genesis_hash: info.genesis_hash,
+		state_version,
+		base,
+	}
+	.encode_to(&mut output);
+
+	let mut child_storage_keys = Vec::new();
+	let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+	let mut entries = 0usize;
+	for (key, value) in client.storage_pairs(base_hash, None, None)? {
+		if let Some(storage_key) =
+			key.0.strip_prefix(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX)
+		{
+			child_storage_keys.push(storage_key.to_vec());
+		}
+		chunk.push((key.0, value.0));
+		if chunk.len() == CHUNK_SIZE {
+			entries += chunk.len();
+			SnapshotItem::<B>::TopStorage(std::mem::take(&mut chunk)).encode_to(&mut output);
+		}
+	}
+	if !chunk.is_empty() {
+		entries += chunk.len();
+		SnapshotItem::<B>::TopStorage(std::mem::take(&mut chunk)).encode_to(&mut output);
+	}
+
+	for storage_key in child_storage_keys {
+		let child_info = ChildInfo::new_default(&storage_key);
+		for key in client.child_storage_keys(base_hash, child_info.clone(), None, None)? {
+			if let Some(value) = client.child_storage(base_hash, &child_info, &key)? {
+				chunk.push((key.0, value.0));
+			}
+			if chunk.len() == CHUNK_SIZE {
+				entries += chunk.len();
+				SnapshotItem::<B>::ChildStorage {
+					storage_key: storage_key.clone(),
+					entries: std::mem::take(&mut chunk),
+				}
+				.encode_to(&mut output);
+			}
+		}
+		if !chunk.is_empty() {
+			entries += chunk.len();
+			SnapshotItem::<B>::ChildStorage { storage_key, entries: std::mem::take(&mut chunk) }
+				.encode_to(&mut output);
+		}
+	}
+	info!("Exported {} state entries", entries);
+
+	let mut block = base_number + One::one();
+	while block <= number {
+		SnapshotItem::Block(signed_block(block)?).encode_to(&mut output);
+		block += One::one();
+	}
+	SnapshotItem::<B>::End.encode_to(&mut output);
+	output.flush()?;
+
+	info!("🎉 Exported snapshot of #{} ({:?})", number, hash);
+	Ok(())
+}
+
+/// Import a snapshot into an empty database.
+///
+/// The state is read chunk by chunk into the state trie of the base block. The trie nodes are
+/// written to `backend` in batches of at most [`MAX_STATE_BATCH_SIZE`] bytes, the root node is
+/// imported together with the base block once it was checked. The remaining blocks are then queued
+/// for execution on top of it while they are read. The database should be discarded if the import
+/// fails, a corrupted state leaves unreachable trie nodes behind.
+pub async fn import_snapshot<B, BA, IQ, C>(
+	client: Arc<C>,
+	backend: Arc<BA>,
+	mut import_queue: IQ,
+	mut input: impl Read + Send,
+) -> Result<(), Error>
+where
+	B: BlockT,
+	BA: Backend<B> + 'static,
+	IQ: ImportQueue<B> + 'static,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+{
+	let info = client.info();
+	if !info.best_number.is_zero() {
+		return Err("Snapshots can only be imported into an empty database".into())
+	}
+
+	let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+	input.read_exact(&mut magic)?;
+	if &magic != SNAPSHOT_MAGIC {
+		return Err("Input is not a snapshot".into())
+	}
+
+	let mut reader = SnapshotReader::<B, _>::new(input);
+	let (state_version, base) = match reader.next()? {
+		SnapshotItem::Header { version, genesis_hash, state_version, base } => {
+			if version != SNAPSHOT_VERSION {
+				return Err(format!("Unsupported snapshot version {}", version).into())
+			}
+			if genesis_hash != info.genesis_hash {
+				return Err(format!(
+					"Snapshot of chain {:?} can not be imported into chain {:?}",
+					genesis_hash, info.genesis_hash
+				)
+				.into())
+			}
+			(state_version, base)
+		},
+		_ => return Err("Snapshot does not start with a header".into()),
+	};
+
+	let (header, body) = base.block.deconstruct();
+	let base_hash = header.hash();
+	info!("Importing snapshot with the state of #{} ({:?})", header.number(), base_hash);
+	let write = |nodes| backend.import_state_nodes(nodes);
+	let nodes = match state_version {
+		StateVersion::V0 =>
+			read_state::<B, LayoutV0<HashingFor<B>>, _>(&mut reader, header.state_root(), &write)?,
+		StateVersion::V1 =>
+			read_state::<B, LayoutV1<HashingFor<B>>, _>(&mut reader, header.state_root(), &write)?,
+	};
+
+	let link = WaitLink { imported_blocks: AtomicU64::new(0), has_error: AtomicBool::new(false) };
+	import_queue.service_ref().import_blocks(
+		BlockOrigin::File,
+		vec![IncomingBlock::<B> {
+			hash: base_hash,
+			header: Some(header),
+			body: Some(body),
+			indexed_body: None,
+			justifications: base.justifications,
+			origin: None,
+			allow_missing_state: false,
+			import_existing: true,
+			state: Some(ImportedState { block: base_hash, state: ImportedStateData::Trie(nodes) }),
+			skip_execution: false,
+		}],
+	);
+	let mut queued = 1u64;
+
+	loop {
+		match reader.next()? {
+			SnapshotItem::Block(block) => {
+				wait_for_import(&mut import_queue, &link, |imported| {
+					queued - imported < MAX_PENDING_BLOCKS
+				})
+				.await?;
+
+				let (header, body) = block.block.deconstruct();
+				import_queue.service_ref().import_blocks(
+					BlockOrigin::File,
+					vec![IncomingBlock {
+						hash: header.hash(),
+						header: Some(header),
+						body: Some(body),
+						indexed_body: None,
+						justifications: block.justifications,
+						origin: None,
+						allow_missing_state: false,
+						import_existing: false,
+						state: None,
+						skip_execution: false,
+					}],
+				);
+				queued += 1;
+			},
+			SnapshotItem::End => break,
+			SnapshotItem::Header { .. } => return Err("Unexpected snapshot header".into()),
+			SnapshotItem::TopStorage(_) | SnapshotItem::ChildStorage { .. } =>
+				return Err("Unexpected state entries after the snapshot state".into()),
+		}
+	}
+	wait_for_import(&mut import_queue, &link, |imported| imported >= queued).await?;
+
+	info!("🎉 Imported snapshot. Best: #{}", client.info().best_number);
+	Ok(())
+}
+
+/// Reads the state of the base block from `reader` and builds its state trie.
+///
+/// The trie nodes are handed to `write` in batches, only the remaining nodes and the root node are
+/// returned. Fails if the root of the built trie is not `state_root` or if the child tries don't
+/// match the child roots in the top trie.
+fn read_state<B, L, R>(
+	reader: &mut SnapshotReader<B, R>,
+	state_root: &B::Hash,
+	write: &WriteNodes<HashingFor<B>>,
+) -> Result<BackendTransaction<HashingFor<B>>, Error>
+where
+	B: BlockT,
+	L: TrieLayout<Hash = HashingFor<B>>,
+	R: Read,
+{
+	let mut nodes = NodeBatches::new(write);
+
+	let mut top = StorageEntries::new(reader, None, Vec::new());
+	let mut builder = TrieBuilder::<L, _>::new(&mut nodes);
+	trie_visit::<L, _, _, _, _>(&mut top, &mut builder);
+	let StorageEntries { child_roots: mut pending_children, mut count, .. } = top.finish()?;
+	if builder.root.as_ref() != Some(state_root) {
+		return Err("Snapshot state does not match the state root of the base block".into())
+	}
+	// The root node is the last inserted node, so it wasn't written yet. It is kept apart to be
+	// imported with the base block.
+	let root_node = nodes
+		.nodes
+		.remove_and_purge(state_root, EMPTY_PREFIX)
+		.ok_or("Missing the state root node of the base block")?;
+
+	loop {
+		let (storage_key, entries) = match reader.next()? {
+			SnapshotItem::ChildStorage { storage_key, entries } => (storage_key, entries),
+			item => {
+				reader.push_back(item);
+				break
+			},
+		};
+		let child_root = pending_children.remove(&storage_key).ok_or_else(|| {
+			format!("Unexpected entries of child trie 0x{}", HexDisplay::from(&storage_key))
+		})?;
+
+		let child_info = ChildInfo::new_default(&storage_key);
+		let mut child = StorageEntries::new(reader, Some(storage_key.clone()), entries);
+		let mut db = KeySpacedDBMut::new(&mut nodes, child_info.keyspace());
+		let mut builder = TrieBuilder::<L, _>::new(&mut db);
+		trie_visit::<L, _, _, _, _>(&mut child, &mut builder);
+		count += child.finish()?.count;
+		if builder.root.as_ref().map(AsRef::as_ref) != Some(&child_root[..]) {
+			return Err(format!(
+				"Snapshot state does not match the root of child trie 0x{}",
+				HexDisplay::from(&storage_key)
+			)
+			.into())
+		}
+	}
+	if let Some(storage_key) = pending_children.keys().next() {
+		return Err(
+			format!("Missing entries of child trie 0x{}", HexDisplay::from(storage_key)).into()
+		)
+	}
+
+	let mut nodes = nodes.finish()?;
+	HashDB::emplace(&mut nodes, *state_root, EMPTY_PREFIX, root_node);
+
+	info!("Imported {} state entries", count);
+	Ok(nodes)
+}
+
+/// Writes a batch of state trie nodes to the database.
+type WriteNodes<H> = dyn Fn(BackendTransaction<H>) -> sp_blockchain::Result<()> + Send + Sync;
+
+/// Trie node database that writes the inserted nodes in batches of at most
+/// [`MAX_STATE_BATCH_SIZE`] bytes.
+///
+/// A batch is written before a node is inserted, so the last inserted node is always kept. Write
+/// errors are returned by [`Self::finish`].
+struct NodeBatches<'a, H: Hasher> {
+	nodes: BackendTransaction<H>,
+	/// Size of the nodes in the current batch.
+	size: usize,
+	write: &'a WriteNodes<H>,
+	error: Option<String>,
+}
+
+impl<'a, H: Hasher> NodeBatches<'a, H> {
+	fn new(write: &'a WriteNodes<H>) -> Self {
+		Self { nodes: Default::default(), size: 0, write, error: None }
+	}
+
+	/// Makes room for a node of `len` bytes, writing the current batch if it is full.
+	fn reserve(&mut self, len: usize) {
+		if self.size > 0 && self.size + len > MAX_STATE_BATCH_SIZE {
+			let nodes = std::mem::take(&mut self.nodes);
+			self.size = 0;
+			if self.error.is_none() {
+				if let Err(e) = (self.write)(nodes) {
+					self.error = Some(e.to_string());
+				}
+			}
+		}
+		self.size += len;
+	}
+
+	/// Returns the nodes that were not written yet.
+	fn finish(self) -> Result<BackendTransaction<H>, Error> {
+		match self.error {
+			Some(e) => Err(format!("Error writing the snapshot state: {}", e).into()),
+			None => Ok(self.nodes),
+		}
+	}
+}
+
+impl<'a, H: Hasher> HashDB<H, DBValue> for NodeBatches<'a, H> {
+	fn get(&self, key: &H::Out, prefix: Prefix) -> Option<DBValue> {
+		HashDB::get(&self.nodes, key, prefix)
+	}
+
+	fn contains(&self, key: &H::Out, prefix: Prefix) -> bool {
+		HashDB::contains(&self.nodes, key, prefix)
+	}
+
+	fn insert(&mut self, prefix: Prefix, value: &[u8]) -> H::Out {
+		self.reserve(value.len());
+		HashDB::insert(&mut self.nodes, prefix, value)
+	}
+
+	fn emplace(&mut self, key: H::Out, prefix: Prefix, value: DBValue) {
+		self.reserve(value.len());
+		HashDB::emplace(&mut self.nodes, key, prefix, value)
+	}
+
+	fn remove(&mut self, key: &H::Out, prefix: Prefix) {
+		HashDB::remove(&mut self.nodes, key, prefix)
+	}
+}
+
+impl<'a, H: Hasher> AsHashDB<H, DBValue> for NodeBatches<'a, H> {
+	fn as_hash_db(&self) -> &dyn HashDB<H, DBValue> {
+		self
+	}
+
+	fn as_hash_db_mut<'b>(&'b mut self) -> &'b mut (dyn HashDB<H, DBValue> + 'b) {
+		self
+	}
+}
+
+/// Reads the items of a snapshot one by one.
+struct SnapshotReader<B: BlockT, R> {
+	input: CodecIoReader<R>,
+	/// Item that was read, but not consumed yet.
+	peeked: Option<SnapshotItem<B>>,
+}
+
+impl<B: BlockT, R: Read> SnapshotReader<B, R> {
+	fn new(input: R) -> Self {
+		Self { input: CodecIoReader(input), peeked: None }
+	}
+
+	/// Returns the next item of the snapshot.
+	fn next(&mut self) -> Result<SnapshotItem<B>, Error> {
+		match self.peeked.take() {
+			Some(item) => Ok(item),
+			None => SnapshotItem::decode(&mut self.input)
+				.map_err(|e| Error::Other(format!("Error decoding snapshot: {}", e))),
+		}
+	}
+
+	/// Puts back an item, it is returned by the next call to [`Self::next`].
+	fn push_back(&mut self, item: SnapshotItem<B>) {
+		debug_assert!(self.peeked.is_none());
+		self.peeked = Some(item);
+	}
+}
+
+/// Iterator over the key value pairs of consecutive storage chunks of the same trie.
+///
+/// Stops at the first item that doesn't belong to the trie or on the first error, which is
+/// returned by [`Self::finish`].
+struct StorageEntries<'a, B: BlockT, R> {
+	reader: &'a mut SnapshotReader<B, R>,
+	/// Storage key of the child trie or `None` for the top trie.
+	storage_key: Option<Vec<u8>>,
+	chunk: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+	last_key: Option<Vec<u8>>,
+	/// Roots of the child tries found in the top trie, by their unprefixed storage key.
+	child_roots: BTreeMap<Vec<u8>, Vec<u8>>,
+	/// Number of returned key value pairs.
+	count: usize,
+	error: Option<Error>,
+}
+
+impl<'a, B: BlockT, R: Read> StorageEntries<'a, B, R> {
+	fn new(
+		reader: &'a mut SnapshotReader<B, R>,
+		storage_key: Option<Vec<u8>>,
+		chunk: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Self {
+		Self {
+			reader,
+			storage_key,
+			chunk: chunk.into_iter(),
+			last_key: None,
+			child_roots: BTreeMap::new(),
+			count: 0,
+			error: None,
+		}
+	}
+
+	fn finish(self) -> Result<Self, Error> {
+		match self.error {
+			Some(error) => Err(error),
+			None => Ok(self),
+		}
+	}
+}
+
+impl<'a, B: BlockT, R: Read> Iterator for StorageEntries<'a, B, R> {
+	type Item = (Vec<u8>, Vec<u8>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while self.error.is_none() {
+			if let Some((key, value)) = self.chunk.next() {
+				// The trie is built on the fly, which requires the keys to be sorted.
+				if self.last_key.as_ref().map_or(false, |last| *last >= key) {
+					self.error = Some("Snapshot storage keys are not sorted".into());
+					return None
+				}
+				if self.storage_key.is_none() {
+					if let Some(storage_key) =
+						key.strip_prefix(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX)
+					{
+						self.child_roots.insert(storage_key.to_vec(), value.clone());
+					}
+				}
+				self.last_key = Some(key.clone());
+				self.count += 1;
+				return Some((key, value))
+			}
+
+			match self.reader.next() {
+				Ok(SnapshotItem::TopStorage(entries)) if self.storage_key.is_none() =>
+					self.chunk = entries.into_iter(),
+				Ok(SnapshotItem::ChildStorage { storage_key, entries })
+					if self.storage_key.as_ref() == Some(&storage_key) =>
+					self.chunk = entries.into_iter(),
+				Ok(item) => {
+					self.reader.push_back(item);
+					return None
+				},
+				Err(error) => self.error = Some(error),
+			}
+		}
+		None
+	}
+}
+
+/// Link that counts the imported blocks and remembers import errors.
+struct WaitLink {
+	imported_blocks: AtomicU64,
+	has_error: AtomicBool,
+}
+
+impl<B: BlockT> Link<B> for WaitLink {
+	fn blocks_processed(
+		&self,
+		imported: usize,
+		_num_expected_blocks: usize,
+		results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+	) {
+		self.imported_blocks.fetch_add(imported as u64, Ordering::AcqRel);
+
+		for (result, hash) in results {
+			if let Err(err) = result {
+				log::warn!("Error importing snapshot block {:?}: {}", hash, err);
+				self.has_error.store(true, Ordering::Release);
+			}
+		}
+	}
+}
+
+/// Polls `import_queue` until `done` returns `true` for the number of imported blocks.
+async fn wait_for_import<B: BlockT, IQ: ImportQueue<B>>(
+	import_queue: &mut IQ,
+	link: &WaitLink,
+	done: impl Fn(u64) -> bool,
+) -> Result<(), Error> {
+	loop {
+		future::poll_fn(|cx| {
+			import_queue.poll_actions(cx, link);
+			Poll::Ready(())
+		})
+		.await;
+		if link.has_error.load(Ordering::Acquire) {
+			return Err("Error importing snapshot".into())
+		}
+		if done(link.imported_blocks.load(Ordering::Acquire)) {
+			return Ok(())
+		}
+		Delay::new(Duration::from_millis(DELAY_TIME)).await;
+	}
+}
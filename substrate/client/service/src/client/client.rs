@@ -44,7 +44,8 @@ use sc_client_api::{
 	ProofProvider, UnpinWorkerMessage, UsageProvider,
 };
 use sc_consensus::{
-	BlockCheckParams, BlockImportParams, ForkChoiceStrategy, ImportResult, StateAction,
+	BlockCheckParams, BlockImportParams, ForkChoiceStrategy, ImportResult, ImportedState,
+	ImportedStateData, StateAction,
 };
 use sc_executor::RuntimeVersion;
 use sc_telemetry::{telemetry, TelemetryHandle, SUBSTRATE_INFO};
@@ -77,7 +78,10 @@ use sp_state_machine::{
 	ChildStorageCollection, KeyValueStates, KeyValueStorageLevel, StorageCollection,
 	MAX_NESTED_TRIE_DEPTH,
 };
-use sp_trie::{proof_size_extension::ProofSizeExt, CompactProof, MerkleValue, StorageProof};
+use sp_trie::{
+	proof_size_extension::ProofSizeExt, CompactProof, HashDBT, MerkleValue, StorageProof,
+	EMPTY_PREFIX,
+};
 use std::{
 	collections::{HashMap, HashSet},
 	marker::PhantomData,
@@ -610,9 +614,31 @@ where
 
 						Some((main_sc, child_sc))
 					},
-					sc_consensus::StorageChanges::Import(changes) => {
+					sc_consensus::StorageChanges::Import(ImportedState {
+						state: ImportedStateData::Trie(nodes),
+						..
+					}) => {
+						// The trie was already built and checked by the importer, make sure it
+						// is the state of this block.
+						if !HashDBT::contains(
+							&nodes,
+							import_headers.post().state_root(),
+							EMPTY_PREFIX,
+						) {
+							warn!("Error importing state: Missing state root node.");
+							return Err(Error::InvalidStateRoot)
+						}
+						self.backend
+							.begin_state_operation(&mut operation.op, Default::default())?;
+						operation.op.update_db_storage(nodes)?;
+						None
+					},
+					sc_consensus::StorageChanges::Import(ImportedState {
+						state: ImportedStateData::KeyValues(state),
+						..
+					}) => {
 						let mut storage = sp_storage::Storage::default();
-						for state in changes.state.0.into_iter() {
+						for state in state.0.into_iter() {
 							if state.parent_storage_keys.is_empty() && state.state_root.is_empty() {
 								for (key, value) in state.key_values.into_iter() {
 									storage.top.insert(key, value);
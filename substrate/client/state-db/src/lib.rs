@@ -46,7 +46,7 @@ mod pruning;
 #[cfg(test)]
 mod test;
 
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use log::trace;
 use noncanonical::NonCanonicalOverlay;
 use parking_lot::RwLock;
@@ -62,6 +62,10 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MODE_MIXED: &[u8] = b"mixed";
+const PRUNING_MODE_ARCHIVED_PREFIXES: &[u8] = b"mode_archived_prefixes";
+/// Length of the node hash suffix of the trie node keys.
+const NODE_HASH_LEN: usize = 32;
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
 
 /// Database value type.
@@ -79,6 +83,7 @@ pub trait Hash:
 	+ fmt::Debug
 	+ Codec
 	+ std::hash::Hash
+	+ AsRef<[u8]>
 	+ 'static
 {
 }
@@ -93,6 +98,7 @@ impl<
 			+ fmt::Debug
 			+ Codec
 			+ std::hash::Hash
+			+ AsRef<[u8]>
 			+ 'static,
 	> Hash for T
 {
@@ -145,6 +151,8 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// The pruning mode is not supported by the backing database.
+	UnsupportedPruningMode(PruningMode),
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -196,6 +204,8 @@ impl fmt::Debug for StateDbError {
 				write!(f, "Trying to get a block record from db while it is not commit to db yet")
 			},
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::UnsupportedPruningMode(mode) =>
+				write!(f, "Pruning mode {:?} is not supported by the database", mode),
 		}
 	}
 }
@@ -226,11 +236,63 @@ pub struct Constraints {
 	pub max_blocks: Option<u32>,
 }
 
+/// Constraints of the mixed pruning mode.
+///
+/// Trie nodes are identified by their prefixed database key, i.e. the nibble path of the node
+/// (prepended by the child trie keyspace for child tries) followed by the node hash. A node is
+/// archived if its path leads to, or lies below, any of the archived prefixes.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MixedConstraints {
+	/// Pruning window applied to the trie nodes that are not archived.
+	pub constraints: Constraints,
+	/// Storage key prefixes whose history is kept in the database.
+	pub archived_prefixes: Vec<Vec<u8>>,
+}
+
+impl MixedConstraints {
+	/// Archive all the keys of the top trie starting with `prefix`.
+	pub fn archive_prefix(&mut self, prefix: Vec<u8>) {
+		if !self.archived_prefixes.contains(&prefix) {
+			self.archived_prefixes.push(prefix);
+		}
+	}
+
+	/// Archive the default child trie stored under the unprefixed `storage_key`.
+	///
+	/// This keeps both the nodes of the child trie and the top trie nodes leading to its root.
+	pub fn archive_child_trie(&mut self, storage_key: &[u8]) {
+		self.archive_prefix(storage_key.to_vec());
+		self.archive_prefix([b":child_storage:default:", storage_key].concat());
+	}
+
+	/// Returns `true` if the trie node stored under the given database key is archived.
+	pub fn is_archived(&self, key: &[u8]) -> bool {
+		let path = &key[..key.len().saturating_sub(NODE_HASH_LEN)];
+		self.archived_prefixes.iter().any(|prefix| {
+			if path.len() > prefix.len() {
+				return path.starts_with(prefix)
+			}
+			// The last byte of the path may only hold the high nibble, so the nodes leading to
+			// the prefix are matched conservatively.
+			match path.split_last() {
+				None => true,
+				Some((last, full)) =>
+					prefix.starts_with(full) && prefix[full.len()] & 0xf0 == last & 0xf0,
+			}
+		})
+	}
+}
+
 /// Pruning mode.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum PruningMode {
 	/// Maintain a pruning window.
 	Constrained(Constraints),
+	/// Maintain a pruning window, but keep the history of the archived prefixes.
+	///
+	/// The state of canonical blocks that left the pruning window can still be opened, but only
+	/// the keys of the archived prefixes and child tries can be read from it.
+	Mixed(MixedConstraints),
 	/// No pruning. Canonicalization is a no-op.
 	ArchiveAll,
 	/// Canonicalization discards non-canonical nodes. All the canonical nodes are kept in the DB.
@@ -247,7 +309,7 @@ impl PruningMode {
 	pub fn is_archive(&self) -> bool {
 		match *self {
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => true,
-			PruningMode::Constrained(_) => false,
+			PruningMode::Constrained(_) | PruningMode::Mixed(_) => false,
 		}
 	}
 
@@ -257,6 +319,7 @@ impl PruningMode {
 			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
 			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
 			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::Mixed(_) => PRUNING_MODE_MIXED,
 		}
 	}
 
@@ -265,6 +328,7 @@ impl PruningMode {
 			PRUNING_MODE_ARCHIVE => Some(Self::ArchiveAll),
 			PRUNING_MODE_ARCHIVE_CANON => Some(Self::ArchiveCanonical),
 			PRUNING_MODE_CONSTRAINED => Some(Self::Constrained(Default::default())),
+			PRUNING_MODE_MIXED => Some(Self::Mixed(Default::default())),
 			_ => None,
 		}
 	}
@@ -317,8 +381,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 
 		let non_canonical: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(&db)?;
 		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
-			PruningMode::Constrained(Constraints { max_blocks }) =>
-				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
+			PruningMode::Constrained(Constraints { max_blocks }) |
+			PruningMode::Mixed(MixedConstraints {
+				constraints: Constraints { max_blocks },
+				..
+			}) => Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -338,10 +405,10 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 				// write changes immediately
 				Ok(CommitSet { data: changeset, meta: Default::default() })
 			},
-			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => self
-				.non_canonical
-				.insert(hash, number, parent_hash, changeset)
-				.map_err(Into::into),
+			PruningMode::Constrained(_) | PruningMode::Mixed(_) | PruningMode::ArchiveCanonical =>
+				self.non_canonical
+					.insert(hash, number, parent_hash, changeset)
+					.map_err(Into::into),
 		}
 	}
 
@@ -355,8 +422,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 			return Ok(commit)
 		}
 		let number = self.non_canonical.canonicalize(hash, &mut commit)?;
-		if self.mode == PruningMode::ArchiveCanonical {
-			commit.data.deleted.clear();
+		match &self.mode {
+			PruningMode::ArchiveCanonical => commit.data.deleted.clear(),
+			PruningMode::Mixed(mixed) =>
+				commit.data.deleted.retain(|key| !mixed.is_archived(key.as_ref())),
+			_ => {},
 		}
 		if let Some(ref mut pruning) = self.pruning {
 			pruning.note_canonical(hash, number, &mut commit)?;
@@ -377,10 +447,16 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 		}
 	}
 
+	/// Returns `true` if part of the state of the canonical blocks that left the pruning window
+	/// is kept in the database.
+	fn keeps_archived_state(&self) -> bool {
+		matches!(&self.mode, PruningMode::Mixed(mixed) if !mixed.archived_prefixes.is_empty())
+	}
+
 	fn is_pruned(&self, hash: &BlockHash, number: u64) -> IsPruned {
 		match self.mode {
 			PruningMode::ArchiveAll => IsPruned::NotPruned,
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) | PruningMode::Mixed(_) => {
 				if self
 					.non_canonical
 					.last_canonicalized_block_number()
@@ -397,6 +473,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 						// We don't know for sure.
 						None => IsPruned::MaybePruned,
 						Some(pruning) => match pruning.have_block(hash, number) {
+							// The archived state of canonical blocks is kept, which is only
+							// known by looking at the database.
+							HaveBlock::No if self.keeps_archived_state() => IsPruned::MaybePruned,
 							HaveBlock::No => IsPruned::Pruned,
 							HaveBlock::Yes => IsPruned::NotPruned,
 							HaveBlock::Maybe => IsPruned::MaybePruned,
@@ -408,9 +487,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	}
 
 	fn prune(&mut self, commit: &mut CommitSet<Key>) -> Result<(), Error<D::Error>> {
-		if let (&mut Some(ref mut pruning), PruningMode::Constrained(constraints)) =
-			(&mut self.pruning, &self.mode)
-		{
+		let constraints = match &self.mode {
+			PruningMode::Constrained(constraints) |
+			PruningMode::Mixed(MixedConstraints { constraints, .. }) => constraints,
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => return Ok(()),
+		};
+		if let Some(ref mut pruning) = self.pruning {
 			loop {
 				if pruning.window_size() <= constraints.max_blocks.unwrap_or(0) as u64 {
 					break
@@ -442,7 +524,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn revert_one(&mut self) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
+			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) | PruningMode::Mixed(_) =>
 				self.non_canonical.revert_one(),
 		}
 	}
@@ -450,7 +532,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn remove(&mut self, hash: &BlockHash) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
+			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) | PruningMode::Mixed(_) =>
 				self.non_canonical.remove(hash),
 		}
 	}
@@ -461,11 +543,13 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	{
 		match self.mode {
 			PruningMode::ArchiveAll => Ok(()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) | PruningMode::Mixed(_) => {
+				let keeps_archived_state = self.keeps_archived_state();
 				let have_block = self.non_canonical.have_block(hash) ||
 					self.pruning.as_ref().map_or_else(
 						|| hint(),
 						|pruning| match pruning.have_block(hash, number) {
+							HaveBlock::No if keeps_archived_state => hint(),
 							HaveBlock::No => false,
 							HaveBlock::Yes => true,
 							HaveBlock::Maybe => hint(),
@@ -556,6 +640,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 			(false, Some(stored), Some(requested)) => choose_pruning_mode(stored, requested)?,
 		};
 
+		// Archived nodes are matched by their path, which is only part of the key when the
+		// database keys are prefixed, i.e. when the backend does not count references itself.
+		if matches!(selected_mode, PruningMode::Mixed(_)) && !ref_counting {
+			return Err(StateDbError::UnsupportedPruningMode(selected_mode).into())
+		}
+
 		let db_init_commit_set = if should_init {
 			let mut cs: CommitSet<Key> = Default::default();
 
@@ -564,6 +654,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 
 			cs.meta.inserted.push((key, value));
 
+			if let PruningMode::Mixed(mixed) = &selected_mode {
+				let key = to_meta_key(PRUNING_MODE_ARCHIVED_PREFIXES, &());
+				cs.meta.inserted.push((key, mixed.archived_prefixes.encode()));
+			}
+
 			cs
 		} else {
 			Default::default()
@@ -671,10 +766,36 @@ pub enum IsPruned {
 	MaybePruned,
 }
 
+/// Convert the journals of a state database whose backend doesn't count the references of the
+/// state nodes itself for a backend that does.
+///
+/// `sanitize_key` maps the keys of the state nodes to the keys of the new backend. The returned
+/// journal records replace the records with the same keys in `db`, all other meta data stays the
+/// same. [`PruningMode::Mixed`] can't be converted, because it needs the keys of the state nodes
+/// to contain their path.
+pub fn convert_journals_to_ref_counting<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	sanitize_key: impl Fn(&mut Key),
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	if let Some(mode @ PruningMode::Mixed(_)) = fetch_stored_pruning_mode(db)? {
+		return Err(StateDbError::UnsupportedPruningMode(mode).into())
+	}
+
+	let mut converted = noncanonical::convert_journals::<BlockHash, Key, D>(db, &sanitize_key)?;
+	converted.extend(pruning::convert_journals::<BlockHash, Key, D>(db, &sanitize_key)?);
+	Ok(converted)
+}
+
 fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
 	let meta_key_mode = to_meta_key(PRUNING_MODE, &());
 	if let Some(stored_mode) = db.get_meta(&meta_key_mode).map_err(Error::Db)? {
-		if let Some(mode) = PruningMode::from_id(&stored_mode) {
+		if let Some(mut mode) = PruningMode::from_id(&stored_mode) {
+			if let PruningMode::Mixed(mixed) = &mut mode {
+				let meta_key_prefixes = to_meta_key(PRUNING_MODE_ARCHIVED_PREFIXES, &());
+				if let Some(prefixes) = db.get_meta(&meta_key_prefixes).map_err(Error::Db)? {
+					mixed.archived_prefixes = Decode::decode(&mut prefixes.as_slice())?;
+				}
+			}
 			Ok(Some(mode))
 		} else {
 			Err(StateDbError::Metadata(format!(
@@ -698,6 +819,10 @@ fn choose_pruning_mode(
 			Ok(PruningMode::ArchiveCanonical),
 		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
 			Ok(PruningMode::Constrained(requested)),
+		// The pruning window can change between runs, the archived prefixes can not.
+		(PruningMode::Mixed(stored), PruningMode::Mixed(requested))
+			if stored.archived_prefixes == requested.archived_prefixes =>
+			Ok(PruningMode::Mixed(requested)),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
 }
@@ -706,7 +831,7 @@ fn choose_pruning_mode(
 mod tests {
 	use crate::{
 		test::{make_changeset, make_db, TestDb},
-		Constraints, Error, IsPruned, PruningMode, StateDb, StateDbError,
+		Constraints, Error, IsPruned, MixedConstraints, PruningMode, StateDb, StateDbError,
 	};
 	use sp_core::H256;
 
@@ -946,4 +1071,76 @@ mod tests {
 			check_stored_and_requested_mode_compatibility(created, reopened, expected);
 		}
 	}
+	fn mixed_mode(max_blocks: u32, prefixes: &[&[u8]]) -> PruningMode {
+		PruningMode::Mixed(MixedConstraints {
+			constraints: Constraints { max_blocks: Some(max_blocks) },
+			archived_prefixes: prefixes.iter().map(|p| p.to_vec()).collect(),
+		})
+	}
+
+	#[test]
+	fn mixed_mode_archives_matching_paths() {
+		let mut mixed = MixedConstraints::default();
+		mixed.archive_prefix(vec![0x12, 0x34]);
+		mixed.archive_child_trie(b"child");
+		let node = |path: &[u8]| [path, &[0xaa; 32]].concat();
+
+		// The root and the nodes leading to the prefix.
+		assert!(mixed.is_archived(&node(&[])));
+		assert!(mixed.is_archived(&node(&[0x10])));
+		assert!(mixed.is_archived(&node(&[0x12])));
+		assert!(mixed.is_archived(&node(&[0x12, 0x30])));
+		// The nodes below the prefix.
+		assert!(mixed.is_archived(&node(&[0x12, 0x34, 0x50])));
+		assert!(mixed.is_archived(&node(b"child\x01\x02")));
+		assert!(mixed.is_archived(&node(b":child_storage:default:child")));
+		// Unrelated nodes.
+		assert!(!mixed.is_archived(&node(&[0x20])));
+		assert!(!mixed.is_archived(&node(&[0x12, 0x40])));
+		assert!(!mixed.is_archived(&node(&[0x12, 0x35, 0x00])));
+		assert!(!mixed.is_archived(&node(b":child_storage:default:other")));
+	}
+
+	#[test]
+	fn mixed_mode_requires_prefixed_keys() {
+		let db = make_db(&[]);
+		assert!(matches!(
+			StateDb::<H256, H256, TestDb>::open(db.clone(), Some(mixed_mode(1, &[])), false, true),
+			Err(Error::StateDb(StateDbError::UnsupportedPruningMode(_)))
+		));
+		assert!(
+			StateDb::<H256, H256, TestDb>::open(db, Some(mixed_mode(1, &[])), true, true).is_ok()
+		);
+	}
+
+	#[test]
+	fn mixed_mode_stores_archived_prefixes() {
+		let mut db = make_db(&[]);
+		let (state_db_init, _) = StateDb::<H256, H256, TestDb>::open(
+			db.clone(),
+			Some(mixed_mode(256, &[b"abc"])),
+			true,
+			true,
+		)
+		.unwrap();
+		db.commit(&state_db_init);
+
+		let (_, state_db) =
+			StateDb::<H256, H256, TestDb>::open(db.clone(), None, true, false).unwrap();
+		assert_eq!(state_db.pruning_mode(), mixed_mode(256, &[b"abc"]));
+
+		let (_, state_db) = StateDb::<H256, H256, TestDb>::open(
+			db.clone(),
+			Some(mixed_mode(128, &[b"abc"])),
+			true,
+			false,
+		)
+		.unwrap();
+		assert_eq!(state_db.pruning_mode(), mixed_mode(128, &[b"abc"]));
+
+		assert!(matches!(
+			StateDb::<H256, H256, TestDb>::open(db, Some(mixed_mode(256, &[b"xyz"])), true, false),
+			Err(Error::StateDb(StateDbError::IncompatiblePruningModes { .. }))
+		));
+	}
 }
@@ -568,6 +568,40 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	}
 }
 
+/// Convert the journals of the non-canonical blocks, see
+/// [`crate::convert_journals_to_ref_counting`].
+pub(crate) fn convert_journals<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	sanitize_key: &impl Fn(&mut Key),
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let last_canonicalized = db.get_meta(&to_meta_key(LAST_CANONICAL, &())).map_err(Error::Db)?;
+	let Some((_, mut block)) = last_canonicalized
+		.map(|buffer| <(BlockHash, u64)>::decode(&mut buffer.as_slice()))
+		.transpose()?
+	else {
+		return Ok(Vec::new())
+	};
+
+	let mut converted = Vec::new();
+	loop {
+		block += 1;
+		let level = converted.len();
+		for index in 0..MAX_BLOCKS_PER_LEVEL {
+			let journal_key = to_journal_key(block, index);
+			if let Some(record) = db.get_meta(&journal_key).map_err(Error::Db)? {
+				let mut record: JournalRecord<BlockHash, Key> =
+					Decode::decode(&mut record.as_slice())?;
+				record.inserted.iter_mut().for_each(|(key, _)| sanitize_key(key));
+				record.deleted.iter_mut().for_each(sanitize_key);
+				converted.push((journal_key, record.encode()));
+			}
+		}
+		if converted.len() == level {
+			return Ok(converted)
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::{to_journal_key, NonCanonicalOverlay};
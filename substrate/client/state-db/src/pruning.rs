@@ -25,12 +25,12 @@
 //! The changes are journaled in the DB.
 
 use crate::{
-	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError,
-	DEFAULT_MAX_BLOCK_CONSTRAINT, LOG_TARGET,
+	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, DBValue, Error, Hash, MetaDb,
+	StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT, LOG_TARGET,
 };
 use codec::{Decode, Encode};
 use log::trace;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 pub(crate) const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
@@ -437,15 +437,74 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 	}
 }
 
+/// Convert the pruning journals of a window that counts insertions itself, see
+/// [`crate::convert_journals_to_ref_counting`].
+///
+/// A key that is deleted by a block and inserted again by a later block is kept by such a window,
+/// so the deletion is dropped from the converted journal. So is any deletion of a key that is
+/// already pending deletion. The inserted keys are only needed to count insertions and are
+/// dropped as well.
+pub(crate) fn convert_journals<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	sanitize_key: &impl Fn(&mut Key),
+) -> Result<Vec<(Vec<u8>, DBValue)>, Error<D::Error>> {
+	let base = match db.get_meta(&to_meta_key(LAST_PRUNED, &())).map_err(Error::Db)? {
+		Some(buffer) => u64::decode(&mut buffer.as_slice())? + 1,
+		None => 0,
+	};
+
+	let mut records = Vec::<(BlockHash, HashSet<Key>)>::new();
+	let mut death_index = HashMap::<Key, usize>::new();
+	while let Some(record) =
+		db.get_meta(&to_journal_key(base + records.len() as u64)).map_err(Error::Db)?
+	{
+		let JournalRecord::<BlockHash, Key> { hash, inserted, deleted } =
+			Decode::decode(&mut record.as_slice())?;
+		for key in inserted {
+			if let Some(index) = death_index.remove(&key) {
+				records[index].1.remove(key);
+			}
+		}
+		let mut pending = HashSet::new();
+		for key in deleted {
+			if let Entry::Vacant(entry) = death_index.entry(key.clone()) {
+				entry.insert(records.len());
+				pending.insert(key);
+			}
+		}
+		records.push((hash, pending));
+	}
+
+	let sanitize = |mut key: Key| {
+		sanitize_key(&mut key);
+		key
+	};
+	Ok(records
+		.into_iter()
+		.enumerate()
+		.map(|(index, (hash, deleted))| {
+			let record = JournalRecord {
+				hash,
+				inserted: Vec::new(),
+				deleted: deleted.into_iter().map(sanitize).collect(),
+			};
+			(to_journal_key(base + index as u64), record.encode())
+		})
+		.collect())
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow, LAST_PRUNED};
+	use super::{
+		convert_journals, to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow,
+		LAST_PRUNED,
+	};
 	use crate::{
 		noncanonical::LAST_CANONICAL,
 		test::{make_commit, make_db, TestDb},
-		to_meta_key, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
+		to_meta_key, ChangeSet, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
-	use codec::Encode;
+	use codec::{Decode, Encode};
 	use sp_core::H256;
 
 	fn check_journal(pruning: &RefWindow<H256, H256, TestDb>, db: &TestDb) {
@@ -907,4 +966,42 @@ mod tests {
 			assert_eq!(HaveBlock::Yes, pruning.have_block(&block, block));
 		}
 	}
+
+	#[test]
+	fn converted_journals_keep_reinserted_keys() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+		let mut commit = make_commit(&[4], &[1, 2]);
+		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
+		db.commit(&commit);
+		let mut commit = make_commit(&[1], &[3]);
+		pruning.note_canonical(&H256::random(), 1, &mut commit).unwrap();
+		commit
+			.meta
+			.inserted
+			.push((to_meta_key(LAST_CANONICAL, &()), (H256::random(), 1u64).encode()));
+		db.commit(&commit);
+
+		// Key 1 is inserted again by block 1, so block 0 only deletes key 2.
+		let converted = convert_journals::<H256, H256, _>(&db, &|_| ()).unwrap();
+		assert_eq!(converted.len(), 2);
+		assert_eq!(converted[0].0, to_journal_key(0));
+		let record = JournalRecord::<H256, H256>::decode(&mut &converted[0].1[..]).unwrap();
+		assert_eq!(record.deleted, vec![H256::from_low_u64_be(2)]);
+		let record = JournalRecord::<H256, H256>::decode(&mut &converted[1].1[..]).unwrap();
+		assert!(record.inserted.is_empty());
+		assert_eq!(record.deleted, vec![H256::from_low_u64_be(3)]);
+
+		// A window that relies on reference counting releases key 2 when pruning block 0.
+		db.commit(&CommitSet {
+			data: Default::default(),
+			meta: ChangeSet { inserted: converted, deleted: Vec::new() },
+		});
+		let mut pruning: RefWindow<H256, H256, TestDb> =
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit).unwrap();
+		assert_eq!(commit.data.deleted, vec![H256::from_low_u64_be(2)]);
+	}
 }
@@ -51,10 +51,12 @@ mod metrics;
 
 pub use sp_statement_store::{Error, StatementStore, MAX_TOPICS};
 
+use futures::StreamExt;
 use metrics::MetricsLink as PrometheusMetrics;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_keystore::LocalKeystore;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::{crypto::UncheckedFrom, hexdisplay::HexDisplay, traits::SpawnNamed, Decode, Encode};
@@ -64,7 +66,7 @@ use sp_statement_store::{
 		InvalidStatement, StatementSource, StatementStoreExt, ValidStatement, ValidateStatement,
 	},
 	AccountId, BlockHash, Channel, DecryptionKey, Hash, NetworkPriority, Proof, Result, Statement,
-	SubmitResult, Topic,
+	StatementStream, SubmitResult, Topic,
 };
 use std::{
 	collections::{BTreeMap, HashMap, HashSet},
@@ -82,6 +84,8 @@ const DEFAULT_MAX_TOTAL_SIZE: usize = 64 * 1024 * 1024;
 
 const MAINTENANCE_PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
 
+const SUBSCRIPTION_CHANNEL_WARNING_THRESHOLD: usize = 10_000;
+
 mod col {
 	pub const META: u8 = 0;
 	pub const STATEMENTS: u8 = 1;
@@ -203,6 +207,8 @@ pub struct Store {
 			+ Sync,
 	>,
 	keystore: Arc<LocalKeystore>,
+	// Senders of the active new statement subscriptions.
+	subscribers: Mutex<Vec<TracingUnboundedSender<Statement>>>,
 	// Used for testing
 	time_override: Option<u64>,
 	metrics: PrometheusMetrics,
@@ -571,6 +577,7 @@ impl Store {
 			index: RwLock::new(Index::new(options)),
 			validate_fn,
 			keystore,
+			subscribers: Default::default(),
 			time_override: None,
 			metrics: PrometheusMetrics::new(prometheus),
 		};
@@ -668,6 +675,14 @@ impl Store {
 		Ok(result)
 	}
 
+	/// Notify all active subscriptions about a newly accepted statement, dropping the ones that
+	/// were closed.
+	fn notify_subscribers(&self, statement: &Statement) {
+		self.subscribers
+			.lock()
+			.retain(|subscriber| subscriber.unbounded_send(statement.clone()).is_ok());
+	}
+
 	/// Perform periodic store maintenance
 	pub fn maintain(&self) {
 		log::trace!(target: LOG_TARGET, "Started store maintenance");
@@ -896,6 +911,7 @@ impl StatementStore for Store {
 				return SubmitResult::InternalError(Error::Db(e.to_string()))
 			}
 		} // Release index lock
+		self.notify_subscribers(&statement);
 		self.metrics.report(|metrics| metrics.submitted_statements.inc());
 		let network_priority = NetworkPriority::High;
 		log::trace!(target: LOG_TARGET, "Statement submitted: {:?}", HexDisplay::from(&hash));
@@ -925,11 +941,22 @@ impl StatementStore for Store {
 		}
 		Ok(())
 	}
+
+	/// Subscribe to new statements.
+	fn subscribe(&self) -> StatementStream {
+		let (sender, receiver) = tracing_unbounded(
+			"mpsc_statement_store_subscription",
+			SUBSCRIPTION_CHANNEL_WARNING_THRESHOLD,
+		);
+		self.subscribers.lock().push(sender);
+		receiver.boxed()
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::Store;
+	use futures::{FutureExt, StreamExt};
 	use sc_keystore::Keystore;
 	use sp_core::Pair;
 	use sp_statement_store::{
@@ -1111,6 +1138,30 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn subscription_receives_new_statements() {
+		let (store, _temp) = test_store();
+		let mut subscription = store.subscribe();
+		let statement0 = signed_statement(0);
+		let statement1 = signed_statement(1);
+		assert_eq!(
+			store.submit(statement0.clone(), StatementSource::Network),
+			SubmitResult::New(NetworkPriority::High)
+		);
+		assert_eq!(store.submit(statement0.clone(), StatementSource::Network), SubmitResult::Known);
+		assert_eq!(
+			store.submit(statement1.clone(), StatementSource::Network),
+			SubmitResult::New(NetworkPriority::High)
+		);
+		assert_eq!(subscription.next().now_or_never(), Some(Some(statement0)));
+		assert_eq!(subscription.next().now_or_never(), Some(Some(statement1)));
+		assert_eq!(subscription.next().now_or_never(), None);
+
+		drop(subscription);
+		store.submit(signed_statement(2), StatementSource::Network);
+		assert!(store.subscribers.lock().is_empty());
+	}
+
 	#[test]
 	fn save_and_load_statements() {
 		let (store, temp) = test_store();
@@ -69,6 +69,10 @@ pub enum Error {
 
 	#[error("The pool is not accepting future transactions")]
 	RejectedFutureTransaction,
+
+	/// The sender of the transaction reached its limit of transactions in the pool.
+	#[error("Transaction couldn't enter the pool because of the per-sender limit")]
+	SenderLimitReached,
 }
 
 impl Error {
@@ -87,7 +91,9 @@ impl Error {
 			// The node might be lagging behind, or during a warp sync.
 			Error::InvalidBlockId(_) |
 			// The pool is configured to not accept future transactions.
-			Error::RejectedFutureTransaction => {
+			Error::RejectedFutureTransaction |
+			// The sender's transactions may leave the pool later.
+			Error::SenderLimitReached => {
 				true
 			}
 			_ => false
@@ -21,15 +21,18 @@
 use crate::{
 	common::api::FullChainApi,
 	fork_aware_txpool::ForkAwareTxPool as ForkAwareFullPool,
-	graph::{base_pool::Transaction, ChainApi, ExtrinsicFor, ExtrinsicHash, IsValidator, Options},
+	graph::{
+		base_pool::Transaction, ChainApi, ExtrinsicFor, ExtrinsicHash, IsValidator, Options,
+		SenderIdentity, SenderLimits,
+	},
 	single_state_txpool::BasicPool as SingleStateFullPool,
-	TransactionPoolWrapper, LOG_TARGET,
+	RevalidationType, TransactionPoolWrapper, LOG_TARGET,
 };
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_transaction_pool_api::{LocalTransactionPool, MaintainedTransactionPool};
 use sp_core::traits::SpawnEssentialNamed;
-use sp_runtime::traits::Block as BlockT;
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use sp_runtime::{traits::Block as BlockT, Percent};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc, time::Duration};
 
 /// The type of transaction pool.
 #[derive(Debug, Clone)]
@@ -45,11 +48,16 @@ pub enum TransactionPoolType {
 pub struct TransactionPoolOptions {
 	txpool_type: TransactionPoolType,
 	options: Options,
+	journal_path: Option<PathBuf>,
 }
 
 impl Default for TransactionPoolOptions {
 	fn default() -> Self {
-		Self { txpool_type: TransactionPoolType::SingleState, options: Default::default() }
+		Self {
+			txpool_type: TransactionPoolType::SingleState,
+			options: Default::default(),
+			journal_path: None,
+		}
 	}
 }
 
@@ -81,7 +89,7 @@ impl TransactionPoolOptions {
 			Duration::from_secs(30 * 60)
 		};
 
-		TransactionPoolOptions { options, txpool_type }
+		TransactionPoolOptions { options, txpool_type, journal_path: None }
 	}
 
 	/// Creates predefined options for benchmarking
@@ -98,10 +106,37 @@ impl TransactionPoolOptions {
 				},
 				reject_future_transactions: false,
 				ban_time: Duration::from_secs(30 * 60),
+				sender_limits: Default::default(),
+				replacement_priority_bump: Percent::zero(),
 			},
 			txpool_type: TransactionPoolType::SingleState,
+			journal_path: None,
 		}
 	}
+
+	/// Sets the per-sender limits.
+	///
+	/// The limits are only enforced if the sender identity is provided, see
+	/// [`Builder::with_sender_identity`].
+	pub fn with_sender_limits(mut self, sender_limits: SenderLimits) -> Self {
+		self.options.sender_limits = sender_limits;
+		self
+	}
+
+	/// Sets the minimal priority increase required to replace a transaction providing the same
+	/// tag.
+	pub fn with_replacement_priority_bump(mut self, bump: Percent) -> Self {
+		self.options.replacement_priority_bump = bump;
+		self
+	}
+
+	/// Sets the path of the journal used to persist the pool contents across restarts.
+	///
+	/// The journal is disabled if `None` is given.
+	pub fn with_journal(mut self, journal_path: Option<PathBuf>) -> Self {
+		self.journal_path = journal_path;
+		self
+	}
 }
 
 /// `FullClientTransactionPool` is a trait that combines the functionality of
@@ -175,6 +210,7 @@ pub struct Builder<'a, Block, Client> {
 	prometheus: Option<&'a PrometheusRegistry>,
 	client: Arc<Client>,
 	spawner: Box<dyn SpawnEssentialNamed>,
+	sender_identity: Option<SenderIdentity<Block>>,
 	_phantom: PhantomData<(Client, Block)>,
 }
 
@@ -207,6 +243,7 @@ where
 			client,
 			is_validator,
 			prometheus: None,
+			sender_identity: None,
 		}
 	}
 
@@ -222,24 +259,61 @@ where
 		self
 	}
 
+	/// Sets the function returning the encoded sender of an extrinsic.
+	///
+	/// Required to enforce the per-sender limits. The function shall return the same identity for
+	/// all extrinsics of a single account, e.g. the encoded signer address.
+	pub fn with_sender_identity(
+		mut self,
+		sender_identity: impl Fn(&Block::Extrinsic) -> Option<Vec<u8>> + Send + Sync + 'static,
+	) -> Self {
+		self.sender_identity = Some(Arc::new(sender_identity));
+		self
+	}
+
 	/// Creates an instance of transaction pool.
 	pub fn build(self) -> TransactionPoolHandle<Block, Client> {
 		log::info!(target:LOG_TARGET, " creating {:?} txpool {:?}/{:?}.", self.options.txpool_type, self.options.options.ready, self.options.options.future);
+		let journal_path = self.options.journal_path;
+		let mut pool_api = FullChainApi::new(self.client.clone(), self.prometheus, &self.spawner);
+		if let Some(sender_identity) = self.sender_identity {
+			pool_api = pool_api.with_sender_identity(sender_identity);
+		}
+		let pool_api = Arc::new(pool_api);
+		let chain_info = self.client.usage_info().chain;
 		TransactionPoolWrapper::<Block, Client>(match self.options.txpool_type {
-			TransactionPoolType::SingleState => Box::new(SingleStateFullPool::new_full(
-				self.options.options,
-				self.is_validator,
-				self.prometheus,
-				self.spawner,
-				self.client,
-			)),
-			TransactionPoolType::ForkAware => Box::new(ForkAwareFullPool::new_full(
-				self.options.options,
-				self.is_validator,
-				self.prometheus,
-				self.spawner,
-				self.client,
-			)),
+			TransactionPoolType::SingleState => {
+				let pool = SingleStateFullPool::with_revalidation_type(
+					self.options.options,
+					self.is_validator,
+					pool_api,
+					self.prometheus,
+					RevalidationType::Full,
+					self.spawner,
+					chain_info.best_number,
+					chain_info.best_hash,
+					chain_info.finalized_hash,
+				);
+				match journal_path {
+					Some(path) => Box::new(pool.with_journal(path)),
+					None => Box::new(pool),
+				}
+			},
+			TransactionPoolType::ForkAware => {
+				let pool = ForkAwareFullPool::new_with_background_worker(
+					self.options.options,
+					self.is_validator,
+					pool_api,
+					self.prometheus,
+					self.spawner,
+					chain_info.best_hash,
+					chain_info.finalized_hash,
+				);
+				match journal_path {
+					Some(path) => Box::new(pool.with_journal(path)),
+					None => Box::new(pool),
+				}
+			},
 		})
 	}
 }
@@ -47,11 +47,12 @@ use super::{
 use crate::graph;
 
 /// The transaction pool logic for full client.
-pub struct FullChainApi<Client, Block> {
+pub struct FullChainApi<Client, Block: BlockT> {
 	client: Arc<Client>,
 	_marker: PhantomData<Block>,
 	metrics: Option<Arc<ApiMetrics>>,
 	validation_pool: mpsc::Sender<Pin<Box<dyn Future<Output = ()> + Send>>>,
+	sender_identity: Option<graph::SenderIdentity<Block>>,
 }
 
 /// Spawn a validation task that will be used by the transaction pool to validate transactions.
@@ -76,7 +77,7 @@ fn spawn_validation_pool_task(
 	);
 }
 
-impl<Client, Block> FullChainApi<Client, Block> {
+impl<Client, Block: BlockT> FullChainApi<Client, Block> {
 	/// Create new transaction pool logic.
 	pub fn new(
 		client: Arc<Client>,
@@ -101,7 +102,21 @@ impl<Client, Block> FullChainApi<Client, Block> {
 		spawn_validation_pool_task("transaction-pool-task-0", receiver.clone(), spawner);
 		spawn_validation_pool_task("transaction-pool-task-1", receiver, spawner);
 
-		FullChainApi { client, validation_pool: sender, _marker: Default::default(), metrics }
+		FullChainApi {
+			client,
+			validation_pool: sender,
+			_marker: Default::default(),
+			metrics,
+			sender_identity: None,
+		}
+	}
+
+	/// Sets the function returning the sender of an extrinsic.
+	///
+	/// The per-sender limits of the pool are only enforced if the sender identity is known.
+	pub fn with_sender_identity(mut self, sender_identity: graph::SenderIdentity<Block>) -> Self {
+		self.sender_identity = Some(sender_identity);
+		self
 	}
 }
 
@@ -195,6 +210,10 @@ where
 		ex.using_encoded(|x| (<traits::HashingFor<Block> as traits::Hash>::hash(x), x.len()))
 	}
 
+	fn sender_of(&self, ex: &graph::RawExtrinsicFor<Self>) -> Option<Vec<u8>> {
+		self.sender_identity.as_ref().and_then(|sender_identity| sender_identity(ex))
+	}
+
 	fn block_header(
 		&self,
 		hash: <Self::Block as BlockT>::Hash,
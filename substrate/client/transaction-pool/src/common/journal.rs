@@ -0,0 +1,311 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk journal of the transaction pool contents.
+//!
+//! The journal keeps a copy of the pending transactions on disk, so they are not lost across
+//! node restarts. The pool contents are periodically written to the journal file and read back
+//! when the pool is created. Restored transactions are resubmitted to the pool (and thus
+//! revalidated against the best block) during the first maintenance, keeping their original
+//! submission time.
+
+use crate::{graph::base_pool::TimedTransactionSource, LOG_TARGET};
+use codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_transaction_pool_api::TransactionSource;
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, warn};
+
+/// The minimum interval between two consecutive periodic writes of the journal.
+const JOURNAL_STORE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The version of the journal file format.
+const JOURNAL_VERSION: u32 = 1;
+
+/// Single transaction persisted in the journal.
+#[derive(Encode, Decode)]
+struct JournalEntry {
+	/// The source the transaction was submitted from.
+	source: TransactionSource,
+	/// The submission time, in milliseconds since the unix epoch.
+	///
+	/// `None` if the pool doesn't track the submission time of its transactions.
+	submitted_at: Option<u64>,
+	/// The encoded transaction.
+	data: Vec<u8>,
+}
+
+/// Transactions read from the journal, ordered by their submission time.
+pub(crate) type RestoredTransactions<Xt> = Vec<(TimedTransactionSource, Xt)>;
+
+/// On-disk journal of the transaction pool contents.
+pub(crate) struct TransactionJournal<Xt> {
+	/// The path of the journal file.
+	path: PathBuf,
+	/// Transactions read from the journal which were not resubmitted to the pool yet.
+	///
+	/// The journal is not written until these transactions are taken, so they are not lost
+	/// if the node is stopped before the first maintenance.
+	restored: Mutex<Option<RestoredTransactions<Xt>>>,
+	/// The time of the most recent write.
+	last_store: Mutex<Instant>,
+}
+
+impl<Xt: Encode + Decode> TransactionJournal<Xt> {
+	/// Opens the journal at the given path and reads the transactions persisted in it.
+	///
+	/// A missing journal file is not an error; an unreadable one is reported and ignored.
+	pub(crate) fn open(path: PathBuf) -> Self {
+		let restored = Self::read(&path).unwrap_or_else(|error| {
+			warn!(target: LOG_TARGET, ?path, %error, "txpool journal: read failed");
+			Vec::new()
+		});
+		debug!(target: LOG_TARGET, ?path, count = restored.len(), "txpool journal: opened");
+		Self { path, restored: Mutex::new(Some(restored)), last_store: Mutex::new(Instant::now()) }
+	}
+
+	fn read(path: &Path) -> io::Result<RestoredTransactions<Xt>> {
+		let bytes = match fs::read(path) {
+			Ok(bytes) => bytes,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+			Err(error) => return Err(error),
+		};
+
+		let invalid_data = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+		let input = &mut &bytes[..];
+		let version = u32::decode(input).map_err(invalid_data)?;
+		if version != JOURNAL_VERSION {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("unsupported journal version: {version}"),
+			))
+		}
+		let mut entries = Vec::<JournalEntry>::decode(input).map_err(invalid_data)?;
+		entries.sort_by_key(|entry| entry.submitted_at);
+
+		let count = entries.len();
+		let (now, now_instant) = (SystemTime::now(), Instant::now());
+		let transactions = entries
+			.into_iter()
+			.filter_map(|entry| {
+				let xt = Xt::decode(&mut &entry.data[..]).ok()?;
+				// Timestamps from the future (e.g. after a clock adjustment) are clamped to now.
+				let timestamp = entry.submitted_at.map(|millis| {
+					now.duration_since(UNIX_EPOCH + Duration::from_millis(millis))
+						.ok()
+						.and_then(|age| now_instant.checked_sub(age))
+						.unwrap_or(now_instant)
+				});
+				Some((TimedTransactionSource { source: entry.source, timestamp }, xt))
+			})
+			.collect::<Vec<_>>();
+		if transactions.len() != count {
+			warn!(
+				target: LOG_TARGET,
+				?path,
+				skipped = count - transactions.len(),
+				"txpool journal: undecodable transactions skipped"
+			);
+		}
+
+		Ok(transactions)
+	}
+
+	/// Takes the transactions read from the journal which still need to be resubmitted.
+	///
+	/// Returns `None` if the transactions were already taken.
+	pub(crate) fn take_restored(&self) -> Option<RestoredTransactions<Xt>> {
+		self.restored.lock().take()
+	}
+
+	/// Writes the transactions provided by `transactions` to the journal, if enough time passed
+	/// since the most recent write.
+	pub(crate) fn maybe_store<I>(&self, transactions: impl FnOnce() -> I)
+	where
+		I: IntoIterator<Item = (TimedTransactionSource, Arc<Xt>)>,
+	{
+		{
+			let mut last_store = self.last_store.lock();
+			if last_store.elapsed() < JOURNAL_STORE_INTERVAL {
+				return
+			}
+			*last_store = Instant::now();
+		}
+		self.store(transactions())
+	}
+
+	/// Writes the given transactions to the journal, replacing its previous content.
+	///
+	/// Nothing is written while the transactions read from the journal were not taken.
+	pub(crate) fn store(
+		&self,
+		transactions: impl IntoIterator<Item = (TimedTransactionSource, Arc<Xt>)>,
+	) {
+		if self.restored.lock().is_some() {
+			return
+		}
+
+		let now = SystemTime::now();
+		let entries = transactions
+			.into_iter()
+			.map(|(source, xt)| {
+				let submitted_at = source.timestamp.map(|timestamp| {
+					now.checked_sub(timestamp.elapsed())
+						.unwrap_or(now)
+						.duration_since(UNIX_EPOCH)
+						.unwrap_or_default()
+						.as_millis() as u64
+				});
+				JournalEntry { source: source.source, submitted_at, data: xt.encode() }
+			})
+			.collect::<Vec<_>>();
+
+		match self.write(&(JOURNAL_VERSION, &entries).encode()) {
+			Ok(()) => debug!(
+				target: LOG_TARGET,
+				path = ?self.path,
+				count = entries.len(),
+				"txpool journal: stored"
+			),
+			Err(error) => warn!(
+				target: LOG_TARGET,
+				path = ?self.path,
+				%error,
+				"txpool journal: write failed"
+			),
+		}
+	}
+
+	fn write(&self, bytes: &[u8]) -> io::Result<()> {
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		// The journal is replaced atomically, so a crash while writing doesn't corrupt it.
+		let tmp_path = self.path.with_extension("tmp");
+		fs::write(&tmp_path, bytes)?;
+		fs::rename(&tmp_path, &self.path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn source(source: TransactionSource) -> TimedTransactionSource {
+		TimedTransactionSource::from_transaction_source(source, true)
+	}
+
+	/// The sources and transactions, without the timestamps.
+	fn untimed(
+		restored: Option<RestoredTransactions<u64>>,
+	) -> Option<Vec<(TransactionSource, u64)>> {
+		restored.map(|txs| txs.into_iter().map(|(source, xt)| (source.source, xt)).collect())
+	}
+
+	#[test]
+	fn missing_journal_restores_nothing() {
+		let dir = tempfile::tempdir().unwrap();
+		let journal = TransactionJournal::<u64>::open(dir.path().join("txpool.journal"));
+		assert_eq!(journal.take_restored(), Some(vec![]));
+		assert_eq!(journal.take_restored(), None);
+	}
+
+	#[test]
+	fn stored_transactions_are_restored_in_order() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("txpool.journal");
+
+		let journal = TransactionJournal::<u64>::open(path.clone());
+		journal.take_restored();
+		journal.store(vec![
+			(source(TransactionSource::External), Arc::new(1)),
+			(source(TransactionSource::External), Arc::new(2)),
+			(source(TransactionSource::Local), Arc::new(3)),
+		]);
+
+		let journal = TransactionJournal::<u64>::open(path);
+		assert_eq!(
+			untimed(journal.take_restored()),
+			Some(vec![
+				(TransactionSource::External, 1),
+				(TransactionSource::External, 2),
+				(TransactionSource::Local, 3),
+			])
+		);
+	}
+
+	#[test]
+	fn submission_time_is_restored() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("txpool.journal");
+		let age = Duration::from_secs(60);
+
+		let journal = TransactionJournal::<u64>::open(path.clone());
+		journal.take_restored();
+		let old = TimedTransactionSource {
+			source: TransactionSource::External,
+			timestamp: Instant::now().checked_sub(age),
+		};
+		journal.store(vec![
+			(source(TransactionSource::Local), Arc::new(1)),
+			(old, Arc::new(2)),
+			(
+				TimedTransactionSource::from_transaction_source(TransactionSource::External, false),
+				Arc::new(3),
+			),
+		]);
+
+		let restored = TransactionJournal::<u64>::open(path).take_restored().unwrap();
+		// Transactions without a submission time come first, the oldest one follows.
+		assert_eq!(restored.iter().map(|(_, xt)| *xt).collect::<Vec<_>>(), vec![3, 2, 1]);
+		assert_eq!(restored[0].0.timestamp, None);
+		let restored_age = restored[1].0.timestamp.unwrap().elapsed();
+		assert!(restored_age >= age && restored_age < age + Duration::from_secs(10));
+		assert!(restored[2].0.timestamp.unwrap().elapsed() < Duration::from_secs(10));
+	}
+
+	#[test]
+	fn journal_is_not_stored_before_restoring() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("txpool.journal");
+
+		let journal = TransactionJournal::<u64>::open(path.clone());
+		journal.take_restored();
+		journal.store(vec![(source(TransactionSource::External), Arc::new(1))]);
+
+		// the pending transactions are not overwritten by the (empty) pool contents.
+		let journal = TransactionJournal::<u64>::open(path.clone());
+		journal.store(vec![]);
+		assert_eq!(untimed(journal.take_restored()), Some(vec![(TransactionSource::External, 1)]));
+	}
+
+	#[test]
+	fn unsupported_journal_version_is_ignored() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("txpool.journal");
+		fs::write(&path, (JOURNAL_VERSION + 1, Vec::<JournalEntry>::new()).encode()).unwrap();
+
+		let journal = TransactionJournal::<u64>::open(path);
+		assert_eq!(journal.take_restored(), Some(vec![]));
+	}
+}
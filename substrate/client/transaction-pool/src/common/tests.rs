@@ -191,6 +191,11 @@ impl ChainApi for TestApi {
 		})
 	}
 
+	/// Returns the sender of transfers.
+	fn sender_of(&self, uxt: &RawExtrinsicFor<Self>) -> Option<Vec<u8>> {
+		TransferData::try_from(uxt).ok().map(|transfer| transfer.from.encode())
+	}
+
 	/// Hash the extrinsic.
 	fn hash_and_length(&self, uxt: &RawExtrinsicFor<Self>) -> (BlockHash<Self>, usize) {
 		let encoded = uxt.encode();
@@ -29,7 +29,10 @@ use super::{
 };
 use crate::{
 	api::FullChainApi,
-	common::tracing_log_xt::{log_xt_debug, log_xt_trace},
+	common::{
+		journal::TransactionJournal,
+		tracing_log_xt::{log_xt_debug, log_xt_trace},
+	},
 	enactment_state::{EnactmentAction, EnactmentState},
 	fork_aware_txpool::{
 		dropped_watcher::{DroppedReason, DroppedTransaction},
@@ -38,7 +41,8 @@ use crate::{
 	graph::{
 		self,
 		base_pool::{TimedTransactionSource, Transaction},
-		BlockHash, ExtrinsicFor, ExtrinsicHash, IsValidator, Options,
+		BlockHash, ExtrinsicFor, ExtrinsicHash, IsValidator, Options, RawExtrinsicFor,
+		ValidatedTransaction,
 	},
 	ReadyIteratorFor, LOG_TARGET,
 };
@@ -61,11 +65,12 @@ use sp_core::traits::SpawnEssentialNamed;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, NumberFor},
-	transaction_validity::{TransactionValidityError, ValidTransaction},
+	transaction_validity::{TransactionTag, TransactionValidityError, ValidTransaction},
 	Saturating,
 };
 use std::{
 	collections::{BTreeMap, HashMap, HashSet},
+	path::PathBuf,
 	pin::Pin,
 	sync::Arc,
 	time::Instant,
@@ -183,6 +188,9 @@ where
 	/// Intended to be used in the finality stall cleanups and also as a cache for all in-block
 	/// transactions.
 	included_transactions: Mutex<BTreeMap<HashAndNumber<Block>, Vec<ExtrinsicHash<ChainApi>>>>,
+
+	/// Optional on-disk journal of the mempool contents.
+	journal: Option<TransactionJournal<RawExtrinsicFor<ChainApi>>>,
 }
 
 impl<ChainApi, Block> ForkAwareTxPool<ChainApi, Block>
@@ -220,6 +228,26 @@ where
 		future_limits: crate::PoolLimit,
 		mempool_max_transactions_count: usize,
 		finality_timeout_threshold: Option<usize>,
+	) -> (Self, ForkAwareTxPoolTask) {
+		Self::new_test_with_options(
+			pool_api,
+			best_block_hash,
+			finalized_hash,
+			Options { ready: ready_limits, future: future_limits, ..Default::default() },
+			mempool_max_transactions_count,
+			finality_timeout_threshold,
+		)
+	}
+
+	/// Create new fork aware transaction pool with given options and with provided shared instance
+	/// of `ChainApi` intended for tests.
+	pub fn new_test_with_options(
+		pool_api: Arc<ChainApi>,
+		best_block_hash: Block::Hash,
+		finalized_hash: Block::Hash,
+		options: Options,
+		mempool_max_transactions_count: usize,
+		finality_timeout_threshold: Option<usize>,
 	) -> (Self, ForkAwareTxPoolTask) {
 		let (listener, listener_task) = MultiViewListener::new_with_worker(Default::default());
 		let listener = Arc::new(listener);
@@ -232,7 +260,8 @@ where
 			listener.clone(),
 			Default::default(),
 			mempool_max_transactions_count,
-			ready_limits.total_bytes + future_limits.total_bytes,
+			options.ready.total_bytes + options.future.total_bytes,
+			options.replacement_priority_bump,
 		));
 
 		let (dropped_stream_controller, dropped_stream) =
@@ -257,8 +286,6 @@ where
 		}
 		.boxed();
 
-		let options = Options { ready: ready_limits, future: future_limits, ..Default::default() };
-
 		(
 			Self {
 				mempool,
@@ -278,6 +305,7 @@ where
 				finality_timeout_threshold: finality_timeout_threshold
 					.unwrap_or(FINALITY_TIMEOUT_THRESHOLD),
 				included_transactions: Default::default(),
+				journal: None,
 			},
 			combined_tasks,
 		)
@@ -367,6 +395,7 @@ where
 			metrics.clone(),
 			TXMEMPOOL_TRANSACTION_LIMIT_MULTIPLIER * options.total_count(),
 			options.ready.total_bytes + options.future.total_bytes,
+			options.replacement_priority_bump,
 		));
 
 		let (dropped_stream_controller, dropped_stream) =
@@ -411,9 +440,19 @@ where
 			is_validator,
 			finality_timeout_threshold: FINALITY_TIMEOUT_THRESHOLD,
 			included_transactions: Default::default(),
+			journal: None,
 		}
 	}
 
+	/// Enables persisting the mempool contents in the journal at the given path.
+	///
+	/// The transactions found in the journal are resubmitted to the pool during the first
+	/// maintenance.
+	pub fn with_journal(mut self, path: PathBuf) -> Self {
+		self.journal = Some(TransactionJournal::open(path));
+		self
+	}
+
 	/// Get access to the underlying api
 	pub fn api(&self) -> &ChainApi {
 		&self.api
@@ -725,7 +764,6 @@ where
 		source: TransactionSource,
 		xts: Vec<TransactionFor<Self>>,
 	) -> Result<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
-		let view_store = self.view_store.clone();
 		debug!(
 			target: LOG_TARGET,
 			count = xts.len(),
@@ -734,85 +772,15 @@ where
 		);
 		log_xt_trace!(target: LOG_TARGET, xts.iter().map(|xt| self.tx_hash(xt)), "fatp::submit_at");
 		let xts = xts.into_iter().map(Arc::from).collect::<Vec<_>>();
-		let mempool_results = self.mempool.extend_unwatched(source, &xts);
-
-		if view_store.is_empty() {
-			return Ok(mempool_results
-				.into_iter()
-				.map(|r| r.map(|r| r.hash).map_err(Into::into))
-				.collect::<Vec<_>>())
-		}
-
-		// Submit all the transactions to the mempool
-		let retries = mempool_results
-			.into_iter()
-			.zip(xts.clone())
-			.map(|(result, xt)| async move {
-				match result {
-					Err(TxPoolApiError::ImmediatelyDropped) =>
-						self.attempt_transaction_replacement(source, false, xt).await,
-					_ => result,
-				}
-			})
-			.collect::<Vec<_>>();
-
-		let mempool_results = futures::future::join_all(retries).await;
-
-		// Collect transactions that were successfully submitted to the mempool...
-		let to_be_submitted = mempool_results
+		let mempool_results = xts
 			.iter()
-			.zip(xts)
-			.filter_map(|(result, xt)| {
-				result.as_ref().ok().map(|insertion| {
-					self.events_metrics_collector.report_submitted(&insertion);
-					(insertion.source.clone(), xt)
-				})
+			.map(|xt| {
+				self.check_sender_has_room(xt)?;
+				self.mempool.push_unwatched(source, xt.clone())
 			})
-			.collect::<Vec<_>>();
-
-		self.metrics
-			.report(|metrics| metrics.submitted_transactions.inc_by(to_be_submitted.len() as _));
-
-		// ... and submit them to the view_store. Please note that transactions rejected by mempool
-		// are not sent here.
-		let mempool = self.mempool.clone();
-		let results_map = view_store.submit(to_be_submitted.into_iter()).await;
-		let mut submission_results = reduce_multiview_result(results_map).into_iter();
-
-		// Note for composing final result:
-		//
-		// For each failed insertion into the mempool, the mempool result should be placed into
-		// the returned vector.
-		//
-		// For each successful insertion into the mempool, the corresponding
-		// view_store submission result needs to be examined:
-		// - If there is an error during view_store submission, the transaction is removed from
-		// the mempool, and the final result recorded in the vector for this transaction is the
-		// view_store submission error.
-		//
-		// - If the view_store submission is successful, the transaction priority is updated in the
-		// mempool.
-		//
-		// Finally, it collects the hashes of updated transactions or submission errors (either
-		// from the mempool or view_store) into a returned vector.
-		const RESULTS_ASSUMPTION : &str =
-			"The number of Ok results in mempool is exactly the same as the size of view_store submission result. qed.";
-		Ok(mempool_results
-			.into_iter()
-			.map(|result| {
-				result.map_err(Into::into).and_then(|insertion| {
-					submission_results.next().expect(RESULTS_ASSUMPTION).inspect_err(|_| {
-						mempool.remove_transactions(&[insertion.hash]);
-					})
-				})
-			})
-			.map(|r| {
-				r.map(|r| {
-					mempool.update_transaction_priority(&r);
-					r.hash()
-				})
-			})
-			.collect::<Vec<_>>())
+			.collect();
+		self.submit_to_view_store(xts.into_iter().map(|xt| (source, xt)).collect(), mempool_results)
+			.await
 	}
 
 	/// Submits a single transaction and returns a future resolving to the submission results.
@@ -855,6 +823,7 @@ where
 		);
 		let xt = Arc::from(xt);
 
+		self.check_sender_has_room(&xt)?;
 		let insertion = match self.mempool.push_watched(source, xt.clone()) {
 			Ok(result) => result,
 			Err(TxPoolApiError::ImmediatelyDropped) =>
@@ -1018,8 +987,8 @@ where
 		);
 		let xt = Arc::from(xt);
 
-		let result =
-			self.mempool.extend_unwatched(TransactionSource::Local, &[xt.clone()]).remove(0);
+		self.check_sender_has_room(&xt)?;
+		let result = self.mempool.push_unwatched(TransactionSource::Local, xt.clone());
 
 		let insertion = match result {
 			Err(TxPoolApiError::ImmediatelyDropped) => self.attempt_transaction_replacement_sync(
@@ -1592,11 +1561,14 @@ where
 			)
 			.await;
 
-		let Some(priority) = validated_tx.priority() else {
+		let ValidatedTransaction::Valid(Transaction { priority, provides, .. }) = validated_tx
+		else {
 			return Err(TxPoolApiError::ImmediatelyDropped)
 		};
 
-		self.attempt_transaction_replacement_inner(xt, xt_hash, priority, source, watched)
+		self.attempt_transaction_replacement_inner(
+			xt, xt_hash, priority, &provides, source, watched,
+		)
 	}
 
 	/// Sync version of [`Self::attempt_transaction_replacement`].
@@ -1612,7 +1584,7 @@ where
 			.read()
 			.ok_or(TxPoolApiError::ImmediatelyDropped)?;
 
-		let ValidTransaction { priority, .. } = self
+		let ValidTransaction { priority, provides, .. } = self
 			.api
 			.validate_transaction_blocking(at, TransactionSource::Local, Arc::from(xt.clone()))
 			.map_err(|_| TxPoolApiError::ImmediatelyDropped)?
@@ -1621,7 +1593,9 @@ where
 				TransactionValidityError::Unknown(u) => TxPoolApiError::UnknownTransaction(u),
 			})?;
 		let xt_hash = self.hash_of(&xt);
-		self.attempt_transaction_replacement_inner(xt, xt_hash, priority, source, watched)
+		self.attempt_transaction_replacement_inner(
+			xt, xt_hash, priority, &provides, source, watched,
+		)
 	}
 
 	fn attempt_transaction_replacement_inner(
@@ -1629,11 +1603,13 @@ where
 		xt: ExtrinsicFor<ChainApi>,
 		tx_hash: ExtrinsicHash<ChainApi>,
 		priority: TransactionPriority,
+		provides: &[TransactionTag],
 		source: TransactionSource,
 		watched: bool,
 	) -> Result<InsertionInfo<ExtrinsicHash<ChainApi>>, TxPoolApiError> {
-		let insertion_info =
-			self.mempool.try_insert_with_replacement(xt, priority, source, watched)?;
+		let insertion_info = self
+			.mempool
+			.try_insert_with_replacement(xt, priority, provides, source, watched)?;
 
 		for worst_hash in &insertion_info.removed {
 			trace!(
@@ -1654,6 +1630,155 @@ where
 
 		return Ok(insertion_info)
 	}
+
+	/// Checks if the sender of the given transaction can have more transactions in the most recent
+	/// view.
+	///
+	/// Allows to reject the transaction before it is inserted into the mempool, if its sender
+	/// reached the per-sender limits of both the ready and the future queue. The limit of the
+	/// queue the transaction is actually imported to is enforced by the views.
+	fn check_sender_has_room(&self, xt: &ExtrinsicFor<ChainApi>) -> Result<(), TxPoolApiError> {
+		let Some(sender) = self.api.sender_of(xt) else { return Ok(()) };
+		let Some(at) = *self.view_store.most_recent_view.read() else { return Ok(()) };
+		let Some((view, _)) = self.view_store.get_view_at(at, false) else { return Ok(()) };
+
+		view.pool.validated_pool().check_sender_has_room(&sender).inspect_err(|_| {
+			trace!(
+				target: LOG_TARGET,
+				tx_hash = ?self.tx_hash(xt),
+				"fatp::check_sender_has_room: sender limit reached"
+			);
+			self.metrics.report(|metrics| metrics.mempool_sender_limits_rejected_txs.inc());
+		})
+	}
+
+	/// Submits the transactions, which were already inserted into the mempool with the given
+	/// results, to the view store.
+	///
+	/// Transactions rejected by the mempool due to its limits are given a chance to replace
+	/// transactions with a lower priority.
+	async fn submit_to_view_store(
+		&self,
+		xts: Vec<(TransactionSource, ExtrinsicFor<ChainApi>)>,
+		mempool_results: Vec<Result<InsertionInfo<ExtrinsicHash<ChainApi>>, TxPoolApiError>>,
+	) -> Result<Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>>, ChainApi::Error> {
+		let view_store = self.view_store.clone();
+		if view_store.is_empty() {
+			return Ok(mempool_results
+				.into_iter()
+				.map(|r| r.map(|r| r.hash).map_err(Into::into))
+				.collect::<Vec<_>>())
+		}
+
+		// Submit all the transactions to the mempool
+		let retries = mempool_results
+			.into_iter()
+			.zip(xts.clone())
+			.map(|(result, (source, xt))| async move {
+				match result {
+					Err(TxPoolApiError::ImmediatelyDropped) =>
+						self.attempt_transaction_replacement(source, false, xt).await,
+					_ => result,
+				}
+			})
+			.collect::<Vec<_>>();
+
+		let mempool_results = futures::future::join_all(retries).await;
+
+		// Collect transactions that were successfully submitted to the mempool...
+		let to_be_submitted = mempool_results
+			.iter()
+			.zip(xts)
+			.filter_map(|(result, (_, xt))| {
+				result.as_ref().ok().map(|insertion| {
+					self.events_metrics_collector.report_submitted(&insertion);
+					(insertion.source.clone(), xt)
+				})
+			})
+			.collect::<Vec<_>>();
+
+		self.metrics
+			.report(|metrics| metrics.submitted_transactions.inc_by(to_be_submitted.len() as _));
+
+		// ... and submit them to the view_store. Please note that transactions rejected by mempool
+		// are not sent here.
+		let mempool = self.mempool.clone();
+		let results_map = view_store.submit(to_be_submitted.into_iter()).await;
+		let mut submission_results = reduce_multiview_result(results_map).into_iter();
+
+		// Note for composing final result:
+		//
+		// For each failed insertion into the mempool, the mempool result should be placed into
+		// the returned vector.
+		//
+		// For each successful insertion into the mempool, the corresponding
+		// view_store submission result needs to be examined:
+		// - If there is an error during view_store submission, the transaction is removed from
+		// the mempool, and the final result recorded in the vector for this transaction is the
+		// view_store submission error.
+		//
+		// - If the view_store submission is successful, the transaction priority is updated in the
+		// mempool.
+		//
+		// Finally, it collects the hashes of updated transactions or submission errors (either
+		// from the mempool or view_store) into a returned vector.
+		const RESULTS_ASSUMPTION : &str =
+			"The number of Ok results in mempool is exactly the same as the size of view_store submission result. qed.";
+		Ok(mempool_results
+			.into_iter()
+			.map(|result| {
+				result.map_err(Into::into).and_then(|insertion| {
+					submission_results.next().expect(RESULTS_ASSUMPTION).inspect_err(|_| {
+						mempool.remove_transactions(&[insertion.hash]);
+					})
+				})
+			})
+			.map(|r| {
+				r.map(|r| {
+					mempool.update_transaction_priority(&r);
+					r.hash()
+				})
+			})
+			.collect::<Vec<_>>())
+	}
+
+	/// Updates the on-disk journal, if enabled.
+	///
+	/// On the first call the transactions read from the journal are resubmitted to the pool,
+	/// which revalidates them against the best block. Afterwards the mempool contents are
+	/// periodically written to the journal.
+	async fn update_journal(&self) {
+		let Some(journal) = self.journal.as_ref() else { return };
+
+		if let Some(restored) = journal.take_restored() {
+			let count = restored.len();
+			// The restored sources keep the original submission time.
+			let (sources, xts): (Vec<_>, Vec<_>) =
+				restored.into_iter().map(|(source, xt)| (source, Arc::from(xt))).unzip();
+			let mempool_results = self
+				.mempool
+				.extend_unwatched_with_sources(sources.iter().cloned().zip(xts.iter().cloned()));
+			let xts = sources.into_iter().map(|source| source.source).zip(xts).collect();
+			match self.submit_to_view_store(xts, mempool_results).await {
+				Ok(results) => debug!(
+					target: LOG_TARGET,
+					count,
+					valid = results.iter().filter(|r| r.is_ok()).count(),
+					"fatp::journal: transactions resubmitted"
+				),
+				Err(error) => warn!(
+					target: LOG_TARGET,
+					count,
+					?error,
+					"fatp::journal: transactions resubmission failed"
+				),
+			}
+		}
+
+		journal.maybe_store(|| {
+			self.mempool.clone_transactions().into_values().map(|tx| (tx.source(), tx.tx()))
+		});
+	}
 }
 
 #[async_trait]
@@ -1731,6 +1856,8 @@ where
 			},
 		}
 
+		self.update_journal().await;
+
 		let duration = start.elapsed();
 
 		info!(
@@ -1757,6 +1884,20 @@ where
 	}
 }
 
+impl<ChainApi, Block> Drop for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	fn drop(&mut self) {
+		if let Some(journal) = self.journal.as_ref() {
+			journal.store(
+				self.mempool.clone_transactions().into_values().map(|tx| (tx.source(), tx.tx())),
+			);
+		}
+	}
+}
+
 impl<Block, Client> ForkAwareTxPool<FullChainApi<Client, Block>, Block>
 where
 	Block: BlockT,
@@ -75,6 +75,10 @@ pub struct Metrics {
 	pub submitted_from_mempool_txs: Counter<U64>,
 	/// Total number of transactions found as invalid during mempool revalidation.
 	pub mempool_revalidation_invalid_txs: Counter<U64>,
+	/// Total number of transactions rejected by mempool due to the per-sender limits.
+	pub mempool_sender_limits_rejected_txs: Counter<U64>,
+	/// Total number of transactions rejected by mempool due to insufficient priority bump.
+	pub mempool_replacement_rejected_txs: Counter<U64>,
 	/// Total number of transactions found as invalid during view revalidation.
 	pub view_revalidation_invalid_txs: Counter<U64>,
 	/// Total number of valid transactions processed during view revalidation.
@@ -319,6 +323,20 @@ impl MetricsRegistrant for Metrics {
 				)?,
 				registry,
 			)?,
+			mempool_sender_limits_rejected_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_mempool_sender_limits_rejected_txs_total",
+					"Total number of transactions rejected by mempool due to the per-sender limits.",
+				)?,
+				registry,
+			)?,
+			mempool_replacement_rejected_txs: register(
+				Counter::new(
+					"substrate_sub_txpool_mempool_replacement_rejected_txs_total",
+					"Total number of transactions rejected by mempool due to insufficient priority bump.",
+				)?,
+				registry,
+			)?,
 			view_revalidation_invalid_txs: register(
 				Counter::new(
 					"substrate_sub_txpool_view_revalidation_invalid_txs_total",
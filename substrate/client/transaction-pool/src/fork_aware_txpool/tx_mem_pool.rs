@@ -45,13 +45,18 @@ use sc_transaction_pool_api::{TransactionPriority, TransactionSource};
 use sp_blockchain::HashAndNumber;
 use sp_runtime::{
 	traits::Block as BlockT,
-	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	transaction_validity::{InvalidTransaction, TransactionTag as Tag, TransactionValidityError},
+	Percent,
 };
 
 use crate::{
 	common::tracing_log_xt::log_xt_trace,
 	graph,
-	graph::{base_pool::TimedTransactionSource, tracked_map::Size, ExtrinsicFor, ExtrinsicHash},
+	graph::{
+		base_pool::{is_replacement_allowed, TimedTransactionSource},
+		tracked_map::Size,
+		ExtrinsicFor, ExtrinsicHash, SubmittedTags,
+	},
 	LOG_TARGET,
 };
 
@@ -94,6 +99,8 @@ where
 	/// Priority of transaction at some block. It is assumed it will not be changed often. None if
 	/// not known.
 	priority: RwLock<Option<TransactionPriority>>,
+	/// Tags of transaction reported by the most recent view submission. None if not known.
+	tags: RwLock<Option<SubmittedTags>>,
 }
 
 impl<ChainApi, Block> TxInMemPool<ChainApi, Block>
@@ -154,6 +161,7 @@ where
 			validated_at: AtomicU64::new(0),
 			bytes,
 			priority: priority.into(),
+			tags: Default::default(),
 		}
 	}
 
@@ -173,6 +181,14 @@ where
 	pub(crate) fn priority(&self) -> Option<TransactionPriority> {
 		*self.priority.read()
 	}
+
+	/// Returns true if the transaction is known to provide any of the given tags.
+	fn provides_any(&self, tags: &[Tag]) -> bool {
+		self.tags
+			.read()
+			.as_ref()
+			.is_some_and(|t| t.provides.iter().any(|tag| tags.contains(tag)))
+	}
 }
 
 impl<ChainApi, Block> Size for Arc<TxInMemPool<ChainApi, Block>>
@@ -224,6 +240,9 @@ where
 
 	/// Maximal size of encodings of all transactions in the memory pool.
 	max_transactions_total_bytes: usize,
+
+	/// Minimal priority increase required to replace a transaction providing the same tag.
+	replacement_priority_bump: Percent,
 }
 
 /// Helper structure to encapsulate a result of [`TxMemPool::try_insert`].
@@ -250,13 +269,14 @@ where
 	<Block as BlockT>::Hash: Unpin,
 {
 	/// Creates a new `TxMemPool` instance with the given API, listener, metrics,
-	/// and max transaction count.
+	/// and limits.
 	pub(super) fn new(
 		api: Arc<ChainApi>,
 		listener: Arc<MultiViewListener<ChainApi>>,
 		metrics: PrometheusMetrics,
 		max_transactions_count: usize,
 		max_transactions_total_bytes: usize,
+		replacement_priority_bump: Percent,
 	) -> Self {
 		Self {
 			api,
@@ -265,6 +285,7 @@ where
 			metrics,
 			max_transactions_count,
 			max_transactions_total_bytes,
+			replacement_priority_bump,
 		}
 	}
 
@@ -282,6 +303,7 @@ where
 			metrics: Default::default(),
 			max_transactions_count,
 			max_transactions_total_bytes,
+			replacement_priority_bump: Percent::zero(),
 		}
 	}
 
@@ -354,7 +376,9 @@ where
 	/// transactions.
 	///
 	/// A "worse" transaction means transaction with lower priority, or older transaction with the
-	/// same prio.
+	/// same prio. Transactions providing any of the `provides` tags of the new transaction are
+	/// always replaced, but only if the priority of the new transaction exceeds theirs by at least
+	/// the configured replacement bump.
 	///
 	/// This operation will not overflow the limit of the mempool. It means that cumulative
 	/// size of removed transactions will be equal (or greated) then size of newly inserted
@@ -366,6 +390,7 @@ where
 		&self,
 		new_tx: ExtrinsicFor<ChainApi>,
 		priority: TransactionPriority,
+		provides: &[Tag],
 		source: TransactionSource,
 		watched: bool,
 	) -> Result<InsertionInfo<ExtrinsicHash<ChainApi>>, sc_transaction_pool_api::error::Error> {
@@ -381,10 +406,27 @@ where
 			return Err(sc_transaction_pool_api::error::Error::AlreadyImported(Box::new(hash)));
 		}
 
-		let mut sorted = transactions
+		let (same_tags, mut sorted): (Vec<_>, Vec<_>) = transactions
 			.iter()
 			.filter_map(|(h, v)| v.priority().map(|_| (*h, v.clone())))
-			.collect::<Vec<_>>();
+			.partition(|(_, v)| v.provides_any(provides));
+
+		if let Some(old) = same_tags.iter().filter_map(|(_, v)| v.priority()).max() {
+			if !is_replacement_allowed(old, priority, self.replacement_priority_bump) {
+				trace!(
+					target: LOG_TARGET,
+					tx_hash = ?hash,
+					old,
+					new = priority,
+					"mempool::try_insert_with_replacement: priority bump too low"
+				);
+				self.metrics.report(|metrics| metrics.mempool_replacement_rejected_txs.inc());
+				return Err(sc_transaction_pool_api::error::Error::TooLowPriority {
+					old,
+					new: priority,
+				});
+			}
+		}
 
 		// When pushing higher prio transaction, we need to find a number of lower prio txs, such
 		// that the sum of their bytes is ge then size of new tx. Otherwise we could overflow size
@@ -399,11 +441,12 @@ where
 			ordering => ordering,
 		});
 
-		let mut total_size_removed = 0usize;
-		let mut to_be_removed = vec![];
+		// Transactions providing the same tags are removed first.
+		let mut total_size_removed = same_tags.iter().map(|(_, v)| v.bytes).sum::<usize>();
+		let mut to_be_removed = same_tags.into_iter().map(|(h, _)| h).collect::<Vec<_>>();
 		let free_bytes = self.max_transactions_total_bytes - self.transactions.bytes();
 
-		loop {
+		while to_be_removed.is_empty() || free_bytes + total_size_removed < new_tx.bytes {
 			let Some((worst_hash, worst_tx)) = sorted.pop() else {
 				return Err(sc_transaction_pool_api::error::Error::ImmediatelyDropped);
 			};
@@ -414,10 +457,6 @@ where
 
 			total_size_removed += worst_tx.bytes;
 			to_be_removed.push(worst_hash);
-
-			if free_bytes + total_size_removed >= new_tx.bytes {
-				break;
-			}
 		}
 
 		let source = new_tx.source();
@@ -434,20 +473,47 @@ where
 	///
 	/// Returns the vector of results for each transaction, the order corresponds to the input
 	/// vector.
-	pub(super) fn extend_unwatched(
+	#[cfg(test)]
+	fn extend_unwatched(
 		&self,
 		source: TransactionSource,
 		xts: &[ExtrinsicFor<ChainApi>],
 	) -> Vec<Result<InsertionInfo<ExtrinsicHash<ChainApi>>, sc_transaction_pool_api::error::Error>>
 	{
-		let result = xts
-			.iter()
-			.map(|xt| {
+		xts.iter().map(|xt| self.push_unwatched(source, xt.clone())).collect()
+	}
+
+	/// Adds new unwatched transactions with their own sources to the internal buffer not
+	/// exceeding the limit.
+	///
+	/// Unlike [`Self::extend_unwatched`], the submission time of the given sources is kept. This is
+	/// used for the transactions restored from the journal.
+	pub(super) fn extend_unwatched_with_sources(
+		&self,
+		xts: impl IntoIterator<Item = (TimedTransactionSource, ExtrinsicFor<ChainApi>)>,
+	) -> Vec<Result<InsertionInfo<ExtrinsicHash<ChainApi>>, sc_transaction_pool_api::error::Error>>
+	{
+		xts.into_iter()
+			.map(|(source, xt)| {
 				let (hash, length) = self.api.hash_and_length(&xt);
-				self.try_insert(hash, TxInMemPool::new_unwatched(source, xt.clone(), length))
+				let tx = TxInMemPool {
+					source: source.clone(),
+					..TxInMemPool::new_unwatched(source.source, xt, length)
+				};
+				self.try_insert(hash, tx)
 			})
-			.collect::<Vec<_>>();
-		result
+			.collect()
+	}
+
+	/// Adds a new unwatched transaction to the memory pool if it does not exceed the maximum
+	/// allowed transaction count.
+	pub(super) fn push_unwatched(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> Result<InsertionInfo<ExtrinsicHash<ChainApi>>, sc_transaction_pool_api::error::Error> {
+		let (hash, length) = self.api.hash_and_length(&xt);
+		self.try_insert(hash, TxInMemPool::new_unwatched(source, xt, length))
 	}
 
 	/// Adds a new watched transaction to the memory pool if it does not exceed the maximum allowed
@@ -617,15 +683,17 @@ where
 		);
 	}
 
-	/// Updates the priority of transaction stored in mempool using provided view_store submission
-	/// outcome.
+	/// Updates the priority and the tags of transaction stored in mempool using provided
+	/// view_store submission outcome.
 	pub(super) fn update_transaction_priority(&self, outcome: &ViewStoreSubmitOutcome<ChainApi>) {
-		outcome.priority().map(|priority| {
-			self.transactions
-				.write()
-				.get_mut(&outcome.hash())
-				.map(|p| *p.priority.write() = Some(priority))
-		});
+		let transactions = self.transactions.read();
+		let Some(tx) = transactions.get(&outcome.hash()) else { return };
+		if let Some(priority) = outcome.priority() {
+			*tx.priority.write() = Some(priority);
+		}
+		if let Some(tags) = outcome.tags() {
+			*tx.tags.write() = Some(tags.clone());
+		}
 	}
 
 	/// Counts the number of transactions in the provided iterator of hashes
@@ -830,7 +898,7 @@ mod tx_mem_pool_tests {
 		let xt = Arc::from(large_uxt(98));
 		let hash = api.hash_and_length(&xt).0;
 		let result = mempool
-			.try_insert_with_replacement(xt, hi_prio, TransactionSource::External, false)
+			.try_insert_with_replacement(xt, hi_prio, &[], TransactionSource::External, false)
 			.unwrap();
 
 		assert_eq!(result.hash, hash);
@@ -872,7 +940,7 @@ mod tx_mem_pool_tests {
 		let (hash, length) = api.hash_and_length(&xt);
 		assert_eq!(length, 1130);
 		let result = mempool
-			.try_insert_with_replacement(xt, hi_prio, TransactionSource::External, false)
+			.try_insert_with_replacement(xt, hi_prio, &[], TransactionSource::External, false)
 			.unwrap();
 
 		assert_eq!(result.hash, hash);
@@ -914,7 +982,7 @@ mod tx_mem_pool_tests {
 		// overhead is 105, thus length: 105 + 2154
 		assert_eq!(length, 2 * LARGE_XT_SIZE + 1);
 		let result = mempool
-			.try_insert_with_replacement(xt, hi_prio, TransactionSource::External, false)
+			.try_insert_with_replacement(xt, hi_prio, &[], TransactionSource::External, false)
 			.unwrap();
 
 		assert_eq!(result.hash, hash);
@@ -951,8 +1019,13 @@ mod tx_mem_pool_tests {
 			.for_each(|o| mempool.update_transaction_priority(&o));
 
 		let xt = Arc::from(large_uxt(98));
-		let result =
-			mempool.try_insert_with_replacement(xt, low_prio, TransactionSource::External, false);
+		let result = mempool.try_insert_with_replacement(
+			xt,
+			low_prio,
+			&[],
+			TransactionSource::External,
+			false,
+		);
 
 		// lower prio tx is rejected immediately
 		assert!(matches!(
@@ -984,8 +1057,13 @@ mod tx_mem_pool_tests {
 		// overhead is 105, thus length: 105 + 2154
 		assert_eq!(length, 2 * LARGE_XT_SIZE + 1);
 
-		let result =
-			mempool.try_insert_with_replacement(xt, hi_prio, TransactionSource::External, false);
+		let result = mempool.try_insert_with_replacement(
+			xt,
+			hi_prio,
+			&[],
+			TransactionSource::External,
+			false,
+		);
 
 		// we did not update priorities (update_transaction_priority was not called):
 		assert!(matches!(
@@ -993,4 +1071,74 @@ mod tx_mem_pool_tests {
 			sc_transaction_pool_api::error::Error::ImmediatelyDropped
 		));
 	}
+
+	fn nonce_tags(sender: &[u8], nonce: u8, is_future: bool) -> SubmittedTags {
+		let tag = |nonce: u8| [sender, &[nonce]].concat();
+		SubmittedTags {
+			requires: if nonce > 0 { vec![tag(nonce - 1)] } else { vec![] },
+			provides: vec![tag(nonce)],
+			is_future,
+		}
+	}
+
+	#[test]
+	fn replacing_txs_with_same_tag_requires_priority_bump() {
+		sp_tracing::try_init_simple();
+		const COUNT: usize = 10;
+		let api = Arc::from(TestApi::default());
+		let mut mempool = TxMemPool::new_test(api.clone(), usize::MAX, COUNT * LARGE_XT_SIZE);
+		mempool.replacement_priority_bump = Percent::from_percent(10);
+
+		let xts = (0..COUNT).map(|x| Arc::from(large_uxt(x))).collect::<Vec<_>>();
+
+		let prio = 100u64;
+
+		let (submit_outcomes, hashes): (Vec<_>, Vec<_>) = xts
+			.iter()
+			.enumerate()
+			.map(|(i, t)| {
+				let h = api.hash_and_length(t).0;
+				let tags = nonce_tags(&[i as u8; 32], 1, false);
+				(ViewStoreSubmitOutcome::new(h, Some(prio)).with_tags(tags), h)
+			})
+			.unzip();
+
+		let results = mempool.extend_unwatched(TransactionSource::External, &xts);
+		assert!(results.iter().all(Result::is_ok));
+
+		submit_outcomes
+			.into_iter()
+			.for_each(|o| mempool.update_transaction_priority(&o));
+
+		let provides = nonce_tags(&[5u8; 32], 1, false).provides;
+
+		// the priority bump is too low:
+		let xt = Arc::from(large_uxt(98));
+		let result = mempool.try_insert_with_replacement(
+			xt.clone(),
+			prio + 9,
+			&provides,
+			TransactionSource::External,
+			false,
+		);
+		assert!(matches!(
+			result.unwrap_err(),
+			sc_transaction_pool_api::error::Error::TooLowPriority { old: 100, new: 109 }
+		));
+
+		// the transaction providing the same tag is replaced:
+		let hash = api.hash_and_length(&xt).0;
+		let result = mempool
+			.try_insert_with_replacement(
+				xt,
+				prio + 10,
+				&provides,
+				TransactionSource::External,
+				false,
+			)
+			.unwrap();
+
+		assert_eq!(result.hash, hash);
+		assert_eq!(result.removed, hashes[5..6]);
+	}
 }
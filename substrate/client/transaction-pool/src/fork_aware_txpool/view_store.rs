@@ -185,7 +185,11 @@ impl<ChainApi: graph::ChainApi> From<ValidatedPoolSubmitOutcome<ChainApi>>
 	for ViewStoreSubmitOutcome<ChainApi>
 {
 	fn from(value: ValidatedPoolSubmitOutcome<ChainApi>) -> Self {
-		Self::new(value.hash(), value.priority())
+		let outcome = Self::new(value.hash(), value.priority());
+		match value.tags() {
+			Some(tags) => outcome.with_tags(tags.clone()),
+			None => outcome,
+		}
 	}
 }
 
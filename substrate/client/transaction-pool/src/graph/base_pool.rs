@@ -33,6 +33,7 @@ use sp_runtime::{
 		TransactionLongevity as Longevity, TransactionPriority as Priority, TransactionSource,
 		TransactionTag as Tag,
 	},
+	Percent,
 };
 
 use super::{
@@ -276,6 +277,13 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 	}
 
+	/// Sets the minimal priority increase required to replace a ready transaction providing the
+	/// same tag.
+	pub fn with_replacement_priority_bump(mut self, bump: Percent) -> Self {
+		self.ready.set_replacement_priority_bump(bump);
+		self
+	}
+
 	/// Clears buffer keeping recently pruned transaction.
 	pub fn clear_recently_pruned(&mut self) {
 		self.recently_pruned = Default::default();
@@ -303,6 +311,26 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.contains(tx_hash) || self.ready.contains(tx_hash)
 	}
 
+	/// Returns true if the given transaction would be imported to the ready queue.
+	pub(crate) fn is_ready_candidate(&self, tx: &Transaction<Hash, Ex>) -> bool {
+		tx.requires.iter().all(|tag| {
+			self.ready.provided_tags().contains_key(tag) ||
+				self.recently_pruned.iter().any(|pruned| pruned.contains(tag))
+		})
+	}
+
+	/// Counts the ready and the future transactions matching the given predicate.
+	pub(crate) fn count_matching(
+		&self,
+		mut predicate: impl FnMut(&Transaction<Hash, Ex>) -> bool,
+	) -> (usize, usize) {
+		let ready = self
+			.ready
+			.fold(0, |count, tx| count + predicate(&tx.transaction.transaction) as usize);
+		let future = self.future.all().filter(|tx| predicate(tx)).count();
+		(ready, future)
+	}
+
 	/// Imports transaction to the pool.
 	///
 	/// The pool consists of two parts: Future and Ready.
@@ -612,6 +640,12 @@ impl Limit {
 	}
 }
 
+/// Returns true if a transaction with the `new` priority can replace transactions with the `old`
+/// priority, given the minimal priority increase `bump`.
+pub(crate) fn is_replacement_allowed(old: Priority, new: Priority, bump: Percent) -> bool {
+	new > old && new >= old.saturating_add(bump.mul_ceil(old))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -39,10 +39,10 @@ pub mod watcher;
 
 pub use self::pool::{
 	BlockHash, ChainApi, ExtrinsicFor, ExtrinsicHash, NumberFor, Options, Pool, RawExtrinsicFor,
-	TransactionFor, ValidatedTransactionFor,
+	SenderIdentity, SenderLimits, TransactionFor, ValidatedTransactionFor,
 };
 pub use validated_pool::{
-	BaseSubmitOutcome, EventDispatcher, IsValidator, ValidatedPoolSubmitOutcome,
+	BaseSubmitOutcome, EventDispatcher, IsValidator, SubmittedTags, ValidatedPoolSubmitOutcome,
 	ValidatedTransaction,
 };
 
@@ -27,6 +27,7 @@ use sp_runtime::{
 	transaction_validity::{
 		TransactionSource, TransactionTag as Tag, TransactionValidity, TransactionValidityError,
 	},
+	Percent,
 };
 use std::{
 	sync::Arc,
@@ -57,6 +58,9 @@ pub type TransactionFor<A> = Arc<base::Transaction<ExtrinsicHash<A>, ExtrinsicFo
 /// A type of validated transaction stored in the pool.
 pub type ValidatedTransactionFor<A> =
 	ValidatedTransaction<ExtrinsicHash<A>, ExtrinsicFor<A>, <A as ChainApi>::Error>;
+/// Function returning the encoded identity of the sender of an extrinsic, if it has one.
+pub type SenderIdentity<Block> =
+	Arc<dyn Fn(&<Block as traits::Block>::Extrinsic) -> Option<Vec<u8>> + Send + Sync>;
 
 /// Concrete extrinsic validation and query logic.
 pub trait ChainApi: Send + Sync {
@@ -122,6 +126,14 @@ pub trait ChainApi: Send + Sync {
 		to: <Self::Block as BlockT>::Hash,
 	) -> Result<TreeRoute<Self::Block>, Self::Error>;
 
+	/// Returns the encoded identity of the sender of the extrinsic.
+	///
+	/// Used to enforce the per-sender limits. Returns `None` if the extrinsic has no sender, or if
+	/// the sender is not known, in which case the extrinsic is not limited.
+	fn sender_of(&self, _uxt: &RawExtrinsicFor<Self>) -> Option<Vec<u8>> {
+		None
+	}
+
 	/// Resolves block number by id.
 	fn resolve_block_number(
 		&self,
@@ -144,6 +156,22 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// Per-sender limits.
+	pub sender_limits: SenderLimits,
+	/// Minimal priority increase required to replace a transaction providing the same tag.
+	pub replacement_priority_bump: Percent,
+}
+
+/// Maximal number of transactions a single sender can keep in the pool.
+///
+/// The sender of a transaction is given by [`ChainApi::sender_of`]. Transactions without a known
+/// sender are not limited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SenderLimits {
+	/// Maximal number of ready transactions of a single sender. Not limited if `None`.
+	pub ready: Option<usize>,
+	/// Maximal number of future transactions of a single sender. Not limited if `None`.
+	pub future: Option<usize>,
 }
 
 impl Default for Options {
@@ -153,6 +181,8 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			sender_limits: Default::default(),
+			replacement_priority_bump: Percent::zero(),
 		}
 	}
 }
@@ -912,6 +942,55 @@ mod tests {
 		assert_matches!(err, error::Error::NoTagsProvided);
 	}
 
+	#[test]
+	fn should_enforce_sender_limits() {
+		// given
+		let options = Options {
+			sender_limits: SenderLimits { ready: Some(2), future: Some(1) },
+			..Default::default()
+		};
+		let api = Arc::new(TestApi::default());
+		let pool = Pool::new_with_staticly_sized_rotator(options, true.into(), api.clone());
+		let submit = |from: AccountId, nonce| {
+			block_on(
+				pool.submit_one(
+					&api.expect_hash_and_number(0),
+					SOURCE,
+					uxt(Transfer {
+						from,
+						to: AccountId::from_h256(H256::from_low_u64_be(2)),
+						amount: 5,
+						nonce,
+					})
+					.into(),
+				),
+			)
+			.map(|o| o.hash())
+		};
+
+		// when
+		submit(Alice.into(), 0).unwrap();
+		submit(Alice.into(), 1).unwrap();
+		let ready_err = submit(Alice.into(), 2).unwrap_err();
+		submit(Alice.into(), 5).unwrap();
+		let future_err = submit(Alice.into(), 7).unwrap_err();
+		submit(Bob.into(), 10).unwrap();
+
+		// then
+		assert_matches!(ready_err, error::Error::SenderLimitReached);
+		assert_matches!(future_err, error::Error::SenderLimitReached);
+		assert_eq!(pool.validated_pool().status().ready, 2);
+		assert_eq!(pool.validated_pool().status().future, 2);
+		assert_matches!(
+			pool.validated_pool().check_sender_has_room(&AccountId::from(Alice).encode()),
+			Err(error::Error::SenderLimitReached)
+		);
+		assert!(pool
+			.validated_pool()
+			.check_sender_has_room(&AccountId::from(Bob).encode())
+			.is_ok());
+	}
+
 	mod listener {
 		use super::*;
 
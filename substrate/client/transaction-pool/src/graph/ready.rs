@@ -27,10 +27,10 @@ use crate::LOG_TARGET;
 use log::trace;
 use sc_transaction_pool_api::error;
 use serde::Serialize;
-use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag};
+use sp_runtime::{traits::Member, transaction_validity::TransactionTag as Tag, Percent};
 
 use super::{
-	base_pool::Transaction,
+	base_pool::{is_replacement_allowed, Transaction},
 	future::WaitingTransaction,
 	tracked_map::{self, TrackedMap},
 };
@@ -118,6 +118,8 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	/// Best transactions that are ready to be included to the block without any other previous
 	/// transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// Minimal priority increase required to replace transactions providing the same tags.
+	replacement_priority_bump: Percent,
 }
 
 impl<Hash, Ex> tracked_map::Size for ReadyTx<Hash, Ex> {
@@ -133,11 +135,18 @@ impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			replacement_priority_bump: Percent::zero(),
 		}
 	}
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
+	/// Sets the minimal priority increase required to replace transactions providing the same
+	/// tags.
+	pub fn set_replacement_priority_bump(&mut self, bump: Percent) {
+		self.replacement_priority_bump = bump;
+	}
+
 	/// Borrows a map of tags that are provided by transactions in this queue.
 	pub fn provided_tags(&self) -> &HashMap<Tag, Hash> {
 		&self.provided_tags
@@ -445,7 +454,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			};
 
 			// bail - the transaction has too low priority to replace the old ones
-			if old_priority >= tx.priority {
+			if !is_replacement_allowed(old_priority, tx.priority, self.replacement_priority_bump) {
 				return Err(error::Error::TooLowPriority { old: old_priority, new: tx.priority })
 			}
 
@@ -638,6 +647,32 @@ mod tests {
 		assert_eq!(ready.get().count(), 1);
 	}
 
+	#[test]
+	fn should_require_priority_bump_to_replace_transaction() {
+		// given
+		let mut ready = ReadyTransactions::default();
+		ready.set_replacement_priority_bump(Percent::from_percent(10));
+		let mut tx1 = tx(1);
+		tx1.requires.clear();
+		tx1.priority = 100;
+		let mut tx2 = tx(2);
+		tx2.requires.clear();
+		tx2.priority = 109;
+		import(&mut ready, tx1).unwrap();
+
+		// when
+		let err = import(&mut ready, tx2.clone()).unwrap_err();
+		assert!(matches!(err, error::Error::TooLowPriority { old: 100, new: 109 }));
+
+		tx2.priority = 110;
+		let removed = import(&mut ready, tx2).unwrap();
+
+		// then
+		assert_eq!(removed.len(), 1);
+		assert_eq!(removed[0].hash, 1);
+		assert_eq!(ready.get().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+	}
+
 	#[test]
 	fn should_replace_multiple_transactions_correctly() {
 		// given
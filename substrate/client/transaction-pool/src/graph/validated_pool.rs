@@ -37,7 +37,8 @@ use super::{
 	base_pool::{self as base, PruneStatus},
 	listener::EventHandler,
 	pool::{
-		BlockHash, ChainApi, EventStream, ExtrinsicFor, ExtrinsicHash, Options, TransactionFor,
+		BlockHash, ChainApi, EventStream, ExtrinsicFor, ExtrinsicHash, Options, SenderLimits,
+		TransactionFor,
 	},
 	rotator::PoolRotator,
 	watcher::Watcher,
@@ -120,6 +121,20 @@ pub struct BaseSubmitOutcome<B: ChainApi, W> {
 
 	/// The priority of the transaction. Defaults to None if unknown.
 	priority: Option<TransactionPriority>,
+
+	/// The tags of the transaction. Defaults to None if unknown.
+	tags: Option<SubmittedTags>,
+}
+
+/// Tags of the submitted transaction and the queue it was imported to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmittedTags {
+	/// Tags required by the transaction.
+	pub requires: Vec<Tag>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<Tag>,
+	/// Indicates if the transaction was imported to the future queue.
+	pub is_future: bool,
 }
 
 /// Type alias to outcome of submission to `ValidatedPool`.
@@ -129,7 +144,13 @@ pub type ValidatedPoolSubmitOutcome<B> =
 impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 	/// Creates a new instance with given hash and priority.
 	pub fn new(hash: ExtrinsicHash<B>, priority: Option<TransactionPriority>) -> Self {
-		Self { hash, priority, watcher: None }
+		Self { hash, priority, watcher: None, tags: None }
+	}
+
+	/// Sets the tags of the transaction.
+	pub fn with_tags(mut self, tags: SubmittedTags) -> Self {
+		self.tags = Some(tags);
+		self
 	}
 
 	/// Sets the transaction watcher.
@@ -143,6 +164,11 @@ impl<B: ChainApi, W> BaseSubmitOutcome<B, W> {
 		self.priority
 	}
 
+	/// Provides tags of submitted transaction.
+	pub fn tags(&self) -> Option<&SubmittedTags> {
+		self.tags.as_ref()
+	}
+
 	/// Provides hash of submitted transaction.
 	pub fn hash(&self) -> ExtrinsicHash<B> {
 		self.hash
@@ -164,6 +190,11 @@ pub struct ValidatedPool<B: ChainApi, L: EventHandler<B>> {
 	pub(crate) pool: RwLock<base::BasePool<ExtrinsicHash<B>, ExtrinsicFor<B>>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
 	rotator: PoolRotator<ExtrinsicHash<B>>,
+	/// Senders of the transactions in the pool, as returned by [`ChainApi::sender_of`].
+	///
+	/// Filled lazily when the per-sender limits are checked. Entries of transactions which left
+	/// the pool are removed on each check.
+	senders: RwLock<HashMap<ExtrinsicHash<B>, Option<Vec<u8>>>>,
 }
 
 impl<B: ChainApi, L: EventHandler<B>> Clone for ValidatedPool<B, L> {
@@ -176,6 +207,7 @@ impl<B: ChainApi, L: EventHandler<B>> Clone for ValidatedPool<B, L> {
 			pool: RwLock::from(self.pool.read().clone()),
 			import_notification_sinks: Default::default(),
 			rotator: self.rotator.clone(),
+			senders: RwLock::new(self.senders.read().clone()),
 		}
 	}
 }
@@ -238,7 +270,8 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		rotator: PoolRotator<ExtrinsicHash<B>>,
 		event_handler: Option<L>,
 	) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let base_pool = base::BasePool::new(options.reject_future_transactions)
+			.with_replacement_priority_bump(options.replacement_priority_bump);
 		Self {
 			is_validator,
 			options,
@@ -247,6 +280,7 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
 			rotator,
+			senders: Default::default(),
 		}
 	}
 
@@ -314,12 +348,17 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		match tx {
 			ValidatedTransaction::Valid(tx) => {
 				let priority = tx.priority;
+				let (requires, provides) = (tx.requires.clone(), tx.provides.clone());
 				log::trace!(target: LOG_TARGET, "[{:?}] ValidatedPool::submit_one", tx.hash);
 				if !tx.propagate && !(self.is_validator.0)() {
 					return Err(error::Error::Unactionable.into())
 				}
 
-				let imported = self.pool.write().import(tx)?;
+				let imported = {
+					let mut pool = self.pool.write();
+					self.check_sender_limits(&pool, &tx)?;
+					pool.import(tx)?
+				};
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					let sinks = &mut self.import_notification_sinks.lock();
@@ -341,7 +380,9 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 
 				let mut event_dispatcher = self.event_dispatcher.write();
 				fire_events(&mut *event_dispatcher, &imported);
-				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority)))
+				let is_future = matches!(imported, base::Imported::Future { .. });
+				Ok(ValidatedPoolSubmitOutcome::new(*imported.hash(), Some(priority))
+					.with_tags(SubmittedTags { requires, provides, is_future }))
 			},
 			ValidatedTransaction::Invalid(hash, err) => {
 				log::trace!(target: LOG_TARGET, "[{:?}] ValidatedPool::submit_one invalid: {:?}", hash, err);
@@ -356,6 +397,69 @@ impl<B: ChainApi, L: EventHandler<B>> ValidatedPool<B, L> {
 		}
 	}
 
+	/// Checks if the sender of the given transaction can import it to the pool.
+	///
+	/// Returns an error if the sender already reached its limit in the queue the transaction
+	/// would be imported to.
+	fn check_sender_limits(
+		&self,
+		pool: &base::BasePool<ExtrinsicHash<B>, ExtrinsicFor<B>>,
+		tx: &base::Transaction<ExtrinsicHash<B>, ExtrinsicFor<B>>,
+	) -> Result<(), error::Error> {
+		let SenderLimits { ready, future } = self.options.sender_limits;
+		let is_ready = pool.is_ready_candidate(tx);
+		let Some(limit) = (if is_ready { ready } else { future }) else { return Ok(()) };
+		let Some(sender) = self.api.sender_of(&tx.data) else { return Ok(()) };
+
+		let (ready_count, future_count) = self.count_sender_transactions(pool, &sender);
+		let count = if is_ready { ready_count } else { future_count };
+		if count >= limit {
+			log::debug!(
+				target: LOG_TARGET,
+				"[{:?}] Sender limit reached ({}/{} {})",
+				tx.hash,
+				count,
+				limit,
+				if is_ready { "ready" } else { "future" },
+			);
+			return Err(error::Error::SenderLimitReached)
+		}
+
+		self.senders.write().insert(tx.hash, Some(sender));
+		Ok(())
+	}
+
+	/// Returns an error if the given sender reached its limits in both the ready and the future
+	/// queue, so none of its transactions can be imported to the pool.
+	pub fn check_sender_has_room(&self, sender: &[u8]) -> Result<(), error::Error> {
+		let SenderLimits { ready: Some(ready), future: Some(future) } = self.options.sender_limits
+		else {
+			return Ok(())
+		};
+
+		let (ready_count, future_count) = self.count_sender_transactions(&self.pool.read(), sender);
+		if ready_count >= ready && future_count >= future {
+			return Err(error::Error::SenderLimitReached)
+		}
+		Ok(())
+	}
+
+	/// Counts the ready and the future transactions of the given sender.
+	fn count_sender_transactions(
+		&self,
+		pool: &base::BasePool<ExtrinsicHash<B>, ExtrinsicFor<B>>,
+		sender: &[u8],
+	) -> (usize, usize) {
+		let mut senders = self.senders.write();
+		senders.retain(|hash, _| pool.is_imported(hash));
+		pool.count_matching(|tx| {
+			senders
+				.entry(tx.hash)
+				.or_insert_with(|| self.api.sender_of(&tx.data))
+				.as_deref() == Some(sender)
+		})
+	}
+
 	fn enforce_limits(&self) -> HashSet<ExtrinsicHash<B>> {
 		let status = self.pool.read().status();
 		let ready_limit = &self.options.ready;
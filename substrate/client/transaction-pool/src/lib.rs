@@ -38,7 +38,7 @@ pub use common::notification_future;
 pub use fork_aware_txpool::{ForkAwareTxPool, ForkAwareTxPoolTask};
 pub use graph::{
 	base_pool::{Limit as PoolLimit, TimedTransactionSource},
-	ChainApi, Options, Pool,
+	ChainApi, Options, Pool, SenderIdentity, SenderLimits,
 };
 use single_state_txpool::prune_known_txs_for_block;
 pub use single_state_txpool::{BasicPool, RevalidationType};
@@ -27,6 +27,7 @@ use crate::{
 	common::{
 		enactment_state::{EnactmentAction, EnactmentState},
 		error,
+		journal::TransactionJournal,
 		log_xt::log_xt_trace,
 	},
 	graph::{self, base_pool::TimedTransactionSource, EventHandler, ExtrinsicHash, IsValidator},
@@ -52,6 +53,7 @@ use sp_runtime::{
 };
 use std::{
 	collections::{HashMap, HashSet},
+	path::PathBuf,
 	pin::Pin,
 	sync::Arc,
 	time::Instant,
@@ -71,6 +73,7 @@ where
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
 	enactment_state: Arc<Mutex<EnactmentState<Block>>>,
+	journal: Option<TransactionJournal<graph::RawExtrinsicFor<PoolApi>>>,
 }
 
 struct ReadyPoll<T, Block: BlockT> {
@@ -166,6 +169,7 @@ where
 					best_block_hash,
 					finalized_hash,
 				))),
+				journal: None,
 			},
 			background_task,
 		)
@@ -221,9 +225,19 @@ where
 				best_block_hash,
 				finalized_hash,
 			))),
+			journal: None,
 		}
 	}
 
+	/// Enables persisting the pool contents in the journal at the given path.
+	///
+	/// The transactions found in the journal are resubmitted to the pool during the first
+	/// maintenance.
+	pub fn with_journal(mut self, path: PathBuf) -> Self {
+		self.journal = Some(TransactionJournal::open(path));
+		self
+	}
+
 	/// Gets shared reference to the underlying pool.
 	pub fn pool(&self) -> &Arc<graph::Pool<PoolApi, ()>> {
 		&self.pool
@@ -748,6 +762,82 @@ where
 			self.revalidation_strategy.lock().clear();
 		}
 	}
+
+	/// Updates the on-disk journal, if enabled.
+	///
+	/// On the first call the transactions read from the journal are resubmitted to the pool.
+	/// Afterwards the pool contents are periodically written to the journal.
+	async fn update_journal(&self) {
+		let Some(journal) = self.journal.as_ref() else { return };
+
+		if let Some(restored) = journal.take_restored() {
+			let hash = self.enactment_state.lock().recent_best_block();
+			match self.api.resolve_block_number(hash) {
+				Ok(number) => {
+					let count = restored.len();
+					self.metrics
+						.report(|metrics| metrics.submitted_transactions.inc_by(count as u64));
+					// The restored sources keep the original submission time.
+					let results = self
+						.pool
+						.submit_at(
+							&HashAndNumber { hash, number },
+							restored.into_iter().map(|(source, xt)| (source, Arc::from(xt))),
+						)
+						.await;
+					log::debug!(
+						target: LOG_TARGET,
+						"Resubmitted {} transactions from the journal, {} valid",
+						count,
+						results.iter().filter(|r| r.is_ok()).count(),
+					);
+				},
+				Err(e) => log::warn!(
+					target: LOG_TARGET,
+					"Failed to resubmit {} transactions from the journal: {}",
+					restored.len(),
+					e,
+				),
+			}
+		}
+
+		journal.maybe_store(|| self.journal_transactions());
+	}
+}
+
+impl<PoolApi, Block> BasicPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: graph::ChainApi<Block = Block>,
+{
+	/// Returns the source and body of every ready and future transaction in the pool.
+	fn journal_transactions(&self) -> Vec<(TimedTransactionSource, graph::ExtrinsicFor<PoolApi>)> {
+		let validated_pool = self.pool.validated_pool();
+		let mut transactions = validated_pool
+			.ready()
+			.map(|tx| (tx.source.clone(), tx.data.clone()))
+			.collect::<Vec<_>>();
+		transactions.extend(
+			validated_pool
+				.pool
+				.read()
+				.futures()
+				.map(|tx| (tx.source.clone(), tx.data.clone())),
+		);
+		transactions
+	}
+}
+
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: graph::ChainApi<Block = Block>,
+{
+	fn drop(&mut self) {
+		if let Some(journal) = self.journal.as_ref() {
+			journal.store(self.journal_transactions());
+		}
+	}
 }
 
 #[async_trait]
@@ -804,5 +894,7 @@ where
 				}
 			}
 		}
+
+		self.update_journal().await;
 	}
 }
@@ -20,7 +20,7 @@
 
 use fatp_common::{
 	finalized_block_event, invalid_hash, new_best_block_event, pool, pool_with_api,
-	test_chain_with_forks, LOG_TARGET, SOURCE,
+	test_chain_with_forks, TestPoolBuilder, LOG_TARGET, SOURCE,
 };
 use futures::{executor::block_on, task::Poll, FutureExt, StreamExt};
 use sc_transaction_pool::ChainApi;
@@ -31,7 +31,7 @@ use sc_transaction_pool_api::{
 use sp_runtime::transaction_validity::InvalidTransaction;
 use std::{sync::Arc, time::Duration};
 use substrate_test_runtime_client::Sr25519Keyring::*;
-use substrate_test_runtime_transaction_pool::uxt;
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
 
 pub mod fatp_common;
 
@@ -2275,3 +2275,41 @@ fn fatp_ready_at_with_timeout_works_for_misc_scenarios() {
 	assert_eq!(ready_at2.next().unwrap().hash, api.hash_and_length(&xt2).0);
 	assert!(ready_at2.next().is_none());
 }
+
+#[test]
+fn fatp_journal_restores_and_revalidates_transactions() {
+	sp_tracing::try_init_simple();
+
+	let dir = tempfile::tempdir().unwrap();
+	let journal_path = dir.path().join("txpool.journal");
+	let api = Arc::new(TestApi::with_alice_nonce(200).enable_stale_check());
+
+	let (pool, _, _) = TestPoolBuilder::new().with_api(api.clone()).build();
+	let pool = pool.with_journal(journal_path.clone());
+
+	let header01 = api.push_block(1, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, header01.hash())));
+
+	let xts = (200..203).map(|i| uxt(Alice, i)).collect::<Vec<_>>();
+	let results = block_on(pool.submit_at(header01.hash(), SOURCE, xts.clone())).unwrap();
+	assert!(results.iter().all(Result::is_ok));
+	assert_pool_status!(header01.hash(), &pool, 3, 0);
+
+	// Dropping the pool writes its contents to the journal.
+	drop(pool);
+
+	// The first transaction got included while the node was down.
+	let header02 = api.push_block(2, vec![], true);
+	api.set_nonce(header02.hash(), Alice.into(), 201);
+
+	let (pool, _, _) = TestPoolBuilder::new().with_api(api.clone()).build();
+	let pool = pool.with_journal(journal_path);
+	assert_eq!(pool.mempool_len(), (0, 0));
+
+	// The journal is resubmitted and revalidated on the first maintenance.
+	block_on(pool.maintain(new_best_block_event(&pool, None, header02.hash())));
+
+	assert_pool_status!(header02.hash(), &pool, 2, 0);
+	assert_ready_iterator!(header02.hash(), pool, [xts[1], xts[2]]);
+	assert_eq!(pool.mempool_len(), (2, 0));
+}
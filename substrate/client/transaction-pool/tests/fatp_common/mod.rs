@@ -18,9 +18,9 @@
 
 //! Tests for fork-aware transaction pool.
 
-use sc_transaction_pool::{ChainApi, PoolLimit};
+use sc_transaction_pool::{ChainApi, Options, PoolLimit, SenderLimits};
 use sc_transaction_pool_api::ChainEvent;
-use sp_runtime::transaction_validity::TransactionSource;
+use sp_runtime::{transaction_validity::TransactionSource, Percent};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	runtime::{Block, Hash, Header},
@@ -69,6 +69,8 @@ pub struct TestPoolBuilder {
 	use_default_limits: bool,
 	ready_limits: sc_transaction_pool::PoolLimit,
 	future_limits: sc_transaction_pool::PoolLimit,
+	sender_limits: SenderLimits,
+	replacement_priority_bump: Percent,
 	mempool_max_transactions_count: usize,
 	finality_timeout_threshold: Option<usize>,
 }
@@ -80,6 +82,8 @@ impl Default for TestPoolBuilder {
 			use_default_limits: true,
 			ready_limits: PoolLimit { count: 8192, total_bytes: 20 * 1024 * 1024 },
 			future_limits: PoolLimit { count: 512, total_bytes: 1 * 1024 * 1024 },
+			sender_limits: Default::default(),
+			replacement_priority_bump: Percent::zero(),
 			mempool_max_transactions_count: usize::MAX,
 			finality_timeout_threshold: None,
 		}
@@ -126,6 +130,18 @@ impl TestPoolBuilder {
 		self
 	}
 
+	pub fn with_sender_limits(mut self, ready: Option<usize>, future: Option<usize>) -> Self {
+		self.sender_limits = SenderLimits { ready, future };
+		self.use_default_limits = false;
+		self
+	}
+
+	pub fn with_replacement_priority_bump(mut self, bump: Percent) -> Self {
+		self.replacement_priority_bump = bump;
+		self.use_default_limits = false;
+		self
+	}
+
 	pub fn with_finality_timeout_threshold(mut self, threshold: usize) -> Self {
 		self.finality_timeout_threshold = Some(threshold);
 		self
@@ -154,12 +170,17 @@ impl TestPoolBuilder {
 				self.finality_timeout_threshold,
 			)
 		} else {
-			ForkAwareTxPool::new_test_with_limits(
+			ForkAwareTxPool::new_test_with_options(
 				api.clone(),
 				genesis_hash,
 				genesis_hash,
-				self.ready_limits,
-				self.future_limits,
+				Options {
+					ready: self.ready_limits,
+					future: self.future_limits,
+					sender_limits: self.sender_limits,
+					replacement_priority_bump: self.replacement_priority_bump,
+					..Default::default()
+				},
 				self.mempool_max_transactions_count,
 				self.finality_timeout_threshold,
 			)
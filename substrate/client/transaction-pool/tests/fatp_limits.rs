@@ -829,3 +829,45 @@ fn fatp_limits_watcher_future_transactions_are_droped_when_view_is_dropped() {
 		assert_eq!(x_status, vec![TransactionStatus::Future]);
 	}
 }
+
+#[test]
+fn fatp_limits_sender_limits_are_enforced() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new();
+	let (pool, api, _) = builder.with_sender_limits(Some(2), Some(1)).build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Alice, 201);
+	let xt2 = uxt(Alice, 202);
+	let xt3 = uxt(Alice, 205);
+	let xt4 = uxt(Alice, 207);
+	let xt5 = uxt(Alice, 208);
+	let xt6 = uxt(Bob, 0);
+
+	let submit = |xt| block_on(pool.submit_one(header01.hash(), SOURCE, xt));
+
+	submit(xt0.clone()).unwrap();
+	submit(xt1.clone()).unwrap();
+
+	// ready part of Alice is full, future part is not
+	let result2 = submit(xt2.clone());
+	assert!(matches!(result2.unwrap_err().0, TxPoolError::SenderLimitReached));
+	submit(xt3.clone()).unwrap();
+
+	// both parts of Alice are full, transactions are rejected before entering the mempool
+	let result4 = submit(xt4.clone());
+	assert!(matches!(result4.unwrap_err().0, TxPoolError::SenderLimitReached));
+	let result5 = block_on(pool.submit_and_watch(header01.hash(), SOURCE, xt5.clone()));
+	assert!(matches!(result5.map(|_| ()).unwrap_err().0, TxPoolError::SenderLimitReached));
+
+	// other senders are not affected
+	submit(xt6.clone()).unwrap();
+
+	assert_eq!(pool.mempool_len(), (4, 0));
+	assert_pool_status!(header01.hash(), &pool, 3, 1);
+}
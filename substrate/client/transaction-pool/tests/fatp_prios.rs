@@ -27,6 +27,7 @@ use sc_transaction_pool_api::{
 	error::Error as TxPoolError, LocalTransactionPool, MaintainedTransactionPool, TransactionPool,
 	TransactionStatus,
 };
+use sp_runtime::Percent;
 use substrate_test_runtime_client::Sr25519Keyring::*;
 use substrate_test_runtime_transaction_pool::uxt;
 #[test]
@@ -558,3 +559,33 @@ fn fatp_prios_submit_local_full_mempool_higher_prio_is_accepted() {
 	assert_ready_iterator!(header02.hash(), pool, [xt3, xt2]);
 	assert_ready_iterator!(header03.hash(), pool, [xt5, xt4]);
 }
+
+#[test]
+fn fatp_prios_replacement_requires_priority_bump() {
+	sp_tracing::try_init_simple();
+
+	let builder = TestPoolBuilder::new();
+	let (pool, api, _) = builder.with_replacement_priority_bump(Percent::from_percent(10)).build();
+
+	let header01 = api.push_block(1, vec![], true);
+	let event = new_best_block_event(&pool, None, header01.hash());
+	block_on(pool.maintain(event));
+
+	let xt0 = uxt(Alice, 200);
+	let xt1 = uxt(Alice, 200);
+	let xt2 = uxt(Alice, 200);
+
+	api.set_priority(&xt0, 100);
+	api.set_priority(&xt1, 109);
+	api.set_priority(&xt2, 110);
+
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt0.clone())).unwrap();
+
+	let result1 = block_on(pool.submit_one(header01.hash(), SOURCE, xt1.clone()));
+	assert!(matches!(result1.unwrap_err().0, TxPoolError::TooLowPriority { old: 100, new: 109 }));
+
+	block_on(pool.submit_one(header01.hash(), SOURCE, xt2.clone())).unwrap();
+
+	assert_pool_status!(header01.hash(), &pool, 1, 0);
+	assert_ready_iterator!(header01.hash(), pool, [xt2]);
+}
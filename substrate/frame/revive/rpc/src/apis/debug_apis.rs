@@ -22,18 +22,22 @@ use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 pub trait DebugRpc {
 	/// Returns the tracing of the execution of a specific block using its number.
 	///
+	/// When no tracer config is provided, the struct logger is used.
+	///
 	/// ## References
 	///
-	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-debug#debugtraceblockbynumb>er
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-debug#debugtraceblockbynumber>
 	#[method(name = "debug_traceBlockByNumber")]
 	async fn trace_block_by_number(
 		&self,
 		block: BlockNumberOrTag,
-		tracer_config: TracerConfig,
+		tracer_config: Option<TracerConfig>,
 	) -> RpcResult<Vec<TransactionTrace>>;
 
 	/// Returns a transaction's traces by replaying it.
 	///
+	/// When no tracer config is provided, the struct logger is used.
+	///
 	/// ## References
 	///
 	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-debug#debugtracetransaction>
@@ -41,11 +45,13 @@ pub trait DebugRpc {
 	async fn trace_transaction(
 		&self,
 		transaction_hash: H256,
-		tracer_config: TracerConfig,
-	) -> RpcResult<CallTrace>;
+		tracer_config: Option<TracerConfig>,
+	) -> RpcResult<Trace>;
 
 	/// Dry run a call and returns the transaction's traces.
 	///
+	/// When no tracer config is provided, the struct logger is used.
+	///
 	/// ## References
 	///
 	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-debug#debugtracecall>
@@ -54,8 +60,8 @@ pub trait DebugRpc {
 		&self,
 		transaction: GenericTransaction,
 		block: BlockNumberOrTag,
-		tracer_config: TracerConfig,
-	) -> RpcResult<CallTrace>;
+		tracer_config: Option<TracerConfig>,
+	) -> RpcResult<Trace>;
 }
 
 pub struct DebugRpcServerImpl {
@@ -73,8 +79,9 @@ impl DebugRpcServer for DebugRpcServerImpl {
 	async fn trace_block_by_number(
 		&self,
 		block: BlockNumberOrTag,
-		tracer_config: TracerConfig,
+		tracer_config: Option<TracerConfig>,
 	) -> RpcResult<Vec<TransactionTrace>> {
+		let tracer_config = tracer_config.unwrap_or_default();
 		log::debug!(target: crate::LOG_TARGET, "trace_block_by_number: {block:?} config: {tracer_config:?}");
 		let traces = self.client.trace_block_by_number(block, tracer_config).await?;
 		Ok(traces)
@@ -83,8 +90,9 @@ impl DebugRpcServer for DebugRpcServerImpl {
 	async fn trace_transaction(
 		&self,
 		transaction_hash: H256,
-		tracer_config: TracerConfig,
-	) -> RpcResult<CallTrace> {
+		tracer_config: Option<TracerConfig>,
+	) -> RpcResult<Trace> {
+		let tracer_config = tracer_config.unwrap_or_default();
 		let trace = self.client.trace_transaction(transaction_hash, tracer_config).await?;
 		Ok(trace)
 	}
@@ -93,8 +101,9 @@ impl DebugRpcServer for DebugRpcServerImpl {
 		&self,
 		transaction: GenericTransaction,
 		block: BlockNumberOrTag,
-		tracer_config: TracerConfig,
-	) -> RpcResult<CallTrace> {
+		tracer_config: Option<TracerConfig>,
+	) -> RpcResult<Trace> {
+		let tracer_config = tracer_config.unwrap_or_default();
 		log::debug!(target: crate::LOG_TARGET, "trace_call: {transaction:?} block: {block:?} config: {tracer_config:?}");
 		let trace = self.client.trace_call(transaction, block, tracer_config).await?;
 		Ok(trace)
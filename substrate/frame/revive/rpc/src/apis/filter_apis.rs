@@ -0,0 +1,118 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::*;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Ethereum JSON-RPC filter apis.
+#[rpc(server, client)]
+pub trait EthFilterRpc {
+	/// Install a filter that notifies when new logs matching the given filter are added.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newfilter>
+	#[method(name = "eth_newFilter")]
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256>;
+
+	/// Install a filter that notifies when new blocks arrive.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newblockfilter>
+	#[method(name = "eth_newBlockFilter")]
+	async fn new_block_filter(&self) -> RpcResult<U256>;
+
+	/// Install a filter that notifies when new transactions are submitted through this server.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newpendingtransactionfilter>
+	#[method(name = "eth_newPendingTransactionFilter")]
+	async fn new_pending_transaction_filter(&self) -> RpcResult<U256>;
+
+	/// Returns the changes of the given filter since it was last polled.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getfilterchanges>
+	#[method(name = "eth_getFilterChanges")]
+	async fn get_filter_changes(&self, filter_id: U256) -> RpcResult<FilterResults>;
+
+	/// Returns all the logs matching the given log filter.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getfilterlogs>
+	#[method(name = "eth_getFilterLogs")]
+	async fn get_filter_logs(&self, filter_id: U256) -> RpcResult<FilterResults>;
+
+	/// Uninstall the given filter. Returns `false` if the filter was not found.
+	///
+	/// ## References
+	///
+	/// - <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_uninstallfilter>
+	#[method(name = "eth_uninstallFilter")]
+	async fn uninstall_filter(&self, filter_id: U256) -> RpcResult<bool>;
+}
+
+pub struct EthFilterRpcServerImpl {
+	client: client::Client,
+	filter_manager: FilterManager,
+}
+
+impl EthFilterRpcServerImpl {
+	pub fn new(client: client::Client, filter_manager: FilterManager) -> Self {
+		Self { client, filter_manager }
+	}
+}
+
+#[async_trait]
+impl EthFilterRpcServer for EthFilterRpcServerImpl {
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+		Ok(self.filter_manager.install(FilterKind::Logs(filter)).await)
+	}
+
+	async fn new_block_filter(&self) -> RpcResult<U256> {
+		Ok(self.filter_manager.install(FilterKind::Blocks).await)
+	}
+
+	async fn new_pending_transaction_filter(&self) -> RpcResult<U256> {
+		Ok(self.filter_manager.install(FilterKind::PendingTransactions).await)
+	}
+
+	async fn get_filter_changes(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		let changes = self
+			.filter_manager
+			.take_changes(&filter_id)
+			.await
+			.ok_or(EthRpcError::FilterNotFound(filter_id))?;
+		Ok(changes)
+	}
+
+	async fn get_filter_logs(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		let Some(FilterKind::Logs(filter)) = self.filter_manager.kind(&filter_id).await else {
+			return Err(EthRpcError::FilterNotFound(filter_id).into());
+		};
+
+		let logs = self.client.logs(Some(filter)).await?;
+		Ok(FilterResults::Logs(logs))
+	}
+
+	async fn uninstall_filter(&self, filter_id: U256) -> RpcResult<bool> {
+		Ok(self.filter_manager.uninstall(&filter_id).await)
+	}
+}
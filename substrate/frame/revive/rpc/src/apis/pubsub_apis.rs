@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::*;
+use futures::{Stream, StreamExt};
+use jsonrpsee::{proc_macros::rpc, PendingSubscriptionSink};
+use sc_rpc::utils::{BoundedVecDeque, PendingSubscription};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// The kind of events to subscribe to with `eth_subscribe`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+	/// New block headers.
+	NewHeads,
+	/// Logs of new blocks matching a filter, and the logs of retracted blocks with `removed` set.
+	Logs,
+	/// Hashes of the transactions submitted through this server.
+	NewPendingTransactions,
+}
+
+/// An item sent to an `eth_subscribe` subscriber.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionItem {
+	/// A new block header.
+	Header(Block),
+	/// A log matching the subscription filter.
+	Log(Log),
+	/// The hash of a pending transaction.
+	TransactionHash(H256),
+}
+
+/// Ethereum JSON-RPC publish/subscribe apis.
+#[rpc(server, client)]
+pub trait EthPubSubRpc {
+	/// Subscribe to new block headers, logs or pending transactions.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/pubsub>
+	#[subscription(
+		name = "eth_subscribe" => "eth_subscription",
+		unsubscribe = "eth_unsubscribe",
+		item = SubscriptionItem
+	)]
+	fn subscribe(&self, kind: SubscriptionKind, filter: Option<Filter>);
+}
+
+pub struct EthPubSubRpcServerImpl {
+	client: client::Client,
+}
+
+impl EthPubSubRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client }
+	}
+}
+
+/// Turn a broadcast receiver into a stream, skipping over the items missed when lagging behind.
+fn broadcast_stream<T: Clone + Send + 'static>(
+	receiver: broadcast::Receiver<T>,
+) -> impl Stream<Item = T> {
+	futures::stream::unfold(receiver, |mut receiver| async move {
+		loop {
+			match receiver.recv().await {
+				Ok(item) => return Some((item, receiver)),
+				Err(RecvError::Lagged(skipped)) => {
+					log::debug!(target: LOG_TARGET, "Subscription lagged behind, skipped {skipped} items");
+				},
+				Err(RecvError::Closed) => return None,
+			}
+		}
+	})
+}
+
+impl EthPubSubRpcServer for EthPubSubRpcServerImpl {
+	fn subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		kind: SubscriptionKind,
+		filter: Option<Filter>,
+	) {
+		let stream = match kind {
+			SubscriptionKind::NewHeads => {
+				let client = self.client.clone();
+				broadcast_stream(self.client.subscribe_blocks())
+					.then(move |notification| {
+						let client = client.clone();
+						async move {
+							SubscriptionItem::Header(
+								client.evm_block(notification.block, false).await,
+							)
+						}
+					})
+					.boxed()
+			},
+			SubscriptionKind::Logs => {
+				let filter = filter.unwrap_or_default();
+				let mut recent_blocks = RecentBlocks::default();
+				broadcast_stream(self.client.subscribe_blocks())
+					.flat_map(move |notification| {
+						let logs = notification
+							.receipts
+							.iter()
+							.flat_map(|receipt| receipt.logs.iter().cloned())
+							.collect::<Vec<_>>();
+						let removed_logs = recent_blocks.import(
+							notification.block.hash(),
+							notification.block.header().parent_hash,
+							logs.clone(),
+						);
+
+						let logs = removed_logs
+							.into_iter()
+							.chain(logs)
+							.filter(|log| matches_log(&filter, log))
+							.map(SubscriptionItem::Log)
+							.collect::<Vec<_>>();
+						futures::stream::iter(logs)
+					})
+					.boxed()
+			},
+			SubscriptionKind::NewPendingTransactions =>
+				broadcast_stream(self.client.subscribe_pending_transactions())
+					.map(SubscriptionItem::TransactionHash)
+					.boxed(),
+		};
+
+		tokio::spawn(
+			PendingSubscription::from(pending).pipe_from_stream(stream, BoundedVecDeque::default()),
+		);
+	}
+}
@@ -18,8 +18,10 @@
 use crate::{
 	client::{connect, native_to_eth_ratio, Client, SubscriptionType, SubstrateBlockNumber},
 	BlockInfoProvider, BlockInfoProviderImpl, CacheReceiptProvider, DBReceiptProvider,
-	DebugRpcServer, DebugRpcServerImpl, EthRpcServer, EthRpcServerImpl, ReceiptExtractor,
-	ReceiptProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl, LOG_TARGET,
+	DebugRpcServer, DebugRpcServerImpl, EthFilterRpcServer, EthFilterRpcServerImpl,
+	EthPubSubRpcServer, EthPubSubRpcServerImpl, EthRpcServer, EthRpcServerImpl, FilterManager,
+	ReceiptExtractor, ReceiptProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl,
+	LOG_TARGET,
 };
 use clap::Parser;
 use futures::{pin_mut, FutureExt};
@@ -29,7 +31,7 @@ use sc_service::{
 	config::{PrometheusConfig, RpcConfiguration},
 	start_rpc_servers, TaskManager,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 // Default port if --prometheus-port is not specified
 const DEFAULT_PROMETHEUS_PORT: u16 = 9616;
@@ -65,6 +67,10 @@ pub struct CliCommand {
 	#[clap(long)]
 	pub index_until_block: Option<SubstrateBlockNumber>,
 
+	/// The number of seconds after which a filter that has not been polled is uninstalled.
+	#[clap(long, default_value = "300")]
+	pub filter_timeout: u64,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -158,6 +164,7 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		database_url,
 		earliest_receipt_block,
 		index_until_block,
+		filter_timeout,
 		shared_params,
 		..
 	} = cmd;
@@ -212,11 +219,18 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		);
 	}
 
+	let filter_manager = FilterManager::new(Duration::from_secs(filter_timeout));
+	task_manager.spawn_essential_handle().spawn(
+		"filter-manager",
+		None,
+		filter_manager.clone().run(client.clone()),
+	);
+
 	let rpc_server_handle = start_rpc_servers(
 		&rpc_config,
 		prometheus_registry,
 		tokio_handle,
-		|| rpc_module(is_dev, client.clone()),
+		|| rpc_module(is_dev, client.clone(), filter_manager.clone()),
 		None,
 	)?;
 
@@ -239,17 +253,25 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 }
 
 /// Create the JSON-RPC module.
-fn rpc_module(is_dev: bool, client: Client) -> Result<RpcModule<()>, sc_service::Error> {
+fn rpc_module(
+	is_dev: bool,
+	client: Client,
+	filter_manager: FilterManager,
+) -> Result<RpcModule<()>, sc_service::Error> {
 	let eth_api = EthRpcServerImpl::new(client.clone())
 		.with_accounts(if is_dev { vec![crate::Account::default()] } else { vec![] })
 		.into_rpc();
 
 	let health_api = SystemHealthRpcServerImpl::new(client.clone()).into_rpc();
+	let filter_api = EthFilterRpcServerImpl::new(client.clone(), filter_manager).into_rpc();
+	let pubsub_api = EthPubSubRpcServerImpl::new(client.clone()).into_rpc();
 	let debug_api = DebugRpcServerImpl::new(client).into_rpc();
 
 	let mut module = RpcModule::new(());
 	module.merge(eth_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(health_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(debug_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(filter_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(pubsub_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	Ok(module)
 }
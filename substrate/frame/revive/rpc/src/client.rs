@@ -26,15 +26,21 @@ use codec::{Decode, Encode};
 use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObjectOwned};
 use pallet_revive::{
 	evm::{
-		decode_revert_reason, Block, BlockNumberOrTag, BlockNumberOrTagOrHash, CallTrace, Filter,
-		GenericTransaction, Log, ReceiptInfo, SyncingProgress, SyncingStatus, TracerConfig,
-		TransactionSigned, TransactionTrace, H160, H256, U256,
+		decode_revert_reason, AccessListEntry, AccessListResult, Block, BlockNumberOrTag,
+		BlockNumberOrTagOrHash, BlockTag, CallTrace, FeeHistoryResult, Filter, GenericTransaction,
+		Log, PrestateTrace, PrestateTracerConfig, ReceiptInfo, SyncingProgress, SyncingStatus,
+		Trace, TracerConfig, TransactionSigned, TransactionTrace, H160, H256, U256,
 	},
 	EthTransactError, EthTransactInfo,
 };
 use sp_runtime::OpaqueExtrinsic;
 use sp_weights::Weight;
-use std::{ops::ControlFlow, sync::Arc, time::Duration};
+use std::{
+	collections::{HashMap, VecDeque},
+	ops::ControlFlow,
+	sync::Arc,
+	time::Duration,
+};
 use subxt::{
 	backend::{
 		legacy::{rpc_methods::SystemHealth, LegacyRpcMethods},
@@ -49,7 +55,7 @@ use subxt::{
 	Config, OnlineClient,
 };
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use crate::subxt_client::{self, SrcChainConfig};
 
@@ -68,6 +74,23 @@ pub type Shared<T> = Arc<RwLock<T>>;
 /// The runtime balance type.
 pub type Balance = u128;
 
+/// The maximum number of blocks that can be requested with `eth_feeHistory`.
+///
+/// This is also the number of blocks whose fee data is cached.
+pub const MAX_FEE_HISTORY_BLOCK_COUNT: u64 = 128;
+
+/// The capacity of the channels used to broadcast notifications to subscribers.
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 256;
+
+/// A notification sent for every new block ingested by the client.
+#[derive(Clone)]
+pub struct BlockNotification {
+	/// The new block.
+	pub block: Arc<SubstrateBlock>,
+	/// The receipts of the Ethereum transactions included in the block.
+	pub receipts: Vec<ReceiptInfo>,
+}
+
 /// The subscription type used to listen to new blocks.
 pub enum SubscriptionType {
 	/// Subscribe to the best blocks.
@@ -134,12 +157,28 @@ pub enum ClientError {
 	/// The cache is empty.
 	#[error("cache is empty")]
 	CacheEmpty,
+	/// The runtime predates the requested tracer.
+	#[error("tracer not supported by the runtime")]
+	TracerNotSupported,
 	/// Failed to filter logs.
 	#[error("Failed to filter logs")]
 	LogFilterFailed(#[from] anyhow::Error),
 }
 
 const REVERT_CODE: i32 = 3;
+
+/// The `ReviveApi` version from which the trace functions return a [`Trace`].
+///
+/// Older runtimes only support the call tracer and return a [`CallTrace`].
+const TRACE_API_VERSION: u64 = 2;
+
+/// Make sure `tracer_config` can be served by a runtime predating [`TRACE_API_VERSION`].
+fn ensure_call_tracer(tracer_config: &TracerConfig) -> Result<(), ClientError> {
+	match tracer_config {
+		TracerConfig::CallTracer { .. } => Ok(()),
+		_ => Err(ClientError::TracerNotSupported),
+	}
+}
 impl From<ClientError> for ErrorObjectOwned {
 	fn from(err: ClientError) -> Self {
 		match err {
@@ -181,6 +220,46 @@ pub struct Client {
 	receipt_extractor: ReceiptExtractor,
 	chain_id: u64,
 	max_block_weight: Weight,
+	block_notifier: broadcast::Sender<BlockNotification>,
+	pending_transaction_notifier: broadcast::Sender<H256>,
+	/// Whether the runtime supports [`TRACE_API_VERSION`], keyed by spec version.
+	trace_api_support: Shared<HashMap<u32, bool>>,
+	/// The fee data of the most recently requested blocks.
+	fee_history_cache: Shared<FeeHistoryCache>,
+}
+
+/// The fee data of a block, as reported by `eth_feeHistory`.
+struct BlockFeeData {
+	base_fee: U256,
+	gas_used_ratio: f64,
+	/// The effective tip and the gas used of every transaction, sorted by tip.
+	rewards: Vec<(U256, U256)>,
+}
+
+/// Cache of the fee data of the last [`MAX_FEE_HISTORY_BLOCK_COUNT`] requested blocks.
+#[derive(Default)]
+struct FeeHistoryCache {
+	blocks: HashMap<SubstrateBlockHash, Arc<BlockFeeData>>,
+	/// The cached block hashes, in insertion order.
+	buffer: VecDeque<SubstrateBlockHash>,
+}
+
+impl FeeHistoryCache {
+	fn get(&self, hash: &SubstrateBlockHash) -> Option<Arc<BlockFeeData>> {
+		self.blocks.get(hash).cloned()
+	}
+
+	fn insert(&mut self, hash: SubstrateBlockHash, data: Arc<BlockFeeData>) {
+		if self.blocks.insert(hash, data).is_some() {
+			return;
+		}
+		self.buffer.push_back(hash);
+		if self.buffer.len() > MAX_FEE_HISTORY_BLOCK_COUNT as usize {
+			if let Some(pruned) = self.buffer.pop_front() {
+				self.blocks.remove(&pruned);
+			}
+		}
+	}
 }
 
 /// Fetch the chain ID from the substrate chain.
@@ -255,6 +334,10 @@ impl Client {
 			receipt_extractor,
 			chain_id,
 			max_block_weight,
+			block_notifier: broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0,
+			pending_transaction_notifier: broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0,
+			trace_api_support: Default::default(),
+			fee_history_cache: Default::default(),
 		})
 	}
 
@@ -352,12 +435,14 @@ impl Client {
 		let res = self
 			.subscribe_new_blocks(subscription_type, |block| async {
 				let receipts = self.receipt_extractor.extract_from_block(&block).await?;
+				let block_hash = block.hash();
 
-				self.receipt_provider.insert(&block.hash(), &receipts).await;
+				self.receipt_provider.insert(&block_hash, &receipts).await;
 				if let Some(pruned) = self.block_provider.cache_block(block).await {
 					self.receipt_provider.remove(&pruned).await;
 				}
 
+				self.notify_new_block(&block_hash, receipts).await;
 				Ok(())
 			})
 			.await;
@@ -367,6 +452,40 @@ impl Client {
 		}
 	}
 
+	/// Broadcast the given block to the new blocks subscribers, if any.
+	async fn notify_new_block(
+		&self,
+		block_hash: &H256,
+		receipts: Vec<(TransactionSigned, ReceiptInfo)>,
+	) {
+		if self.block_notifier.receiver_count() == 0 {
+			return;
+		}
+
+		let Ok(Some(block)) = self.block_provider.block_by_hash(block_hash).await else {
+			log::debug!(target: LOG_TARGET, "Failed to notify new block {block_hash:?}: block not found");
+			return;
+		};
+
+		let receipts = receipts.into_iter().map(|(_, receipt)| receipt).collect();
+		let _ = self.block_notifier.send(BlockNotification { block, receipts });
+	}
+
+	/// Subscribe to the new blocks ingested by the client.
+	pub fn subscribe_blocks(&self) -> broadcast::Receiver<BlockNotification> {
+		self.block_notifier.subscribe()
+	}
+
+	/// Broadcast the hash of a transaction submitted through this client.
+	pub fn notify_pending_transaction(&self, transaction_hash: H256) {
+		let _ = self.pending_transaction_notifier.send(transaction_hash);
+	}
+
+	/// Subscribe to the hashes of the transactions submitted through this client.
+	pub fn subscribe_pending_transactions(&self) -> broadcast::Receiver<H256> {
+		self.pending_transaction_notifier.subscribe()
+	}
+
 	/// Cache old blocks up to the given block number.
 	pub async fn cache_old_blocks(&self, oldest_block: SubstrateBlockNumber) {
 		let res = self
@@ -651,6 +770,51 @@ impl Client {
 		let gas_price = runtime_api.call(payload).await?;
 		Ok(*gas_price)
 	}
+	/// Whether the runtime at the given block implements [`TRACE_API_VERSION`] of the
+	/// `ReviveApi`.
+	///
+	/// The answer is cached per runtime spec version, read from `System::LastRuntimeUpgrade`,
+	/// so the runtime version only has to be fetched once per runtime.
+	async fn supports_trace_api(
+		&self,
+		at: Option<SubstrateBlockHash>,
+	) -> Result<bool, ClientError> {
+		let storage = match at {
+			Some(hash) => self.api.storage().at(hash),
+			None => self.api.storage().at_latest().await?,
+		};
+		let query = subxt_client::storage().system().last_runtime_upgrade();
+		let last_upgrade = storage.fetch(&query).await?.map(|info| info.spec_version.0);
+
+		if let Some(spec_version) = last_upgrade {
+			if let Some(supported) = self.trace_api_support.read().await.get(&spec_version) {
+				return Ok(*supported);
+			}
+		}
+
+		let api_id = format!("0x{}", hex::encode(sp_crypto_hashing::blake2_64(b"ReviveApi")));
+		let runtime_version = self.rpc.state_get_runtime_version(at).await?;
+		let spec_version = runtime_version.spec_version;
+		let api_version = runtime_version
+			.other
+			.get("apis")
+			.and_then(|apis| apis.as_array())
+			.into_iter()
+			.flatten()
+			.filter_map(|api| Some((api.get(0)?.as_str()?, api.get(1)?.as_u64()?)))
+			.find_map(|(id, version)| (id == api_id).then_some(version));
+
+		let supported = api_version.is_some_and(|version| version >= TRACE_API_VERSION);
+
+		// `LastRuntimeUpgrade` lags behind the code in the block enacting an upgrade, only cache
+		// the answer once both agree.
+		if last_upgrade == Some(spec_version) {
+			self.trace_api_support.write().await.insert(spec_version, supported);
+		}
+
+		Ok(supported)
+	}
+
 	/// Get the transaction traces for the given block.
 	pub async fn trace_block_by_number(
 		&self,
@@ -682,6 +846,11 @@ impl Client {
 			.filter_map(|e| OpaqueExtrinsic::decode(&mut &e[..]).ok())
 			.collect::<Vec<_>>();
 
+		let supports_trace_api = self.supports_trace_api(Some(parent_hash)).await?;
+		if !supports_trace_api {
+			ensure_call_tracer(&tracer_config)?;
+		}
+
 		let params = ((header, exts), tracer_config).encode();
 
 		let bytes = self
@@ -692,7 +861,14 @@ impl Client {
 				log::error!(target: LOG_TARGET, "state_call failed with: {err:?}");
 			})?;
 
-		let traces = Vec::<(u32, CallTrace)>::decode(&mut &bytes[..])?;
+		let traces = if supports_trace_api {
+			Vec::<(u32, Trace)>::decode(&mut &bytes[..])?
+		} else {
+			Vec::<(u32, CallTrace)>::decode(&mut &bytes[..])?
+				.into_iter()
+				.map(|(index, trace)| (index, Trace::Call(trace)))
+				.collect()
+		};
 
 		let mut hashes = self
 			.receipt_provider
@@ -715,7 +891,7 @@ impl Client {
 		&self,
 		transaction_hash: H256,
 		tracer_config: TracerConfig,
-	) -> Result<CallTrace, ClientError> {
+	) -> Result<Trace, ClientError> {
 		let ReceiptInfo { block_hash, transaction_index, .. } = self
 			.receipt_provider
 			.receipt_by_hash(&transaction_hash)
@@ -740,6 +916,11 @@ impl Client {
 			.filter_map(|e| OpaqueExtrinsic::decode(&mut &e[..]).ok())
 			.collect::<Vec<_>>();
 
+		let supports_trace_api = self.supports_trace_api(Some(parent_hash)).await?;
+		if !supports_trace_api {
+			ensure_call_tracer(&tracer_config)?;
+		}
+
 		let params = ((header, exts), transaction_index.as_u32(), tracer_config).encode();
 		let bytes = self
 			.rpc
@@ -749,7 +930,11 @@ impl Client {
 				log::error!(target: LOG_TARGET, "state_call failed with: {err:?}");
 			})?;
 
-		let trace = Option::<CallTrace>::decode(&mut &bytes[..])?;
+		let trace = if supports_trace_api {
+			Option::<Trace>::decode(&mut &bytes[..])?
+		} else {
+			Option::<CallTrace>::decode(&mut &bytes[..])?.map(Trace::Call)
+		};
 		trace.ok_or(ClientError::EthExtrinsicNotFound)
 	}
 
@@ -759,7 +944,7 @@ impl Client {
 		transaction: GenericTransaction,
 		block: BlockNumberOrTag,
 		tracer_config: TracerConfig,
-	) -> Result<CallTrace, ClientError> {
+	) -> Result<Trace, ClientError> {
 		let block_hash = match block {
 			BlockNumberOrTag::U256(n) => {
 				let block_number: SubstrateBlockNumber =
@@ -769,6 +954,11 @@ impl Client {
 			BlockNumberOrTag::BlockTag(_) => self.latest_block().await.map(|b| b.hash()),
 		};
 
+		let supports_trace_api = self.supports_trace_api(block_hash).await?;
+		if !supports_trace_api {
+			ensure_call_tracer(&tracer_config)?;
+		}
+
 		let params = (transaction, tracer_config).encode();
 		let bytes = self
 			.rpc
@@ -778,8 +968,12 @@ impl Client {
 				log::error!(target: LOG_TARGET, "state_call failed with: {err:?}");
 			})?;
 
-		Result::<CallTrace, EthTransactError>::decode(&mut &bytes[..])?
-			.map_err(ClientError::TransactError)
+		if supports_trace_api {
+			Result::<Trace, EthTransactError>::decode(&mut &bytes[..])?
+		} else {
+			Result::<CallTrace, EthTransactError>::decode(&mut &bytes[..])?.map(Trace::Call)
+		}
+		.map_err(ClientError::TransactError)
 	}
 	/// Get the EVM block for the given hash.
 	pub async fn evm_block(
@@ -851,6 +1045,147 @@ impl Client {
 		self.max_block_weight
 	}
 
+	/// Get the receipts of the given block, ordered by transaction index.
+	pub async fn block_receipts(
+		&self,
+		block: &BlockNumberOrTagOrHash,
+	) -> Result<Option<Vec<ReceiptInfo>>, ClientError> {
+		let block = match block {
+			BlockNumberOrTagOrHash::H256(hash) => self.block_by_hash(hash).await?,
+			BlockNumberOrTagOrHash::U256(n) => {
+				let n = (*n).try_into().map_err(|_| ClientError::ConversionFailed)?;
+				self.block_by_number(n).await?
+			},
+			BlockNumberOrTagOrHash::BlockTag(_) => self.latest_block().await,
+		};
+
+		let Some(block) = block else { return Ok(None) };
+		Ok(Some(self.receipt_provider.block_receipts(&block.hash()).await.unwrap_or_default()))
+	}
+
+	/// Get the base fees, gas used ratios and priority fee percentiles of a range of blocks
+	/// ending at `newest_block`.
+	pub async fn fee_history(
+		&self,
+		block_count: u64,
+		newest_block: &BlockNumberOrTag,
+		reward_percentiles: Option<Vec<f64>>,
+	) -> Result<FeeHistoryResult, ClientError> {
+		// An empty range yields an empty result, as specified by `eth_feeHistory`.
+		if block_count == 0 {
+			return Ok(FeeHistoryResult::default());
+		}
+
+		let newest_block = self
+			.block_by_number_or_tag(newest_block)
+			.await?
+			.ok_or(ClientError::BlockNotFound)?
+			.number();
+		let block_count = block_count.min(MAX_FEE_HISTORY_BLOCK_COUNT) as SubstrateBlockNumber;
+		let oldest_block = newest_block.saturating_sub(block_count - 1);
+
+		let mut result = FeeHistoryResult {
+			oldest_block: oldest_block.into(),
+			reward: reward_percentiles.as_ref().map(|_| Vec::new()),
+			..Default::default()
+		};
+
+		for block_number in oldest_block..=newest_block {
+			let block_hash =
+				self.get_block_hash(block_number).await?.ok_or(ClientError::BlockNotFound)?;
+			let fee_data = self.block_fee_data(block_hash).await?;
+
+			result.base_fee_per_gas.push(fee_data.base_fee);
+			result.gas_used_ratio.push(fee_data.gas_used_ratio);
+			if let (Some(rewards), Some(percentiles)) = (&mut result.reward, &reward_percentiles) {
+				rewards.push(fee_history_rewards(&fee_data.rewards, percentiles));
+			}
+		}
+
+		// The base fee of the next block is not known yet, use the current gas price instead.
+		let next_base_fee = self.gas_price(&BlockTag::Latest.into()).await?;
+		result.base_fee_per_gas.push(next_base_fee);
+		Ok(result)
+	}
+
+	/// Get the fee data of the given block, from the cache if possible.
+	async fn block_fee_data(
+		&self,
+		block_hash: SubstrateBlockHash,
+	) -> Result<Arc<BlockFeeData>, ClientError> {
+		if let Some(fee_data) = self.fee_history_cache.read().await.get(&block_hash) {
+			return Ok(fee_data);
+		}
+
+		let runtime_api = self.api.runtime_api().at(block_hash);
+		let base_fee = self.gas_price(&block_hash.into()).await?;
+		let gas_limit = Self::block_gas_limit(&runtime_api).await?;
+		let receipts = self.receipt_provider.block_receipts(&block_hash).await.unwrap_or_default();
+
+		let gas_used = receipts.iter().fold(U256::zero(), |acc, receipt| acc + receipt.gas_used);
+		let gas_used_ratio = if gas_limit.is_zero() {
+			0.0
+		} else {
+			(gas_used * 1_000_000 / gas_limit).low_u64() as f64 / 1_000_000.0
+		};
+
+		let mut rewards = receipts
+			.iter()
+			.map(|receipt| (receipt.effective_gas_price.saturating_sub(base_fee), receipt.gas_used))
+			.collect::<Vec<_>>();
+		rewards.sort_by_key(|(reward, _)| *reward);
+
+		let fee_data = Arc::new(BlockFeeData { base_fee, gas_used_ratio, rewards });
+		self.fee_history_cache.write().await.insert(block_hash, fee_data.clone());
+		Ok(fee_data)
+	}
+
+	/// Dry run a transaction and return the addresses and storage keys it accesses.
+	pub async fn create_access_list(
+		&self,
+		transaction: GenericTransaction,
+		block: BlockNumberOrTag,
+	) -> Result<AccessListResult, ClientError> {
+		let excluded = [transaction.from, transaction.to];
+		let (gas_used, error) = match self.dry_run(transaction.clone(), block.clone().into()).await
+		{
+			Ok(dry_run) => (dry_run.eth_gas, None),
+			Err(ClientError::TransactError(EthTransactError::Data(_))) =>
+				(U256::zero(), Some("execution reverted".to_string())),
+			Err(ClientError::TransactError(EthTransactError::Message(msg))) =>
+				(U256::zero(), Some(msg)),
+			Err(err) => return Err(err),
+		};
+
+		let tracer_config = TracerConfig::PrestateTracer(PrestateTracerConfig {
+			disable_code: true,
+			..Default::default()
+		});
+		let Trace::Prestate(PrestateTrace::Prestate(accounts)) =
+			self.trace_call(transaction, block, tracer_config).await?
+		else {
+			return Err(ClientError::ConversionFailed);
+		};
+
+		let access_list = accounts
+			.into_iter()
+			.map(|(address, info)| AccessListEntry {
+				address,
+				storage_keys: info
+					.storage
+					.into_keys()
+					.filter(|key| key.0.len() == 32)
+					.map(|key| H256::from_slice(&key.0))
+					.collect(),
+			})
+			.filter(|entry| {
+				!entry.storage_keys.is_empty() || !excluded.contains(&Some(entry.address))
+			})
+			.collect();
+
+		Ok(AccessListResult { access_list, error, gas_used })
+	}
+
 	/// Get the logs matching the given filter.
 	pub async fn logs(&self, filter: Option<Filter>) -> Result<Vec<Log>, ClientError> {
 		let logs =
@@ -858,3 +1193,27 @@ impl Client {
 		Ok(logs)
 	}
 }
+
+/// Compute the effective priority fees paid at the given percentiles of the block gas used, from
+/// the tips and gas used of the block transactions sorted by tip.
+fn fee_history_rewards(rewards: &[(U256, U256)], percentiles: &[f64]) -> Vec<U256> {
+	let Some((_, first_gas_used)) = rewards.first() else {
+		return vec![U256::zero(); percentiles.len()];
+	};
+
+	let total_gas_used = rewards.iter().fold(U256::zero(), |acc, (_, gas_used)| acc + gas_used);
+	let mut index = 0;
+	let mut cumulative_gas_used = *first_gas_used;
+
+	percentiles
+		.iter()
+		.map(|percentile| {
+			let threshold = total_gas_used * U256::from((percentile * 100.0) as u64) / 10_000;
+			while cumulative_gas_used < threshold && index < rewards.len() - 1 {
+				index += 1;
+				cumulative_gas_used += rewards[index].1;
+			}
+			rewards[index].0
+		})
+		.collect()
+}
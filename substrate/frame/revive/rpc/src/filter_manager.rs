@@ -0,0 +1,405 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Keeps track of the filters installed with `eth_newFilter`, `eth_newBlockFilter` and
+//! `eth_newPendingTransactionFilter`, and accumulates their changes until they are polled.
+use crate::{
+	client::{BlockNotification, Client},
+	LOG_TARGET,
+};
+use pallet_revive::evm::{
+	AddressOrAddresses, BlockNumberOrTag, Filter, FilterResults, FilterTopic, Log, H256, U256,
+};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+use tokio::sync::{broadcast::error::RecvError, Mutex};
+
+/// The maximum number of changes kept for a filter between two polls.
+/// Older changes are dropped when this limit is reached.
+const MAX_PENDING_CHANGES: usize = 10_000;
+
+/// The number of recent blocks kept to find the logs retracted by a reorg.
+const MAX_REORG_DEPTH: usize = 256;
+
+/// The kind of an installed filter.
+#[derive(Debug, Clone)]
+pub enum FilterKind {
+	/// Matches the logs of new blocks against the given filter.
+	Logs(Filter),
+	/// Collects the hashes of new blocks.
+	Blocks,
+	/// Collects the hashes of the transactions submitted through this server.
+	PendingTransactions,
+}
+
+/// An installed filter, along with the changes accumulated since it was last polled.
+struct InstalledFilter {
+	kind: FilterKind,
+	changes: FilterResults,
+	last_poll: Instant,
+}
+
+impl InstalledFilter {
+	fn new(kind: FilterKind) -> Self {
+		let changes = match kind {
+			FilterKind::Logs(_) => FilterResults::Logs(Vec::new()),
+			FilterKind::Blocks | FilterKind::PendingTransactions =>
+				FilterResults::Hashes(Vec::new()),
+		};
+		Self { kind, changes, last_poll: Instant::now() }
+	}
+
+	/// Record the changes introduced by a new block.
+	fn on_new_block(&mut self, notification: &BlockNotification) {
+		match (&self.kind, &mut self.changes) {
+			(FilterKind::Blocks, FilterResults::Hashes(hashes)) => {
+				push_bounded(hashes, [notification.block.hash()]);
+			},
+			(FilterKind::Logs(filter), FilterResults::Logs(logs)) => {
+				let new_logs = notification
+					.receipts
+					.iter()
+					.flat_map(|receipt| receipt.logs.iter())
+					.filter(|log| matches_log(filter, log))
+					.cloned();
+				push_bounded(logs, new_logs);
+			},
+			_ => {},
+		}
+	}
+
+	/// Record the logs of a block retracted by a reorg.
+	///
+	/// The logs are expected to be marked as removed already.
+	fn on_removed_logs(&mut self, removed_logs: &[Log]) {
+		if let (FilterKind::Logs(filter), FilterResults::Logs(logs)) =
+			(&self.kind, &mut self.changes)
+		{
+			push_bounded(logs, removed_logs.iter().filter(|log| matches_log(filter, log)).cloned());
+		}
+	}
+
+	/// Record a new pending transaction.
+	fn on_pending_transaction(&mut self, transaction_hash: H256) {
+		if let (FilterKind::PendingTransactions, FilterResults::Hashes(hashes)) =
+			(&self.kind, &mut self.changes)
+		{
+			push_bounded(hashes, [transaction_hash]);
+		}
+	}
+}
+
+/// Append the given items, dropping the oldest ones once [`MAX_PENDING_CHANGES`] is exceeded.
+fn push_bounded<T>(changes: &mut Vec<T>, items: impl IntoIterator<Item = T>) {
+	changes.extend(items);
+	if changes.len() > MAX_PENDING_CHANGES {
+		changes.drain(..changes.len() - MAX_PENDING_CHANGES);
+	}
+}
+
+/// A block recently passed to the filters.
+struct RecentBlock {
+	hash: H256,
+	logs: Vec<Log>,
+}
+
+/// Keeps track of the recent best blocks, to find the logs retracted by a reorg.
+#[derive(Default)]
+pub(crate) struct RecentBlocks(VecDeque<RecentBlock>);
+
+impl RecentBlocks {
+	/// Import a new best block, and return the logs of the blocks it retracted, marked as
+	/// removed, from the most recent one.
+	///
+	/// Nothing is retracted when the parent is unknown, e.g. after the notifications lagged.
+	pub(crate) fn import(&mut self, hash: H256, parent_hash: H256, logs: Vec<Log>) -> Vec<Log> {
+		let mut removed = Vec::new();
+		if self.0.iter().any(|block| block.hash == parent_hash) {
+			while let Some(block) = self.0.pop_back() {
+				if block.hash == parent_hash {
+					self.0.push_back(block);
+					break;
+				}
+				removed.extend(
+					block.logs.into_iter().rev().map(|log| Log { removed: Some(true), ..log }),
+				);
+			}
+		} else {
+			self.0.clear();
+		}
+
+		self.0.push_back(RecentBlock { hash, logs });
+		if self.0.len() > MAX_REORG_DEPTH {
+			self.0.pop_front();
+		}
+		removed
+	}
+}
+
+/// Returns whether the given log matches the filter.
+///
+/// Block tags are not used to bound the block range, since only new blocks are matched against
+/// installed filters.
+pub fn matches_log(filter: &Filter, log: &Log) -> bool {
+	if let Some(block_hash) = filter.block_hash {
+		if log.block_hash != block_hash {
+			return false;
+		}
+	}
+
+	if let Some(BlockNumberOrTag::U256(from_block)) = filter.from_block {
+		if log.block_number < from_block {
+			return false;
+		}
+	}
+
+	if let Some(BlockNumberOrTag::U256(to_block)) = filter.to_block {
+		if log.block_number > to_block {
+			return false;
+		}
+	}
+
+	match &filter.address {
+		Some(AddressOrAddresses::Address(address)) if *address != log.address => return false,
+		Some(AddressOrAddresses::Addresses(addresses))
+			if !addresses.is_empty() && !addresses.contains(&log.address) =>
+			return false,
+		_ => {},
+	}
+
+	filter.topics.iter().flatten().enumerate().all(|(i, topic)| {
+		let log_topic = log.topics.get(i);
+		match topic {
+			FilterTopic::Single(hash) => log_topic == Some(hash),
+			FilterTopic::Multiple(hashes) =>
+				hashes.is_empty() || log_topic.is_some_and(|t| hashes.contains(t)),
+		}
+	})
+}
+
+/// Manages the filters installed by the RPC clients.
+#[derive(Clone)]
+pub struct FilterManager {
+	filters: Arc<Mutex<HashMap<U256, InstalledFilter>>>,
+	timeout: Duration,
+}
+
+impl FilterManager {
+	/// Create a new [`FilterManager`].
+	///
+	/// Filters that are not polled within the given `timeout` are uninstalled.
+	pub fn new(timeout: Duration) -> Self {
+		Self { filters: Default::default(), timeout }
+	}
+
+	/// Install a new filter and return its id.
+	///
+	/// Ids are random, so they can not be guessed to poll or uninstall the filters of other
+	/// clients.
+	pub async fn install(&self, kind: FilterKind) -> U256 {
+		let mut filters = self.filters.lock().await;
+		let id = loop {
+			let id = U256::from_big_endian(&rand::random::<[u8; 16]>());
+			if !filters.contains_key(&id) {
+				break id;
+			}
+		};
+		filters.insert(id, InstalledFilter::new(kind));
+		id
+	}
+
+	/// Uninstall the filter with the given id. Returns whether the filter existed.
+	pub async fn uninstall(&self, id: &U256) -> bool {
+		self.filters.lock().await.remove(id).is_some()
+	}
+
+	/// Get the kind of the filter with the given id.
+	pub async fn kind(&self, id: &U256) -> Option<FilterKind> {
+		self.filters.lock().await.get(id).map(|filter| filter.kind.clone())
+	}
+
+	/// Take the changes accumulated by the filter with the given id since it was last polled.
+	pub async fn take_changes(&self, id: &U256) -> Option<FilterResults> {
+		let mut filters = self.filters.lock().await;
+		let filter = filters.get_mut(id)?;
+		filter.last_poll = Instant::now();
+
+		let empty = InstalledFilter::new(filter.kind.clone()).changes;
+		Some(std::mem::replace(&mut filter.changes, empty))
+	}
+
+	/// Remove the filters that have not been polled within the timeout.
+	async fn prune_expired(&self) {
+		let timeout = self.timeout;
+		self.filters.lock().await.retain(|id, filter| {
+			let keep = filter.last_poll.elapsed() < timeout;
+			if !keep {
+				log::debug!(target: LOG_TARGET, "Uninstalling expired filter {id:?}");
+			}
+			keep
+		});
+	}
+
+	/// Feed the installed filters with the new blocks and pending transactions of the client,
+	/// until the client stops sending notifications.
+	pub async fn run(self, client: Client) {
+		let mut blocks = client.subscribe_blocks();
+		let mut pending_transactions = client.subscribe_pending_transactions();
+		let mut recent_blocks = RecentBlocks::default();
+		let mut prune_interval = tokio::time::interval(
+			self.timeout.clamp(Duration::from_secs(1), Duration::from_secs(60)),
+		);
+
+		loop {
+			tokio::select! {
+				notification = blocks.recv() => match notification {
+					Ok(notification) => {
+						let logs = notification
+							.receipts
+							.iter()
+							.flat_map(|receipt| receipt.logs.iter().cloned())
+							.collect();
+						let removed_logs = recent_blocks.import(
+							notification.block.hash(),
+							notification.block.header().parent_hash,
+							logs,
+						);
+
+						for filter in self.filters.lock().await.values_mut() {
+							filter.on_removed_logs(&removed_logs);
+							filter.on_new_block(&notification);
+						}
+					},
+					Err(RecvError::Lagged(skipped)) => {
+						log::warn!(target: LOG_TARGET, "Filter manager lagged behind, skipped {skipped} blocks");
+					},
+					Err(RecvError::Closed) => return,
+				},
+				transaction_hash = pending_transactions.recv() => match transaction_hash {
+					Ok(transaction_hash) => {
+						for filter in self.filters.lock().await.values_mut() {
+							filter.on_pending_transaction(transaction_hash);
+						}
+					},
+					Err(RecvError::Lagged(skipped)) => {
+						log::warn!(target: LOG_TARGET, "Filter manager lagged behind, skipped {skipped} transactions");
+					},
+					Err(RecvError::Closed) => return,
+				},
+				_ = prune_interval.tick() => self.prune_expired().await,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_revive::evm::H160;
+
+	fn log(address: H160, block_number: u64, topics: Vec<H256>) -> Log {
+		Log { address, block_number: block_number.into(), topics, ..Default::default() }
+	}
+
+	#[test]
+	fn matches_log_works() {
+		let address = H160::from([1u8; 20]);
+		let topic_a = H256::from([1u8; 32]);
+		let topic_b = H256::from([2u8; 32]);
+		let log = log(address, 10, vec![topic_a, topic_b]);
+
+		assert!(matches_log(&Filter::default(), &log));
+
+		let filter = Filter { address: Some(address.into()), ..Default::default() };
+		assert!(matches_log(&filter, &log));
+
+		let filter = Filter { address: Some(H160::from([2u8; 20]).into()), ..Default::default() };
+		assert!(!matches_log(&filter, &log));
+
+		let filter =
+			Filter { from_block: Some(BlockNumberOrTag::U256(11u64.into())), ..Default::default() };
+		assert!(!matches_log(&filter, &log));
+
+		let filter = Filter {
+			topics: Some(vec![
+				FilterTopic::Multiple(vec![]),
+				FilterTopic::Multiple(vec![topic_a, topic_b]),
+			]),
+			..Default::default()
+		};
+		assert!(matches_log(&filter, &log));
+
+		let filter =
+			Filter { topics: Some(vec![FilterTopic::Single(topic_b)]), ..Default::default() };
+		assert!(!matches_log(&filter, &log));
+	}
+
+	#[test]
+	fn recent_blocks_retract_logs_on_reorg() {
+		let hash = |n: u8| H256::from([n; 32]);
+		let log = |n: u8| Log { block_hash: hash(n), ..Default::default() };
+		let mut recent_blocks = RecentBlocks::default();
+
+		assert!(recent_blocks.import(hash(1), hash(0), vec![log(1)]).is_empty());
+		assert!(recent_blocks.import(hash(2), hash(1), vec![log(2)]).is_empty());
+		assert!(recent_blocks.import(hash(3), hash(2), vec![log(3)]).is_empty());
+
+		// A fork of block 2 retracts blocks 3 and 2, the most recent first.
+		let removed = recent_blocks.import(hash(4), hash(1), vec![log(4)]);
+		assert_eq!(
+			removed,
+			vec![Log { removed: Some(true), ..log(3) }, Log { removed: Some(true), ..log(2) },]
+		);
+
+		// Blocks with an unknown parent don't retract anything.
+		assert!(recent_blocks.import(hash(6), hash(5), vec![]).is_empty());
+	}
+
+	#[tokio::test]
+	async fn install_uses_unique_random_ids() {
+		let manager = FilterManager::new(Duration::from_secs(60));
+		let first = manager.install(FilterKind::Blocks).await;
+		let second = manager.install(FilterKind::Blocks).await;
+		assert_ne!(first, second);
+		assert_ne!(second, first + 1);
+	}
+
+	#[tokio::test]
+	async fn take_changes_drains_and_expires() {
+		let manager = FilterManager::new(Duration::from_millis(10));
+		let id = manager.install(FilterKind::PendingTransactions).await;
+
+		manager
+			.filters
+			.lock()
+			.await
+			.get_mut(&id)
+			.unwrap()
+			.on_pending_transaction(H256::zero());
+		assert_eq!(
+			manager.take_changes(&id).await,
+			Some(FilterResults::Hashes(vec![H256::zero()]))
+		);
+		assert_eq!(manager.take_changes(&id).await, Some(FilterResults::Hashes(vec![])));
+
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		manager.prune_expired().await;
+		assert_eq!(manager.take_changes(&id).await, None);
+	}
+}
@@ -44,6 +44,9 @@ pub use receipt_provider::*;
 mod receipt_extractor;
 pub use receipt_extractor::*;
 
+mod filter_manager;
+pub use filter_manager::*;
+
 mod apis;
 pub use apis::*;
 
@@ -95,6 +98,12 @@ pub enum EthRpcError {
 	/// Received an invalid transaction
 	#[error("Invalid transaction {0:?}")]
 	TransactionTypeNotSupported(Byte),
+	/// The reward percentiles are not monotonically increasing values between 0 and 100
+	#[error("Invalid reward percentiles")]
+	InvalidRewardPercentiles,
+	/// The filter was not found, or has expired
+	#[error("Filter not found {0:?}")]
+	FilterNotFound(U256),
 }
 
 // TODO use https://eips.ethereum.org/EIPS/eip-1474#error-codes
@@ -165,6 +174,7 @@ impl EthRpcServer for EthRpcServerImpl {
 			err
 		})?;
 
+		self.client.notify_pending_transaction(hash);
 		log::debug!(target: LOG_TARGET, "send_raw_transaction hash: {hash:?}");
 		Ok(hash)
 	}
@@ -231,6 +241,32 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(self.client.gas_price(&BlockTag::Latest.into()).await?)
 	}
 
+	async fn fee_history(
+		&self,
+		block_count: U256,
+		newest_block: BlockNumberOrTag,
+		reward_percentiles: Option<Vec<f64>>,
+	) -> RpcResult<FeeHistoryResult> {
+		if let Some(percentiles) = &reward_percentiles {
+			let in_range = percentiles.iter().all(|p| (0.0..=100.0).contains(p));
+			let sorted = percentiles.windows(2).all(|w| w[0] <= w[1]);
+			if !in_range || !sorted {
+				return Err(EthRpcError::InvalidRewardPercentiles.into());
+			}
+		}
+
+		let block_count = block_count.try_into().map_err(|_| EthRpcError::ConversionError)?;
+		Ok(self.client.fee_history(block_count, &newest_block, reward_percentiles).await?)
+	}
+
+	async fn create_access_list(
+		&self,
+		transaction: GenericTransaction,
+		block: Option<BlockNumberOrTag>,
+	) -> RpcResult<AccessListResult> {
+		Ok(self.client.create_access_list(transaction, block.unwrap_or_default()).await?)
+	}
+
 	async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
 		// TODO: Provide better estimation
 		let gas_price = self.gas_price().await?;
@@ -258,6 +294,13 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(Some(block))
 	}
 
+	async fn get_block_receipts(
+		&self,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<Option<Vec<ReceiptInfo>>> {
+		Ok(self.client.block_receipts(&block).await?)
+	}
+
 	async fn get_block_transaction_count_by_hash(
 		&self,
 		block_hash: Option<H256>,
@@ -70,6 +70,20 @@ impl ReceiptProvider for CacheReceiptProvider {
 		cache.transaction_hashes_by_block_and_index.get(block_hash).map(|v| v.len())
 	}
 
+	async fn block_receipts(&self, block_hash: &H256) -> Option<Vec<ReceiptInfo>> {
+		let cache = self.cache().await;
+		let mut hashes = cache
+			.transaction_hashes_by_block_and_index
+			.get(block_hash)?
+			.iter()
+			.collect::<Vec<_>>();
+		hashes.sort_by_key(|(index, _)| **index);
+		hashes
+			.into_iter()
+			.map(|(_, hash)| cache.receipts_by_hash.get(hash).cloned())
+			.collect()
+	}
+
 	async fn block_transaction_hashes(&self, block_hash: &H256) -> Option<HashMap<usize, H256>> {
 		let cache = self.cache().await;
 		cache.transaction_hashes_by_block_and_index.get(block_hash).cloned()
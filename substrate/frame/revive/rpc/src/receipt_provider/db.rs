@@ -332,6 +332,12 @@ impl ReceiptProvider for DBReceiptProvider {
 		Some(count)
 	}
 
+	async fn block_receipts(&self, block_hash: &H256) -> Option<Vec<ReceiptInfo>> {
+		let block = self.block_provider.block_by_hash(block_hash).await.ok()??;
+		let receipts = self.receipt_extractor.extract_from_block(&block).await.ok()?;
+		Some(receipts.into_iter().map(|(_, receipt)| receipt).collect())
+	}
+
 	async fn block_transaction_hashes(&self, block_hash: &H256) -> Option<HashMap<usize, H256>> {
 		let block_hash = block_hash.as_ref();
 		let rows = query!(
@@ -27,7 +27,7 @@ use clap::Parser;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use pallet_revive::{
 	create1,
-	evm::{Account, BlockTag, U256},
+	evm::{AccessListEntry, Account, BlockTag, GenericTransaction, H256, U256},
 };
 use static_init::dynamic;
 use std::{sync::Arc, thread};
@@ -125,6 +125,52 @@ async fn transfer() -> anyhow::Result<()> {
 	let increase =
 		client.get_balance(ethan.address(), BlockTag::Latest.into()).await? - initial_balance;
 	assert_eq!(value, increase);
+
+	let block_receipts = client.get_block_receipts(receipt.block_hash.into()).await?;
+	assert!(
+		block_receipts.unwrap_or_default().contains(&receipt),
+		"Block receipts should contain the transaction receipt."
+	);
+
+	let fee_history = client
+		.fee_history(2u32.into(), receipt.block_number.into(), Some(vec![25.0, 75.0]))
+		.await?;
+	assert_eq!(fee_history.oldest_block, receipt.block_number - 1);
+	assert_eq!(fee_history.base_fee_per_gas.len(), 3);
+	assert_eq!(fee_history.gas_used_ratio.len(), 2);
+	assert_eq!(fee_history.reward.map(|r| r.len()), Some(2));
+
+	let empty_history = client.fee_history(0u32.into(), receipt.block_number.into(), None).await?;
+	assert!(empty_history.base_fee_per_gas.is_empty());
+	assert!(empty_history.gas_used_ratio.is_empty());
+	Ok(())
+}
+
+#[tokio::test]
+async fn create_access_list() -> anyhow::Result<()> {
+	let _lock = SHARED_RESOURCES.write();
+	let client = Arc::new(SharedResources::client().await);
+	let account = Account::default();
+
+	let (bytes, _) = pallet_revive_fixtures::compile_module("set_empty_storage")?;
+	let nonce = client.get_transaction_count(account.address(), BlockTag::Latest.into()).await?;
+	let tx = TransactionBuilder::new(&client).input(bytes).send().await?;
+	tx.wait_for_receipt().await?;
+	let contract_address = create1(&account.address(), nonce.try_into().unwrap());
+
+	let transaction = GenericTransaction {
+		from: Some(account.address()),
+		to: Some(contract_address),
+		..Default::default()
+	};
+	let result = client.create_access_list(transaction, None).await?;
+	assert_eq!(result.error, None);
+	assert!(!result.gas_used.is_zero());
+	assert_eq!(
+		result.access_list,
+		vec![AccessListEntry { address: contract_address, storage_keys: vec![H256::zero()] }],
+		"Access list should contain the storage slot written by the contract."
+	);
 	Ok(())
 }
 
@@ -15,8 +15,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::evm::{Bytes, CallTracer};
-use alloc::{fmt, string::String, vec::Vec};
+use crate::evm::Bytes;
+use alloc::{collections::BTreeMap, fmt, string::String, vec::Vec};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{
@@ -36,15 +36,36 @@ pub enum TracerConfig {
 		#[serde(rename = "withLog")]
 		with_logs: bool,
 	},
+
+	/// A tracer that captures the state touched by a transaction.
+	#[serde(rename = "prestateTracer")]
+	PrestateTracer(PrestateTracerConfig),
+
+	/// The default tracer, that captures a log entry for each executed syscall.
+	#[serde(rename = "structLogger")]
+	StructLogger(StructLoggerConfig),
 }
 
-impl TracerConfig {
-	/// Build the tracer associated to this config.
-	pub fn build<G>(self, gas_mapper: G) -> CallTracer<U256, G> {
-		match self {
-			Self::CallTracer { with_logs } => CallTracer::new(with_logs, gas_mapper),
-		}
-	}
+/// Configuration of the prestate tracer.
+#[derive(TypeInfo, Default, Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrestateTracerConfig {
+	/// Whether to return the pre and post state of the modified accounts only.
+	pub diff_mode: bool,
+	/// Whether to omit the code of the touched accounts.
+	pub disable_code: bool,
+	/// Whether to omit the storage of the touched accounts.
+	pub disable_storage: bool,
+}
+
+/// Configuration of the struct logger.
+#[derive(TypeInfo, Default, Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+	/// Whether to omit the storage touched by each step.
+	pub disable_storage: bool,
+	/// Whether to include the return data of the transaction.
+	pub enable_return_data: bool,
 }
 
 /// Custom deserializer to support the following JSON format:
@@ -56,11 +77,33 @@ impl TracerConfig {
 /// ```json
 /// { "tracer": "callTracer" }
 /// ```
+///
+/// ```json
+/// { "tracer": "prestateTracer", "tracerConfig": { "diffMode": true } }
+/// ```
+///
+/// When no tracer is specified, the struct logger is used, with its options set at the top level:
+///
+/// ```json
+/// { "disableStorage": true, "enableReturnData": true }
+/// ```
 impl<'de> Deserialize<'de> for TracerConfig {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 	where
 		D: Deserializer<'de>,
 	{
+		/// Union of all the options that can be set in `tracerConfig`.
+		#[derive(Default, Deserialize)]
+		#[serde(default, rename_all = "camelCase")]
+		struct InnerConfig {
+			#[serde(alias = "withLog")]
+			with_logs: Option<bool>,
+			diff_mode: Option<bool>,
+			disable_code: Option<bool>,
+			disable_storage: Option<bool>,
+			enable_return_data: Option<bool>,
+		}
+
 		struct TracerConfigVisitor;
 
 		impl<'de> Visitor<'de> for TracerConfigVisitor {
@@ -75,7 +118,9 @@ impl<'de> Deserialize<'de> for TracerConfig {
 				M: MapAccess<'de>,
 			{
 				let mut tracer_type: Option<String> = None;
-				let mut with_logs = None;
+				let mut inner = InnerConfig::default();
+				let mut disable_storage = None;
+				let mut enable_return_data = None;
 
 				while let Some(key) = map.next_key::<String>()? {
 					match key.as_str() {
@@ -83,22 +128,39 @@ impl<'de> Deserialize<'de> for TracerConfig {
 							tracer_type = map.next_value()?;
 						},
 						"tracerConfig" => {
-							#[derive(Deserialize)]
-							struct CallTracerConfig {
-								#[serde(rename = "withLogs")]
-								with_logs: Option<bool>,
-							}
-							let inner: CallTracerConfig = map.next_value()?;
-							with_logs = inner.with_logs;
+							inner = map.next_value()?;
+						},
+						"disableStorage" => {
+							disable_storage = map.next_value()?;
+						},
+						"enableReturnData" => {
+							enable_return_data = map.next_value()?;
+						},
+						_ => {
+							map.next_value::<de::IgnoredAny>()?;
 						},
-						_ => {},
 					}
 				}
 
 				match tracer_type.as_deref() {
 					Some("callTracer") =>
-						Ok(TracerConfig::CallTracer { with_logs: with_logs.unwrap_or(true) }),
-					_ => Err(de::Error::custom("Unsupported or missing tracer type")),
+						Ok(TracerConfig::CallTracer { with_logs: inner.with_logs.unwrap_or(true) }),
+					Some("prestateTracer") =>
+						Ok(TracerConfig::PrestateTracer(PrestateTracerConfig {
+							diff_mode: inner.diff_mode.unwrap_or_default(),
+							disable_code: inner.disable_code.unwrap_or_default(),
+							disable_storage: inner.disable_storage.unwrap_or_default(),
+						})),
+					None | Some("structLogger") =>
+						Ok(TracerConfig::StructLogger(StructLoggerConfig {
+							disable_storage: disable_storage
+								.or(inner.disable_storage)
+								.unwrap_or_default(),
+							enable_return_data: enable_return_data
+								.or(inner.enable_return_data)
+								.unwrap_or_default(),
+						})),
+					_ => Err(de::Error::custom("Unsupported tracer type")),
 				}
 			}
 		}
@@ -119,6 +181,29 @@ fn test_tracer_config_serialization() {
 			r#"{"tracer": "callTracer", "tracerConfig": { "withLogs": false }}"#,
 			TracerConfig::CallTracer { with_logs: false },
 		),
+		(
+			r#"{"tracer": "callTracer", "tracerConfig": { "withLog": false }}"#,
+			TracerConfig::CallTracer { with_logs: false },
+		),
+		(
+			r#"{"tracer": "prestateTracer"}"#,
+			TracerConfig::PrestateTracer(PrestateTracerConfig::default()),
+		),
+		(
+			r#"{"tracer": "prestateTracer", "tracerConfig": { "diffMode": true }}"#,
+			TracerConfig::PrestateTracer(PrestateTracerConfig {
+				diff_mode: true,
+				..Default::default()
+			}),
+		),
+		(r#"{}"#, TracerConfig::default()),
+		(
+			r#"{"disableStorage": true, "enableReturnData": true, "disableStack": true}"#,
+			TracerConfig::StructLogger(StructLoggerConfig {
+				disable_storage: true,
+				enable_return_data: true,
+			}),
+		),
 	];
 
 	for (json_data, expected) in tracers {
@@ -130,7 +215,7 @@ fn test_tracer_config_serialization() {
 
 impl Default for TracerConfig {
 	fn default() -> Self {
-		TracerConfig::CallTracer { with_logs: false }
+		TracerConfig::StructLogger(StructLoggerConfig::default())
 	}
 }
 
@@ -206,6 +291,96 @@ pub struct CallLog {
 	pub position: u32,
 }
 
+/// The trace of a transaction, as produced by the tracer selected in [`TracerConfig`].
+#[derive(TypeInfo, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum Trace {
+	/// A call trace, see [`TracerConfig::CallTracer`].
+	Call(CallTrace),
+	/// A struct log trace, see [`TracerConfig::StructLogger`].
+	StructLog(StructLogTrace),
+	/// A prestate trace, see [`TracerConfig::PrestateTracer`].
+	Prestate(PrestateTrace),
+}
+
+/// The state touched by a transaction.
+#[derive(TypeInfo, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	/// The state of every touched account before the transaction was executed.
+	Prestate(BTreeMap<H160, PrestateTraceInfo>),
+
+	/// The pre and post state of the accounts modified by the transaction.
+	///
+	/// The post state only contains the fields that were modified.
+	DiffMode {
+		/// The state of the modified accounts before the transaction was executed.
+		pre: BTreeMap<H160, PrestateTraceInfo>,
+		/// The modified fields of the accounts after the transaction was executed.
+		post: BTreeMap<H160, PrestateTraceInfo>,
+	},
+}
+
+/// The state of an account, as captured by the prestate tracer.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+pub struct PrestateTraceInfo {
+	/// The balance of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	/// The nonce of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u32>,
+	/// The code of the account.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	/// The storage touched, indexed by the unhashed storage key.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<Bytes, Option<Bytes>>,
+}
+
+/// A struct log trace, containing a log entry for each executed syscall.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+pub struct StructLogTrace<Gas = U256> {
+	/// Amount of gas used by the transaction.
+	pub gas: Gas,
+	/// Whether the transaction failed.
+	pub failed: bool,
+	/// The return data of the transaction, if enabled in the [`StructLoggerConfig`].
+	#[serde(rename = "returnValue")]
+	pub return_value: Bytes,
+	/// The logs of every executed step.
+	#[serde(rename = "structLogs")]
+	pub struct_logs: Vec<StructLog<Gas>>,
+}
+
+/// A single step of a [`StructLogTrace`].
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+pub struct StructLog<Gas = U256> {
+	/// The program counter of the contract when the step was executed.
+	pub pc: u64,
+	/// The name of the executed syscall.
+	pub op: String,
+	/// Amount of gas left before the step.
+	pub gas: Gas,
+	/// Amount of gas used by the step, including the gas used by nested calls.
+	#[serde(rename = "gasCost")]
+	pub gas_cost: Gas,
+	/// The call depth of the step, starting at 1.
+	pub depth: u32,
+	/// The error message if the step failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// The storage of the current contract accessed so far, for steps that accessed storage.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<Bytes, Bytes>>,
+}
+
 /// A transaction trace
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TransactionTrace {
@@ -214,5 +389,5 @@ pub struct TransactionTrace {
 	pub tx_hash: H256,
 	/// The trace of the transaction.
 	#[serde(rename = "result")]
-	pub trace: CallTrace,
+	pub trace: Trace,
 }
@@ -0,0 +1,61 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Result types of the `eth_` execution methods that are not covered by the generated
+//! [`super::rpc_types_gen`] module.
+
+use super::AccessList;
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::U256;
+
+/// Result of `eth_createAccessList`.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+pub struct AccessListResult {
+	/// The addresses and storage keys accessed by the transaction.
+	#[serde(rename = "accessList")]
+	pub access_list: AccessList,
+	/// The error message, if the transaction reverted or failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// The gas used by the transaction.
+	#[serde(rename = "gasUsed")]
+	pub gas_used: U256,
+}
+
+/// Result of `eth_feeHistory`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeHistoryResult {
+	/// Lowest number block of returned range.
+	#[serde(rename = "oldestBlock")]
+	pub oldest_block: U256,
+	/// An array of block base fees per gas, including the next block after the newest of the
+	/// returned range.
+	#[serde(rename = "baseFeePerGas")]
+	pub base_fee_per_gas: Vec<U256>,
+	/// An array of block gas used ratios. These are calculated as the ratio of gasUsed and
+	/// gasLimit.
+	#[serde(rename = "gasUsedRatio")]
+	pub gas_used_ratio: Vec<f64>,
+	/// A two-dimensional array of effective priority fees per gas at the requested block
+	/// percentiles.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reward: Option<Vec<Vec<U256>>>,
+}
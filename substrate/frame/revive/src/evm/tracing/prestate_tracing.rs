@@ -0,0 +1,195 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, PrestateTrace, PrestateTraceInfo, PrestateTracerConfig},
+	exec::Key,
+	primitives::ExecReturnValue,
+	tracing::Tracer,
+	AddressMapper, Config, ContractInfoOf, DispatchError, Pallet, PristineCode, Weight,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::marker::PhantomData;
+use frame_system::Pallet as System;
+use sp_core::{H160, H256, U256};
+use sp_runtime::SaturatedConversion;
+
+/// A Tracer that captures the state of every account touched by a transaction.
+pub struct PrestateTracer<T> {
+	/// The tracer configuration.
+	config: PrestateTracerConfig,
+	/// The state of each touched account, before it was first accessed.
+	pre: BTreeMap<H160, PrestateTraceInfo>,
+	/// The storage keys touched by each account, used to read back the post state.
+	touched_keys: BTreeMap<H160, BTreeMap<Bytes, Key>>,
+	/// Stack of the addresses whose storage is accessed by the active frames.
+	current_stack: Vec<H160>,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config> PrestateTracer<T> {
+	/// Create a new [`PrestateTracer`] instance.
+	pub fn new(config: PrestateTracerConfig) -> Self {
+		Self {
+			config,
+			pre: Default::default(),
+			touched_keys: Default::default(),
+			current_stack: Default::default(),
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Collect the state touched since the last call and return it.
+	///
+	/// In diff mode, this reads back the current state of the touched accounts, so it must be
+	/// called right after the traced transaction was executed.
+	pub fn collect_trace(&mut self) -> Option<PrestateTrace> {
+		let pre = core::mem::take(&mut self.pre);
+		let mut touched_keys = core::mem::take(&mut self.touched_keys);
+		self.current_stack.clear();
+
+		if pre.is_empty() {
+			return None;
+		}
+
+		if !self.config.diff_mode {
+			return Some(PrestateTrace::Prestate(pre));
+		}
+
+		let mut diff_pre = BTreeMap::new();
+		let mut diff_post = BTreeMap::new();
+		for (address, mut pre_info) in pre {
+			let current = self.read_account(&address);
+			let keys = touched_keys.remove(&address).unwrap_or_default();
+			let contract_info = ContractInfoOf::<T>::get(&address);
+
+			let mut post_info = PrestateTraceInfo {
+				balance: current.balance.filter(|balance| Some(*balance) != pre_info.balance),
+				nonce: current.nonce.filter(|nonce| Some(*nonce) != pre_info.nonce),
+				code: current.code.filter(|code| Some(code) != pre_info.code.as_ref()),
+				storage: Default::default(),
+			};
+
+			pre_info.storage.retain(|raw_key, old_value| {
+				let new_value = keys
+					.get(raw_key)
+					.and_then(|key| contract_info.as_ref().and_then(|info| info.read(key)))
+					.map(Bytes);
+				if new_value == *old_value {
+					return false;
+				}
+				post_info.storage.insert(raw_key.clone(), new_value);
+				true
+			});
+
+			let is_modified = post_info.balance.is_some() ||
+				post_info.nonce.is_some() ||
+				post_info.code.is_some() ||
+				!post_info.storage.is_empty();
+
+			if is_modified {
+				diff_pre.insert(address, pre_info);
+				diff_post.insert(address, post_info);
+			}
+		}
+
+		Some(PrestateTrace::DiffMode { pre: diff_pre, post: diff_post })
+	}
+
+	/// Read the balance, nonce and code of the given account.
+	fn read_account(&self, address: &H160) -> PrestateTraceInfo {
+		let account_id = T::AddressMapper::to_account_id(address);
+		let nonce: u32 = System::<T>::account_nonce(&account_id).saturated_into();
+		let code = if self.config.disable_code {
+			None
+		} else {
+			ContractInfoOf::<T>::get(address)
+				.and_then(|info| PristineCode::<T>::get(info.code_hash))
+				.map(|code| Bytes(code.into_inner()))
+		};
+
+		PrestateTraceInfo {
+			balance: Some(Pallet::<T>::evm_balance(address)),
+			nonce: (nonce != 0).then_some(nonce),
+			code,
+			storage: Default::default(),
+		}
+	}
+
+	/// Record the value of `key` before it was first accessed by the current contract.
+	fn record_storage(&mut self, key: &Key, value: Option<&[u8]>) {
+		if self.config.disable_storage {
+			return;
+		}
+
+		let Some(address) = self.current_stack.last().copied() else { return };
+		let raw_key = Bytes(key.unhashed().to_vec());
+
+		self.watch_address(&address);
+		if let Some(info) = self.pre.get_mut(&address) {
+			info.storage
+				.entry(raw_key.clone())
+				.or_insert_with(|| value.map(|value| Bytes(value.to_vec())));
+		}
+		self.touched_keys
+			.entry(address)
+			.or_default()
+			.entry(raw_key)
+			.or_insert_with(|| key.clone());
+	}
+}
+
+impl<T: Config> Tracer for PrestateTracer<T> {
+	fn watch_address(&mut self, address: &H160) {
+		if !self.pre.contains_key(address) {
+			let info = self.read_account(address);
+			self.pre.insert(*address, info);
+		}
+	}
+
+	fn enter_child_span(
+		&mut self,
+		from: H160,
+		to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas: Weight,
+	) {
+		self.watch_address(&from);
+		self.watch_address(&to);
+		self.current_stack.push(to);
+	}
+
+	fn log_event(&mut self, _address: H160, _topics: &[H256], _data: &[u8]) {}
+
+	fn storage_read(&mut self, key: &Key, value: Option<&[u8]>) {
+		self.record_storage(key, value);
+	}
+
+	fn storage_write(&mut self, key: &Key, old_value: Option<&[u8]>, _new_value: Option<&[u8]>) {
+		self.record_storage(key, old_value);
+	}
+
+	fn exit_child_span(&mut self, _output: &ExecReturnValue, _gas_used: Weight) {
+		self.current_stack.pop();
+	}
+
+	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_used: Weight) {
+		self.current_stack.pop();
+	}
+}
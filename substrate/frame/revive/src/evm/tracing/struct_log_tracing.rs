@@ -0,0 +1,173 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, StructLog, StructLogTrace, StructLoggerConfig},
+	exec::Key,
+	primitives::ExecReturnValue,
+	tracing::Tracer,
+	DispatchError, Weight,
+};
+use alloc::{collections::BTreeMap, format, string::ToString, vec::Vec};
+use sp_core::{H160, H256, U256};
+
+/// A step whose syscall did not return yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingStep {
+	/// Index of the step in the struct logs.
+	index: usize,
+	/// Amount of gas left when the step was entered.
+	gas_left: Weight,
+	/// Whether the step accessed the storage of the current contract.
+	accessed_storage: bool,
+}
+
+/// A Tracer that reports a log entry for every syscall executed by a transaction.
+///
+/// This is the equivalent of the default struct logger of Geth, where each step is a syscall
+/// rather than an EVM opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLogger<Gas, GasMapper> {
+	/// Map Weight to Gas equivalent.
+	gas_mapper: GasMapper,
+	/// The tracer configuration.
+	config: StructLoggerConfig,
+	/// The steps executed so far.
+	struct_logs: Vec<StructLog<Gas>>,
+	/// Stack of the steps whose syscall is still executing.
+	pending_steps: Vec<PendingStep>,
+	/// Storage accessed by each active frame.
+	storage_stack: Vec<BTreeMap<Bytes, Bytes>>,
+	/// The trace of the last transaction, available once its outermost frame exited.
+	trace: Option<StructLogTrace<Gas>>,
+}
+
+impl<Gas, GasMapper> StructLogger<Gas, GasMapper> {
+	/// Create a new [`StructLogger`] instance.
+	pub fn new(config: StructLoggerConfig, gas_mapper: GasMapper) -> Self {
+		Self {
+			gas_mapper,
+			config,
+			struct_logs: Vec::new(),
+			pending_steps: Vec::new(),
+			storage_stack: Vec::new(),
+			trace: None,
+		}
+	}
+
+	/// Collect the trace and return it.
+	pub fn collect_trace(&mut self) -> Option<StructLogTrace<Gas>> {
+		self.trace.take()
+	}
+
+	/// Record an access to the storage of the current contract.
+	fn record_storage(&mut self, key: &Key, value: Option<&[u8]>) {
+		if self.config.disable_storage {
+			return;
+		}
+
+		if let Some(storage) = self.storage_stack.last_mut() {
+			let value = value.map(|value| value.to_vec()).unwrap_or_default();
+			storage.insert(Bytes(key.unhashed().to_vec()), Bytes(value));
+		}
+
+		if let Some(step) = self.pending_steps.last_mut() {
+			step.accessed_storage = true;
+		}
+	}
+
+	/// Pop the current frame, and build the trace once the outermost frame exited.
+	fn exit_frame(&mut self, gas_used: Gas, failed: bool, output: &[u8]) {
+		self.storage_stack.pop();
+		if !self.storage_stack.is_empty() {
+			return;
+		}
+
+		self.pending_steps.clear();
+		self.trace = Some(StructLogTrace {
+			gas: gas_used,
+			failed,
+			return_value: if self.config.enable_return_data {
+				output.to_vec().into()
+			} else {
+				Default::default()
+			},
+			struct_logs: core::mem::take(&mut self.struct_logs),
+		});
+	}
+}
+
+impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> Tracer for StructLogger<Gas, GasMapper> {
+	fn enter_child_span(
+		&mut self,
+		_from: H160,
+		_to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas_left: Weight,
+	) {
+		self.storage_stack.push(Default::default());
+	}
+
+	fn log_event(&mut self, _address: H160, _topics: &[H256], _data: &[u8]) {}
+
+	fn storage_read(&mut self, key: &Key, value: Option<&[u8]>) {
+		self.record_storage(key, value);
+	}
+
+	fn storage_write(&mut self, key: &Key, _old_value: Option<&[u8]>, new_value: Option<&[u8]>) {
+		self.record_storage(key, new_value);
+	}
+
+	fn enter_syscall(&mut self, pc: u64, name: &[u8], gas_left: Weight) {
+		self.struct_logs.push(StructLog {
+			pc,
+			op: core::str::from_utf8(name).unwrap_or_default().to_string(),
+			gas: (self.gas_mapper)(gas_left),
+			depth: self.storage_stack.len() as u32,
+			..Default::default()
+		});
+
+		self.pending_steps.push(PendingStep {
+			index: self.struct_logs.len() - 1,
+			gas_left,
+			accessed_storage: false,
+		});
+	}
+
+	fn exit_syscall(&mut self, gas_left: Weight, error: Option<DispatchError>) {
+		let Some(step) = self.pending_steps.pop() else { return };
+		let storage = if step.accessed_storage { self.storage_stack.last().cloned() } else { None };
+		let log = &mut self.struct_logs[step.index];
+		log.gas_cost = (self.gas_mapper)(step.gas_left.saturating_sub(gas_left));
+		log.storage = storage;
+		log.error = error.map(|error| match error {
+			DispatchError::Module(sp_runtime::ModuleError { message, .. }) =>
+				message.unwrap_or_default().to_string(),
+			_ => format!("{:?}", error),
+		});
+	}
+
+	fn exit_child_span(&mut self, output: &ExecReturnValue, gas_used: Weight) {
+		self.exit_frame((self.gas_mapper)(gas_used), output.did_revert(), &output.data);
+	}
+
+	fn exit_child_span_with_error(&mut self, _error: DispatchError, gas_used: Weight) {
+		self.exit_frame((self.gas_mapper)(gas_used), true, &[]);
+	}
+}
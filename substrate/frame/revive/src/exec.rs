@@ -19,8 +19,9 @@ use crate::{
 	address::{self, AddressMapper},
 	gas::GasMeter,
 	limits,
+	precompiles::{is_precompile, Environment, Precompiles as _},
 	primitives::{ExecReturnValue, StorageDeposit},
-	pure_precompiles::{self, is_precompile},
+	pure_precompiles,
 	runtime_decl_for_revive_api::{Decode, Encode, RuntimeDebugNoBound, TypeInfo},
 	storage::{self, meter::Diff, WriteOutcome},
 	tracing::if_tracing,
@@ -74,6 +75,7 @@ pub const EMPTY_CODE_HASH: H256 =
 	H256(sp_core::hex2array!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
 
 /// Combined key type for both fixed and variable sized storage keys.
+#[derive(Clone)]
 pub enum Key {
 	/// Variant for fixed sized keys.
 	Fix([u8; 32]),
@@ -83,11 +85,6 @@ pub enum Key {
 
 impl Key {
 	/// Reference to the raw unhashed key.
-	///
-	/// # Note
-	///
-	/// Only used by benchmarking in order to generate storage collisions on purpose.
-	#[cfg(feature = "runtime-benchmarks")]
 	pub fn unhashed(&self) -> &[u8] {
 		match self {
 			Key::Fix(v) => v.as_ref(),
@@ -740,6 +737,13 @@ where
 		)? {
 			stack.run(executable, input_data).map(|_| stack.first_frame.last_frame_output)
 		} else {
+			if_tracing(|t| {
+				if let Some(from) = origin.account_id().ok().map(T::AddressMapper::to_address) {
+					t.watch_address(&from);
+				}
+				t.watch_address(&T::AddressMapper::to_address(&dest));
+			});
+
 			let result = Self::transfer_from_origin(&origin, &origin, &dest, value);
 			if_tracing(|t| {
 				t.enter_child_span(
@@ -1404,12 +1408,20 @@ where
 				)?;
 			}
 
-			pure_precompiles::Precompiles::<T>::execute(
-				precompile_address,
-				self.gas_meter_mut(),
-				input_data,
-			)
-			.map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })
+			let output = if pure_precompiles::is_precompile(&precompile_address) {
+				pure_precompiles::Precompiles::<T>::execute(
+					precompile_address,
+					self.gas_meter_mut(),
+					input_data,
+				)
+			} else if is_delegate {
+				Err(Error::<T>::PrecompileDelegateDenied.into())
+			} else {
+				let mut env = Environment::new(&mut *self, precompile_address, value_transferred);
+				T::Precompiles::call(&mut env, input_data)
+			};
+
+			output.map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })
 		};
 
 		let transaction_outcome =
@@ -1482,7 +1494,7 @@ where
 			// Enable read-only access if requested; cannot disable it if already set.
 			let is_read_only = read_only || self.is_read_only();
 
-			if is_precompile(dest_addr) {
+			if is_precompile::<T>(dest_addr) {
 				return self.run_precompile(*dest_addr, false, is_read_only, value, &input_data);
 			}
 
@@ -1513,6 +1525,11 @@ where
 			)? {
 				self.run(executable, input_data)
 			} else {
+				if_tracing(|t| {
+					t.watch_address(&T::AddressMapper::to_address(self.account_id()));
+					t.watch_address(&T::AddressMapper::to_address(&dest));
+				});
+
 				let result = if is_read_only && value.is_zero() {
 					Ok(Default::default())
 				} else if is_read_only {
@@ -1561,7 +1578,7 @@ where
 		address: H160,
 		input_data: Vec<u8>,
 	) -> Result<(), ExecError> {
-		if is_precompile(&address) {
+		if is_precompile::<T>(&address) {
 			return self.run_precompile(
 				address,
 				true,
@@ -1640,6 +1657,7 @@ where
 		if frame.entry_point == ExportedFunction::Constructor {
 			return Err(Error::<T>::TerminatedInConstructor.into());
 		}
+		if_tracing(|t| t.watch_address(beneficiary));
 		let info = frame.terminate();
 		let beneficiary_account = T::AddressMapper::to_account_id(beneficiary);
 		frame.nested_storage.terminate(&info, beneficiary_account);
@@ -1654,7 +1672,9 @@ where
 	}
 
 	fn get_storage(&mut self, key: &Key) -> Option<Vec<u8>> {
-		self.top_frame_mut().contract_info().read(key)
+		let value = self.top_frame_mut().contract_info().read(key);
+		if_tracing(|t| t.storage_read(key, value.as_deref()));
+		value
 	}
 
 	fn get_storage_size(&mut self, key: &Key) -> Option<u32> {
@@ -1668,6 +1688,10 @@ where
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
 		let frame = self.top_frame_mut();
+		if_tracing(|t| {
+			let old_value = frame.contract_info().read(key);
+			t.storage_write(key, old_value.as_deref(), value.as_deref());
+		});
 		frame.contract_info.get(&frame.account_id).write(
 			key.into(),
 			value,
@@ -1724,6 +1748,7 @@ where
 	}
 
 	fn code_hash(&self, address: &H160) -> H256 {
+		if_tracing(|t| t.watch_address(address));
 		<ContractInfoOf<T>>::get(&address)
 			.map(|contract| contract.code_hash)
 			.unwrap_or_else(|| {
@@ -1735,6 +1760,7 @@ where
 	}
 
 	fn code_size(&self, address: &H160) -> u64 {
+		if_tracing(|t| t.watch_address(address));
 		<ContractInfoOf<T>>::get(&address)
 			.and_then(|contract| CodeInfoOf::<T>::get(contract.code_hash))
 			.map(|info| info.code_len())
@@ -1788,6 +1814,7 @@ where
 	}
 
 	fn balance_of(&self, address: &H160) -> U256 {
+		if_tracing(|t| t.watch_address(address));
 		self.account_balance(&<Self::T as Config>::AddressMapper::to_account_id(address))
 	}
 
@@ -37,12 +37,16 @@ mod tests;
 
 pub mod chain_extension;
 pub mod evm;
+pub mod precompiles;
 pub mod test_utils;
 pub mod tracing;
 pub mod weights;
 
 use crate::{
-	evm::{runtime::GAS_PRICE, CallTrace, GasEncoder, GenericTransaction, TracerConfig},
+	evm::{
+		runtime::GAS_PRICE, CallTrace, EvmTracer, GasEncoder, GenericTransaction, Trace,
+		TracerConfig,
+	},
 	exec::{AccountIdOf, ExecError, Executable, Key, Stack as ExecStack},
 	gas::GasMeter,
 	storage::{meter::Meter as StorageMeter, ContractInfo, DeletionQueueManager},
@@ -80,7 +84,7 @@ use sp_runtime::{
 
 pub use crate::{
 	address::{create1, create2, is_eth_derived, AccountId32Mapper, AddressMapper},
-	exec::{MomentOf, Origin},
+	exec::{Key, MomentOf, Origin},
 	pallet::*,
 };
 pub use primitives::*;
@@ -189,6 +193,12 @@ pub mod pallet {
 		#[pallet::no_default_bounds]
 		type ChainExtension: chain_extension::ChainExtension<Self> + Default;
 
+		/// The precompiles backed by the runtime which are callable by contracts.
+		///
+		/// Consult [`precompiles`] for more information.
+		#[pallet::no_default_bounds]
+		type Precompiles: precompiles::Precompiles<Self>;
+
 		/// Find the author of the current block.
 		type FindAuthor: FindAuthor<Self::AccountId>;
 
@@ -349,6 +359,7 @@ pub mod pallet {
 			type RuntimeCall = ();
 			type CallFilter = ();
 			type ChainExtension = ();
+			type Precompiles = ();
 			type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
 			type DepositPerByte = DepositPerByte;
 			type DepositPerItem = DepositPerItem;
@@ -499,6 +510,8 @@ pub mod pallet {
 		UnsupportedPrecompileAddress = 0x2F,
 		/// Precompile Error
 		PrecompileFailure = 0x30,
+		/// Precompiles backed by the runtime can not be delegate called.
+		PrecompileDelegateDenied = 0x31,
 	}
 
 	/// A reason for the pallet contracts placing a hold on funds.
@@ -1375,6 +1388,11 @@ where
 		GAS_PRICE.into()
 	}
 
+	/// Build the EVM tracer selected by the given `config`.
+	pub fn evm_tracer(config: TracerConfig) -> EvmTracer<T> {
+		EvmTracer::new(config, Self::evm_gas_from_weight)
+	}
+
 	/// A generalized version of [`Self::upload_code`].
 	///
 	/// It is identical to [`Self::upload_code`] and only differs in the information it returns.
@@ -1472,7 +1490,7 @@ environmental!(executing_contract: bool);
 
 sp_api::decl_runtime_apis! {
 	/// The API used to dry-run contract interactions.
-	#[api_version(1)]
+	#[api_version(2)]
 	pub trait ReviveApi<AccountId, Balance, Nonce, BlockNumber> where
 		AccountId: Codec,
 		Balance: Codec,
@@ -1548,6 +1566,13 @@ sp_api::decl_runtime_apis! {
 		/// parent block.
 		///
 		/// See eth-rpc `debug_traceBlockByNumber` for usage.
+		fn trace_block(
+			block: Block,
+			config: TracerConfig
+		) -> Vec<(u32, Trace)>;
+
+		/// Traces the execution of an entire block and returns call traces.
+		#[changed_in(2)]
 		fn trace_block(
 			block: Block,
 			config: TracerConfig
@@ -1559,6 +1584,14 @@ sp_api::decl_runtime_apis! {
 		/// parent hash up to the transaction.
 		///
 		/// See eth-rpc `debug_traceTransaction` for usage.
+		fn trace_tx(
+			block: Block,
+			tx_index: u32,
+			config: TracerConfig
+		) -> Option<Trace>;
+
+		/// Traces the execution of a specific transaction within a block.
+		#[changed_in(2)]
 		fn trace_tx(
 			block: Block,
 			tx_index: u32,
@@ -1568,7 +1601,14 @@ sp_api::decl_runtime_apis! {
 		/// Dry run and return the trace of the given call.
 		///
 		/// See eth-rpc `debug_traceCall` for usage.
-		fn trace_call(tx: GenericTransaction, config: TracerConfig) -> Result<CallTrace, EthTransactError>;
+		fn trace_call(tx: GenericTransaction, config: TracerConfig) -> Result<Trace, EthTransactError>;
+
+		/// Dry run and return the trace of the given call.
+		#[changed_in(2)]
+		fn trace_call(
+			tx: GenericTransaction,
+			config: TracerConfig
+		) -> Result<CallTrace, EthTransactError>;
 
 	}
 }
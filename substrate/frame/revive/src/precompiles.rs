@@ -0,0 +1,265 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A mechanism for runtime authors to expose pallets to contracts as precompiles.
+//!
+//! The pure precompiles built into this pallet cover the stateless functions that are
+//! defined by Ethereum. In addition to those, a runtime can make any of its pallets callable by
+//! contracts by mapping an address to a type implementing [`Precompile`]. Such a precompile
+//! describes its interface in terms of the Solidity ABI and is therefore callable by any contract
+//! as if it were a regular contract deployed at that address. Examples shipped with this pallet
+//! are the [`Erc20Precompile`] over `pallet-assets` and the [`XcmPrecompile`].
+//!
+//! In order to register precompiles the runtime author lists them as a tuple in
+//! [`Config::Precompiles`] like this `type Precompiles = (PrecompileA, PrecompileB)`. The unit type
+//! `()` can be used to signal that no precompiles are available.
+//!
+//! # Addresses
+//!
+//! Every precompile decides which addresses it is reachable at using [`Precompile::matches`].
+//! This allows a single precompile to serve a range of addresses, for example one per asset.
+//! The addresses reserved for the pure precompiles always take precedence and can not be claimed.
+//! When multiple precompiles match the same address the first one in the tuple is called.
+//!
+//! # Security
+//!
+//! As with [chain extensions](crate::chain_extension) the runtime author alone is responsible
+//! for the security of the precompiles. This includes charging the appropriate amount of weight
+//! using [`Environment::charge_weight`] **before** carrying out any action that consumes it,
+//! charging storage deposits using [`Environment::charge_storage`], and refusing to mutate state
+//! when the precompile is called within a read-only context.
+
+mod erc20;
+mod xcm;
+
+pub use crate::{exec::Ext, gas::ChargedAmount, storage::meter::Diff, Config, Origin};
+pub use alloy_core::sol_types::SolInterface;
+pub use erc20::*;
+pub use xcm::*;
+
+use crate::{
+	exec::ExecResult, pure_precompiles, wasm::RuntimeCosts, Error as PalletError, ExecReturnValue,
+	LOG_TARGET,
+};
+use alloc::vec::Vec;
+use alloy_core::sol_types::{Revert, SolError};
+use frame_support::weights::Weight;
+use pallet_revive_uapi::ReturnFlags;
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+
+/// The error returned by a [`Precompile`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+	/// Revert the call and return the supplied ABI encoded data to the caller.
+	///
+	/// All state changes made by the precompile are rolled back.
+	Revert(Vec<u8>),
+	/// Abort the call with the supplied error.
+	Error(DispatchError),
+}
+
+impl Error {
+	/// Revert with the supplied reason, encoded like Solidity's `Error(string)`.
+	pub fn revert(reason: &str) -> Self {
+		Self::Revert(Revert { reason: reason.into() }.abi_encode())
+	}
+}
+
+impl From<DispatchError> for Error {
+	fn from(error: DispatchError) -> Self {
+		Self::Error(error)
+	}
+}
+
+impl<T: Config> From<PalletError<T>> for Error {
+	fn from(error: PalletError<T>) -> Self {
+		Self::Error(error.into())
+	}
+}
+
+/// A precompile that is backed by the runtime.
+///
+/// Consult the [module documentation](self) for a general explanation of precompiles.
+pub trait Precompile<C: Config> {
+	/// The Solidity interface of this precompile.
+	///
+	/// This is usually the `Calls` enum generated by `alloy_core::sol!` for an `interface`.
+	type Interface: SolInterface;
+
+	/// Returns `true` if this precompile is reachable at `address`.
+	fn matches(address: &H160) -> bool;
+
+	/// Call the precompile with the decoded `input`.
+	///
+	/// In case of `Ok` the returned buffer is passed to the caller as the ABI encoded return
+	/// value of the called function.
+	///
+	/// # Note
+	///
+	/// The precompile can be called within a read-only context, where any state-changing calls
+	/// are disallowed. This information can be obtained using [`Environment::is_read_only`].
+	fn call<E: Ext<T = C>>(
+		env: &mut Environment<E>,
+		input: Self::Interface,
+	) -> Result<Vec<u8>, Error>;
+}
+
+/// The set of precompiles registered by the runtime.
+///
+/// This trait is implemented for tuples of up to twenty [`Precompile`]s.
+pub trait Precompiles<C: Config> {
+	/// Returns `true` if one of the precompiles is reachable at `address`.
+	fn matches(address: &H160) -> bool;
+
+	/// Call the precompile reachable at [`Environment::address`].
+	fn call<E: Ext<T = C>>(env: &mut Environment<E>, input: &[u8]) -> ExecResult;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(20)]
+#[tuple_types_custom_trait_bound(Precompile<C>)]
+impl<C: Config> Precompiles<C> for Tuple {
+	fn matches(address: &H160) -> bool {
+		for_tuples!(
+			#(
+				if Tuple::matches(address) {
+					return true;
+				}
+			)*
+		);
+		false
+	}
+
+	fn call<E: Ext<T = C>>(env: &mut Environment<E>, input: &[u8]) -> ExecResult {
+		for_tuples!(
+			#(
+				if Tuple::matches(&env.address) {
+					return call_precompile::<C, Tuple, E>(env, input);
+				}
+			)*
+		);
+		Err(PalletError::<C>::UnsupportedPrecompileAddress.into())
+	}
+}
+
+/// Decode the `input` and call the precompile `P` with it.
+fn call_precompile<C: Config, P: Precompile<C>, E: Ext<T = C>>(
+	env: &mut Environment<E>,
+	input: &[u8],
+) -> ExecResult {
+	env.ext
+		.gas_meter_mut()
+		.charge(RuntimeCosts::CopyFromContract(input.len() as u32))?;
+
+	// Calling an unknown function reverts without data, just like a Solidity contract would do.
+	let Ok(input) = P::Interface::abi_decode(input, true) else {
+		log::debug!(target: LOG_TARGET, "Failed to decode input of precompile {:?}", env.address);
+		return Ok(ExecReturnValue { flags: ReturnFlags::REVERT, data: Vec::new() });
+	};
+
+	match P::call(env, input) {
+		Ok(data) => Ok(ExecReturnValue { flags: ReturnFlags::empty(), data }),
+		Err(Error::Revert(data)) => Ok(ExecReturnValue { flags: ReturnFlags::REVERT, data }),
+		Err(Error::Error(error)) => Err(error.into()),
+	}
+}
+
+/// Determine if the given address is a precompile, either pure or registered by the runtime.
+pub(crate) fn is_precompile<T: Config>(address: &H160) -> bool {
+	pure_precompiles::is_precompile(address) || T::Precompiles::matches(address)
+}
+
+/// Grants the precompile access to the execution environment of its caller.
+pub struct Environment<'a, E: Ext> {
+	/// The execution environment of the calling contract.
+	ext: &'a mut E,
+	/// The address the precompile was called at.
+	address: H160,
+	/// The value transferred to the precompile.
+	value_transferred: U256,
+}
+
+impl<'a, E: Ext> Environment<'a, E> {
+	/// Creates a new environment for a call to the precompile at `address`.
+	pub(crate) fn new(ext: &'a mut E, address: H160, value_transferred: U256) -> Self {
+		Self { ext, address, value_transferred }
+	}
+
+	/// The address the precompile was called at.
+	pub fn address(&self) -> H160 {
+		self.address
+	}
+
+	/// The contract calling the precompile.
+	pub fn caller(&self) -> Origin<E::T> {
+		Origin::from_account_id(self.ext.account_id().clone())
+	}
+
+	/// The value transferred to the precompile.
+	///
+	/// The value is already credited to the account of the precompile address when it is called.
+	pub fn value_transferred(&self) -> U256 {
+		self.value_transferred
+	}
+
+	/// Returns `true` if the precompile is called within a read-only context.
+	pub fn is_read_only(&self) -> bool {
+		self.ext.is_read_only()
+	}
+
+	/// Charge the passed `amount` of weight from the overall limit.
+	///
+	/// It returns `Err` when the remaining weight budget is smaller than `amount`. In this case
+	/// the precompile should abort the execution and pass through the error.
+	pub fn charge_weight(&mut self, amount: Weight) -> Result<ChargedAmount, DispatchError> {
+		self.ext.gas_meter_mut().charge(RuntimeCosts::Precompile(amount))
+	}
+
+	/// Adjust a previously charged amount down to its actual amount.
+	///
+	/// This is when a maximum a priori amount was charged and then should be partially
+	/// refunded to match the actual amount.
+	pub fn adjust_weight(&mut self, charged: ChargedAmount, actual_weight: Weight) {
+		self.ext
+			.gas_meter_mut()
+			.adjust_gas(charged, RuntimeCosts::Precompile(actual_weight))
+	}
+
+	/// Charge or refund a storage deposit to the calling contract's storage meter.
+	pub fn charge_storage(&mut self, diff: &Diff) {
+		self.ext.charge_storage(diff)
+	}
+
+	/// Deposit an event with the given topics, emitted by the precompile address.
+	pub fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
+		let contract = self.address;
+		crate::tracing::if_tracing(|tracer| {
+			tracer.log_event(contract, &topics, &data);
+		});
+		crate::Pallet::<E::T>::deposit_event(crate::Event::ContractEmitted {
+			contract,
+			data,
+			topics,
+		});
+	}
+
+	/// Grants access to the execution environment of the calling contract.
+	///
+	/// Consult the functions on the returned type before re-implementing those functions.
+	pub fn ext(&mut self) -> &mut E {
+		self.ext
+	}
+}
@@ -0,0 +1,280 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A precompile exposing the assets of `pallet-assets` to contracts as ERC-20 tokens.
+use super::{Environment, Error, Ext, Precompile};
+use crate::{weights::WeightInfo as _, AddressMapper, Config, Error as PalletError};
+use alloc::{vec, vec::Vec};
+use alloy_core::{
+	primitives::{Address, FixedBytes, U256},
+	sol_types::{SolEvent, SolValue},
+};
+use core::marker::PhantomData;
+use frame_support::{
+	traits::{fungibles::approvals::Inspect as _, Get},
+	weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_assets::WeightInfo as _;
+use sp_core::{H160, H256};
+use sp_runtime::{
+	traits::{StaticLookup, UniqueSaturatedInto},
+	DispatchError,
+};
+
+alloy_core::sol! {
+	/// The Solidity interface of the [`Erc20Precompile`].
+	///
+	/// This is the interface defined by ERC-20 without the optional metadata functions.
+	interface IERC20 {
+		event Transfer(address indexed from, address indexed to, uint256 value);
+		event Approval(address indexed owner, address indexed spender, uint256 value);
+
+		/// Returns the total issuance of the asset.
+		function totalSupply() external view returns (uint256);
+
+		/// Returns the balance of `account`.
+		function balanceOf(address account) external view returns (uint256);
+
+		/// Transfer `value` from the calling contract to `to`.
+		function transfer(address to, uint256 value) external returns (bool);
+
+		/// Returns the amount `spender` is allowed to transfer on behalf of `owner`.
+		function allowance(address owner, address spender) external view returns (uint256);
+
+		/// Allow `spender` to transfer up to `value` on behalf of the calling contract.
+		function approve(address spender, uint256 value) external returns (bool);
+
+		/// Transfer `value` from `from` to `to` using the allowance of the calling contract.
+		function transferFrom(address from, address to, uint256 value) external returns (bool);
+	}
+}
+
+/// The address of the [`Erc20Precompile`] of the asset `asset_id`.
+///
+/// The asset id is stored big endian in the first four bytes of the address and `prefix` in
+/// the bytes 16 and 17. All other bytes are zero.
+pub fn erc20_address(asset_id: u32, prefix: u16) -> H160 {
+	let mut address = [0u8; 20];
+	address[..4].copy_from_slice(&asset_id.to_be_bytes());
+	address[16..18].copy_from_slice(&prefix.to_be_bytes());
+	H160(address)
+}
+
+/// A precompile that exposes every asset of the `pallet-assets` instance `I` as an ERC-20 token.
+///
+/// Each asset is reachable at its own [`erc20_address`] using `Prefix`, which needs to be unique
+/// among the precompiles of the runtime. The calling contract is the owner of the transferred
+/// and approved assets.
+pub struct Erc20Precompile<T, Prefix, I = ()>(PhantomData<(T, Prefix, I)>);
+
+impl<T, Prefix, I> Precompile<T> for Erc20Precompile<T, Prefix, I>
+where
+	T: Config + pallet_assets::Config<I>,
+	T::AssetId: From<u32>,
+	Prefix: Get<u16>,
+	I: 'static,
+{
+	type Interface = IERC20::IERC20Calls;
+
+	fn matches(address: &H160) -> bool {
+		let address = address.as_bytes();
+		address[4..16].iter().all(|byte| *byte == 0) &&
+			address[16..18] == Prefix::get().to_be_bytes() &&
+			address[18..] == [0, 0]
+	}
+
+	fn call<E: Ext<T = T>>(
+		env: &mut Environment<E>,
+		input: Self::Interface,
+	) -> Result<Vec<u8>, Error> {
+		use IERC20::IERC20Calls;
+
+		let mut asset_id = [0u8; 4];
+		asset_id.copy_from_slice(&env.address().as_bytes()[..4]);
+		let asset_id = T::AssetId::from(u32::from_be_bytes(asset_id));
+
+		match input {
+			IERC20Calls::totalSupply(_) => {
+				env.charge_weight(T::DbWeight::get().reads(1))?;
+				let supply = pallet_assets::Pallet::<T, I>::total_supply(asset_id);
+				Ok(to_u256(supply).abi_encode())
+			},
+			IERC20Calls::balanceOf(IERC20::balanceOfCall { account }) => {
+				env.charge_weight(T::DbWeight::get().reads(1))?;
+				let balance =
+					pallet_assets::Pallet::<T, I>::balance(asset_id, to_account_id::<T>(&account));
+				Ok(to_u256(balance).abi_encode())
+			},
+			IERC20Calls::allowance(IERC20::allowanceCall { owner, spender }) => {
+				env.charge_weight(T::DbWeight::get().reads(1))?;
+				let allowance = pallet_assets::Pallet::<T, I>::allowance(
+					asset_id,
+					&to_account_id::<T>(&owner),
+					&to_account_id::<T>(&spender),
+				);
+				Ok(to_u256(allowance).abi_encode())
+			},
+			IERC20Calls::transfer(IERC20::transferCall { to, value }) => {
+				ensure_mutable::<E>(env)?;
+				env.charge_weight(
+					<T as pallet_assets::Config<I>>::WeightInfo::transfer()
+						.saturating_add(event_weight::<T>()),
+				)?;
+
+				let from = env.ext().address();
+				pallet_assets::Pallet::<T, I>::transfer(
+					origin::<E>(env),
+					asset_id.into(),
+					lookup_source::<T>(&to),
+					to_balance::<T, I>(value)?,
+				)
+				.map_err(revert)?;
+
+				deposit_event(env, IERC20::Transfer::SIGNATURE_HASH, from, to_h160(&to), value);
+				Ok(true.abi_encode())
+			},
+			IERC20Calls::approve(IERC20::approveCall { spender, value }) => {
+				ensure_mutable::<E>(env)?;
+				let approve_weight =
+					<T as pallet_assets::Config<I>>::WeightInfo::approve_transfer()
+						.saturating_add(event_weight::<T>());
+				let charged = env.charge_weight(
+					<T as pallet_assets::Config<I>>::WeightInfo::cancel_approval()
+						.saturating_add(approve_weight),
+				)?;
+
+				// Approvals of `pallet-assets` accumulate while ERC-20 replaces the allowance.
+				let amount = to_balance::<T, I>(value)?;
+				let owner = env.ext().address();
+				let spender_id = to_account_id::<T>(&spender);
+				let existing = pallet_assets::Approvals::<T, I>::contains_key((
+					asset_id.clone(),
+					env.ext().account_id(),
+					&spender_id,
+				));
+				if existing {
+					pallet_assets::Pallet::<T, I>::cancel_approval(
+						origin::<E>(env),
+						asset_id.clone().into(),
+						T::Lookup::unlookup(spender_id.clone()),
+					)
+					.map_err(revert)?;
+				} else {
+					env.adjust_weight(charged, approve_weight);
+				}
+
+				if !value.is_zero() {
+					pallet_assets::Pallet::<T, I>::approve_transfer(
+						origin::<E>(env),
+						asset_id.into(),
+						T::Lookup::unlookup(spender_id),
+						amount,
+					)
+					.map_err(revert)?;
+				}
+
+				deposit_event(
+					env,
+					IERC20::Approval::SIGNATURE_HASH,
+					owner,
+					to_h160(&spender),
+					value,
+				);
+				Ok(true.abi_encode())
+			},
+			IERC20Calls::transferFrom(IERC20::transferFromCall { from, to, value }) => {
+				ensure_mutable::<E>(env)?;
+				env.charge_weight(
+					<T as pallet_assets::Config<I>>::WeightInfo::transfer_approved()
+						.saturating_add(event_weight::<T>()),
+				)?;
+
+				pallet_assets::Pallet::<T, I>::transfer_approved(
+					origin::<E>(env),
+					asset_id.into(),
+					lookup_source::<T>(&from),
+					lookup_source::<T>(&to),
+					to_balance::<T, I>(value)?,
+				)
+				.map_err(revert)?;
+
+				let (from, to) = (to_h160(&from), to_h160(&to));
+				deposit_event(env, IERC20::Transfer::SIGNATURE_HASH, from, to, value);
+				Ok(true.abi_encode())
+			},
+		}
+	}
+}
+
+/// Refuse to change any state when called within a read-only context.
+fn ensure_mutable<E: Ext>(env: &Environment<E>) -> Result<(), Error> {
+	if env.is_read_only() {
+		return Err(PalletError::<E::T>::StateChangeDenied.into());
+	}
+	Ok(())
+}
+
+/// The calling contract as a signed origin.
+fn origin<E: Ext>(env: &mut Environment<E>) -> <E::T as frame_system::Config>::RuntimeOrigin {
+	RawOrigin::Signed(env.ext().account_id().clone()).into()
+}
+
+fn to_account_id<T: Config>(address: &Address) -> T::AccountId {
+	T::AddressMapper::to_account_id(&to_h160(address))
+}
+
+fn lookup_source<T: Config>(address: &Address) -> <T::Lookup as StaticLookup>::Source {
+	T::Lookup::unlookup(to_account_id::<T>(address))
+}
+
+fn to_h160(address: &Address) -> H160 {
+	H160(address.into_array())
+}
+
+fn to_u256<B: UniqueSaturatedInto<u128>>(balance: B) -> U256 {
+	let balance: u128 = balance.unique_saturated_into();
+	U256::from(balance)
+}
+
+fn to_balance<T: pallet_assets::Config<I>, I: 'static>(value: U256) -> Result<T::Balance, Error> {
+	u128::try_from(value)
+		.ok()
+		.and_then(|value| value.try_into().ok())
+		.ok_or_else(|| Error::revert("value out of range"))
+}
+
+/// Revert with the name of the error returned by `pallet-assets`.
+fn revert(error: DispatchError) -> Error {
+	let reason: &'static str = error.into();
+	Error::revert(reason)
+}
+
+/// The weight of depositing a [`IERC20::Transfer`] or [`IERC20::Approval`] event.
+fn event_weight<T: Config>() -> Weight {
+	T::WeightInfo::seal_deposit_event(3, 32)
+}
+
+fn deposit_event<E: Ext>(
+	env: &mut Environment<E>,
+	signature: FixedBytes<32>,
+	from: H160,
+	to: H160,
+	value: U256,
+) {
+	let topics = vec![H256(signature.0), H256::from(from), H256::from(to)];
+	env.deposit_event(topics, value.abi_encode());
+}
@@ -0,0 +1,121 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A precompile to send and execute XCM programs on behalf of the calling contract.
+use super::{Environment, Error, Ext, Precompile};
+use crate::{Config, Error as PalletError};
+use alloc::{boxed::Box, vec::Vec};
+use codec::DecodeLimit;
+use core::marker::PhantomData;
+use frame_support::weights::Weight;
+use frame_system::RawOrigin;
+use sp_core::H160;
+use xcm::{VersionedLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+use xcm_builder::{
+	ExecuteController, ExecuteControllerWeightInfo, SendController, SendControllerWeightInfo,
+};
+
+type CallOf<T> = <T as frame_system::Config>::RuntimeCall;
+
+alloy_core::sol! {
+	/// The Solidity interface of the [`XcmPrecompile`].
+	///
+	/// All locations and messages are SCALE encoded versioned XCM types.
+	interface IXcm {
+		/// Execute an XCM program locally, using at most the given amount of weight.
+		function xcmExecute(bytes calldata message, uint64 refTime, uint64 proofSize) external;
+
+		/// Send an XCM program to `destination` and return the message id.
+		function xcmSend(bytes calldata destination, bytes calldata message) external returns (bytes32);
+	}
+}
+
+/// The address of the [`XcmPrecompile`].
+pub const XCM_PRECOMPILE_ADDRESS: H160 =
+	H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0a, 0, 0]);
+
+/// A precompile that sends and executes XCM programs using [`Config::Xcm`].
+///
+/// The calling contract is used as the origin of the programs.
+pub struct XcmPrecompile<T>(PhantomData<T>);
+
+impl<T: Config> Precompile<T> for XcmPrecompile<T> {
+	type Interface = IXcm::IXcmCalls;
+
+	fn matches(address: &H160) -> bool {
+		*address == XCM_PRECOMPILE_ADDRESS
+	}
+
+	fn call<E: Ext<T = T>>(
+		env: &mut Environment<E>,
+		input: Self::Interface,
+	) -> Result<Vec<u8>, Error> {
+		if env.is_read_only() {
+			return Err(PalletError::<T>::StateChangeDenied.into());
+		}
+
+		let origin = RawOrigin::Signed(env.ext().account_id().clone()).into();
+		match input {
+			IXcm::IXcmCalls::xcmExecute(IXcm::xcmExecuteCall { message, refTime, proofSize }) => {
+				let message = VersionedXcm::<CallOf<T>>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &message[..],
+				)
+				.map_err(|_| Error::revert("invalid XCM message"))?;
+
+				let execute_weight =
+					<<T as Config>::Xcm as ExecuteController<_, _>>::WeightInfo::execute();
+				let max_weight = Weight::from_parts(refTime, proofSize);
+				let charged = env.charge_weight(execute_weight.saturating_add(max_weight))?;
+
+				let weight_used =
+					<<T as Config>::Xcm>::execute(origin, Box::new(message), max_weight).map_err(
+						|e| {
+							log::debug!(target: crate::LOG_TARGET, "XCM execution failed with: {e:?}");
+							Error::revert("XCM execution failed")
+						},
+					)?;
+				env.adjust_weight(charged, weight_used.saturating_add(execute_weight));
+
+				Ok(Vec::new())
+			},
+			IXcm::IXcmCalls::xcmSend(IXcm::xcmSendCall { destination, message }) => {
+				let destination = VersionedLocation::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &destination[..],
+				)
+				.map_err(|_| Error::revert("invalid XCM destination"))?;
+				let message = VersionedXcm::<()>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &message[..],
+				)
+				.map_err(|_| Error::revert("invalid XCM message"))?;
+
+				let weight = <<T as Config>::Xcm as SendController<_>>::WeightInfo::send();
+				env.charge_weight(weight)?;
+
+				let message_id =
+					<<T as Config>::Xcm>::send(origin, Box::new(destination), Box::new(message))
+						.map_err(|e| {
+							log::debug!(target: crate::LOG_TARGET, "XCM send failed with: {e:?}");
+							Error::revert("XCM send failed")
+						})?;
+
+				Ok(message_id.to_vec())
+			},
+		}
+	}
+}
@@ -38,11 +38,28 @@ pub use modexp::*;
 mod blake2f;
 pub use blake2f::*;
 
+mod bls12_381;
+pub use bls12_381::*;
+
+mod p256_verify;
+pub use p256_verify::*;
+
+/// The address index of the `P256Verify` precompile.
+const P256_VERIFY: u16 = 0x100;
+
 /// Determine if the given address is a precompile.
-/// For now, we consider that all addresses between 0x1 and 0xff are reserved for precompiles.
+/// For now, we consider that all addresses between 0x1 and 0xff are reserved for precompiles,
+/// together with the `P256Verify` precompile at 0x100.
 pub fn is_precompile(address: &H160) -> bool {
+	matches!(precompile_index(address), Some(1..=0xff | P256_VERIFY))
+}
+
+/// Returns the index of a precompile address, i.e. its last two bytes if all the others are zero.
+fn precompile_index(address: &H160) -> Option<u16> {
 	let bytes = address.as_bytes();
-	bytes.starts_with(&[0u8; 19]) && bytes[19] != 0
+	bytes
+		.starts_with(&[0u8; 18])
+		.then(|| u16::from_be_bytes([bytes[18], bytes[19]]))
 }
 
 /// The `Precompile` trait defines the functionality for executing a precompiled contract.
@@ -57,16 +74,28 @@ pub struct Precompiles<T: Config> {
 
 impl<T: Config> Precompiles<T> {
 	pub fn execute(addr: H160, gas_meter: &mut GasMeter<T>, input: &[u8]) -> ExecResult {
-		match addr.as_bytes()[19] {
-			1u8 => ECRecover::execute(gas_meter, input),
-			2u8 => Sha256::execute(gas_meter, input),
-			3u8 => Ripemd160::execute(gas_meter, input),
-			4u8 => Identity::execute(gas_meter, input),
-			5u8 => Modexp::execute(gas_meter, input),
-			6u8 => Bn128Add::execute(gas_meter, input),
-			7u8 => Bn128Mul::execute(gas_meter, input),
-			8u8 => Bn128Pairing::execute(gas_meter, input),
-			9u8 => Blake2F::execute(gas_meter, input),
+		let Some(index) = precompile_index(&addr) else {
+			return Err(Error::<T>::UnsupportedPrecompileAddress.into())
+		};
+
+		match index {
+			1 => ECRecover::execute(gas_meter, input),
+			2 => Sha256::execute(gas_meter, input),
+			3 => Ripemd160::execute(gas_meter, input),
+			4 => Identity::execute(gas_meter, input),
+			5 => Modexp::execute(gas_meter, input),
+			6 => Bn128Add::execute(gas_meter, input),
+			7 => Bn128Mul::execute(gas_meter, input),
+			8 => Bn128Pairing::execute(gas_meter, input),
+			9 => Blake2F::execute(gas_meter, input),
+			0x0b => Bls12G1Add::execute(gas_meter, input),
+			0x0c => Bls12G1Msm::execute(gas_meter, input),
+			0x0d => Bls12G2Add::execute(gas_meter, input),
+			0x0e => Bls12G2Msm::execute(gas_meter, input),
+			0x0f => Bls12Pairing::execute(gas_meter, input),
+			0x10 => Bls12MapFpToG1::execute(gas_meter, input),
+			0x11 => Bls12MapFp2ToG2::execute(gas_meter, input),
+			P256_VERIFY => P256Verify::execute(gas_meter, input),
 			_ => return Err(Error::<T>::UnsupportedPrecompileAddress.into()),
 		}
 		.map_err(|reason| {
@@ -0,0 +1,417 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! The BLS12-381 precompiles, as defined by [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537).
+use super::Precompile;
+use crate::{Config, ExecReturnValue, GasMeter, RuntimeCosts};
+use alloc::vec::Vec;
+use ark_bls12_381::{
+	g1, g2, Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective,
+};
+use ark_ec::{
+	hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+	pairing::Pairing,
+	short_weierstrass::{Affine, SWCurveConfig},
+	AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInt, BigInteger, One, PrimeField, Zero};
+use pallet_revive_uapi::ReturnFlags;
+
+/// Number of zero bytes used to pad a base field element to 64 bytes.
+const FP_PADDING: usize = 16;
+/// Length of an encoded base field element.
+const FP_LENGTH: usize = 64;
+/// Length of an encoded G1 point.
+const G1_LENGTH: usize = 2 * FP_LENGTH;
+/// Length of an encoded G2 point.
+const G2_LENGTH: usize = 4 * FP_LENGTH;
+/// Length of an encoded scalar.
+const SCALAR_LENGTH: usize = 32;
+/// Length of an encoded (G1 point, scalar) pair.
+const G1_MSM_PAIR_LENGTH: usize = G1_LENGTH + SCALAR_LENGTH;
+/// Length of an encoded (G2 point, scalar) pair.
+const G2_MSM_PAIR_LENGTH: usize = G2_LENGTH + SCALAR_LENGTH;
+/// Length of an encoded (G1 point, G2 point) pair.
+const PAIRING_PAIR_LENGTH: usize = G1_LENGTH + G2_LENGTH;
+
+const G1_ADD_GAS: u64 = 375;
+const G2_ADD_GAS: u64 = 600;
+const G1_MSM_BASE_GAS: u64 = 12_000;
+const G2_MSM_BASE_GAS: u64 = 22_500;
+const PAIRING_BASE_GAS: u64 = 37_700;
+const PAIRING_PER_PAIR_GAS: u64 = 32_600;
+const MAP_FP_TO_G1_GAS: u64 = 5_500;
+const MAP_FP2_TO_G2_GAS: u64 = 23_800;
+
+/// Discounts applied to the G1 MSM gas cost, indexed by the number of pairs minus one.
+const G1_MSM_DISCOUNTS: [u16; 128] = [
+	1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+	661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+	613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+	584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+	562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+	544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+	528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// Discounts applied to the G2 MSM gas cost, indexed by the number of pairs minus one.
+const G2_MSM_DISCOUNTS: [u16; 128] = [
+	1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+	699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+	629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+	592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+	567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+	548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+	533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+/// The Bls12G1Add precompile.
+pub struct Bls12G1Add;
+
+impl<T: Config> Precompile<T> for Bls12G1Add {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.len() != 2 * G1_LENGTH {
+			return Err("invalid input length");
+		}
+		gas_meter.charge(RuntimeCosts::Bls12(G1_ADD_GAS))?;
+
+		let a = read_g1(&input[..G1_LENGTH], false)?;
+		let b = read_g1(&input[G1_LENGTH..], false)?;
+		let sum = (a + b).into_affine();
+
+		Ok(ExecReturnValue { data: encode_g1(&sum), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12G1Msm precompile.
+pub struct Bls12G1Msm;
+
+impl<T: Config> Precompile<T> for Bls12G1Msm {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.is_empty() || input.len() % G1_MSM_PAIR_LENGTH != 0 {
+			return Err("invalid input length");
+		}
+		let pairs = input.len() / G1_MSM_PAIR_LENGTH;
+		gas_meter.charge(RuntimeCosts::Bls12(msm_gas(
+			pairs,
+			G1_MSM_BASE_GAS,
+			&G1_MSM_DISCOUNTS,
+		)))?;
+
+		let mut points = Vec::with_capacity(pairs);
+		let mut scalars = Vec::with_capacity(pairs);
+		for pair in input.chunks_exact(G1_MSM_PAIR_LENGTH) {
+			points.push(read_g1(&pair[..G1_LENGTH], true)?);
+			scalars.push(read_scalar(&pair[G1_LENGTH..]));
+		}
+
+		let result = G1Projective::msm(&points, &scalars)
+			.map_err(|_| "invalid input length")?
+			.into_affine();
+
+		Ok(ExecReturnValue { data: encode_g1(&result), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12G2Add precompile.
+pub struct Bls12G2Add;
+
+impl<T: Config> Precompile<T> for Bls12G2Add {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.len() != 2 * G2_LENGTH {
+			return Err("invalid input length");
+		}
+		gas_meter.charge(RuntimeCosts::Bls12(G2_ADD_GAS))?;
+
+		let a = read_g2(&input[..G2_LENGTH], false)?;
+		let b = read_g2(&input[G2_LENGTH..], false)?;
+		let sum = (a + b).into_affine();
+
+		Ok(ExecReturnValue { data: encode_g2(&sum), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12G2Msm precompile.
+pub struct Bls12G2Msm;
+
+impl<T: Config> Precompile<T> for Bls12G2Msm {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.is_empty() || input.len() % G2_MSM_PAIR_LENGTH != 0 {
+			return Err("invalid input length");
+		}
+		let pairs = input.len() / G2_MSM_PAIR_LENGTH;
+		gas_meter.charge(RuntimeCosts::Bls12(msm_gas(
+			pairs,
+			G2_MSM_BASE_GAS,
+			&G2_MSM_DISCOUNTS,
+		)))?;
+
+		let mut points = Vec::with_capacity(pairs);
+		let mut scalars = Vec::with_capacity(pairs);
+		for pair in input.chunks_exact(G2_MSM_PAIR_LENGTH) {
+			points.push(read_g2(&pair[..G2_LENGTH], true)?);
+			scalars.push(read_scalar(&pair[G2_LENGTH..]));
+		}
+
+		let result = G2Projective::msm(&points, &scalars)
+			.map_err(|_| "invalid input length")?
+			.into_affine();
+
+		Ok(ExecReturnValue { data: encode_g2(&result), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12Pairing precompile.
+pub struct Bls12Pairing;
+
+impl<T: Config> Precompile<T> for Bls12Pairing {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.is_empty() || input.len() % PAIRING_PAIR_LENGTH != 0 {
+			return Err("invalid input length");
+		}
+		let pairs = input.len() / PAIRING_PAIR_LENGTH;
+		gas_meter.charge(RuntimeCosts::Bls12(
+			PAIRING_BASE_GAS.saturating_add(PAIRING_PER_PAIR_GAS.saturating_mul(pairs as u64)),
+		))?;
+
+		let mut g1_points = Vec::with_capacity(pairs);
+		let mut g2_points = Vec::with_capacity(pairs);
+		for pair in input.chunks_exact(PAIRING_PAIR_LENGTH) {
+			g1_points.push(read_g1(&pair[..G1_LENGTH], true)?);
+			g2_points.push(read_g2(&pair[G1_LENGTH..], true)?);
+		}
+
+		let mut buf = [0u8; 32];
+		if Bls12_381::multi_pairing(g1_points, g2_points).0.is_one() {
+			buf[31] = 1;
+		}
+
+		Ok(ExecReturnValue { data: buf.to_vec(), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12MapFpToG1 precompile.
+pub struct Bls12MapFpToG1;
+
+impl<T: Config> Precompile<T> for Bls12MapFpToG1 {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.len() != FP_LENGTH {
+			return Err("invalid input length");
+		}
+		gas_meter.charge(RuntimeCosts::Bls12(MAP_FP_TO_G1_GAS))?;
+
+		let fp = read_fp(input)?;
+		let point = <WBMap<g1::Config> as MapToCurve<G1Projective>>::new()
+			.and_then(|map| map.map_to_curve(fp))
+			.map_err(|_| "failed to map field element to curve")?
+			.clear_cofactor();
+
+		Ok(ExecReturnValue { data: encode_g1(&point), flags: ReturnFlags::empty() })
+	}
+}
+
+/// The Bls12MapFp2ToG2 precompile.
+pub struct Bls12MapFp2ToG2;
+
+impl<T: Config> Precompile<T> for Bls12MapFp2ToG2 {
+	fn execute(gas_meter: &mut GasMeter<T>, input: &[u8]) -> Result<ExecReturnValue, &'static str> {
+		if input.len() != 2 * FP_LENGTH {
+			return Err("invalid input length");
+		}
+		gas_meter.charge(RuntimeCosts::Bls12(MAP_FP2_TO_G2_GAS))?;
+
+		let fp2 = read_fp2(input)?;
+		let point = <WBMap<g2::Config> as MapToCurve<G2Projective>>::new()
+			.and_then(|map| map.map_to_curve(fp2))
+			.map_err(|_| "failed to map field element to curve")?
+			.clear_cofactor();
+
+		Ok(ExecReturnValue { data: encode_g2(&point), flags: ReturnFlags::empty() })
+	}
+}
+
+/// Gas cost of a multi-scalar multiplication over the given number of pairs.
+fn msm_gas(pairs: usize, base_gas: u64, discounts: &[u16]) -> u64 {
+	let discount = discounts[pairs.min(discounts.len()) - 1] as u64;
+	(pairs as u64).saturating_mul(base_gas).saturating_mul(discount) / 1000
+}
+
+/// Decode a base field element, encoded as 16 zero bytes followed by 48 big-endian bytes.
+fn read_fp(input: &[u8]) -> Result<Fq, &'static str> {
+	let (padding, bytes) = input.split_at(FP_PADDING);
+	if padding.iter().any(|byte| *byte != 0) {
+		return Err("invalid field element top bytes");
+	}
+
+	let mut limbs = [0u64; 6];
+	for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(8)) {
+		*limb = u64::from_be_bytes(chunk.try_into().expect("chunk is 8 bytes long; qed"));
+	}
+
+	Fq::from_bigint(BigInt::new(limbs)).ok_or("invalid field element")
+}
+
+/// Decode an extension field element, encoded as its `c0` and `c1` base field elements.
+fn read_fp2(input: &[u8]) -> Result<Fq2, &'static str> {
+	let c0 = read_fp(&input[..FP_LENGTH])?;
+	let c1 = read_fp(&input[FP_LENGTH..2 * FP_LENGTH])?;
+	Ok(Fq2::new(c0, c1))
+}
+
+/// Decode a scalar, encoded as 32 big-endian bytes.
+fn read_scalar(input: &[u8]) -> Fr {
+	Fr::from_be_bytes_mod_order(input)
+}
+
+/// Build a point from its affine coordinates, where `(0, 0)` encodes the point at infinity.
+fn read_point<P: SWCurveConfig>(
+	x: P::BaseField,
+	y: P::BaseField,
+	subgroup_check: bool,
+) -> Result<Affine<P>, &'static str> {
+	if x.is_zero() && y.is_zero() {
+		return Ok(Affine::identity());
+	}
+
+	let point = Affine::new_unchecked(x, y);
+	if !point.is_on_curve() {
+		return Err("point is not on curve");
+	}
+
+	if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+		return Err("point is not in the correct subgroup");
+	}
+
+	Ok(point)
+}
+
+fn read_g1(input: &[u8], subgroup_check: bool) -> Result<G1Affine, &'static str> {
+	let x = read_fp(&input[..FP_LENGTH])?;
+	let y = read_fp(&input[FP_LENGTH..G1_LENGTH])?;
+	read_point(x, y, subgroup_check)
+}
+
+fn read_g2(input: &[u8], subgroup_check: bool) -> Result<G2Affine, &'static str> {
+	let x = read_fp2(&input[..2 * FP_LENGTH])?;
+	let y = read_fp2(&input[2 * FP_LENGTH..G2_LENGTH])?;
+	read_point(x, y, subgroup_check)
+}
+
+fn write_fp(fp: &Fq, output: &mut Vec<u8>) {
+	output.extend_from_slice(&[0u8; FP_PADDING]);
+	output.extend(fp.into_bigint().to_bytes_be());
+}
+
+fn encode_g1(point: &G1Affine) -> Vec<u8> {
+	let mut output = Vec::with_capacity(G1_LENGTH);
+	match point.xy() {
+		Some((x, y)) => {
+			write_fp(x, &mut output);
+			write_fp(y, &mut output);
+		},
+		None => output.resize(G1_LENGTH, 0),
+	}
+	output
+}
+
+fn encode_g2(point: &G2Affine) -> Vec<u8> {
+	let mut output = Vec::with_capacity(G2_LENGTH);
+	match point.xy() {
+		Some((x, y)) => {
+			write_fp(&x.c0, &mut output);
+			write_fp(&x.c1, &mut output);
+			write_fp(&y.c0, &mut output);
+			write_fp(&y.c1, &mut output);
+		},
+		None => output.resize(G2_LENGTH, 0),
+	}
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::pure_precompiles::test::*;
+
+	#[test]
+	fn test_bls12_g1_add() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12G1Add>(include_str!("./testdata/11-bls12g1add.json"))?;
+		test_precompile_failure_test_vectors::<Bls12G1Add>(include_str!(
+			"./testdata/11-bls12g1add-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_g1_msm() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12G1Msm>(include_str!("./testdata/12-bls12g1msm.json"))?;
+		test_precompile_failure_test_vectors::<Bls12G1Msm>(include_str!(
+			"./testdata/12-bls12g1msm-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_g2_add() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12G2Add>(include_str!("./testdata/13-bls12g2add.json"))?;
+		test_precompile_failure_test_vectors::<Bls12G2Add>(include_str!(
+			"./testdata/13-bls12g2add-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_g2_msm() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12G2Msm>(include_str!("./testdata/14-bls12g2msm.json"))?;
+		test_precompile_failure_test_vectors::<Bls12G2Msm>(include_str!(
+			"./testdata/14-bls12g2msm-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_pairing() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12Pairing>(include_str!(
+			"./testdata/15-bls12pairing.json"
+		))?;
+		test_precompile_failure_test_vectors::<Bls12Pairing>(include_str!(
+			"./testdata/15-bls12pairing-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_map_fp_to_g1() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12MapFpToG1>(include_str!(
+			"./testdata/16-bls12mapfptog1.json"
+		))?;
+		test_precompile_failure_test_vectors::<Bls12MapFpToG1>(include_str!(
+			"./testdata/16-bls12mapfptog1-failure.json"
+		))?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_bls12_map_fp2_to_g2() -> Result<(), String> {
+		test_precompile_test_vectors::<Bls12MapFp2ToG2>(include_str!(
+			"./testdata/17-bls12mapfp2tog2.json"
+		))?;
+		test_precompile_failure_test_vectors::<Bls12MapFp2ToG2>(include_str!(
+			"./testdata/17-bls12mapfp2tog2-failure.json"
+		))?;
+		Ok(())
+	}
+}
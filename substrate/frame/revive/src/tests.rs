@@ -28,6 +28,7 @@ use crate::{
 	evm::{runtime::GAS_PRICE, CallTrace, CallTracer, CallType, GenericTransaction},
 	exec::Key,
 	limits,
+	precompiles::{self, Precompile},
 	storage::DeletionQueueManager,
 	test_utils::*,
 	tests::test_utils::{get_contract, get_contract_checked},
@@ -51,7 +52,8 @@ use frame_support::{
 	traits::{
 		fungible::{BalancedHold, Inspect, Mutate, MutateHold},
 		tokens::Preservation,
-		ConstU32, ConstU64, Contains, FindAuthor, OnIdle, OnInitialize, StorageVersion,
+		AsEnsureOriginWithArg, ConstU32, ConstU64, Contains, FindAuthor, OnIdle, OnInitialize,
+		StorageVersion,
 	},
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, FixedFee, IdentityFee, Weight, WeightMeter},
 };
@@ -81,7 +83,8 @@ frame_support::construct_runtime!(
 		Contracts: pallet_revive,
 		Proxy: pallet_proxy,
 		TransactionPayment: pallet_transaction_payment,
-		Dummy: pallet_dummy
+		Dummy: pallet_dummy,
+		Assets: pallet_assets,
 	}
 );
 
@@ -373,6 +376,46 @@ impl RegisteredChainExtension<Test> for TempStorageExtension {
 	const ID: u16 = 3;
 }
 
+alloy_core::sol! {
+	interface ITestPrecompile {
+		function echo(bytes calldata data) external returns (bytes memory);
+		function fail(string calldata reason) external;
+	}
+}
+
+pub struct TestPrecompile;
+
+impl TestPrecompile {
+	fn address() -> H160 {
+		H160::from_low_u64_be(0xb0000)
+	}
+}
+
+impl Precompile<Test> for TestPrecompile {
+	type Interface = ITestPrecompile::ITestPrecompileCalls;
+
+	fn matches(address: &H160) -> bool {
+		*address == Self::address()
+	}
+
+	fn call<E: Ext<T = Test>>(
+		env: &mut precompiles::Environment<E>,
+		input: Self::Interface,
+	) -> Result<Vec<u8>, precompiles::Error> {
+		use alloy_core::sol_types::SolValue;
+
+		env.charge_weight(Weight::from_parts(1_000, 0))?;
+		match input {
+			ITestPrecompile::ITestPrecompileCalls::echo(ITestPrecompile::echoCall { data }) => {
+				env.deposit_event(vec![H256::repeat_byte(1)], data.to_vec());
+				Ok(data.abi_encode())
+			},
+			ITestPrecompile::ITestPrecompileCalls::fail(ITestPrecompile::failCall { reason }) =>
+				Err(precompiles::Error::revert(&reason)),
+		}
+	}
+}
+
 parameter_types! {
 	pub BlockWeights: frame_system::limits::BlockWeights =
 		frame_system::limits::BlockWeights::simple_max(
@@ -423,6 +466,17 @@ impl pallet_proxy::Config for Test {
 	type BlockNumberProvider = frame_system::Pallet<Test>;
 }
 
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId32>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId32>;
+}
+
+parameter_types! {
+	pub const Erc20Prefix: u16 = 0x0120;
+}
+
 parameter_types! {
 	pub FeeMultiplier: Multiplier = Multiplier::one();
 }
@@ -528,6 +582,7 @@ impl Config for Test {
 	type CallFilter = TestFilter;
 	type ChainExtension =
 		(TestExtension, DisabledExtension, RevertingExtension, TempStorageExtension);
+	type Precompiles = (TestPrecompile, precompiles::Erc20Precompile<Self, Erc20Prefix>);
 	type DepositPerByte = DepositPerByte;
 	type DepositPerItem = DepositPerItem;
 	type UnsafeUnstableInterface = UnstableInterface;
@@ -4578,6 +4633,150 @@ fn tracing_works() {
 	});
 }
 
+#[test]
+fn prestate_tracing_works_for_transfers() {
+	use crate::evm::*;
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+		let alice_pre = Pallet::<Test>::evm_balance(&ALICE_ADDR);
+		let bob_pre = Pallet::<Test>::evm_balance(&BOB_ADDR);
+
+		let mut tracer = PrestateTracer::<Test>::new(Default::default());
+		trace(&mut tracer, || {
+			builder::bare_call(BOB_ADDR).value(10_000_000).build_and_unwrap_result();
+		});
+		assert_eq!(
+			tracer.collect_trace(),
+			Some(PrestateTrace::Prestate(
+				[
+					(
+						ALICE_ADDR,
+						PrestateTraceInfo { balance: Some(alice_pre), ..Default::default() }
+					),
+					(BOB_ADDR, PrestateTraceInfo { balance: Some(bob_pre), ..Default::default() }),
+				]
+				.into()
+			))
+		);
+
+		let alice_pre = Pallet::<Test>::evm_balance(&ALICE_ADDR);
+		let bob_pre = Pallet::<Test>::evm_balance(&BOB_ADDR);
+		let mut tracer = PrestateTracer::<Test>::new(PrestateTracerConfig {
+			diff_mode: true,
+			..Default::default()
+		});
+		trace(&mut tracer, || {
+			builder::bare_call(BOB_ADDR).value(10_000_000).build_and_unwrap_result();
+		});
+		assert_eq!(
+			tracer.collect_trace(),
+			Some(PrestateTrace::DiffMode {
+				pre: [
+					(
+						ALICE_ADDR,
+						PrestateTraceInfo { balance: Some(alice_pre), ..Default::default() }
+					),
+					(BOB_ADDR, PrestateTraceInfo { balance: Some(bob_pre), ..Default::default() }),
+				]
+				.into(),
+				post: [
+					(
+						ALICE_ADDR,
+						PrestateTraceInfo {
+							balance: Some(Pallet::<Test>::evm_balance(&ALICE_ADDR)),
+							..Default::default()
+						}
+					),
+					(
+						BOB_ADDR,
+						PrestateTraceInfo {
+							balance: Some(Pallet::<Test>::evm_balance(&BOB_ADDR)),
+							..Default::default()
+						}
+					),
+				]
+				.into(),
+			})
+		);
+	});
+}
+
+#[test]
+fn prestate_tracing_captures_storage() {
+	use crate::evm::*;
+	let (code, _) = compile_module("storage").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code.clone())).build_and_unwrap_contract();
+
+		let mut tracer = PrestateTracer::<Test>::new(Default::default());
+		trace(&mut tracer, || {
+			builder::bare_call(addr).build_and_unwrap_result();
+		});
+
+		let Some(PrestateTrace::Prestate(pre)) = tracer.collect_trace() else {
+			panic!("expected a prestate trace");
+		};
+		assert_eq!(pre[&addr].code, Some(code.into()));
+		assert_eq!(pre[&addr].storage, [(vec![1u8; 32].into(), None)].into());
+
+		// The storage is cleared at the end of the call, so only balances are modified.
+		let mut tracer = PrestateTracer::<Test>::new(PrestateTracerConfig {
+			diff_mode: true,
+			disable_code: true,
+			..Default::default()
+		});
+		trace(&mut tracer, || {
+			builder::bare_call(addr).build_and_unwrap_result();
+		});
+
+		let Some(PrestateTrace::DiffMode { pre, post }) = tracer.collect_trace() else {
+			panic!("expected a diff mode trace");
+		};
+		assert!(pre.values().chain(post.values()).all(|info| info.storage.is_empty()));
+		assert!(pre.values().chain(post.values()).all(|info| info.code.is_none()));
+	});
+}
+
+#[test]
+fn struct_logger_works() {
+	use crate::evm::*;
+	let (code, _) = compile_module("storage").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		let mut tracer = StructLogger::new(StructLoggerConfig::default(), |w: Weight| w);
+		let gas_consumed = trace(&mut tracer, || builder::bare_call(addr).build().gas_consumed);
+
+		let struct_log_trace = tracer.collect_trace().expect("call was traced; qed");
+		assert_eq!(struct_log_trace.gas, gas_consumed);
+		assert!(!struct_log_trace.failed);
+		assert!(struct_log_trace
+			.struct_logs
+			.iter()
+			.all(|log| log.depth == 1 && log.error.is_none()));
+
+		let first_write = struct_log_trace
+			.struct_logs
+			.iter()
+			.find(|log| log.op == "set_storage")
+			.expect("contract writes to storage; qed");
+		assert_eq!(first_write.storage, Some([(vec![1u8; 32].into(), vec![1u8; 4].into())].into()));
+		assert!(first_write.gas_cost.any_gt(Weight::zero()));
+
+		let mut tracer = StructLogger::new(
+			StructLoggerConfig { disable_storage: true, ..Default::default() },
+			|_| U256::zero(),
+		);
+		trace(&mut tracer, || builder::bare_call(addr).build_and_unwrap_result());
+		let struct_log_trace = tracer.collect_trace().expect("call was traced; qed");
+		assert!(struct_log_trace.struct_logs.iter().all(|log| log.storage.is_none()));
+	});
+}
+
 #[test]
 fn unknown_precompiles_revert() {
 	let (code, _code_hash) = compile_module("read_only_call").unwrap();
@@ -4706,3 +4905,131 @@ fn pure_precompile_works() {
 		});
 	}
 }
+
+#[test]
+fn runtime_precompile_works() {
+	use alloy_core::sol_types::{Revert, SolCall, SolError, SolValue};
+
+	let (code, _code_hash) = compile_module("call_and_return").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		let call = |input: Vec<u8>| {
+			builder::bare_call(addr)
+				.data(
+					(&TestPrecompile::address(), 0u64)
+						.encode()
+						.into_iter()
+						.chain(input)
+						.collect::<Vec<_>>(),
+				)
+				.build_and_unwrap_result()
+		};
+
+		// The return value is passed through to the caller.
+		let result = call(ITestPrecompile::echoCall { data: vec![1, 2, 3].into() }.abi_encode());
+		assert_eq!(result.flags, ReturnFlags::empty());
+		assert_eq!(
+			alloy_core::primitives::Bytes::abi_decode(&result.data, true).unwrap(),
+			alloy_core::primitives::Bytes::from(vec![1, 2, 3])
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Contracts(crate::Event::ContractEmitted {
+				contract: TestPrecompile::address(),
+				data: vec![1, 2, 3],
+				topics: vec![H256::repeat_byte(1)],
+			})));
+
+		// A revert of the precompile is passed through to the caller.
+		let result = call(ITestPrecompile::failCall { reason: "failed".into() }.abi_encode());
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert_eq!(result.data, Revert { reason: "failed".into() }.abi_encode());
+
+		// Calling an unknown function reverts without data.
+		let result = call(vec![0u8; 4]);
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert!(result.data.is_empty());
+	});
+}
+
+#[test]
+fn erc20_precompile_works() {
+	use alloy_core::{
+		primitives::{Address, U256 as SolU256},
+		sol_types::{SolCall, SolEvent, SolValue},
+	};
+	use frame_support::traits::fungibles::{approvals::Inspect as _, Mutate as _};
+	use precompiles::{erc20_address, IERC20};
+
+	let (code, _code_hash) = compile_module("call_and_return").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000_000);
+		let Contract { addr, account_id } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+		let _ = <Test as Config>::Currency::set_balance(&account_id, 1_000);
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 1, ALICE, true, 1));
+		assert_ok!(Assets::mint_into(1, &account_id, 1_000));
+
+		let call = |asset_id: u32, input: Vec<u8>| {
+			builder::bare_call(addr)
+				.data(
+					(&erc20_address(asset_id, Erc20Prefix::get()), 0u64)
+						.encode()
+						.into_iter()
+						.chain(input)
+						.collect::<Vec<_>>(),
+				)
+				.build_and_unwrap_result()
+		};
+		let bob = Address::from(BOB_ADDR.0);
+
+		// Reading the supply and balances.
+		let result = call(1, IERC20::totalSupplyCall {}.abi_encode());
+		assert_eq!(result.data, SolU256::from(1_000).abi_encode());
+		let result = call(1, IERC20::balanceOfCall { account: Address::from(addr.0) }.abi_encode());
+		assert_eq!(result.data, SolU256::from(1_000).abi_encode());
+
+		// Transferring from the calling contract.
+		let result =
+			call(1, IERC20::transferCall { to: bob, value: SolU256::from(300) }.abi_encode());
+		assert_eq!(result.flags, ReturnFlags::empty());
+		assert_eq!(result.data, true.abi_encode());
+		assert_eq!(Assets::balance(1, &BOB), 300);
+		assert_eq!(Assets::balance(1, &account_id), 700);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Contracts(crate::Event::ContractEmitted {
+				contract: erc20_address(1, Erc20Prefix::get()),
+				data: SolU256::from(300).abi_encode(),
+				topics: vec![
+					H256(IERC20::Transfer::SIGNATURE_HASH.0),
+					H256::from(addr),
+					H256::from(BOB_ADDR),
+				],
+			})));
+
+		// Approving replaces the previous allowance.
+		let result =
+			call(1, IERC20::approveCall { spender: bob, value: SolU256::from(200) }.abi_encode());
+		assert_eq!(result.data, true.abi_encode());
+		let result =
+			call(1, IERC20::approveCall { spender: bob, value: SolU256::from(50) }.abi_encode());
+		assert_eq!(result.data, true.abi_encode());
+		assert_eq!(Assets::allowance(1, &account_id, &BOB), 50);
+		let result = call(
+			1,
+			IERC20::allowanceCall { owner: Address::from(addr.0), spender: bob }.abi_encode(),
+		);
+		assert_eq!(result.data, SolU256::from(50).abi_encode());
+
+		// Failures of `pallet-assets` revert the call.
+		let result =
+			call(1, IERC20::transferCall { to: bob, value: SolU256::from(10_000) }.abi_encode());
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		let result =
+			call(2, IERC20::transferCall { to: bob, value: SolU256::from(1) }.abi_encode());
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert_eq!(Assets::balance(1, &account_id), 700);
+	});
+}
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{primitives::ExecReturnValue, DispatchError, Weight};
+use crate::{exec::Key, primitives::ExecReturnValue, DispatchError, Weight};
 use environmental::environmental;
 use sp_core::{H160, H256, U256};
 
@@ -40,7 +40,15 @@ pub(crate) fn if_tracing<F: FnOnce(&mut (dyn Tracer + 'static))>(f: F) {
 }
 
 /// Defines methods to trace contract interactions.
+///
+/// Only the call span and log methods are mandatory. The remaining hooks default to no-ops so
+/// that tracers only need to implement the events they are interested in.
 pub trait Tracer {
+	/// Called whenever the state of `address` is about to be accessed.
+	///
+	/// This is used by tracers that need to capture the state of every touched account.
+	fn watch_address(&mut self, _address: &H160) {}
+
 	/// Called before a contract call is executed
 	fn enter_child_span(
 		&mut self,
@@ -56,6 +64,18 @@ pub trait Tracer {
 	/// Record a log event
 	fn log_event(&mut self, event: H160, topics: &[H256], data: &[u8]);
 
+	/// Called after a storage value of the current contract was read.
+	fn storage_read(&mut self, _key: &Key, _value: Option<&[u8]>) {}
+
+	/// Called before a storage value of the current contract is written.
+	fn storage_write(&mut self, _key: &Key, _old_value: Option<&[u8]>, _new_value: Option<&[u8]>) {}
+
+	/// Called before a syscall is executed by the current contract.
+	fn enter_syscall(&mut self, _pc: u64, _name: &[u8], _gas_left: Weight) {}
+
+	/// Called after the syscall announced by [`Self::enter_syscall`] returned.
+	fn exit_syscall(&mut self, _gas_left: Weight, _error: Option<DispatchError>) {}
+
 	/// Called after a contract call is executed
 	fn exit_child_span(&mut self, output: &ExecReturnValue, gas_left: Weight);
 
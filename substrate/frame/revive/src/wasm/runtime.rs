@@ -23,8 +23,8 @@ use crate::{
 	exec::{ExecError, ExecResult, Ext, Key},
 	gas::{ChargedAmount, Token},
 	limits,
+	precompiles::is_precompile,
 	primitives::ExecReturnValue,
-	pure_precompiles::is_precompile,
 	weights::WeightInfo,
 	Config, Error, LOG_TARGET, SENTINEL,
 };
@@ -146,6 +146,7 @@ pub trait PolkaVmInstance<T: Config>: Memory<T> {
 	fn set_gas(&mut self, gas: polkavm::Gas);
 	fn read_input_regs(&self) -> (u64, u64, u64, u64, u64, u64);
 	fn write_output(&mut self, output: u64);
+	fn program_counter(&self) -> u64;
 }
 
 // Memory implementation used in benchmarking where guest memory is mapped into the host.
@@ -216,6 +217,10 @@ impl<T: Config> PolkaVmInstance<T> for polkavm::RawInstance {
 	fn write_output(&mut self, output: u64) {
 		self.set_reg(polkavm::Reg::A0, output);
 	}
+
+	fn program_counter(&self) -> u64 {
+		self.program_counter().map(|pc| pc.0.into()).unwrap_or_default()
+	}
 }
 
 parameter_types! {
@@ -394,6 +399,8 @@ pub enum RuntimeCosts {
 	CallRuntime(Weight),
 	/// Weight charged for calling xcm_execute.
 	CallXcmExecute(Weight),
+	/// Weight charged by a precompile backed by the runtime.
+	Precompile(Weight),
 	/// Weight of calling `seal_set_code_hash`
 	SetCodeHash,
 	/// Weight of calling `ecdsa_to_eth_address`
@@ -414,6 +421,26 @@ pub enum RuntimeCosts {
 	Blake2F(u32),
 	/// Weight of calling `Modexp` precompile
 	Modexp(u64),
+	/// Weight of calling one of the BLS12-381 precompiles for the given EIP-2537 gas cost.
+	Bls12(u64),
+	/// Weight of calling `P256Verify` precompile
+	P256Verify,
+}
+
+/// Convert an amount of EVM gas into the equivalent weight.
+fn evm_gas_to_weight(gas: u64) -> Weight {
+	use frame_support::weights::constants::WEIGHT_REF_TIME_PER_SECOND;
+	/// Current approximation of the gas/s consumption considering
+	/// EVM execution over compiled WASM (on 4.4Ghz CPU).
+	/// Given the 2000ms Weight, from which 75% only are used for transactions,
+	/// the total EVM execution gas limit is: GAS_PER_SECOND * 2 * 0.75 ~= 60_000_000.
+	const GAS_PER_SECOND: u64 = 40_000_000;
+
+	/// Approximate ratio of the amount of Weight per Gas.
+	/// u64 works for approximations because Weight is a very small unit compared to
+	/// gas.
+	const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
+	Weight::from_parts(gas.saturating_mul(WEIGHT_PER_GAS), 0)
 }
 
 /// For functions that modify storage, benchmarks are performed with one item in the
@@ -538,7 +565,10 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			HashBlake128(len) => T::WeightInfo::seal_hash_blake2_128(len),
 			EcdsaRecovery => T::WeightInfo::ecdsa_recover(),
 			Sr25519Verify(len) => T::WeightInfo::seal_sr25519_verify(len),
-			ChainExtension(weight) | CallRuntime(weight) | CallXcmExecute(weight) => weight,
+			ChainExtension(weight) |
+			CallRuntime(weight) |
+			CallXcmExecute(weight) |
+			Precompile(weight) => weight,
 			SetCodeHash => T::WeightInfo::seal_set_code_hash(),
 			EcdsaToEthAddress => T::WeightInfo::seal_ecdsa_to_eth_address(),
 			GetImmutableData(len) => T::WeightInfo::seal_get_immutable_data(len),
@@ -548,20 +578,8 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			Bn128Pairing(len) => T::WeightInfo::bn128_pairing(len),
 			Identity(len) => T::WeightInfo::identity(len),
 			Blake2F(rounds) => T::WeightInfo::blake2f(rounds),
-			Modexp(gas) => {
-				use frame_support::weights::constants::WEIGHT_REF_TIME_PER_SECOND;
-				/// Current approximation of the gas/s consumption considering
-				/// EVM execution over compiled WASM (on 4.4Ghz CPU).
-				/// Given the 2000ms Weight, from which 75% only are used for transactions,
-				/// the total EVM execution gas limit is: GAS_PER_SECOND * 2 * 0.75 ~= 60_000_000.
-				const GAS_PER_SECOND: u64 = 40_000_000;
-
-				/// Approximate ratio of the amount of Weight per Gas.
-				/// u64 works for approximations because Weight is a very small unit compared to
-				/// gas.
-				const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
-				Weight::from_parts(gas.saturating_mul(WEIGHT_PER_GAS), 0)
-			},
+			Modexp(gas) | Bls12(gas) => evm_gas_to_weight(gas),
+			P256Verify => evm_gas_to_weight(crate::pure_precompiles::P256_VERIFY_GAS),
 		}
 	}
 }
@@ -1048,7 +1066,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		output_len_ptr: u32,
 	) -> Result<ReturnErrorCode, TrapReason> {
 		let callee = match memory.read_h160(callee_ptr) {
-			Ok(callee) if is_precompile(&callee) => callee,
+			Ok(callee) if is_precompile::<E::T>(&callee) => callee,
 			Ok(callee) => {
 				self.charge_gas(call_type.cost())?;
 				callee
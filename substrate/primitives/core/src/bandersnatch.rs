@@ -53,6 +53,9 @@ pub const SIGNATURE_SERIALIZED_SIZE: usize = 64;
 /// The byte length of serialized pre-output.
 pub const PREOUT_SERIALIZED_SIZE: usize = 32;
 
+/// The byte length of serialized VRF input.
+pub const INPUT_SERIALIZED_SIZE: usize = 32;
+
 #[doc(hidden)]
 pub struct BandersnatchTag;
 
@@ -208,6 +211,25 @@ pub mod vrf {
 		}
 	}
 
+	impl Encode for VrfInput {
+		fn encode(&self) -> Vec<u8> {
+			let mut bytes = [0; INPUT_SERIALIZED_SIZE];
+			self.0
+				.serialize_compressed(bytes.as_mut_slice())
+				.expect("serialization length is constant and checked by test; qed");
+			bytes.encode()
+		}
+	}
+
+	impl Decode for VrfInput {
+		fn decode<R: codec::Input>(i: &mut R) -> Result<Self, codec::Error> {
+			let buf = <[u8; INPUT_SERIALIZED_SIZE]>::decode(i)?;
+			let input = bandersnatch::Input::deserialize_compressed(buf.as_slice())
+				.map_err(|_| "vrf-input decode error: bad input")?;
+			Ok(VrfInput(input))
+		}
+	}
+
 	/// VRF pre-output derived from [`VrfInput`] using a [`VrfSecret`].
 	///
 	/// This object is hashed to produce the actual VRF output.
@@ -631,6 +653,7 @@ mod tests {
 		assert_eq!(public.compressed_size(), PUBLIC_SERIALIZED_SIZE);
 
 		let input = Input::new(b"foo").unwrap();
+		assert_eq!(input.compressed_size(), INPUT_SERIALIZED_SIZE);
 		let preout = secret.output(input);
 		assert_eq!(preout.compressed_size(), PREOUT_SERIALIZED_SIZE);
 
@@ -699,6 +722,15 @@ mod tests {
 		assert!(public.vrf_verify(&data, &signature));
 	}
 
+	#[test]
+	fn vrf_input_codec_works() {
+		let pair = Pair::from_seed(TEST_SEED);
+		let input = VrfInput::new(b"foo");
+
+		let decoded = VrfInput::decode(&mut input.encode().as_slice()).unwrap();
+		assert_eq!(pair.vrf_pre_output(&decoded), pair.vrf_pre_output(&input));
+	}
+
 	#[test]
 	fn vrf_sign_verify_with_bad_input() {
 		let pair = Pair::from_seed(TEST_SEED);
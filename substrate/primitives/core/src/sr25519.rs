@@ -372,6 +372,11 @@ pub mod vrf {
 			self.extra = Some(extra);
 			self
 		}
+
+		/// Returns whether extra data to be signed was added.
+		pub fn has_extra(&self) -> bool {
+			self.extra.is_some()
+		}
 	}
 
 	/// VRF signature data
@@ -544,6 +549,53 @@ pub mod vrf {
 			let inout = self.0.vrf_create_hash(input.0.clone());
 			inout.make_bytes::<[u8; N]>(context)
 		}
+
+		/// Sign the VRF input point returned by [`Public::vrf_input_point`].
+		///
+		/// This is the same as [`VrfSecret::vrf_sign`] for sign data without extra data.
+		pub fn vrf_sign_input_point(
+			&self,
+			point: &[u8; VRF_PREOUT_LENGTH],
+		) -> Result<VrfSignature, codec::Error> {
+			let inout = self
+				.0
+				.secret
+				.vrf_create_from_compressed_point(&schnorrkel::vrf::VRFPreOut(*point))
+				.map_err(convert_error)?;
+			let extra = merlin::Transcript::new(DEFAULT_EXTRA_DATA_LABEL);
+			let proof = self.0.dleq_proove(extra, &inout, true).0;
+
+			Ok(VrfSignature { pre_output: VrfPreOutput(inout.to_preout()), proof: VrfProof(proof) })
+		}
+
+		/// Compute the VRF pre-output of the VRF input point returned by
+		/// [`Public::vrf_input_point`].
+		pub fn vrf_pre_output_from_input_point(
+			&self,
+			point: &[u8; VRF_PREOUT_LENGTH],
+		) -> Result<VrfPreOutput, codec::Error> {
+			let inout = self
+				.0
+				.secret
+				.vrf_create_from_compressed_point(&schnorrkel::vrf::VRFPreOut(*point))
+				.map_err(convert_error)?;
+			Ok(VrfPreOutput(inout.to_preout()))
+		}
+	}
+
+	impl Public {
+		/// Hash the VRF input to the curve point it is mapped to for this key.
+		///
+		/// Unlike the input, which is a transcript, the point can be transferred, e.g. to a remote
+		/// signer which completes the VRF operations with [`Pair::vrf_sign_input_point`] and
+		/// [`Pair::vrf_pre_output_from_input_point`].
+		pub fn vrf_input_point(
+			&self,
+			input: &VrfInput,
+		) -> Result<[u8; VRF_PREOUT_LENGTH], codec::Error> {
+			let pubkey = schnorrkel::PublicKey::from_bytes(&self.0).map_err(convert_error)?;
+			Ok(pubkey.vrf_hash(input.0.clone()).as_compressed().to_bytes())
+		}
 	}
 
 	impl Public {
@@ -860,6 +912,21 @@ mod tests {
 		assert!(public.vrf_verify(&data, &signature));
 	}
 
+	#[test]
+	fn vrf_sign_input_point_works() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let public = pair.public();
+
+		let input = VrfTranscript::new(b"label", &[(b"domain1", b"data1")]);
+		let point = public.vrf_input_point(&input).unwrap();
+
+		let pre_output = pair.vrf_pre_output_from_input_point(&point).unwrap();
+		assert_eq!(pre_output, pair.vrf_pre_output(&input));
+
+		let signature = pair.vrf_sign_input_point(&point).unwrap();
+		assert!(public.vrf_verify(&input.into_sign_data(), &signature));
+	}
+
 	#[test]
 	fn vrf_make_bytes_matches() {
 		let pair = Pair::from_seed(b"12345678901234567890123456789012");
@@ -115,4 +115,11 @@ impl<D: KeyValueDB, H: Clone + AsRef<[u8]>> Database<H> for DbAdapter<D> {
 	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
 		handle_err(self.0.has_key(col, key))
 	}
+
+	fn iter(&self, col: ColumnId) -> Option<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_>> {
+		Some(Box::new(self.0.iter(col).map(|pair| {
+			let (key, value) = handle_err(pair);
+			(key.to_vec(), value)
+		})))
+	}
 }
@@ -117,6 +117,13 @@ pub trait Database<H: Clone + AsRef<[u8]>>: Send + Sync {
 	///
 	/// Not all database implementations use a prefix for keys, so this function may be a noop.
 	fn sanitize_key(&self, _key: &mut Vec<u8>) {}
+
+	/// Iterate over all the key value pairs stored in `col`, in a stable order.
+	///
+	/// Returns `None` if the database can not enumerate the keys of the column.
+	fn iter(&self, _col: ColumnId) -> Option<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_>> {
+		None
+	}
 }
 
 impl<H> std::fmt::Debug for dyn Database<H> {
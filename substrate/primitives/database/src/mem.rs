@@ -73,6 +73,16 @@ where
 		let s = self.0.read();
 		s.get(&col).and_then(|c| c.get(key).map(|(_, v)| v.clone()))
 	}
+
+	fn iter(&self, col: ColumnId) -> Option<Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_>> {
+		let s = self.0.read();
+		let mut pairs: Vec<_> = s
+			.get(&col)
+			.map(|c| c.iter().map(|(k, (_, v))| (k.clone(), v.clone())).collect())
+			.unwrap_or_default();
+		pairs.sort_unstable();
+		Some(Box::new(pairs.into_iter()))
+	}
 }
 
 impl MemDb {
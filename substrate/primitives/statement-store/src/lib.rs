@@ -47,7 +47,7 @@ pub const MAX_TOPICS: usize = 4;
 
 #[cfg(feature = "std")]
 pub use store_api::{
-	Error, NetworkPriority, Result, StatementSource, StatementStore, SubmitResult,
+	Error, NetworkPriority, Result, StatementSource, StatementStore, StatementStream, SubmitResult,
 };
 
 #[cfg(feature = "std")]
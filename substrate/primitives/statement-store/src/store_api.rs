@@ -61,6 +61,9 @@ pub enum SubmitResult {
 /// Result type for `Error`
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Stream of statements newly accepted into the store.
+pub type StatementStream = futures::stream::BoxStream<'static, Statement>;
+
 /// Statement store API.
 pub trait StatementStore: Send + Sync {
 	/// Return all statements.
@@ -87,4 +90,8 @@ pub trait StatementStore: Send + Sync {
 
 	/// Remove a statement from the store.
 	fn remove(&self, hash: &Hash) -> Result<()>;
+
+	/// Subscribe to new statements. The stream yields every statement for which `submit`
+	/// returns `SubmitResult::New`, starting from the moment of the call.
+	fn subscribe(&self) -> StatementStream;
 }
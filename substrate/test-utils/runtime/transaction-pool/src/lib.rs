@@ -483,6 +483,10 @@ impl ChainApi for TestApi {
 		Self::hash_and_length_inner(ex)
 	}
 
+	fn sender_of(&self, ex: &<Self::Block as BlockT>::Extrinsic) -> Option<Vec<u8>> {
+		TransferData::try_from(ex).ok().map(|transfer| transfer.from.encode())
+	}
+
 	fn block_body(&self, hash: <Self::Block as BlockT>::Hash) -> Self::BodyFuture {
 		futures::future::ready(Ok(self
 			.chain
@@ -281,6 +281,7 @@ pub async fn start_parachain_node(
 			spawn_handle: task_manager.spawn_handle(),
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
+			warp_sync_with_proofs: collator_options.warp_sync_with_proofs,
 			sybil_resistance_level: CollatorSybilResistance::Resistant, // because of Aura
 		})
 		.await?;